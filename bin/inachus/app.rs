@@ -1 +1,918 @@
-/// bin/inachus/app.rs
\ No newline at end of file
+/// bin/inachus/app.rs
+use crate::MethodArgs;
+use alloy::json_abi::Function;
+use alloy::primitives::{Address, Bytes};
+use alloy::signers::local::PrivateKeySigner;
+use chain_info::ChainRegistry;
+use colored::Colorize;
+#[cfg(unix)]
+use inachus::daemon::{self, Daemon, DaemonClient};
+use inachus::{
+    abi, approval,
+    batch::{self, BatchFormat, BatchMode},
+    cancel::CancellationToken,
+    codec,
+    config::{Config, ContractInfo, OperatingMode, PROFILE_ENV},
+    create2,
+    error::{Error, Result},
+    events, export,
+    hooks::CommandHook,
+    migrate,
+    offline::UnsignedTransaction,
+    output::{Output, OutputFormat, OutputKind},
+    params,
+    policy::PolicyHook,
+    prompt,
+    relay::{self, PrivateRelay},
+    safe,
+    script::Script,
+    server::Server,
+    setup,
+    signer::{self, MnemonicConfig},
+    status::SessionStatus,
+    step::{StepOutcome, StepRegistry},
+    steps, validation, wallet, CallResult, ExecutionResult, GlobalContext, Signer, ABI_DIR,
+    CHAINS_FILE, CONTRACTS_FILE, CUSTOM_CHAINS_FILE,
+};
+use inquire::InquireError;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Configuration written on first run.
+const DEFAULT_CONFIG: &str = include_str!("embeds/config.toml");
+
+/// Contract deployments written on first run.
+const DEFAULT_CONTRACTS: &str = include_str!("embeds/contract_infos.json");
+
+/// ABI of the contract shipped in the default deployments.
+const TETHER_ABI: &str = include_str!("embeds/TetherToken.abi");
+
+/// Profile selected with `--profile`, overriding the configured one.
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Selects the configuration profile every command loads, from `--profile`.
+///
+/// # Arguments
+///
+/// * `profile` - Name of the profile
+pub fn use_profile(profile: String) {
+    let _ = PROFILE.set(profile);
+}
+
+/// Mode selected with `--read-only` or `--dry-run`, overriding the configured one.
+static MODE: OnceLock<OperatingMode> = OnceLock::new();
+
+/// Selects the operating mode of every command, from `--read-only` or `--dry-run`.
+///
+/// # Arguments
+///
+/// * `mode` - The mode
+pub fn use_mode(mode: OperatingMode) {
+    let _ = MODE.set(mode);
+}
+
+/// Whether `--paranoid` was passed, turning paranoid mode on over the configuration.
+static PARANOID: AtomicBool = AtomicBool::new(false);
+
+/// Turns paranoid mode on for every command, from `--paranoid`.
+pub fn use_paranoid() {
+    PARANOID.store(true, Ordering::Relaxed);
+}
+
+/// Runs the interactive workflow loop until the user exits.
+///
+/// # Arguments
+///
+/// * `json` - Whether to print results as JSON, overriding `output`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or a fatal error
+pub async fn run(json: bool) -> Result<()> {
+    let (_, mut ctx) = load_context(json).await?;
+
+    // Ctrl-C while a step awaits the network cancels that step instead of
+    // killing the process; prompts handle Ctrl-C themselves.
+    let current = Arc::new(Mutex::new(CancellationToken::new()));
+    let interrupts = current.clone();
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            interrupts
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .cancel();
+        }
+    });
+
+    let registry = StepRegistry::with_defaults();
+    loop {
+        if ctx.output == OutputFormat::Text {
+            eprintln!("\n{}", SessionStatus::fetch(&ctx).await);
+        }
+        let steps = registry.available(&ctx.capabilities());
+        let step = match prompt::select_step(&steps) {
+            Ok(step) => step,
+            Err(Error::Prompt(_)) => break,
+            Err(e) => return Err(e),
+        };
+
+        ctx.cancel = CancellationToken::new();
+        *current.lock().unwrap_or_else(|e| e.into_inner()) = ctx.cancel.clone();
+
+        let profile = ctx.profile.clone();
+        match step.run(&mut ctx).await {
+            Ok(StepOutcome::Continue) => {}
+            Ok(StepOutcome::Reload) => match load_profile(json, ctx.profile.as_deref(), true).await
+            {
+                Ok((_, reloaded)) => ctx = reloaded,
+                Err(e) => {
+                    eprintln!("{} {}", "Error:".red(), e);
+                    ctx.profile = profile;
+                }
+            },
+            Ok(StepOutcome::Exit) => break,
+            Err(Error::Prompt(InquireError::OperationCanceled)) => {}
+            Err(Error::Prompt(InquireError::OperationInterrupted)) => break,
+            Err(e @ Error::Cancelled(_)) => eprintln!("{}", e.to_string().yellow()),
+            Err(e) if ctx.output == OutputFormat::Json => println!("{}", Output::from(&e)),
+            Err(e) => eprintln!("{} {}", "Error:".red(), e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the daemon until Ctrl-C.
+///
+/// # Arguments
+///
+/// * `socket` - Socket path, defaulting to `$HOME/.inachus/daemon.sock`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success once shut down, or a fatal error
+#[cfg(unix)]
+pub async fn daemon(socket: Option<PathBuf>) -> Result<()> {
+    let socket = socket.unwrap_or_else(daemon::default_socket_path);
+    let (_, ctx) = load_service_context().await?;
+    let daemon = Daemon::new(ctx)?;
+
+    eprintln!("{} {}", "Daemon listening on".green(), socket.display());
+    daemon.serve(&socket, shutdown_on_ctrl_c()).await
+}
+
+/// Runs the HTTP server until Ctrl-C.
+///
+/// # Arguments
+///
+/// * `bind` - Address to listen on, overriding `server.bind`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success once shut down, or a fatal error
+pub async fn serve(bind: Option<String>) -> Result<()> {
+    let (config, ctx) = load_service_context().await?;
+    let bind = bind.unwrap_or_else(|| config.server.bind.clone());
+    let server = Server::new(ctx, &config.server)?;
+
+    eprintln!("{} http://{}", "Server listening on".green(), bind);
+    server.serve(&bind, shutdown_on_ctrl_c()).await
+}
+
+/// Calls a read-only method and prints its result, or how its outputs
+/// differ between two blocks.
+///
+/// # Arguments
+///
+/// * `target` - The contract, method and arguments
+/// * `block` - Block to read the state at, a number, hash or tag
+/// * `compare` - Second block to call at and compare the outputs with
+/// * `json` - Whether to print the result as JSON, overriding `output`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if the call failed
+pub async fn call(
+    target: &MethodArgs,
+    block: &str,
+    compare: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let (_, ctx) = load_context(json).await?;
+    let (abi, function, address) = ctx.target(
+        target.contract.as_deref(),
+        target.address.as_deref(),
+        &target.method,
+    )?;
+    let args = codec::coerce_args(function, &method_args(&ctx, target, function)?)?;
+    if let Some(compare) = compare {
+        let diff = ctx
+            .read_context()?
+            .compare(address, function, &args, [block, compare])
+            .await
+            .map_err(|e| e.with_abi(abi))?;
+        return print_result(&ctx, Output::from(&diff), &diff.to_string(), target);
+    }
+    let result = steps::checked_call(
+        &ctx,
+        address,
+        function,
+        &args,
+        validation::parse_block_id(block)?,
+    )
+    .await
+    .map_err(|e| e.with_abi(abi))?;
+    print_result(&ctx, Output::from(&result), &result.to_string(), target)
+}
+
+/// Calls a read-only method once per row of an input file and writes the
+/// results, reporting progress on stderr.
+///
+/// # Arguments
+///
+/// * `contract` - Name of the contract's ABI, the configured contract when `None`
+/// * `address` - Address or alias overriding the contract's known address
+/// * `method` - A function name or signature
+/// * `input` - CSV or JSON file of argument rows
+/// * `output` - File the results are written to, stdout when `None`
+/// * `mode` - Whether to send concurrent calls or Multicall3 batches
+/// * `json` - Whether to print the results as JSON, overriding `output`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success, even if some calls failed, or an error
+pub async fn batch(
+    contract: Option<&str>,
+    address: Option<&str>,
+    method: &str,
+    input: &Path,
+    output: Option<&Path>,
+    mode: BatchMode,
+    json: bool,
+) -> Result<()> {
+    let (_, ctx) = load_context(json).await?;
+    let (abi, function, address) = ctx.target(contract, address, method)?;
+    let rows: Vec<Vec<String>> = batch::read_rows(input, function)?
+        .iter()
+        .map(|row| ctx.address_book.resolve_args(function, row))
+        .collect();
+    let total = rows.len();
+    let show_progress = std::io::stderr().is_terminal();
+    let results = batch::execute(
+        &ctx.read_context()?,
+        address,
+        function,
+        &rows,
+        mode,
+        |done| {
+            if show_progress {
+                eprint!("\r{} {}/{}", "Called".cyan(), done, total);
+            }
+        },
+    )
+    .await?;
+    if show_progress && total > 0 {
+        eprintln!();
+    }
+    let results: Vec<Result<CallResult>> = results
+        .into_iter()
+        .map(|result| result.map_err(|e| e.with_abi(abi)))
+        .collect();
+    let failed = results.iter().filter(|result| result.is_err()).count();
+
+    match output {
+        Some(path) => {
+            let content = match BatchFormat::of(path) {
+                BatchFormat::Csv => batch::to_csv(function, &rows, &results),
+                BatchFormat::Json => {
+                    serde_json::to_string_pretty(&batch::to_json(function, &rows, &results))?
+                }
+            };
+            fs::write(path, content)?;
+            eprintln!(
+                "{} {} result(s) to {}, {} failed",
+                "Wrote".green(),
+                total,
+                path.display(),
+                failed
+            );
+        }
+        None => match ctx.output {
+            OutputFormat::Json => println!(
+                "{}",
+                Output::new(OutputKind::Batch, batch::to_json(function, &rows, &results))
+            ),
+            OutputFormat::Text => print!("{}", batch::to_csv(function, &rows, &results)),
+        },
+    }
+    Ok(())
+}
+
+/// Sends a transaction without prompting and prints its outcome.
+///
+/// # Arguments
+///
+/// * `target` - The contract, method and arguments
+/// * `value` - Native value sent with the transaction, e.g. `0.1 ether`
+/// * `gas_limit` - Gas limit, estimated when `None`
+/// * `nonce` - Nonce, the next pending one when `None`
+/// * `wait` - Whether to wait for the receipt
+/// * `export` - When set, print the unsigned transaction instead of sending it,
+///   from the given sender or else the configured signer
+/// * `json` - Whether to print the outcome as JSON, overriding `output`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if the transaction could not be sent
+pub async fn send(
+    target: &MethodArgs,
+    value: Option<&str>,
+    gas_limit: Option<u64>,
+    nonce: Option<u64>,
+    wait: bool,
+    export: Option<Option<&str>>,
+    json: bool,
+) -> Result<()> {
+    let (_, ctx) = load_context(json).await?;
+    let (abi, function, address) = ctx.target(
+        target.contract.as_deref(),
+        target.address.as_deref(),
+        &target.method,
+    )?;
+    let params = method_args(&ctx, target, function)?;
+    let args = codec::coerce_args(function, &params)?;
+    let mut options = ctx.gas.options().with_confirmations(ctx.confirmations);
+    if let Some(value) = value {
+        options = options.with_value(validation::parse_value(value)?);
+    }
+    if let Some(gas_limit) = gas_limit {
+        options = options.with_gas_limit(gas_limit);
+    }
+    if let Some(nonce) = nonce {
+        options = options.with_nonce(nonce);
+    }
+    options.validate()?;
+
+    if let Some(from) = export {
+        let preview = match from {
+            Some(from) => {
+                let from = ctx.address_book.resolve(from);
+                let from =
+                    Address::from_str(&from).map_err(|_| Error::InvalidAddress(from.clone()))?;
+                let chain_id = ctx
+                    .chain_id
+                    .parse()
+                    .map_err(|_| Error::InvalidChainId(ctx.chain_id.clone()))?;
+                ctx.read_context()?
+                    .preview(from, chain_id, address, function, &args, &options)
+                    .await
+            }
+            None => {
+                ctx.write_context()?
+                    .preview(address, function, &args, &options)
+                    .await
+            }
+        }
+        .map_err(|e| e.with_abi(abi))?;
+        let result =
+            ExecutionResult::Exported(Box::new(UnsignedTransaction::from_preview(&preview)));
+        return print_result(&ctx, Output::from(&result), &result.to_string(), target);
+    }
+    if let Some(warning) = approval::check_call(&ctx, function, &args).await {
+        eprintln!("{}", warning);
+    }
+    if options.generates_access_list() && ctx.safe.is_none() {
+        options =
+            steps::with_access_list(&ctx.write_context()?, address, function, &args, &options)
+                .await;
+    }
+    if ctx.mode == OperatingMode::DryRun {
+        let tx = ctx
+            .write_context()?
+            .dry_run_send(address, function, &args, &options)
+            .await
+            .map_err(|e| e.with_abi(abi))?;
+        let result = ExecutionResult::Simulated(Box::new(tx));
+        return print_result(&ctx, Output::from(&result), &result.to_string(), target);
+    }
+    if ctx.safe.is_some() {
+        let result = safe::propose(&ctx, address, function, &args, &options, false)
+            .await
+            .map_err(|e| e.with_abi(abi))?;
+        return print_result(&ctx, Output::from(&result), &result.to_string(), target);
+    }
+    let write = ctx.write_context()?;
+    let result = match ctx.private_relay.as_ref().filter(|relay| relay.enabled) {
+        Some(relay) => {
+            let relay = PrivateRelay::new(relay)?;
+            let submission = relay::send(&write, &relay, address, function, &args, &options)
+                .await
+                .map_err(|e| e.with_abi(abi))?;
+            match wait {
+                true => relay::wait(&write, &relay, &submission).await?,
+                false => ExecutionResult::Submitted {
+                    hash: submission.hash,
+                },
+            }
+        }
+        None => {
+            let pending = write
+                .send(address, function, &args, &options)
+                .await
+                .map_err(|e| e.with_abi(abi))?;
+            match wait {
+                true => ExecutionResult::Confirmed(Box::new(write.confirm(pending).await?)),
+                false => ExecutionResult::Submitted {
+                    hash: *pending.tx_hash(),
+                },
+            }
+        }
+    };
+    ctx.record(
+        target.contract.as_deref().unwrap_or(&ctx.contract_name),
+        address,
+        function,
+        &params,
+        &options,
+        &result,
+    );
+    print_result(&ctx, Output::from(&result), &result.to_string(), target)?;
+    prompt::display_receipt(&ctx, &result);
+    Ok(())
+}
+
+/// Prints the calldata of a method call.
+///
+/// # Arguments
+///
+/// * `target` - The contract, method and arguments
+/// * `json` - Whether to print the calldata as JSON, overriding `output`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if the arguments do not match the method
+pub async fn encode(target: &MethodArgs, json: bool) -> Result<()> {
+    let (_, ctx) = load_context(json).await?;
+    let (_, function, _) = ctx.target(
+        target.contract.as_deref(),
+        target.address.as_deref(),
+        &target.method,
+    )?;
+    let args = method_args(&ctx, target, function)?;
+    let calldata = codec::encode_calldata(function, &args)?;
+    let output = Output::new(
+        OutputKind::Calldata,
+        serde_json::json!({ "function": function.signature(), "calldata": calldata }),
+    );
+    print_result(&ctx, output, &calldata.to_string(), target)
+}
+
+/// Decodes calldata, or the return data of a method, and prints the values.
+///
+/// # Arguments
+///
+/// * `contract` - Name of the contract's ABI, the configured contract when `None`
+/// * `method` - Method name or signature, looked up by selector when `None`
+/// * `output` - Whether `data` is return data rather than calldata
+/// * `data` - The hex-encoded data
+/// * `json` - Whether to print the values as JSON, overriding `output`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if the data does not match
+pub async fn decode(
+    contract: Option<&str>,
+    method: Option<&str>,
+    output: bool,
+    data: &str,
+    json: bool,
+) -> Result<()> {
+    let (_, ctx) = load_context(json).await?;
+    let name = contract.unwrap_or(&ctx.contract_name);
+    let abi = ctx
+        .abis
+        .get(name)
+        .ok_or_else(|| Error::InvalidContract(format!("ABI not found for contract: {}", name)))?;
+    let data = Bytes::from_str(data.trim())
+        .map_err(|e| Error::InvalidArguments(format!("Invalid hex data: {}", e)))?;
+    let function = match method {
+        Some(method) => abi::find_function(abi, method)?,
+        None => abi
+            .functions()
+            .find(|f| data.starts_with(f.selector().as_slice()))
+            .ok_or_else(|| {
+                Error::InvalidFunction(format!("No method of {} matches the selector", name))
+            })?,
+    };
+    let result = match output {
+        true => CallResult::new(function, codec::decode_output(function, &data)?, data),
+        false => CallResult::from_inputs(function, codec::decode_input(function, &data)?, data),
+    };
+    eprintln!("{}", result.function.cyan());
+    show_result(&ctx, &Output::from(&result), &result.to_string());
+    Ok(())
+}
+
+/// Reads the arguments of a scripted command from `--params-file`, or from
+/// the `--arg` values, any of which may be `@file.json`.
+///
+/// # Arguments
+///
+/// * `ctx` - The context holding the address book
+/// * `target` - The contract, method and arguments
+/// * `function` - The targeted function
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - The arguments with aliases resolved, or an error
+///   if a file cannot be read or does not fit the function
+fn method_args(
+    ctx: &GlobalContext,
+    target: &MethodArgs,
+    function: &Function,
+) -> Result<Vec<String>> {
+    let args = match &target.params_file {
+        Some(path) => params::read_params_file(path, function)?,
+        None => target
+            .args
+            .iter()
+            .enumerate()
+            .map(
+                |(i, arg)| match (params::file_reference(arg), function.inputs.get(i)) {
+                    (Some(path), Some(input)) => params::read_param_file(path, input),
+                    _ => Ok(arg.clone()),
+                },
+            )
+            .collect::<Result<_>>()?,
+    };
+    Ok(ctx.address_book.resolve_args(function, &args))
+}
+
+/// Prints a result of a scripted command in the context's output format,
+/// and writes it to the `--output` file when one is given.
+///
+/// # Arguments
+///
+/// * `ctx` - The context holding the output format and address book
+/// * `output` - The result as structured output
+/// * `text` - The result as text, annotated with address book aliases
+/// * `target` - The command arguments, holding the `--output` file
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if the file cannot be written
+fn print_result(
+    ctx: &GlobalContext,
+    output: Output,
+    text: &str,
+    target: &MethodArgs,
+) -> Result<()> {
+    show_result(ctx, &output, text);
+    if let Some(path) = &target.output {
+        export::write_file(path, &output)?;
+        eprintln!("{} {}", "Wrote result to".green(), path.display());
+    }
+    Ok(())
+}
+
+/// Prints a result of a scripted command in the context's output format.
+///
+/// # Arguments
+///
+/// * `ctx` - The context holding the output format and address book
+/// * `output` - The result as structured output
+/// * `text` - The result as text, annotated with address book aliases
+fn show_result(ctx: &GlobalContext, output: &Output, text: &str) {
+    match ctx.output {
+        OutputFormat::Json => println!("{}", output),
+        OutputFormat::Text => println!("{}", ctx.address_book.annotate(text)),
+    }
+}
+
+/// Runs a script step by step, stopping at the first failure, then prints a summary.
+///
+/// # Arguments
+///
+/// * `path` - Path to the YAML script
+/// * `json` - Whether to print step results as JSON, overriding `output`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success if every step passed, or the error of the failed step
+pub async fn run_script(path: &Path, json: bool) -> Result<()> {
+    let script = Script::from_file(path)?;
+    let (_, ctx) = load_context(json).await?;
+    if let Some(name) = &script.name {
+        eprintln!("{}", name.bold());
+    }
+
+    let total = script.steps.len();
+    for (i, step) in script.steps.iter().enumerate() {
+        eprintln!(
+            "{} {}",
+            format!("[{}/{}]", i + 1, total).dimmed(),
+            step.label()
+        );
+        match step.execute(&ctx).await {
+            Ok(result) => show_result(&ctx, &Output::from(&result), &result.to_string()),
+            Err(e) => {
+                eprintln!(
+                    "{} {} passed, 1 failed ({}), {} skipped",
+                    "Summary:".red(),
+                    i,
+                    step.label(),
+                    total - i - 1
+                );
+                return Err(e);
+            }
+        }
+    }
+    eprintln!("{} {} steps passed", "Summary:".green(), total);
+    Ok(())
+}
+
+/// Returns a token cancelled on the first Ctrl-C.
+fn shutdown_on_ctrl_c() -> CancellationToken {
+    let shutdown = CancellationToken::new();
+    let trigger = shutdown.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            trigger.cancel();
+        }
+    });
+    shutdown
+}
+
+/// Sends one request to a running daemon and prints its result as JSON.
+///
+/// # Arguments
+///
+/// * `socket` - Socket path, defaulting to `$HOME/.inachus/daemon.sock`
+/// * `method` - The daemon method
+/// * `params` - The method parameters as a JSON string
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if the request failed
+#[cfg(unix)]
+pub async fn request(socket: Option<PathBuf>, method: &str, params: Option<&str>) -> Result<()> {
+    let socket = socket.unwrap_or_else(daemon::default_socket_path);
+    let params = match params {
+        Some(params) => serde_json::from_str(params)?,
+        None => serde_json::Value::Null,
+    };
+    let result = DaemonClient::new(socket).request(method, params).await?;
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
+/// Loads the configuration, contracts and ABIs into a fresh context, with
+/// the profile selected by `--profile` applied.
+///
+/// # Arguments
+///
+/// * `json` - Whether to print results as JSON regardless of `output`
+async fn load_context(json: bool) -> Result<(Config, GlobalContext)> {
+    load_profile(json, PROFILE.get().map(String::as_str), true).await
+}
+
+/// Loads the context of the daemon or the HTTP server, which never prompt:
+/// transactions breaking the policy are refused.
+async fn load_service_context() -> Result<(Config, GlobalContext)> {
+    load_profile(false, PROFILE.get().map(String::as_str), false).await
+}
+
+/// Loads the configuration with a profile applied and builds the global
+/// context from it; see [`Config::with_profile`].
+///
+/// # Arguments
+///
+/// * `json` - Whether to print results as JSON regardless of `output`
+/// * `profile` - Name of the profile, the configured one when `None`
+/// * `interactive` - Whether policy violations may be confirmed from the terminal
+async fn load_profile(
+    json: bool,
+    profile: Option<&str>,
+    interactive: bool,
+) -> Result<(Config, GlobalContext)> {
+    let data_dir = inachus::data_dir();
+    let global = Config::global_path(&data_dir);
+    bootstrap(&data_dir, &global).await?;
+
+    // Precedence: CLI flags, then environment variables, then the project-local
+    // configuration, then the global one.
+    let local = Config::local_path(&std::env::current_dir()?, &data_dir);
+    let layers: Vec<PathBuf> = std::iter::once(global.clone())
+        .chain(local.clone())
+        .collect();
+    for layer in &layers {
+        if let Some(migrated) = migrate::migrate(layer, &data_dir)? {
+            eprintln!(
+                "{} {} from version {} to {}, backup at {}",
+                "Upgraded".green(),
+                layer.display(),
+                migrated.from,
+                migrate::CONFIG_VERSION,
+                migrated.backup.display()
+            );
+        }
+    }
+    let profile = profile
+        .map(str::to_string)
+        .or_else(|| std::env::var(PROFILE_ENV).ok());
+    let mut config = Config::from_files(&layers)?.with_profile(profile.as_deref())?;
+    config.apply_env(|name| std::env::var(name).ok())?;
+    if let Some(mode) = MODE.get() {
+        config.mode = *mode;
+    }
+    if config.mode == OperatingMode::ReadOnly {
+        config.clear_signers();
+    }
+    if PARANOID.load(Ordering::Relaxed) {
+        config.paranoid = true;
+    }
+    config.validate()?;
+    // A profile's contracts file is only created once a deployment is saved.
+    let contracts_path = data_dir.join(&config.contracts_file);
+    let contracts = match contracts_path.exists() {
+        true => ContractInfo::load_all(&contracts_path)?,
+        false => Vec::new(),
+    };
+
+    let mut ctx = build_context(&data_dir, &config, contracts)?;
+    ctx.config_path = local.unwrap_or(global);
+    ctx.timeouts = config.timeouts()?;
+    ctx.retry = config.retry.backoff()?;
+    ctx.connect().await?;
+    ctx.etherscan_api_key = config.etherscan_api_key.clone();
+    ctx.rpc_api_keys = config.rpc_api_keys.clone();
+    ctx.safe = config.safe.clone();
+    ctx.private_relay = config.private_relay.clone();
+    ctx.create2_factory = config.create2_factory.unwrap_or(create2::DEFAULT_FACTORY);
+    ctx.gas = config.gas.clone();
+    ctx.mode = config.mode;
+    ctx.cross_check_rpc_url = config.cross_check_endpoint();
+    ctx.require_simulation = config.policy.require_simulation || config.simulate_before_send;
+    ctx.confirmations = config.confirmations;
+    ctx.address_book = config.address_book.clone();
+    ctx.output = match json {
+        true => OutputFormat::Json,
+        false => config.output,
+    };
+    if let Some(ledger) = &config.ledger {
+        ctx.signer = Some(Signer::ledger(&ledger.derivation_path, config.chain_id).await?);
+    }
+    if let Some(mnemonic) = &config.mnemonic {
+        ctx.signer = Some(select_mnemonic_account(&ctx, mnemonic).await?);
+    }
+    ctx.wallets = config.wallets.clone();
+    match (ctx.active_signer(), config.wallets.first()) {
+        (Some(signer), _) => {
+            ctx.unlocked_wallets
+                .insert(wallet::DEFAULT_WALLET.to_string(), signer);
+        }
+        (None, Some(first)) => {
+            let signer = first.unlock(&data_dir, config.chain_id).await?;
+            ctx.unlocked_wallets
+                .insert(first.name.clone(), signer.clone());
+            ctx.signer = Some(signer);
+        }
+        (None, None) => {}
+    }
+    ctx.events.subscribe(events::log_event);
+    ctx.abi_source = abi::source::default_sources(
+        &ctx.abis_dir,
+        config.abi_registry.as_deref(),
+        Some(&ctx.abi_cache_dir()),
+    );
+    ctx.reload_abis().await?;
+    for hook in &config.hooks {
+        ctx.hooks.register(CommandHook::from(hook));
+    }
+    // Last, to check the options as changed by the other hooks.
+    let policy = config.policy.policy(&ctx.address_book)?;
+    let interactive = interactive && std::io::stdin().is_terminal();
+    ctx.hooks.register(PolicyHook::new(policy, interactive));
+
+    Ok((config, ctx))
+}
+
+/// Derives the configured mnemonic's account to sign with, prompting for it
+/// with each account's balance unless `index` is set.
+async fn select_mnemonic_account(ctx: &GlobalContext, mnemonic: &MnemonicConfig) -> Result<Signer> {
+    let phrase = mnemonic.phrase().ok_or_else(|| {
+        Error::InvalidConfig(format!(
+            "mnemonic.phrase is not set and {} is empty",
+            signer::MNEMONIC_ENV
+        ))
+    })?;
+    if let Some(index) = mnemonic.index {
+        return Signer::mnemonic(&phrase, &mnemonic.path(index));
+    }
+
+    let read = ctx.read_context()?;
+    let mut accounts = Vec::new();
+    for index in 0..mnemonic.accounts {
+        let signer = Signer::mnemonic(&phrase, &mnemonic.path(index))?;
+        let balance = read.get_balance(signer.address()).await.ok();
+        accounts.push((signer, balance));
+    }
+    let index = prompt::select_account(&accounts)?;
+    Ok(accounts.swap_remove(index).0)
+}
+
+/// Creates the Inachus directory with default files if it does not exist yet.
+/// Without a global configuration, one is written from the answers to the
+/// setup wizard, or from the defaults when not run from a terminal.
+async fn bootstrap(data_dir: &Path, global: &Path) -> Result<()> {
+    let abi_dir = data_dir.join(ABI_DIR);
+    if !abi_dir.exists() {
+        fs::create_dir_all(&abi_dir)?;
+        fs::write(abi_dir.join("TetherToken.abi"), TETHER_ABI)?;
+    }
+
+    if !global.exists() {
+        let config = match std::io::stdin().is_terminal() {
+            true => setup::render_config(DEFAULT_CONFIG, &setup::run_wizard(data_dir).await?)?,
+            false => DEFAULT_CONFIG.to_string(),
+        };
+        fs::write(global, config)?;
+        eprintln!(
+            "{} {}",
+            "Configuration written to".green(),
+            global.display()
+        );
+    }
+
+    let contracts_path = data_dir.join(CONTRACTS_FILE);
+    if !contracts_path.exists() {
+        fs::write(&contracts_path, DEFAULT_CONTRACTS)?;
+    }
+    Ok(())
+}
+
+/// Builds the global context from the loaded configuration.
+fn build_context(
+    data_dir: &Path,
+    config: &Config,
+    contracts: Vec<ContractInfo>,
+) -> Result<GlobalContext> {
+    let abis_dir = if config.abi_dir.is_absolute() {
+        config.abi_dir.clone()
+    } else {
+        data_dir.join(&config.abi_dir)
+    };
+
+    let contract = match (&config.contract_name, &config.contract_address) {
+        (Some(name), Some(address)) => Some((name.clone(), address.clone())),
+        _ => contracts.iter().find_map(|info| {
+            let address = info.address_on(config.chain_id)?;
+            Some((info.name.clone(), address.to_string()))
+        }),
+    };
+
+    let signer = match (&config.keystore, &config.private_key) {
+        (Some(keystore), _) => {
+            let path = data_dir.join(keystore);
+            let passphrase = match std::env::var(wallet::PASSPHRASE_ENV) {
+                Ok(passphrase) => passphrase,
+                Err(_) => prompt::input_passphrase(&path)?,
+            };
+            Some(Signer::keystore(&path, &passphrase)?)
+        }
+        (None, Some(private_key)) => Some(
+            PrivateKeySigner::from_str(private_key)
+                .map(Signer::local)
+                .map_err(|e| Error::InvalidPrivateKey(e.to_string()))?,
+        ),
+        (None, None) => None,
+    };
+
+    let mut builder = GlobalContext::builder()
+        .abis_dir(abis_dir)
+        .rpc_url(config.rpc_url.primary())
+        .chain_id(config.chain_id)
+        .data_dir(data_dir);
+    if let Some((name, address)) = contract {
+        builder = builder.contract(&name, &address);
+    }
+    if let Some(signer) = signer {
+        builder = builder.signer(signer);
+    }
+    let mut ctx = builder.build()?;
+    ctx.fallback_rpc_urls = config.rpc_url.fallbacks();
+    ctx.chains = ChainRegistry::load(data_dir.join(CHAINS_FILE))
+        .map_err(|e| Error::InvalidConfig(format!("Invalid bundled chain list: {}", e)))?;
+    ctx.chains
+        .merge_custom(data_dir.join(CUSTOM_CHAINS_FILE))
+        .map_err(|e| Error::InvalidConfig(format!("Invalid custom chain list: {}", e)))?;
+    ctx.contracts = contracts;
+    ctx.contracts_file = config.contracts_file.clone();
+    ctx.profile = config.profile.clone();
+    ctx.profiles = config.profiles.keys().cloned().collect();
+    Ok(ctx)
+}
@@ -1,4 +1,277 @@
 /// bin/inachus/main.rs
-fn main() {
-    println!("Hello, world!");
+mod app;
+
+use clap::{Args, Parser, Subcommand};
+use colored::Colorize;
+use inachus::batch::BatchMode;
+use inachus::config::OperatingMode;
+use std::path::PathBuf;
+
+/// Interactive smart contract client.
+#[derive(Debug, Parser)]
+#[command(name = "inachus", version, about)]
+struct Cli {
+    /// Command to run instead of the interactive workflow
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Print results and errors as JSON on stdout, other messages on stderr
+    #[arg(long, global = true)]
+    json: bool,
+    /// Configuration profile to use instead of the configured `profile`
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Load no signer, hide write methods and refuse to sign or send anything
+    #[arg(long, global = true, conflicts_with = "dry_run")]
+    read_only: bool,
+    /// Go through writes up to their simulation and preview, never sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Cross-check view calls on a second RPC endpoint and flag diverging results
+    #[arg(long, global = true)]
+    paranoid: bool,
+}
+
+/// Contract method targeted by a scripted command.
+#[derive(Debug, Args)]
+pub struct MethodArgs {
+    /// Contract name, defaults to the configured contract
+    #[arg(short, long)]
+    pub contract: Option<String>,
+    /// Address or address book alias, defaults to the contract's known address
+    #[arg(long)]
+    pub address: Option<String>,
+    /// Method name or signature, e.g. `transfer` or `transfer(address,uint256)`
+    #[arg(short, long)]
+    pub method: String,
+    /// Method argument, repeated in declaration order; `@file.json` reads it from a JSON file
+    #[arg(short, long = "arg")]
+    pub args: Vec<String>,
+    /// JSON file with every argument: an array in declaration order, or an object keyed by input name
+    #[arg(long, conflicts_with = "args")]
+    pub params_file: Option<PathBuf>,
+    /// Also write the result, raw and decoded, to this file as JSON
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Non-interactive commands.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Call a read-only method and print its result
+    Call {
+        #[command(flatten)]
+        target: MethodArgs,
+        /// Block to read the state at: a number, hash or tag such as `finalized`
+        #[arg(long, default_value = "latest")]
+        block: String,
+        /// Also call at this block and show how the outputs differ from `--block`
+        #[arg(long)]
+        compare: Option<String>,
+    },
+    /// Call a read-only method once per row of a CSV or JSON file of arguments
+    Batch {
+        /// Contract name, defaults to the configured contract
+        #[arg(short, long)]
+        contract: Option<String>,
+        /// Address or address book alias, defaults to the contract's known address
+        #[arg(long)]
+        address: Option<String>,
+        /// Method name or signature, e.g. `balanceOf`
+        #[arg(short, long)]
+        method: String,
+        /// File of argument rows: CSV, or JSON when it ends in `.json`
+        #[arg(short, long)]
+        input: PathBuf,
+        /// File the results are written to, CSV or JSON by extension; stdout when unset
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Calls in flight at once
+        #[arg(long, default_value_t = inachus::batch::DEFAULT_CONCURRENCY)]
+        concurrency: usize,
+        /// Group calls through Multicall3 instead of sending one `eth_call` each
+        #[arg(long)]
+        multicall: bool,
+        /// Calls per Multicall3 batch
+        #[arg(long, requires = "multicall", default_value_t = inachus::batch::DEFAULT_CHUNK_SIZE)]
+        chunk_size: usize,
+    },
+    /// Sign and send a transaction without any confirmation prompt
+    Send {
+        #[command(flatten)]
+        target: MethodArgs,
+        /// Native value sent with the transaction, e.g. `0.1 ether`
+        #[arg(long)]
+        value: Option<String>,
+        /// Gas limit, estimated when unset
+        #[arg(long)]
+        gas_limit: Option<u64>,
+        /// Nonce, the next pending one when unset; reusing a pending nonce replaces that transaction
+        #[arg(long)]
+        nonce: Option<u64>,
+        /// Return once the transaction is broadcast instead of waiting for its receipt
+        #[arg(long)]
+        no_wait: bool,
+        /// Print the unsigned transaction for offline signing instead of sending it
+        #[arg(long, conflicts_with = "no_wait")]
+        export: bool,
+        /// Sender of the exported transaction, defaults to the configured signer
+        #[arg(long, requires = "export")]
+        from: Option<String>,
+    },
+    /// Print the calldata of a method call
+    Encode {
+        #[command(flatten)]
+        target: MethodArgs,
+    },
+    /// Decode calldata, or return data with `--output`
+    Decode {
+        /// Contract name, defaults to the configured contract
+        #[arg(short, long)]
+        contract: Option<String>,
+        /// Method name or signature, looked up by selector when unset
+        #[arg(short, long)]
+        method: Option<String>,
+        /// Decode return data of the method instead of calldata
+        #[arg(long, requires = "method")]
+        output: bool,
+        /// Hex-encoded data
+        data: String,
+    },
+    /// Run a YAML script of calls and transactions, stopping at the first failure
+    Run {
+        /// Path to the script
+        script: PathBuf,
+    },
+    #[cfg(unix)]
+    /// Keep providers, signers and ABIs loaded and serve requests on a Unix socket
+    Daemon {
+        /// Socket path, defaults to ~/.inachus/daemon.sock
+        #[arg(long)]
+        socket: Option<PathBuf>,
+    },
+    /// Serve the configured contract endpoints over authenticated HTTP
+    Serve {
+        /// Address to listen on, defaults to `server.bind` from the configuration
+        #[arg(long)]
+        bind: Option<String>,
+    },
+    #[cfg(unix)]
+    /// Send one request to a running daemon and print its result
+    Rpc {
+        /// Daemon method, e.g. `call`, `send` or `contracts`
+        method: String,
+        /// Method parameters as a JSON object
+        params: Option<String>,
+        /// Socket path, defaults to ~/.inachus/daemon.sock
+        #[arg(long)]
+        socket: Option<PathBuf>,
+    },
+}
+
+/// Main entry point for the Inachus application.
+///
+/// Initializes logging and runs the requested command, or the interactive
+/// workflow loop when none is given, printing any fatal error before exiting
+/// with a non-zero status.
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = inachus::init() {
+        eprintln!("{} {}", "Error:".red(), e);
+        std::process::exit(1);
+    }
+
+    if let Some(profile) = cli.profile {
+        app::use_profile(profile);
+    }
+    if cli.read_only {
+        app::use_mode(OperatingMode::ReadOnly);
+    }
+    if cli.dry_run {
+        app::use_mode(OperatingMode::DryRun);
+    }
+    if cli.paranoid {
+        app::use_paranoid();
+    }
+    let json = cli.json;
+    let result = match cli.command {
+        None => app::run(json).await,
+        Some(Command::Call {
+            target,
+            block,
+            compare,
+        }) => app::call(&target, &block, compare.as_deref(), json).await,
+        Some(Command::Batch {
+            contract,
+            address,
+            method,
+            input,
+            output,
+            concurrency,
+            multicall,
+            chunk_size,
+        }) => {
+            let mode = match multicall {
+                true => BatchMode::Multicall(chunk_size),
+                false => BatchMode::Concurrent(concurrency),
+            };
+            app::batch(
+                contract.as_deref(),
+                address.as_deref(),
+                &method,
+                &input,
+                output.as_deref(),
+                mode,
+                json,
+            )
+            .await
+        }
+        Some(Command::Send {
+            target,
+            value,
+            gas_limit,
+            nonce,
+            no_wait,
+            export,
+            from,
+        }) => {
+            let export = export.then_some(from.as_deref());
+            app::send(
+                &target,
+                value.as_deref(),
+                gas_limit,
+                nonce,
+                !no_wait,
+                export,
+                json,
+            )
+            .await
+        }
+        Some(Command::Encode { target }) => app::encode(&target, json).await,
+        Some(Command::Decode {
+            contract,
+            method,
+            output,
+            data,
+        }) => app::decode(contract.as_deref(), method.as_deref(), output, &data, json).await,
+        Some(Command::Run { script }) => app::run_script(&script, json).await,
+        #[cfg(unix)]
+        Some(Command::Daemon { socket }) => app::daemon(socket).await,
+        Some(Command::Serve { bind }) => app::serve(bind).await,
+        #[cfg(unix)]
+        Some(Command::Rpc {
+            method,
+            params,
+            socket,
+        }) => app::request(socket, &method, params.as_deref()).await,
+    };
+
+    if let Err(e) = result {
+        match json {
+            true => println!("{}", inachus::output::Output::from(&e)),
+            false => eprintln!("{} {}", "Error:".red(), e),
+        }
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,511 @@
+use alloy::dyn_abi::{
+    DecodedEvent, DynSolType, DynSolValue, EventExt, FunctionExt, JsonAbiExt, Specifier,
+};
+use alloy::json_abi::{Event, EventParam, Function, JsonAbi, Param};
+use alloy::primitives::utils::{ParseUnits, Unit};
+use alloy::primitives::{B256, Bytes, I256, LogData, U256, keccak256};
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CodecError {
+    #[error("Invalid type {ty}: {reason}")]
+    InvalidType { ty: String, reason: String },
+
+    #[error("Cannot parse {input:?} as {ty}: {reason}")]
+    InvalidArgument {
+        input: String,
+        ty: String,
+        reason: String,
+    },
+
+    #[error("{signature} expects {expected} arguments, got {got}")]
+    ArgumentCount {
+        signature: String,
+        expected: usize,
+        got: usize,
+    },
+
+    #[error("Calldata selector 0x{selector} does not match {signature}")]
+    SelectorMismatch { selector: String, signature: String },
+
+    #[error("Cannot encode {0}")]
+    Encode(String),
+
+    #[error("Cannot decode {what}: {reason}")]
+    Decode { what: String, reason: String },
+}
+
+pub fn parse_type(ty: &str) -> Result<DynSolType, CodecError> {
+    DynSolType::parse(ty).map_err(|e| CodecError::InvalidType {
+        ty: ty.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+pub fn param_type(param: &Param) -> Result<DynSolType, CodecError> {
+    param.resolve().map_err(|e| CodecError::InvalidType {
+        ty: param.ty.clone(),
+        reason: e.to_string(),
+    })
+}
+
+pub fn event_param_type(param: &EventParam) -> Result<DynSolType, CodecError> {
+    param.resolve().map_err(|e| CodecError::InvalidType {
+        ty: param.ty.clone(),
+        reason: e.to_string(),
+    })
+}
+
+pub fn coerce(ty: &DynSolType, input: &str) -> Result<DynSolValue, CodecError> {
+    let input = input.trim();
+    let nested = matches!(
+        ty,
+        DynSolType::Array(_) | DynSolType::FixedArray(..) | DynSolType::Tuple(_)
+    );
+    if let (true, Ok(json @ Value::Array(_))) = (nested, serde_json::from_str::<Value>(input)) {
+        return from_json(ty, &json);
+    }
+    let coerced = ty.coerce_str(input);
+    match (ty, coerced) {
+        (_, Ok(value)) => Ok(value),
+        (DynSolType::Uint(bits), Err(_)) => parse_uint(input, *bits),
+        (DynSolType::Int(bits), Err(_)) => parse_int(input, *bits),
+        (_, Err(e)) => Err(CodecError::InvalidArgument {
+            input: input.to_string(),
+            ty: ty.to_string(),
+            reason: e.to_string(),
+        }),
+    }
+}
+
+pub fn parse_amount(input: &str, default_unit: Unit) -> Result<U256, CodecError> {
+    let invalid = |reason: String| CodecError::InvalidArgument {
+        input: input.to_string(),
+        ty: "amount".to_string(),
+        reason,
+    };
+    let cleaned: String = input.trim().chars().filter(|c| *c != '_').collect();
+    if let Some(hex) = cleaned.strip_prefix("0x") {
+        return U256::from_str_radix(hex, 16).map_err(|e| invalid(e.to_string()));
+    }
+    let chars: Vec<char> = cleaned.chars().collect();
+    let is_exponent = |i: usize| {
+        matches!(chars[i], 'e' | 'E')
+            && chars
+                .get(i + 1)
+                .is_some_and(|c| c.is_ascii_digit() || *c == '-' || *c == '+')
+    };
+    let split = (0..chars.len())
+        .find(|&i| chars[i].is_ascii_alphabetic() && !is_exponent(i))
+        .map(|i| chars[..i].iter().map(|c| c.len_utf8()).sum())
+        .unwrap_or(cleaned.len());
+    let (number, unit) = cleaned.split_at(split);
+    let unit = match unit.trim().to_lowercase().as_str() {
+        "" => default_unit,
+        "eth" => Unit::ETHER,
+        unit => unit.parse::<Unit>().map_err(|e| invalid(e.to_string()))?,
+    };
+    let (mantissa, exponent) = match number.trim().split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (
+            mantissa,
+            exponent
+                .parse::<i64>()
+                .map_err(|e| invalid(format!("invalid exponent: {}", e)))?,
+        ),
+        None => (number.trim(), 0),
+    };
+    let decimals = unit.get() as i64 + exponent;
+    let unit = u8::try_from(decimals)
+        .ok()
+        .and_then(Unit::new)
+        .ok_or_else(|| invalid("exponent out of range".to_string()))?;
+    let fraction = mantissa
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    if fraction > unit.get() as usize {
+        return Err(invalid("not a whole number of base units".to_string()));
+    }
+    let amount = ParseUnits::parse_units(mantissa, unit).map_err(|e| invalid(e.to_string()))?;
+    if amount.is_negative() {
+        return Err(invalid("must not be negative".to_string()));
+    }
+    Ok(amount.get_absolute())
+}
+
+fn parse_uint(input: &str, bits: usize) -> Result<DynSolValue, CodecError> {
+    let value = parse_amount(input, Unit::WEI).map_err(|e| retype(e, &format!("uint{}", bits)))?;
+    if value > U256::MAX >> (256 - bits) {
+        return Err(CodecError::InvalidArgument {
+            input: input.to_string(),
+            ty: format!("uint{}", bits),
+            reason: "out of range".to_string(),
+        });
+    }
+    Ok(DynSolValue::Uint(value, bits))
+}
+
+fn parse_int(input: &str, bits: usize) -> Result<DynSolValue, CodecError> {
+    let ty = format!("int{}", bits);
+    let trimmed = input.trim();
+    let (negative, magnitude) = match trimmed.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let magnitude = parse_amount(magnitude, Unit::WEI).map_err(|e| retype(e, &ty))?;
+    let limit = U256::from(1) << (bits - 1);
+    let in_range = match negative {
+        true => magnitude <= limit,
+        false => magnitude < limit,
+    };
+    let sign = match negative {
+        true => alloy::primitives::Sign::Negative,
+        false => alloy::primitives::Sign::Positive,
+    };
+    match I256::checked_from_sign_and_abs(sign, magnitude) {
+        Some(value) if in_range => Ok(DynSolValue::Int(value, bits)),
+        _ => Err(CodecError::InvalidArgument {
+            input: input.to_string(),
+            ty,
+            reason: "out of range".to_string(),
+        }),
+    }
+}
+
+fn retype(error: CodecError, ty: &str) -> CodecError {
+    match error {
+        CodecError::InvalidArgument { input, reason, .. } => CodecError::InvalidArgument {
+            input,
+            ty: ty.to_string(),
+            reason,
+        },
+        other => other,
+    }
+}
+
+pub fn from_json(ty: &DynSolType, value: &Value) -> Result<DynSolValue, CodecError> {
+    let invalid = |reason: String| CodecError::InvalidArgument {
+        input: value.to_string(),
+        ty: ty.to_string(),
+        reason,
+    };
+    let elements = |expected: Option<usize>| match value {
+        Value::Array(values) if expected.is_none_or(|n| n == values.len()) => Ok(values),
+        Value::Array(values) => Err(invalid(format!(
+            "expected {} elements, got {}",
+            expected.unwrap_or_default(),
+            values.len()
+        ))),
+        _ => Err(invalid("expected a JSON array".to_string())),
+    };
+    match ty {
+        DynSolType::Array(inner) => elements(None)?
+            .iter()
+            .map(|value| from_json(inner, value))
+            .collect::<Result<_, _>>()
+            .map(DynSolValue::Array),
+        DynSolType::FixedArray(inner, size) => elements(Some(*size))?
+            .iter()
+            .map(|value| from_json(inner, value))
+            .collect::<Result<_, _>>()
+            .map(DynSolValue::FixedArray),
+        DynSolType::Tuple(types) => elements(Some(types.len()))?
+            .iter()
+            .zip(types)
+            .map(|(value, ty)| from_json(ty, value))
+            .collect::<Result<_, _>>()
+            .map(DynSolValue::Tuple),
+        DynSolType::String => match value {
+            Value::String(s) => Ok(DynSolValue::String(s.clone())),
+            _ => Err(invalid("expected a JSON string".to_string())),
+        },
+        _ => match value {
+            Value::String(s) => coerce(ty, s),
+            Value::Number(_) | Value::Bool(_) => coerce(ty, &value.to_string()),
+            _ => Err(invalid(
+                "expected a JSON string, number or boolean".to_string(),
+            )),
+        },
+    }
+}
+
+pub fn coerce_args<S: AsRef<str>>(
+    function: &Function,
+    args: &[S],
+) -> Result<Vec<DynSolValue>, CodecError> {
+    if args.len() != function.inputs.len() {
+        return Err(CodecError::ArgumentCount {
+            signature: function.signature(),
+            expected: function.inputs.len(),
+            got: args.len(),
+        });
+    }
+    function
+        .inputs
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| coerce(&param_type(param)?, arg.as_ref()))
+        .collect()
+}
+
+pub fn encode_calldata<S: AsRef<str>>(
+    function: &Function,
+    args: &[S],
+) -> Result<Bytes, CodecError> {
+    let values = coerce_args(function, args)?;
+    encode_values(function, &values)
+}
+
+pub fn encode_values(function: &Function, values: &[DynSolValue]) -> Result<Bytes, CodecError> {
+    function
+        .abi_encode_input(values)
+        .map(Bytes::from)
+        .map_err(|e| CodecError::Encode(e.to_string()))
+}
+
+pub fn decode_input(function: &Function, calldata: &[u8]) -> Result<Vec<DynSolValue>, CodecError> {
+    let (selector, args) = calldata.split_at(calldata.len().min(4));
+    if selector != function.selector().as_slice() {
+        return Err(CodecError::SelectorMismatch {
+            selector: hex::encode(selector),
+            signature: function.signature(),
+        });
+    }
+    function
+        .abi_decode_input(args, true)
+        .map_err(|e| CodecError::Decode {
+            what: format!("{} input", function.name),
+            reason: e.to_string(),
+        })
+}
+
+pub fn decode_output(function: &Function, data: &[u8]) -> Result<Vec<DynSolValue>, CodecError> {
+    function
+        .abi_decode_output(data, true)
+        .map_err(|e| CodecError::Decode {
+            what: format!("{} output", function.name),
+            reason: e.to_string(),
+        })
+}
+
+pub fn decode_log(event: &Event, log: &LogData) -> Result<DecodedEvent, CodecError> {
+    event.decode_log(log, true).map_err(|e| CodecError::Decode {
+        what: format!("{} log", event.name),
+        reason: e.to_string(),
+    })
+}
+
+pub fn encode_topic(value: &DynSolValue) -> B256 {
+    value
+        .as_word()
+        .unwrap_or_else(|| keccak256(value.abi_encode_packed()))
+}
+
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+pub fn decode_revert(data: &[u8], abi: Option<&JsonAbi>) -> Option<(String, Vec<DynSolValue>)> {
+    let (selector, args) = data.split_at_checked(4)?;
+    let (name, ty) = if selector == ERROR_SELECTOR {
+        (
+            "Error".to_string(),
+            DynSolType::Tuple(vec![DynSolType::String]),
+        )
+    } else if selector == PANIC_SELECTOR {
+        (
+            "Panic".to_string(),
+            DynSolType::Tuple(vec![DynSolType::Uint(256)]),
+        )
+    } else {
+        let error = abi?
+            .errors()
+            .find(|error| error.selector().as_slice() == selector)?;
+        let types = error
+            .inputs
+            .iter()
+            .map(param_type)
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        (error.name.clone(), DynSolType::Tuple(types))
+    };
+    match ty.abi_decode_params(args).ok()? {
+        DynSolValue::Tuple(values) => Some((name, values)),
+        _ => None,
+    }
+}
+
+pub fn panic_reason(code: U256) -> &'static str {
+    match code.saturating_to::<u64>() {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "corrupted storage byte array",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to uninitialized function pointer",
+        _ => "unknown panic code",
+    }
+}
+
+pub fn to_json(value: &DynSolValue) -> Value {
+    match value {
+        DynSolValue::Bool(b) => Value::Bool(*b),
+        DynSolValue::Int(n, _) => Value::String(n.to_string()),
+        DynSolValue::Uint(n, _) => Value::String(n.to_string()),
+        DynSolValue::FixedBytes(word, size) => {
+            Value::String(format!("0x{}", hex::encode(&word[..*size])))
+        }
+        DynSolValue::Address(address) => Value::String(address.to_checksum(None)),
+        DynSolValue::Function(function) => Value::String(function.to_string()),
+        DynSolValue::Bytes(bytes) => Value::String(format!("0x{}", hex::encode(bytes))),
+        DynSolValue::String(s) => Value::String(s.clone()),
+        DynSolValue::Array(values)
+        | DynSolValue::FixedArray(values)
+        | DynSolValue::Tuple(values) => Value::Array(values.iter().map(to_json).collect()),
+        DynSolValue::CustomStruct {
+            prop_names, tuple, ..
+        } => Value::Object(
+            prop_names
+                .iter()
+                .cloned()
+                .zip(tuple.iter().map(to_json))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    #[test]
+    fn test_encode_calldata() {
+        let function = Function::parse("transfer(address to, uint256 amount)").unwrap();
+        let calldata = encode_calldata(
+            &function,
+            &["0xdAC17F958D2ee523a2206206994597C13D831ec7", "1000"],
+        )
+        .unwrap();
+        assert_eq!(&calldata[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+
+        let decoded = decode_input(&function, &calldata).unwrap();
+        assert_eq!(decoded[1], DynSolValue::Uint(U256::from(1000), 256));
+    }
+
+    #[test]
+    fn test_coerce_nested() {
+        let ty = parse_type("(uint8,bool[])[]").unwrap();
+        let value = coerce(&ty, "[(1, [true, false]), (2, [])]").unwrap();
+        assert_eq!(
+            to_json(&value),
+            serde_json::json!([["1", [true, false]], ["2", []]])
+        );
+
+        let function = Function::parse("f(uint8)").unwrap();
+        assert!(matches!(
+            coerce_args(&function, &["1", "2"]),
+            Err(CodecError::ArgumentCount { .. })
+        ));
+        assert!(coerce_args(&function, &["256"]).is_err());
+    }
+
+    #[test]
+    fn test_coerce_json() {
+        let ty = parse_type("string[]").unwrap();
+        let value = coerce(&ty, r#"["a, b", "say \"hi\"", "c\\d"]"#).unwrap();
+        assert_eq!(
+            value,
+            DynSolValue::Array(vec![
+                DynSolValue::String("a, b".to_string()),
+                DynSolValue::String("say \"hi\"".to_string()),
+                DynSolValue::String("c\\d".to_string()),
+            ])
+        );
+
+        let ty = parse_type("(uint8,bool[])[2]").unwrap();
+        let value = coerce(&ty, r#"[[1, [true]], ["2", []]]"#).unwrap();
+        assert_eq!(
+            to_json(&value),
+            serde_json::json!([["1", [true]], ["2", []]])
+        );
+        assert!(coerce(&ty, "[[1, [true]]]").is_err());
+        assert!(coerce(&parse_type("uint8[]").unwrap(), "[256]").is_err());
+        assert!(
+            coerce(
+                &parse_type("address[]").unwrap(),
+                "[0x0000000000000000000000000000000000000001]"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_coerce_amounts() {
+        let uint = DynSolType::Uint(256);
+        let wei = |n: u128| DynSolValue::Uint(U256::from(n), 256);
+        assert_eq!(coerce(&uint, "1 ether").unwrap(), wei(10u128.pow(18)));
+        assert_eq!(coerce(&uint, "2.5 gwei").unwrap(), wei(2_500_000_000));
+        assert_eq!(coerce(&uint, "1e18").unwrap(), wei(10u128.pow(18)));
+        assert_eq!(coerce(&uint, "1.5e3 gwei").unwrap(), wei(1_500_000_000_000));
+        assert_eq!(coerce(&uint, "1_000_000").unwrap(), wei(1_000_000));
+        assert_eq!(coerce(&uint, "0x10").unwrap(), wei(16));
+        assert!(coerce(&uint, "1e-1").is_err());
+        assert!(coerce(&uint, "1 furlong").is_err());
+        assert!(coerce(&DynSolType::Uint(8), "1e3").is_err());
+
+        let int = DynSolType::Int(8);
+        assert_eq!(
+            coerce(&int, "-1e2").unwrap(),
+            DynSolValue::Int(I256::try_from(-100).unwrap(), 8)
+        );
+        assert!(coerce(&int, "-1_28").is_ok());
+        assert!(coerce(&int, "1_28").is_err());
+
+        assert_eq!(
+            parse_amount("0.01", Unit::ETHER).unwrap(),
+            U256::from(10u128.pow(16))
+        );
+    }
+
+    #[test]
+    fn test_decode_revert() {
+        let reason = DynSolValue::Tuple(vec![DynSolValue::String("nope".to_string())]);
+        let data = [&ERROR_SELECTOR[..], &reason.abi_encode_params()].concat();
+        assert_eq!(
+            decode_revert(&data, None),
+            Some((
+                "Error".to_string(),
+                vec![DynSolValue::String("nope".to_string())]
+            ))
+        );
+
+        let code = DynSolValue::Tuple(vec![DynSolValue::Uint(U256::from(0x11), 256)]);
+        let data = [&PANIC_SELECTOR[..], &code.abi_encode_params()].concat();
+        let (name, args) = decode_revert(&data, None).unwrap();
+        assert_eq!(name, "Panic");
+        assert_eq!(
+            panic_reason(args[0].as_uint().unwrap().0),
+            "arithmetic overflow or underflow"
+        );
+
+        let abi =
+            JsonAbi::parse(["error InsufficientBalance(uint256 available, uint256 required)"])
+                .unwrap();
+        let error = &abi.errors["InsufficientBalance"][0];
+        let values = DynSolValue::Tuple(vec![
+            DynSolValue::Uint(U256::from(1), 256),
+            DynSolValue::Uint(U256::from(2), 256),
+        ]);
+        let data = [&error.selector()[..], &values.abi_encode_params()].concat();
+        assert_eq!(
+            decode_revert(&data, Some(&abi)).map(|(name, args)| (name, args.len())),
+            Some(("InsufficientBalance".to_string(), 2))
+        );
+        assert_eq!(decode_revert(&data, None), None);
+    }
+}
@@ -0,0 +1,16 @@
+//! Replaces the bundled snapshot with the latest chainid.network list:
+//! `cargo run -p chain_info --features refresh --example update_snapshot`.
+
+use chain_info::{CHAINS_URL, ChainRegistry, ChainSource};
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/chains.json");
+    let registry = ChainRegistry::refresh(path, Duration::ZERO).await?;
+    if registry.source() != ChainSource::Remote {
+        return Err(format!("Could not download {}", CHAINS_URL).into());
+    }
+    println!("Saved {} chains to {}", registry.len(), path);
+    Ok(())
+}
@@ -1,8 +1,19 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use thiserror::Error;
 
+#[cfg(feature = "probe")]
+mod probe;
+mod registry;
+mod rollup;
+
+#[cfg(feature = "probe")]
+pub use probe::{PROBE_TIMEOUT, RpcHealth, RpcProbe};
+pub use registry::{BUNDLED_CHAINS, CHAINS_URL, ChainRegistry, ChainSource, DEFAULT_TTL};
+pub use rollup::RollupStack;
+
 #[derive(Debug, Error)]
 pub enum ChainError {
     #[error("Failed to read chain info file: {0}")]
@@ -13,15 +24,73 @@ pub enum ChainError {
 
     #[error("Chain ID {0} not found")]
     ChainNotFound(u64),
+
+    #[cfg(any(feature = "probe", feature = "refresh"))]
+    #[error("HTTP request failed: {0}")]
+    FetchError(#[from] reqwest::Error),
+
+    #[error("Unexpected RPC response: {0}")]
+    InvalidResponse(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NativeCurrency {
     pub name: String,
     pub symbol: String,
     pub decimals: i32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Explorer {
+    pub name: String,
+    pub url: String,
+    /// `EIP3091` when the explorer serves `/tx/`, `/address/` and `/block/` paths
+    #[serde(default)]
+    pub standard: Option<String>,
+}
+
+/// Lifecycle of a chain, as listed by chainid.network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainStatus {
+    Active,
+    Incubating,
+    Deprecated,
+    /// A status this version does not know
+    #[serde(other)]
+    Unknown,
+}
+
+/// The chain an L2 or a shard settles on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParentChain {
+    /// `L2` or `shard`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// CAIP-2 style ID of the parent, e.g. `eip155-1`
+    pub chain: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bridges: Vec<Bridge>,
+}
+
+impl ParentChain {
+    /// The parent's chain ID, `None` when it is not an EVM chain.
+    pub fn chain_id(&self) -> Option<u64> {
+        self.chain.strip_prefix("eip155-")?.parse().ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bridge {
+    pub url: String,
+}
+
+/// Words in the names of testnets, since chainid.network does not flag them.
+const TESTNET_MARKERS: [&str; 12] = [
+    "testnet", "devnet", "sepolia", "holesky", "hoodi", "goerli", "ropsten", "rinkeby", "kovan",
+    "amoy", "chiado", "fuji",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainInfo {
     pub name: String,
@@ -34,26 +103,211 @@ pub struct ChainInfo {
     #[serde(rename = "nativeCurrency")]
     pub native_currency: NativeCurrency,
     pub rpc: Vec<String>,
+    #[serde(default)]
     pub faucets: Vec<String>,
-    #[serde(rename = "infoURL")]
+    #[serde(rename = "infoURL", default)]
     pub info_url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub explorers: Vec<Explorer>,
+    /// Network family, e.g. `ETH` for Ethereum, its testnets and rollups
+    #[serde(rename = "chain", default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ChainStatus>,
+    /// Warnings about the chain, e.g. `reusedChainId`
+    #[serde(rename = "redFlags", default, skip_serializing_if = "Vec::is_empty")]
+    pub red_flags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ParentChain>,
 }
 
 impl ChainInfo {
+    /// A chain known only by its ID, name and RPC URL, paying gas in ether,
+    /// e.g. an internal devnet to [`ChainRegistry::register`].
+    pub fn custom(chain_id: u64, name: &str, rpc_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            chain_id,
+            short_name: name.trim().to_lowercase().replace(char::is_whitespace, "-"),
+            network_id: chain_id,
+            native_currency: NativeCurrency {
+                name: "Ether".to_string(),
+                symbol: "ETH".to_string(),
+                decimals: 18,
+            },
+            rpc: vec![rpc_url.to_string()],
+            faucets: Vec::new(),
+            info_url: String::new(),
+            explorers: Vec::new(),
+            family: None,
+            status: None,
+            red_flags: Vec::new(),
+            parent: None,
+        }
+    }
+
     pub fn parse_chains_json(path: impl AsRef<Path>) -> Result<Vec<ChainInfo>, ChainError> {
         let json_data = fs::read_to_string(path)?;
-        let chain_infos = serde_json::from_str(&json_data)?;
+        Self::parse_chains_str(&json_data)
+    }
+
+    pub fn parse_chains_str(json_data: &str) -> Result<Vec<ChainInfo>, ChainError> {
+        let chain_infos = serde_json::from_str(json_data)?;
         Ok(chain_infos)
     }
 
-    pub fn get_by_id(chain_infos: &[ChainInfo], chain_id: u64) -> Result<&ChainInfo, ChainError> {
-        chain_infos
+    /// The explorer links are built against, preferring EIP-3091 ones.
+    pub fn explorer(&self) -> Option<&Explorer> {
+        self.explorers
             .iter()
-            .find(|info| info.chain_id == chain_id)
-            .ok_or(ChainError::ChainNotFound(chain_id))
+            .find(|explorer| explorer.standard.as_deref() == Some("EIP3091"))
+            .or_else(|| self.explorers.first())
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.status == Some(ChainStatus::Deprecated)
+    }
+
+    /// Whether the chain is a testnet, going by its name, e.g. `Sepolia` or
+    /// `Base Sepolia Testnet`.
+    pub fn is_testnet(&self) -> bool {
+        let name = self.name.to_lowercase();
+        TESTNET_MARKERS.iter().any(|marker| name.contains(marker))
+    }
+
+    /// Whether the chain is a layer 2 settling on another chain.
+    pub fn is_l2(&self) -> bool {
+        self.parent
+            .as_ref()
+            .is_some_and(|parent| parent.kind.eq_ignore_ascii_case("L2"))
+    }
+
+    /// The ID of the chain this one settles on, if any.
+    pub fn parent_chain_id(&self) -> Option<u64> {
+        self.parent.as_ref()?.chain_id()
+    }
+
+    /// The rollup stack the chain is built on, `None` for L1s and unknown chains.
+    pub fn rollup_stack(&self) -> Option<RollupStack> {
+        RollupStack::of(self.chain_id)
+    }
+
+    pub fn tx_url(&self, hash: impl std::fmt::Display) -> Option<String> {
+        self.explorer_url("tx", hash)
+    }
+
+    pub fn address_url(&self, address: impl std::fmt::Display) -> Option<String> {
+        self.explorer_url("address", address)
+    }
+
+    pub fn block_url(&self, number: u64) -> Option<String> {
+        self.explorer_url("block", number)
+    }
+
+    fn explorer_url(&self, kind: &str, id: impl std::fmt::Display) -> Option<String> {
+        let explorer = self.explorer()?;
+        Some(format!(
+            "{}/{}/{}",
+            explorer.url.trim_end_matches('/'),
+            kind,
+            id
+        ))
+    }
+
+    /// The `wallet_addEthereumChain` (EIP-3085) parameters adding this chain
+    /// to a wallet, with the public HTTP RPC URLs, since wallets reject
+    /// WebSocket ones and cannot fill API keys.
+    pub fn to_add_chain_params(&self) -> AddChainParams {
+        AddChainParams {
+            chain_id: format!("{:#x}", self.chain_id),
+            chain_name: self.name.clone(),
+            native_currency: self.native_currency.clone(),
+            rpc_urls: self
+                .public_rpcs()
+                .into_iter()
+                .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+                .map(str::to_string)
+                .collect(),
+            block_explorer_urls: self
+                .explorers
+                .iter()
+                .map(|explorer| explorer.url.clone())
+                .collect(),
+        }
+    }
+
+    /// RPC URLs that need no API key.
+    pub fn public_rpcs(&self) -> Vec<&str> {
+        self.rpc
+            .iter()
+            .map(String::as_str)
+            .filter(|url| !url.contains("${"))
+            .filter(|url| is_supported_scheme(url))
+            .collect()
+    }
+
+    /// RPC URLs with `${NAME}` placeholders filled from `keys`, then from
+    /// environment variables. URLs whose keys are unavailable are dropped.
+    pub fn rpcs_with_keys(&self, keys: &HashMap<String, String>) -> Vec<String> {
+        self.keyed_rpcs(keys)
+            .into_iter()
+            .map(|(_, url)| url)
+            .collect()
+    }
+
+    /// Like [`ChainInfo::rpcs_with_keys`], pairing each filled URL with the
+    /// URL as listed, placeholders kept, to show or save it without the key.
+    pub fn keyed_rpcs(&self, keys: &HashMap<String, String>) -> Vec<(String, String)> {
+        self.rpc
+            .iter()
+            .filter(|url| is_supported_scheme(url))
+            .filter_map(|url| {
+                let filled = fill_placeholders(url, |name| {
+                    keys.get(name).cloned().or_else(|| std::env::var(name).ok())
+                })?;
+                Some((url.clone(), filled))
+            })
+            .collect()
     }
 }
 
+/// The parameter of a `wallet_addEthereumChain` request, as defined by EIP-3085.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddChainParams {
+    /// `0x`-prefixed hexadecimal chain ID
+    pub chain_id: String,
+    pub chain_name: String,
+    pub native_currency: NativeCurrency,
+    pub rpc_urls: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_explorer_urls: Vec<String>,
+}
+
+/// Replaces each `${NAME}` in `template` with `lookup(NAME)`, or returns
+/// `None` if any placeholder cannot be filled or is left unterminated.
+pub fn fill_placeholders(
+    template: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..].find('}')? + start;
+        filled.push_str(&rest[..start]);
+        filled.push_str(&lookup(&rest[start + 2..end]).filter(|value| !value.is_empty())?);
+        rest = &rest[end + 1..];
+    }
+    filled.push_str(rest);
+    Some(filled)
+}
+
+fn is_supported_scheme(url: &str) -> bool {
+    ["https://", "http://", "wss://", "ws://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,6 +327,11 @@ mod tests {
             rpc: vec!["https://test.rpc".to_string()],
             faucets: vec!["https://test.faucet".to_string()],
             info_url: "https://test.info".to_string(),
+            explorers: vec![],
+            family: None,
+            status: None,
+            red_flags: vec![],
+            parent: None,
         };
 
         let json = serde_json::to_string(&chain_info).unwrap();
@@ -97,6 +356,11 @@ mod tests {
                 rpc: vec![],
                 faucets: vec![],
                 info_url: "".to_string(),
+                explorers: vec![],
+                family: None,
+                status: None,
+                red_flags: vec![],
+                parent: None,
             },
             ChainInfo {
                 name: "Test Chain 2".to_string(),
@@ -111,14 +375,287 @@ mod tests {
                 rpc: vec![],
                 faucets: vec![],
                 info_url: "".to_string(),
+                explorers: vec![],
+                family: None,
+                status: None,
+                red_flags: vec![],
+                parent: None,
             },
         ];
 
-        let found = ChainInfo::get_by_id(&chain_infos, 1).unwrap();
+        let registry = ChainRegistry::new(chain_infos);
+        let found = registry.get(1).unwrap();
         assert_eq!(found.chain_id, 1);
         assert_eq!(found.name, "Test Chain 1");
+        assert_eq!(registry.get_by_short_name("test2").unwrap().chain_id, 2);
 
-        let not_found = ChainInfo::get_by_id(&chain_infos, 999);
+        let not_found = registry.get(999);
         assert!(matches!(not_found, Err(ChainError::ChainNotFound(999))));
     }
+
+    #[test]
+    fn test_search_and_public_rpcs() {
+        let registry = ChainRegistry::new(ChainInfo::parse_chains_str(
+            r#"[
+                {"name":"Ethereum Mainnet","chainId":1,"shortName":"eth","networkId":1,"nativeCurrency":{"name":"Ether","symbol":"ETH","decimals":18},"rpc":["https://mainnet.infura.io/v3/${INFURA_API_KEY}","https://cloudflare-eth.com","wss://eth.drpc.org"],"faucets":[],"infoURL":"https://ethereum.org"},
+                {"name":"OP Mainnet","chainId":10,"shortName":"oeth","networkId":10,"nativeCurrency":{"name":"Ether","symbol":"ETH","decimals":18},"rpc":["https://mainnet.optimism.io"],"faucets":[],"infoURL":"https://optimism.io"}
+            ]"#,
+        )
+        .unwrap());
+
+        let names = |query| {
+            registry
+                .search(query)
+                .iter()
+                .map(|info| info.chain_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("mainnet"), vec![1, 10]);
+        assert_eq!(names(" 10 "), vec![10]);
+        assert_eq!(names("ETH"), vec![1, 10]);
+        assert_eq!(names("oeth"), vec![10]);
+        assert_eq!(names("ether"), vec![1]);
+        assert_eq!(names("op-main"), vec![10]);
+        assert!(names("unknown").is_empty());
+
+        assert_eq!(
+            registry.chains()[0].public_rpcs(),
+            vec!["https://cloudflare-eth.com", "wss://eth.drpc.org"]
+        );
+    }
+
+    #[test]
+    fn test_registry_cache_and_bundled_fallback() {
+        let dir = std::env::temp_dir().join(format!("chain_info-{}", std::process::id()));
+        let cache = dir.join("chains.json");
+        let _ = fs::remove_dir_all(&dir);
+
+        let registry = ChainRegistry::load(&cache).unwrap();
+        assert_eq!(registry.source(), ChainSource::Bundled);
+        assert!(registry.get(1).is_ok());
+        assert!(ChainRegistry::is_stale(&cache, DEFAULT_TTL));
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &cache,
+            r#"[{"name":"Cached Chain","chainId":7,"shortName":"c","networkId":7,"nativeCurrency":{"name":"C","symbol":"C","decimals":18},"rpc":[],"faucets":[],"infoURL":""}]"#,
+        )
+        .unwrap();
+        let registry = ChainRegistry::load(&cache).unwrap();
+        assert_eq!(registry.source(), ChainSource::Cache);
+        assert_eq!(registry.chains()[0].chain_id, 7);
+        assert!(!ChainRegistry::is_stale(&cache, DEFAULT_TTL));
+        assert!(ChainRegistry::is_stale(&cache, std::time::Duration::ZERO));
+
+        fs::write(&cache, "not json").unwrap();
+        assert_eq!(
+            ChainRegistry::load(&cache).unwrap().source(),
+            ChainSource::Bundled
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fill_api_key_placeholders() {
+        let keys = HashMap::from([("INFURA_API_KEY".to_string(), "abc".to_string())]);
+        let lookup = |name: &str| keys.get(name).cloned();
+        assert_eq!(
+            fill_placeholders("https://mainnet.infura.io/v3/${INFURA_API_KEY}", lookup).as_deref(),
+            Some("https://mainnet.infura.io/v3/abc")
+        );
+        assert_eq!(
+            fill_placeholders("https://x/${ALCHEMY_API_KEY}", lookup),
+            None
+        );
+        assert_eq!(
+            fill_placeholders("https://x/${INFURA_API_KEY", lookup),
+            None
+        );
+
+        let chain: ChainInfo = serde_json::from_str(
+            r#"{"name":"Ethereum Mainnet","chainId":1,"shortName":"eth","networkId":1,"nativeCurrency":{"name":"Ether","symbol":"ETH","decimals":18},"rpc":["https://mainnet.infura.io/v3/${INFURA_API_KEY}","https://eth-mainnet.alchemyapi.io/v2/${INACHUS_TEST_UNSET_KEY}","https://cloudflare-eth.com"],"faucets":[],"infoURL":""}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            chain.rpcs_with_keys(&keys),
+            vec![
+                "https://mainnet.infura.io/v3/abc",
+                "https://cloudflare-eth.com"
+            ]
+        );
+        assert_eq!(
+            chain.keyed_rpcs(&keys)[0],
+            (
+                "https://mainnet.infura.io/v3/${INFURA_API_KEY}".to_string(),
+                "https://mainnet.infura.io/v3/abc".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_explorer_links() {
+        let chain: ChainInfo = serde_json::from_str(
+            r#"{"name":"Ethereum Mainnet","chainId":1,"shortName":"eth","networkId":1,"nativeCurrency":{"name":"Ether","symbol":"ETH","decimals":18},"rpc":[],"faucets":[],"infoURL":"","explorers":[{"name":"blockscout","url":"https://eth.blockscout.com"},{"name":"etherscan","url":"https://etherscan.io/","standard":"EIP3091"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            chain.tx_url("0xabc").as_deref(),
+            Some("https://etherscan.io/tx/0xabc")
+        );
+        assert_eq!(
+            chain
+                .address_url("0xdAC17F958D2ee523a2206206994597C13D831ec7")
+                .as_deref(),
+            Some("https://etherscan.io/address/0xdAC17F958D2ee523a2206206994597C13D831ec7")
+        );
+        assert_eq!(
+            chain.block_url(17).as_deref(),
+            Some("https://etherscan.io/block/17")
+        );
+
+        let bundled = ChainRegistry::bundled().unwrap();
+        assert_eq!(
+            bundled.get(1).unwrap().tx_url("0xabc"),
+            Some("https://etherscan.io/tx/0xabc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_custom_chains() {
+        let dir = std::env::temp_dir().join(format!("chain_info-custom-{}", std::process::id()));
+        let path = dir.join("custom_chains.json");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut registry = ChainRegistry::bundled().unwrap();
+        let canonical = registry.len();
+        registry.merge_custom(&path).unwrap();
+        assert!(registry.custom_chains().is_empty());
+
+        registry.register(ChainInfo::custom(
+            990099,
+            "Acme Devnet",
+            "http://10.0.0.5:8545",
+        ));
+        let devnet = registry.get(990099).unwrap();
+        assert_eq!(devnet.short_name, "acme-devnet");
+        assert_eq!(devnet.public_rpcs(), vec!["http://10.0.0.5:8545"]);
+        assert_eq!(
+            registry.get_by_short_name("ACME-devnet").unwrap().chain_id,
+            990099
+        );
+        assert_eq!(registry.search("acme")[0].chain_id, 990099);
+        registry.register(ChainInfo::custom(
+            1,
+            "Mainnet Fork",
+            "http://127.0.0.1:8545",
+        ));
+        assert_eq!(registry.get(1).unwrap().name, "Mainnet Fork");
+        assert!(registry.get_by_short_name("eth").is_none());
+        assert_eq!(registry.len(), canonical + 1);
+        registry.save_custom(&path).unwrap();
+
+        let mut reloaded = ChainRegistry::bundled().unwrap();
+        reloaded.merge_custom(&path).unwrap();
+        assert_eq!(reloaded.custom_chains().len(), 2);
+        assert_eq!(reloaded.get(990099).unwrap().name, "Acme Devnet");
+        assert_eq!(reloaded.get(1).unwrap().name, "Mainnet Fork");
+
+        fs::write(
+            &path,
+            r#"[{"name":"Hand Written","chainId":990098,"shortName":"hw","networkId":990098,"nativeCurrency":{"name":"Ether","symbol":"ETH","decimals":18},"rpc":["http://devnet:8545"]}]"#,
+        )
+        .unwrap();
+        let mut registry = ChainRegistry::bundled().unwrap();
+        registry.merge_custom(&path).unwrap();
+        assert_eq!(registry.get(990098).unwrap().name, "Hand Written");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_chain_metadata_filters() {
+        let registry = ChainRegistry::new(ChainInfo::parse_chains_str(
+            r#"[
+                {"name":"Ethereum Mainnet","chain":"ETH","chainId":1,"shortName":"eth","networkId":1,"nativeCurrency":{"name":"Ether","symbol":"ETH","decimals":18},"rpc":[],"faucets":[],"infoURL":"","status":"active"},
+                {"name":"Goerli","chain":"ETH","chainId":5,"shortName":"gor","networkId":5,"nativeCurrency":{"name":"Goerli Ether","symbol":"ETH","decimals":18},"rpc":[],"faucets":[],"infoURL":"","status":"deprecated"},
+                {"name":"Sepolia","chain":"ETH","chainId":11155111,"shortName":"sep","networkId":11155111,"nativeCurrency":{"name":"Sepolia Ether","symbol":"ETH","decimals":18},"rpc":[],"faucets":[],"infoURL":""},
+                {"name":"Base","chain":"ETH","chainId":8453,"shortName":"base","networkId":8453,"nativeCurrency":{"name":"Ether","symbol":"ETH","decimals":18},"rpc":[],"faucets":[],"infoURL":"","parent":{"type":"L2","chain":"eip155-1","bridges":[{"url":"https://bridge.base.org"}]}},
+                {"name":"Base Sepolia Testnet","chain":"ETH","chainId":84532,"shortName":"basesep","networkId":84532,"nativeCurrency":{"name":"Sepolia Ether","symbol":"ETH","decimals":18},"rpc":[],"faucets":[],"infoURL":"","parent":{"type":"L2","chain":"eip155-11155111"}},
+                {"name":"Clone Chain","chain":"CLN","chainId":77,"shortName":"cln","networkId":77,"nativeCurrency":{"name":"Clone","symbol":"CLN","decimals":18},"rpc":[],"faucets":[],"infoURL":"","status":"incubating","redFlags":["reusedChainId"]}
+            ]"#,
+        )
+        .unwrap());
+        let ids = |chains: Vec<&ChainInfo>| chains.iter().map(|c| c.chain_id).collect::<Vec<_>>();
+
+        let base = registry.get(8453).unwrap();
+        assert!(base.is_l2());
+        assert_eq!(base.parent_chain_id(), Some(1));
+        assert_eq!(base.parent.as_ref().unwrap().bridges.len(), 1);
+        assert_eq!(
+            registry.get(77).unwrap().status,
+            Some(ChainStatus::Incubating)
+        );
+        assert_eq!(registry.get(77).unwrap().red_flags, vec!["reusedChainId"]);
+
+        assert_eq!(ids(registry.mainnets()), vec![1, 8453, 77]);
+        assert_eq!(ids(registry.testnets_of(1)), vec![11155111]);
+        assert_eq!(ids(registry.testnets_of(8453)), vec![84532]);
+        assert_eq!(ids(registry.l2s_of(1)), vec![8453]);
+        assert!(registry.testnets_of(999).is_empty());
+
+        assert!(ids(registry.search("goerli")).is_empty());
+        assert_eq!(ids(registry.search("5")), vec![5]);
+
+        let unknown: ChainInfo = serde_json::from_str(
+            r#"{"name":"Future","chainId":9,"shortName":"f","networkId":9,"nativeCurrency":{"name":"F","symbol":"F","decimals":18},"rpc":[],"status":"sunset"}"#,
+        )
+        .unwrap();
+        assert_eq!(unknown.status, Some(ChainStatus::Unknown));
+        let bundled = ChainRegistry::bundled().unwrap();
+        assert!(bundled.get(11155111).unwrap().is_testnet());
+        assert!(!bundled.get(100).unwrap().is_testnet());
+        assert!(bundled.get(5).unwrap().is_deprecated());
+        let testnets = ids(bundled.testnets_of(1));
+        assert!(testnets.contains(&11155111) && testnets.contains(&17000));
+        assert!(!testnets.contains(&5));
+        let l2s = ids(bundled.l2s_of(1));
+        assert!(l2s.contains(&10) && l2s.contains(&8453) && l2s.contains(&42161));
+    }
+
+    #[test]
+    fn test_add_chain_params() {
+        let chain: ChainInfo = serde_json::from_str(
+            r#"{"name":"Base","chainId":8453,"shortName":"base","networkId":8453,"nativeCurrency":{"name":"Ether","symbol":"ETH","decimals":18},"rpc":["https://mainnet.base.org","wss://base-rpc.publicnode.com","https://base-mainnet.infura.io/v3/${INFURA_API_KEY}"],"faucets":[],"infoURL":"https://base.org","explorers":[{"name":"basescan","url":"https://basescan.org","standard":"EIP3091"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(chain.to_add_chain_params()).unwrap(),
+            serde_json::json!({
+                "chainId": "0x2105",
+                "chainName": "Base",
+                "nativeCurrency": {"name": "Ether", "symbol": "ETH", "decimals": 18},
+                "rpcUrls": ["https://mainnet.base.org"],
+                "blockExplorerUrls": ["https://basescan.org"]
+            })
+        );
+
+        let bundled = ChainRegistry::bundled().unwrap();
+        let params = bundled.get(1).unwrap().to_add_chain_params();
+        assert_eq!(params.chain_id, "0x1");
+        assert_eq!(params.block_explorer_urls, vec!["https://etherscan.io"]);
+    }
+
+    #[test]
+    fn test_rollup_stacks() {
+        let bundled = ChainRegistry::bundled().unwrap();
+        assert_eq!(bundled.get(1).unwrap().rollup_stack(), None);
+        assert_eq!(
+            bundled.get(8453).unwrap().rollup_stack(),
+            Some(RollupStack::OpStack)
+        );
+        assert_eq!(
+            bundled.get(42161).unwrap().rollup_stack(),
+            Some(RollupStack::Arbitrum)
+        );
+        assert_eq!(RollupStack::of(137), None);
+    }
 }
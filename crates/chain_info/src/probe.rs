@@ -0,0 +1,175 @@
+use crate::{ChainError, ChainInfo};
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a single endpoint gets to answer `eth_chainId`.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of sending `eth_chainId` to one RPC endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcHealth {
+    Healthy,
+    WrongChain(u64),
+    Failed(String),
+}
+
+/// One probed endpoint with how long it took to answer.
+#[derive(Debug, Clone)]
+pub struct RpcProbe {
+    pub url: String,
+    /// The URL as listed, API key placeholders kept, to show or save
+    pub listed_url: String,
+    pub latency: Duration,
+    pub health: RpcHealth,
+}
+
+impl RpcProbe {
+    pub fn is_healthy(&self) -> bool {
+        self.health == RpcHealth::Healthy
+    }
+
+    fn rank(&self) -> u8 {
+        match self.health {
+            RpcHealth::Healthy => 0,
+            RpcHealth::WrongChain(_) => 1,
+            RpcHealth::Failed(_) => 2,
+        }
+    }
+}
+
+impl ChainInfo {
+    /// Sends `eth_chainId` to every HTTP(S) endpoint of the chain at once and
+    /// returns them healthy first, fastest first. Endpoints needing an API key
+    /// are included when the key is set in the environment. WebSocket
+    /// endpoints are not probed.
+    pub async fn probe_rpcs(&self) -> Vec<RpcProbe> {
+        self.probe_rpcs_with_keys(&HashMap::new()).await
+    }
+
+    /// Like [`ChainInfo::probe_rpcs`], filling API key placeholders from
+    /// `keys` before the environment.
+    pub async fn probe_rpcs_with_keys(&self, keys: &HashMap<String, String>) -> Vec<RpcProbe> {
+        let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!("Could not build HTTP client: {}", e);
+                return Vec::new();
+            }
+        };
+        let urls = self.keyed_rpcs(keys);
+        let probes = urls
+            .iter()
+            .filter(|(_, url)| url.starts_with("http://") || url.starts_with("https://"))
+            .map(|(listed_url, url)| probe(&client, listed_url, url, self.chain_id));
+        let mut probes = join_all(probes).await;
+        probes.sort_by_key(|probe| (probe.rank(), probe.latency));
+        probes
+    }
+}
+
+async fn probe(client: &reqwest::Client, listed_url: &str, url: &str, expected: u64) -> RpcProbe {
+    let started = Instant::now();
+    let health = match chain_id(client, url).await {
+        Ok(id) if id == expected => RpcHealth::Healthy,
+        Ok(id) => RpcHealth::WrongChain(id),
+        Err(e) => RpcHealth::Failed(e.to_string()),
+    };
+    RpcProbe {
+        url: url.to_string(),
+        listed_url: listed_url.to_string(),
+        latency: started.elapsed(),
+        health,
+    }
+}
+
+async fn chain_id(client: &reqwest::Client, url: &str) -> Result<u64, ChainError> {
+    // Errors leave the URL out, as it may hold an API key.
+    let body = async {
+        client
+            .post(url)
+            .header("content-type", "application/json")
+            .body(r#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":[]}"#)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    }
+    .await
+    .map_err(reqwest::Error::without_url)?;
+    let response: serde_json::Value = serde_json::from_str(&body)?;
+    response["result"]
+        .as_str()
+        .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| ChainError::InvalidResponse(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NativeCurrency;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serves `eth_chainId` answers of `chain_id` until the test exits.
+    fn serve_chain_id(chain_id: u64) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf);
+                let body = format!(r#"{{"jsonrpc":"2.0","id":1,"result":"0x{:x}"}}"#, chain_id);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_probe_rpcs_ranks_healthy_endpoints_first() {
+        let wrong = serve_chain_id(5);
+        let healthy = serve_chain_id(10);
+        let chain = ChainInfo {
+            name: "OP Mainnet".to_string(),
+            chain_id: 10,
+            short_name: "oeth".to_string(),
+            network_id: 10,
+            native_currency: NativeCurrency {
+                name: "Ether".to_string(),
+                symbol: "ETH".to_string(),
+                decimals: 18,
+            },
+            rpc: vec![
+                "http://127.0.0.1:1".to_string(),
+                wrong.clone(),
+                "wss://ws.example".to_string(),
+                healthy.clone(),
+            ],
+            faucets: vec![],
+            info_url: "".to_string(),
+            explorers: vec![],
+            family: None,
+            status: None,
+            red_flags: vec![],
+            parent: None,
+        };
+
+        let probes = chain.probe_rpcs().await;
+        let urls: Vec<_> = probes.iter().map(|probe| probe.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![healthy.as_str(), wrong.as_str(), "http://127.0.0.1:1"]
+        );
+        assert!(probes[0].is_healthy());
+        assert_eq!(probes[1].health, RpcHealth::WrongChain(5));
+        assert!(matches!(&probes[2].health, RpcHealth::Failed(e) if !e.contains("127.0.0.1:1")));
+        assert_eq!(probes[0].listed_url, healthy);
+    }
+}
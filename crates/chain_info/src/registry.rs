@@ -0,0 +1,336 @@
+use crate::{ChainError, ChainInfo};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Snapshot of the chainid.network list compiled into the binary, rewritten
+/// by the `update_snapshot` example.
+pub const BUNDLED_CHAINS: &str = include_str!("../chains.json");
+
+/// Where [`ChainRegistry::refresh`] downloads the latest list from.
+pub const CHAINS_URL: &str = "https://chainid.network/chains.json";
+
+/// How long a cached list is used before it is downloaded again.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Upper bound on the download, so an offline machine falls back quickly.
+#[cfg(feature = "refresh")]
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the chains of a [`ChainRegistry`] were loaded from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChainSource {
+    #[default]
+    Provided,
+    Bundled,
+    Cache,
+    Remote,
+}
+
+/// Known chains, from the bundled snapshot, a cached download or chainid.network,
+/// indexed by chain ID and short name, along with the custom chains registered
+/// on top of them.
+#[derive(Debug, Clone, Default)]
+pub struct ChainRegistry {
+    chains: Vec<ChainInfo>,
+    by_id: HashMap<u64, usize>,
+    by_short_name: HashMap<String, usize>,
+    source: ChainSource,
+    custom: Vec<ChainInfo>,
+}
+
+impl ChainRegistry {
+    /// Indexes `chains`; the first entry wins when IDs or short names repeat.
+    pub fn new(chains: Vec<ChainInfo>) -> Self {
+        let mut by_id = HashMap::with_capacity(chains.len());
+        let mut by_short_name = HashMap::with_capacity(chains.len());
+        for (index, chain) in chains.iter().enumerate() {
+            by_id.entry(chain.chain_id).or_insert(index);
+            by_short_name
+                .entry(chain.short_name.to_lowercase())
+                .or_insert(index);
+        }
+        Self {
+            chains,
+            by_id,
+            by_short_name,
+            source: ChainSource::Provided,
+            custom: Vec::new(),
+        }
+    }
+
+    fn with_source(chains: Vec<ChainInfo>, source: ChainSource) -> Self {
+        Self {
+            source,
+            ..Self::new(chains)
+        }
+    }
+
+    /// The snapshot shipped with the crate.
+    pub fn bundled() -> Result<Self, ChainError> {
+        Ok(Self::with_source(
+            ChainInfo::parse_chains_str(BUNDLED_CHAINS)?,
+            ChainSource::Bundled,
+        ))
+    }
+
+    /// The cached list at `cache_path` whatever its age, or the bundled
+    /// snapshot when there is no usable cache. Never touches the network.
+    pub fn load(cache_path: impl AsRef<Path>) -> Result<Self, ChainError> {
+        match Self::from_cache(cache_path.as_ref()) {
+            Some(registry) => Ok(registry),
+            None => Self::bundled(),
+        }
+    }
+
+    /// Whether the cache at `cache_path` is missing or older than `ttl`.
+    pub fn is_stale(cache_path: impl AsRef<Path>, ttl: Duration) -> bool {
+        fs::metadata(cache_path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_none_or(|age| age >= ttl)
+    }
+
+    /// Downloads the latest list into `cache_path` when the cache is older
+    /// than `ttl`. A failed download falls back to the stale cache, then to
+    /// the bundled snapshot.
+    #[cfg(feature = "refresh")]
+    pub async fn refresh(cache_path: impl AsRef<Path>, ttl: Duration) -> Result<Self, ChainError> {
+        let cache_path = cache_path.as_ref();
+        if !Self::is_stale(cache_path, ttl)
+            && let Some(registry) = Self::from_cache(cache_path)
+        {
+            return Ok(registry);
+        }
+        match Self::download(cache_path).await {
+            Ok(registry) => Ok(registry),
+            Err(e) => {
+                tracing::warn!("Could not refresh chain list from {}: {}", CHAINS_URL, e);
+                Self::load(cache_path)
+            }
+        }
+    }
+
+    #[cfg(feature = "refresh")]
+    async fn download(cache_path: &Path) -> Result<Self, ChainError> {
+        let body = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()?
+            .get(CHAINS_URL)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let chains = ChainInfo::parse_chains_str(&body)?;
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(cache_path, body)?;
+        Ok(Self::with_source(chains, ChainSource::Remote))
+    }
+
+    fn from_cache(cache_path: &Path) -> Option<Self> {
+        if !cache_path.exists() {
+            return None;
+        }
+        match ChainInfo::parse_chains_json(cache_path) {
+            Ok(chains) => Some(Self::with_source(chains, ChainSource::Cache)),
+            Err(e) => {
+                tracing::warn!("Ignoring chain cache {}: {}", cache_path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Adds a chain missing from the canonical list, such as an internal
+    /// devnet, or replaces the entry with the same ID, e.g. for a fork.
+    pub fn register(&mut self, chain: ChainInfo) {
+        self.custom
+            .retain(|custom| custom.chain_id != chain.chain_id);
+        self.custom.push(chain.clone());
+        let index = match self.by_id.get(&chain.chain_id) {
+            Some(&index) => {
+                self.by_short_name.retain(|_, i| *i != index);
+                self.chains[index] = chain;
+                index
+            }
+            None => {
+                self.by_id.insert(chain.chain_id, self.chains.len());
+                self.chains.push(chain);
+                self.chains.len() - 1
+            }
+        };
+        self.by_short_name
+            .insert(self.chains[index].short_name.to_lowercase(), index);
+    }
+
+    /// Registers the custom chains saved at `path` by [`ChainRegistry::save_custom`]
+    /// or maintained by hand, in the format of chains.json. A missing file
+    /// registers nothing.
+    pub fn merge_custom(&mut self, path: impl AsRef<Path>) -> Result<(), ChainError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(());
+        }
+        for chain in ChainInfo::parse_chains_json(path)? {
+            self.register(chain);
+        }
+        Ok(())
+    }
+
+    /// Writes the registered custom chains to `path`.
+    pub fn save_custom(&self, path: impl AsRef<Path>) -> Result<(), ChainError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.custom)?)?;
+        Ok(())
+    }
+
+    /// The chains added with [`ChainRegistry::register`], in registration order.
+    pub fn custom_chains(&self) -> &[ChainInfo] {
+        &self.custom
+    }
+
+    pub fn get(&self, chain_id: u64) -> Result<&ChainInfo, ChainError> {
+        self.by_id
+            .get(&chain_id)
+            .map(|&index| &self.chains[index])
+            .ok_or(ChainError::ChainNotFound(chain_id))
+    }
+
+    /// Case-insensitive lookup by short name, e.g. `eth` or `arb1`.
+    pub fn get_by_short_name(&self, short_name: &str) -> Option<&ChainInfo> {
+        self.by_short_name
+            .get(&short_name.trim().to_lowercase())
+            .map(|&index| &self.chains[index])
+    }
+
+    /// Chains matching `query`, best first: the chain ID, then the short name,
+    /// then names equal to, starting with or containing the query, then the
+    /// native currency symbol. Only when none of those match are names that
+    /// contain the query's letters in order (`opmain` for `OP Mainnet`) tried.
+    /// Deprecated chains are only found by their ID.
+    pub fn search(&self, query: &str) -> Vec<&ChainInfo> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        if let Ok(chain_id) = query.parse::<u64>() {
+            return self.get(chain_id).into_iter().collect();
+        }
+
+        let short_name = self.by_short_name.get(&query).copied();
+        let mut ranked: Vec<(u8, usize)> = self
+            .chains
+            .iter()
+            .enumerate()
+            .filter(|(_, chain)| !chain.is_deprecated())
+            .filter_map(|(index, chain)| {
+                let name = chain.name.to_lowercase();
+                let rank = if Some(index) == short_name {
+                    0
+                } else if name == query {
+                    1
+                } else if name.starts_with(&query) {
+                    2
+                } else if name.contains(&query) {
+                    3
+                } else if chain.native_currency.symbol.to_lowercase() == query {
+                    4
+                } else {
+                    return None;
+                };
+                Some((rank, index))
+            })
+            .collect();
+        if ranked.is_empty() {
+            let letters = compact(&query);
+            ranked = self
+                .chains
+                .iter()
+                .enumerate()
+                .filter(|(_, chain)| !chain.is_deprecated())
+                .filter(|(_, chain)| is_subsequence(&letters, &compact(&chain.name)))
+                .map(|(index, _)| (5, index))
+                .collect();
+        }
+        ranked.sort_by_key(|&(rank, index)| (rank, self.chains[index].chain_id));
+        ranked
+            .into_iter()
+            .map(|(_, index)| &self.chains[index])
+            .collect()
+    }
+
+    /// Chains that are neither testnets nor deprecated.
+    pub fn mainnets(&self) -> Vec<&ChainInfo> {
+        self.chains
+            .iter()
+            .filter(|chain| !chain.is_testnet() && !chain.is_deprecated())
+            .collect()
+    }
+
+    /// Testnets of the same family as `chain_id` and, like it, either L1s
+    /// or L2s, leaving out deprecated ones: Sepolia and Holesky for 1. Empty
+    /// when the family is unknown.
+    pub fn testnets_of(&self, chain_id: u64) -> Vec<&ChainInfo> {
+        let Ok(mainnet) = self.get(chain_id) else {
+            return Vec::new();
+        };
+        let Some(family) = mainnet.family.as_deref() else {
+            return Vec::new();
+        };
+        self.chains
+            .iter()
+            .filter(|chain| chain.family.as_deref() == Some(family))
+            .filter(|chain| chain.is_testnet() && !chain.is_deprecated())
+            .filter(|chain| chain.is_l2() == mainnet.is_l2())
+            .collect()
+    }
+
+    /// Non-deprecated L2s settling on `chain_id`, e.g. OP Mainnet and Base for 1.
+    pub fn l2s_of(&self, chain_id: u64) -> Vec<&ChainInfo> {
+        self.chains
+            .iter()
+            .filter(|chain| chain.is_l2() && chain.parent_chain_id() == Some(chain_id))
+            .filter(|chain| !chain.is_deprecated())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+
+    pub fn source(&self) -> ChainSource {
+        self.source
+    }
+
+    pub fn chains(&self) -> &[ChainInfo] {
+        &self.chains
+    }
+
+    pub fn into_chains(self) -> Vec<ChainInfo> {
+        self.chains
+    }
+}
+
+/// Lowercase letters and digits of `text`, dropping spaces and punctuation.
+fn compact(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn is_subsequence(needle: &[char], haystack: &[char]) -> bool {
+    let mut haystack = haystack.iter();
+    needle.iter().all(|c| haystack.any(|h| h == c))
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+/// OP-stack chains, which charge an L1 data fee on top of L2 execution.
+const OP_STACK_CHAINS: [u64; 14] = [
+    10,        // OP Mainnet
+    8453,      // Base
+    7777777,   // Zora
+    34443,     // Mode
+    252,       // Fraxtal
+    130,       // Unichain
+    480,       // World Chain
+    57073,     // Ink
+    1135,      // Lisk
+    81457,     // Blast
+    11155420,  // OP Sepolia
+    84532,     // Base Sepolia
+    999999999, // Zora Sepolia
+    1301,      // Unichain Sepolia
+];
+
+/// Arbitrum chains, which charge L1 data as extra L2 gas.
+const ARBITRUM_CHAINS: [u64; 3] = [
+    42161,  // Arbitrum One
+    42170,  // Arbitrum Nova
+    421614, // Arbitrum Sepolia
+];
+
+/// Rollup stack of an L2 chain, which decides how its L1 data fee is estimated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RollupStack {
+    /// OP-stack, priced by the `GasPriceOracle` predeploy
+    OpStack,
+    /// Arbitrum Nitro, priced by the `NodeInterface` precompile
+    Arbitrum,
+}
+
+impl RollupStack {
+    /// The stack a chain is built on, `None` for L1s and unknown chains.
+    pub fn of(chain_id: u64) -> Option<Self> {
+        if OP_STACK_CHAINS.contains(&chain_id) {
+            Some(Self::OpStack)
+        } else if ARBITRUM_CHAINS.contains(&chain_id) {
+            Some(Self::Arbitrum)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for RollupStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OpStack => write!(f, "OP-stack"),
+            Self::Arbitrum => write!(f, "Arbitrum"),
+        }
+    }
+}
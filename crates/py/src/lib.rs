@@ -0,0 +1,217 @@
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::{Address, U256};
+use alloy::signers::local::PrivateKeySigner;
+use chain_info::ChainRegistry;
+use inachus::{ReadContext, TransactionOptions, WriteContext, abi, codec};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList, PyString};
+use serde_json::Value;
+use std::str::FromStr;
+
+create_exception!(pyinachus, InachusError, PyException);
+
+fn to_py_err(e: impl std::fmt::Display) -> PyErr {
+    InachusError::new_err(e.to_string())
+}
+
+fn json_to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into_pyobject(py)?.into_any(),
+            None => n.as_f64().unwrap_or_default().into_pyobject(py)?.into_any(),
+        },
+        Value::String(s) => PyString::new(py, s).into_any(),
+        Value::Array(values) => {
+            let list = PyList::empty(py);
+            for value in values {
+                list.append(json_to_py(py, value)?)?;
+            }
+            list.into_any()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, value) in map {
+                dict.set_item(key, json_to_py(py, value)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+fn values_to_py<'py>(py: Python<'py>, values: &[DynSolValue]) -> PyResult<Bound<'py, PyAny>> {
+    let json = Value::Array(values.iter().map(codec::to_json).collect());
+    json_to_py(py, &json)
+}
+
+fn parse_function(signature: &str) -> PyResult<Function> {
+    Function::parse(signature).map_err(to_py_err)
+}
+
+fn parse_hex(data: &str) -> PyResult<Vec<u8>> {
+    alloy::hex::decode(data.trim()).map_err(to_py_err)
+}
+
+#[pyclass]
+struct ContractSession {
+    runtime: tokio::runtime::Runtime,
+    abi: JsonAbi,
+    address: Address,
+    reader: ReadContext,
+    writer: Option<WriteContext>,
+}
+
+impl ContractSession {
+    fn function(&self, method: &str) -> PyResult<&Function> {
+        abi::find_function(&self.abi, method).map_err(to_py_err)
+    }
+}
+
+#[pymethods]
+impl ContractSession {
+    #[new]
+    #[pyo3(signature = (rpc_url, abi_json, address, private_key=None, chain_id=1))]
+    fn new(
+        rpc_url: &str,
+        abi_json: &str,
+        address: &str,
+        private_key: Option<&str>,
+        chain_id: u64,
+    ) -> PyResult<Self> {
+        let runtime = tokio::runtime::Runtime::new().map_err(to_py_err)?;
+        let abi: JsonAbi = serde_json::from_str(abi_json).map_err(to_py_err)?;
+        let address = Address::from_str(address).map_err(to_py_err)?;
+        let reader = ReadContext::connect(rpc_url).map_err(to_py_err)?;
+        let writer = private_key
+            .map(|key| {
+                let signer = PrivateKeySigner::from_str(key).map_err(to_py_err)?;
+                WriteContext::connect(rpc_url, signer, chain_id).map_err(to_py_err)
+            })
+            .transpose()?;
+        Ok(Self {
+            runtime,
+            abi,
+            address,
+            reader,
+            writer,
+        })
+    }
+
+    #[getter]
+    fn address(&self) -> String {
+        self.address.to_checksum(None)
+    }
+
+    #[getter]
+    fn sender(&self) -> Option<String> {
+        self.writer.as_ref().map(|w| w.address().to_checksum(None))
+    }
+
+    fn methods(&self) -> Vec<String> {
+        self.abi.functions().map(|f| f.signature()).collect()
+    }
+
+    fn encode(&self, method: &str, args: Vec<String>) -> PyResult<String> {
+        let calldata = codec::encode_calldata(self.function(method)?, &args).map_err(to_py_err)?;
+        Ok(calldata.to_string())
+    }
+
+    fn call<'py>(
+        &self,
+        py: Python<'py>,
+        method: &str,
+        args: Vec<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let function = self.function(method)?;
+        let values = codec::coerce_args(function, &args).map_err(to_py_err)?;
+        let result = py
+            .detach(|| {
+                self.runtime
+                    .block_on(self.reader.call(self.address, function, &values))
+            })
+            .map_err(to_py_err)?;
+        values_to_py(py, &result.values)
+    }
+
+    #[pyo3(signature = (method, args, value=None, gas_limit=None, nonce=None))]
+    fn send(
+        &self,
+        py: Python<'_>,
+        method: &str,
+        args: Vec<String>,
+        value: Option<&str>,
+        gas_limit: Option<u64>,
+        nonce: Option<u64>,
+    ) -> PyResult<String> {
+        let mut options = TransactionOptions::new();
+        if let Some(value) = value {
+            options = options.with_value(U256::from_str(value).map_err(to_py_err)?);
+        }
+        if let Some(gas_limit) = gas_limit {
+            options = options.with_gas_limit(gas_limit);
+        }
+        if let Some(nonce) = nonce {
+            options = options.with_nonce(nonce);
+        }
+        let writer = self
+            .writer
+            .as_ref()
+            .ok_or_else(|| to_py_err("Session has no private key, it is read-only"))?;
+        let function = self.function(method)?;
+        let values = codec::coerce_args(function, &args).map_err(to_py_err)?;
+        let pending = py
+            .detach(|| {
+                self.runtime
+                    .block_on(writer.send(self.address, function, &values, &options))
+            })
+            .map_err(to_py_err)?;
+        Ok(pending.tx_hash().to_string())
+    }
+}
+
+#[pyfunction]
+fn encode_calldata(signature: &str, args: Vec<String>) -> PyResult<String> {
+    let calldata = codec::encode_calldata(&parse_function(signature)?, &args).map_err(to_py_err)?;
+    Ok(calldata.to_string())
+}
+
+#[pyfunction]
+fn decode_calldata<'py>(
+    py: Python<'py>,
+    signature: &str,
+    calldata: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let values = codec::decode_input(&parse_function(signature)?, &parse_hex(calldata)?)
+        .map_err(to_py_err)?;
+    values_to_py(py, &values)
+}
+
+#[pyfunction]
+fn decode_output<'py>(py: Python<'py>, signature: &str, data: &str) -> PyResult<Bound<'py, PyAny>> {
+    let values =
+        codec::decode_output(&parse_function(signature)?, &parse_hex(data)?).map_err(to_py_err)?;
+    values_to_py(py, &values)
+}
+
+#[pyfunction]
+fn chain_by_id<'py>(py: Python<'py>, chain_id: u64) -> PyResult<Bound<'py, PyAny>> {
+    let chains = ChainRegistry::bundled().map_err(to_py_err)?;
+    let chain = chains.get(chain_id).map_err(to_py_err)?;
+    let json = serde_json::to_value(chain).map_err(to_py_err)?;
+    json_to_py(py, &json)
+}
+
+#[pymodule]
+fn pyinachus(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("InachusError", m.py().get_type::<InachusError>())?;
+    m.add_class::<ContractSession>()?;
+    m.add_function(wrap_pyfunction!(encode_calldata, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_calldata, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_output, m)?)?;
+    m.add_function(wrap_pyfunction!(chain_by_id, m)?)?;
+    Ok(())
+}
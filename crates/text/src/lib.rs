@@ -1,5 +1,11 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+mod table;
+
+pub use table::{Align, Table};
 
 lazy_static! {
     static ref ANSI_REGEXP: Regex = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
@@ -9,18 +15,158 @@ pub fn strip_ansi(s: &str) -> String {
     ANSI_REGEXP.replace_all(s, "").into_owned()
 }
 
-pub fn pad_right_ansi_aware(colored: &str, width: usize) -> String {
-    let raw = strip_ansi(colored);
-    let char_count = raw.chars().count();
-    let padding = if width > char_count {
-        width - char_count
-    } else {
-        0
+/// Number of terminal columns a string takes, ignoring ANSI escape codes.
+///
+/// Wide characters such as CJK ideographs take two columns, combining marks
+/// none, and each grapheme cluster, e.g. an emoji joined with ZWJ, at most two.
+pub fn display_width(s: &str) -> usize {
+    strip_ansi(s)
+        .graphemes(true)
+        .map(|grapheme| grapheme.width().min(2))
+        .sum()
+}
+
+/// Cuts a string, possibly colored, to at most `width` columns, ending it
+/// with `…` when anything was cut. The escape codes before the cut are kept
+/// and a reset is appended, so the style of the cut text does not bleed into
+/// whatever follows.
+pub fn truncate_ansi_aware(s: &str, width: usize) -> String {
+    if display_width(s) <= width {
+        return s.to_string();
+    }
+    let Some(budget) = width.checked_sub(1) else {
+        return String::new();
     };
+    let mut truncated = String::with_capacity(s.len());
+    let mut used = 0;
+    let mut styled = false;
+    let mut rest = s;
+    'cut: loop {
+        let (text, code) = match ANSI_REGEXP.find(rest) {
+            Some(code) => (&rest[..code.start()], Some(code)),
+            None => (rest, None),
+        };
+        for grapheme in text.graphemes(true) {
+            let grapheme_width = grapheme.width().min(2);
+            if used + grapheme_width > budget {
+                break 'cut;
+            }
+            used += grapheme_width;
+            truncated.push_str(grapheme);
+        }
+        match code {
+            Some(code) => {
+                truncated.push_str(code.as_str());
+                styled = true;
+                rest = &rest[code.end()..];
+            }
+            None => break,
+        }
+    }
+    truncated.push('…');
+    if styled {
+        truncated.push_str("\x1b[0m");
+    }
+    truncated
+}
+
+pub fn pad_right_ansi_aware(colored: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(colored));
 
     format!("{}{}", colored, " ".repeat(padding))
 }
 
+pub fn table<K: AsRef<str>, V: AsRef<str>>(rows: &[(K, V)]) -> String {
+    let width = rows
+        .iter()
+        .map(|(key, _)| display_width(key.as_ref()))
+        .max()
+        .unwrap_or(0);
+    let indent = " ".repeat(width + 2);
+    rows.iter()
+        .map(|(key, value)| {
+            let mut lines = value.as_ref().lines();
+            let first = lines.next().unwrap_or_default();
+            let mut row = format!("{}  {}", pad_right_ansi_aware(key.as_ref(), width), first);
+            for line in lines {
+                row.push('\n');
+                row.push_str(&indent);
+                row.push_str(line);
+            }
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Scores how well a query matches a candidate, for filtering long lists.
+///
+/// Each whitespace-separated term of the query must appear in the candidate
+/// as a case-insensitive subsequence. Matches at the start of a word
+/// (including camelCase humps) and runs of consecutive characters score
+/// higher; gaps and longer candidates score lower. A blank query matches
+/// every candidate with a score of zero.
+///
+/// # Arguments
+///
+/// * `query` - What the user typed
+/// * `candidate` - The text to match against
+///
+/// # Returns
+///
+/// * `Option<i64>` - The score, higher is better, or `None` if a term does not match
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.trim().is_empty() {
+        return Some(0);
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    for term in query.split_whitespace() {
+        score += term_score(term, &chars)?;
+    }
+    Some(score - chars.len() as i64 / 4)
+}
+
+/// Scores the best alignment of one term: `best[i]` is the score of the term
+/// so far with its last character matched at `i`.
+fn term_score(term: &str, chars: &[char]) -> Option<i64> {
+    let is_word_start = |i: usize| {
+        i == 0
+            || !chars[i - 1].is_alphanumeric()
+            || (chars[i].is_uppercase() && chars[i - 1].is_lowercase())
+    };
+    let matches = |i: usize, wanted: char| chars[i].to_lowercase().eq([wanted]);
+    let base = |i: usize| if is_word_start(i) { 9 } else { 1 };
+    let mut best: Option<Vec<Option<i64>>> = None;
+    for wanted in term.chars().flat_map(char::to_lowercase) {
+        let scores = (0..chars.len())
+            .map(|i| {
+                if !matches(i, wanted) {
+                    return None;
+                }
+                let Some(previous) = &best else {
+                    return Some(base(i) - i.min(10) as i64);
+                };
+                let consecutive = i
+                    .checked_sub(1)
+                    .and_then(|j| previous[j])
+                    .map(|score| score + 5);
+                let gapped = previous[..i.saturating_sub(1)]
+                    .iter()
+                    .flatten()
+                    .max()
+                    .map(|score| score - 2);
+                consecutive.max(gapped).map(|score| score + base(i))
+            })
+            .collect();
+        best = Some(scores);
+    }
+    match best {
+        Some(scores) => scores.into_iter().flatten().max(),
+        None => Some(0),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -38,4 +184,70 @@ mod tests {
         assert_eq!(padded.len() - colored.len(), 5);
         assert_eq!(strip_ansi(&padded).len(), 10);
     }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("\x1b[1;32mHello\x1b[0m"), 5);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("🦀"), 2);
+        assert_eq!(display_width("👨\u{200d}👩\u{200d}👧"), 2);
+        assert_eq!(display_width("\x1b[33m⚠\u{fe0f} gas\x1b[0m"), 6);
+
+        let padded = pad_right_ansi_aware("\x1b[36m名前\x1b[0m", 6);
+        assert_eq!(display_width(&padded), 6);
+        assert!(padded.ends_with("\x1b[0m  "));
+    }
+
+    #[test]
+    fn test_truncate_ansi_aware() {
+        assert_eq!(truncate_ansi_aware("transfer", 8), "transfer");
+        assert_eq!(truncate_ansi_aware("transferFrom", 8), "transfe…");
+        assert_eq!(truncate_ansi_aware("transfer", 0), "");
+        assert_eq!(truncate_ansi_aware("transfer", 1), "…");
+
+        let colored = "\x1b[32mapproved\x1b[0m spender";
+        let truncated = truncate_ansi_aware(colored, 5);
+        assert_eq!(truncated, "\x1b[32mappr…\x1b[0m");
+        assert_eq!(display_width(&truncated), 5);
+        assert_eq!(
+            truncate_ansi_aware(colored, 12),
+            "\x1b[32mapproved\x1b[0m sp…\x1b[0m"
+        );
+
+        // A wide character that would straddle the limit is left out.
+        assert_eq!(truncate_ansi_aware("名前です", 4), "名…");
+        assert_eq!(display_width(&truncate_ansi_aware("名前です", 4)), 3);
+    }
+
+    #[test]
+    fn test_table() {
+        let rows = [
+            ("\x1b[1mTo\x1b[0m", "0x01"),
+            ("Arguments", "to: 0x02\namount: 3"),
+        ];
+        assert_eq!(
+            strip_ansi(&table(&rows)),
+            "To         0x01\nArguments  to: 0x02\n           amount: 3"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score(" ", "transfer"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "transfer(address,uint256)"), None);
+        assert_eq!(fuzzy_score("tf addr", "balanceOf(address)"), None);
+        assert!(fuzzy_score("TRF", "transferFrom(address,address,uint256)").is_some());
+
+        let score = |candidate| fuzzy_score("tf", candidate).unwrap();
+        assert!(score("transferFrom()") > score("tariff()"));
+        assert!(
+            fuzzy_score("transfer", "transfer(address,uint256)")
+                > fuzzy_score("transfer", "safeTransferFrom(address,address,uint256)")
+        );
+        assert!(
+            fuzzy_score("approve uint", "approve(address,uint256)")
+                > fuzzy_score("approve uint", "setApprovalForAll(address,bool)")
+        );
+    }
 }
@@ -0,0 +1,249 @@
+use crate::{display_width, truncate_ansi_aware};
+use std::fmt;
+
+/// Horizontal alignment of the cells of a column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+/// A table laid out by display width, so colored, wide and emoji cells line
+/// up. Cells may span several lines; lines wider than their column's maximum
+/// width are truncated with `…`.
+///
+/// ```
+/// use text::{Align, Table};
+///
+/// let table = Table::new()
+///     .headers(["Method", "Calls"])
+///     .row(["transfer", "12"])
+///     .row(["approve", "3"])
+///     .align(1, Align::Right);
+/// assert_eq!(
+///     table.to_string(),
+///     "Method    Calls\n────────  ─────\ntransfer     12\napprove       3"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    align: Vec<Align>,
+    max_widths: Vec<Option<usize>>,
+    borders: bool,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the header row, underlined without borders and boxed with them.
+    pub fn headers<I, S>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Appends a row; rows shorter than others are padded with empty cells.
+    pub fn row<I, S>(mut self, cells: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.push_row(cells);
+        self
+    }
+
+    /// Appends a row, for tables filled in a loop.
+    pub fn push_row<I, S>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+    }
+
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        if self.align.len() <= column {
+            self.align.resize(column + 1, Align::default());
+        }
+        self.align[column] = align;
+        self
+    }
+
+    /// Caps the display width of a column; longer lines are truncated.
+    pub fn max_width(mut self, column: usize, width: usize) -> Self {
+        if self.max_widths.len() <= column {
+            self.max_widths.resize(column + 1, None);
+        }
+        self.max_widths[column] = Some(width);
+        self
+    }
+
+    /// Draws box-drawing borders around and between the cells.
+    pub fn borders(mut self, borders: bool) -> Self {
+        self.borders = borders;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn columns(&self) -> usize {
+        self.rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Lines of every cell of a row, truncated to their column's maximum width.
+    fn cell_lines(&self, row: &[String], columns: usize) -> Vec<Vec<String>> {
+        (0..columns)
+            .map(|column| {
+                let cell = row.get(column).map(String::as_str).unwrap_or_default();
+                let max_width = self.max_widths.get(column).copied().flatten();
+                cell.lines()
+                    .map(|line| match max_width {
+                        Some(width) => truncate_ansi_aware(line, width),
+                        None => line.to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn render_row(&self, cells: &[Vec<String>], widths: &[usize], lines: &mut Vec<String>) {
+        let height = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        for i in 0..height {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(widths)
+                .enumerate()
+                .map(|(column, (cell, &width))| {
+                    let line = cell.get(i).map(String::as_str).unwrap_or_default();
+                    let align = self.align.get(column).copied().unwrap_or_default();
+                    pad(line, width, align)
+                })
+                .collect();
+            lines.push(match self.borders {
+                true => format!("│ {} │", padded.join(" │ ")),
+                false => padded.join("  ").trim_end().to_string(),
+            });
+        }
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = self.columns();
+        if columns == 0 {
+            return Ok(());
+        }
+        let header = match self.headers.is_empty() {
+            true => None,
+            false => Some(self.cell_lines(&self.headers, columns)),
+        };
+        let rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| self.cell_lines(row, columns))
+            .collect();
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                header
+                    .iter()
+                    .chain(&rows)
+                    .flat_map(|cells| &cells[column])
+                    .map(|line| display_width(line))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let rule = |left: &str, fill: &str, middle: &str, right: &str| {
+            let segments: Vec<String> =
+                widths.iter().map(|&width| fill.repeat(width + 2)).collect();
+            format!("{}{}{}", left, segments.join(middle), right)
+        };
+
+        let mut lines = Vec::new();
+        if self.borders {
+            lines.push(rule("┌", "─", "┬", "┐"));
+        }
+        if let Some(ref header) = header {
+            self.render_row(header, &widths, &mut lines);
+            lines.push(match self.borders {
+                true => rule("├", "─", "┼", "┤"),
+                false => widths
+                    .iter()
+                    .map(|&width| "─".repeat(width))
+                    .collect::<Vec<_>>()
+                    .join("  "),
+            });
+        }
+        for row in &rows {
+            self.render_row(row, &widths, &mut lines);
+        }
+        if self.borders {
+            lines.push(rule("└", "─", "┴", "┘"));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+fn pad(line: &str, width: usize, align: Align) -> String {
+    let padding = width.saturating_sub(display_width(line));
+    let (left, right) = match align {
+        Align::Left => (0, padding),
+        Align::Right => (padding, 0),
+        Align::Center => (padding / 2, padding - padding / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), line, " ".repeat(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strip_ansi;
+
+    #[test]
+    fn test_table_layout() {
+        let table = Table::new()
+            .headers(["Event", "From", "Amount"])
+            .row([
+                "\x1b[32mTransfer\x1b[0m",
+                "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+                "1",
+            ])
+            .row(["送金", "alice", "250\n(0.25 gwei)"])
+            .align(2, Align::Right)
+            .max_width(1, 10)
+            .borders(true);
+        assert_eq!(
+            strip_ansi(&table.to_string()),
+            "┌──────────┬────────────┬─────────────┐\n\
+             │ Event    │ From       │      Amount │\n\
+             ├──────────┼────────────┼─────────────┤\n\
+             │ Transfer │ 0xdAC17F9… │           1 │\n\
+             │ 送金     │ alice      │         250 │\n\
+             │          │            │ (0.25 gwei) │\n\
+             └──────────┴────────────┴─────────────┘"
+        );
+
+        let mut table = Table::new().align(1, Align::Center);
+        assert!(table.is_empty());
+        assert_eq!(table.to_string(), "");
+        table.push_row(["a", "b"]);
+        table.push_row(["long", "wide", "extra"]);
+        assert_eq!(table.to_string(), "a      b\nlong  wide  extra");
+    }
+}
@@ -0,0 +1,70 @@
+use alloy::json_abi::Function;
+use chain_info::{ChainInfo, ChainRegistry};
+use wasm_bindgen::prelude::*;
+
+fn parse_function(signature: &str) -> Result<Function, JsError> {
+    Function::parse(signature)
+        .map_err(|e| JsError::new(&format!("Invalid function {}: {}", signature, e)))
+}
+
+fn parse_hex(data: &str) -> Result<Vec<u8>, JsError> {
+    hex::decode(data.trim().trim_start_matches("0x"))
+        .map_err(|e| JsError::new(&format!("Invalid hex: {}", e)))
+}
+
+fn values_to_json(values: &[alloy::dyn_abi::DynSolValue]) -> String {
+    serde_json::Value::Array(values.iter().map(abi_codec::to_json).collect()).to_string()
+}
+
+#[wasm_bindgen(js_name = validateArgument)]
+pub fn validate_argument(ty: &str, input: &str) -> Result<(), JsError> {
+    let ty = abi_codec::parse_type(ty)?;
+    abi_codec::coerce(&ty, input)?;
+    Ok(())
+}
+
+#[wasm_bindgen(js_name = encodeCalldata)]
+pub fn encode_calldata(signature: &str, args: Vec<String>) -> Result<String, JsError> {
+    let function = parse_function(signature)?;
+    let calldata = abi_codec::encode_calldata(&function, &args)?;
+    Ok(calldata.to_string())
+}
+
+#[wasm_bindgen(js_name = decodeCalldata)]
+pub fn decode_calldata(signature: &str, calldata: &str) -> Result<String, JsError> {
+    let function = parse_function(signature)?;
+    let values = abi_codec::decode_input(&function, &parse_hex(calldata)?)?;
+    Ok(values_to_json(&values))
+}
+
+#[wasm_bindgen(js_name = decodeOutput)]
+pub fn decode_output(signature: &str, data: &str) -> Result<String, JsError> {
+    let function = parse_function(signature)?;
+    let values = abi_codec::decode_output(&function, &parse_hex(data)?)?;
+    Ok(values_to_json(&values))
+}
+
+#[wasm_bindgen]
+pub struct ChainList {
+    chains: ChainRegistry,
+}
+
+#[wasm_bindgen]
+impl ChainList {
+    #[wasm_bindgen(constructor)]
+    pub fn new(chains_json: &str) -> Result<ChainList, JsError> {
+        let chains = ChainRegistry::new(ChainInfo::parse_chains_str(chains_json)?);
+        Ok(Self { chains })
+    }
+
+    #[wasm_bindgen(js_name = byId)]
+    pub fn by_id(&self, chain_id: u64) -> Result<String, JsError> {
+        let chain = self.chains.get(chain_id)?;
+        Ok(serde_json::to_string(chain)?)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.chains.len()
+    }
+}
@@ -1,8 +1,17 @@
 /// src/abi.rs
+use crate::codec;
 use crate::error::{Error, Result};
-use alloy::json_abi::{Function, JsonAbi, StateMutability};
-use alloy::primitives::{Address, Bytes, U256};
-use std::{collections::HashMap, path::Path};
+use alloy::dyn_abi::{DynSolType, DynSolValue};
+use alloy::json_abi::{Function, JsonAbi, Param, StateMutability};
+use alloy::primitives::{Address, Bytes};
+use serde::{Deserialize, Serialize};
+use source::{AbiQuery, AbiSource, EtherscanSource};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+pub mod source;
 
 /// Represents the types of methods that can be called on a contract.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,7 +34,42 @@ impl std::fmt::Display for MethodType {
     }
 }
 
-/// Loads all ABI files from a directory.
+/// Extension of files holding human-readable ABIs.
+pub const HUMAN_READABLE_EXTENSION: &str = "habi";
+
+/// Parses a human-readable ABI, one signature per line.
+///
+/// Signatures look like `function transfer(address to, uint256 amount) returns (bool)`
+/// or `event Transfer(address indexed from, address indexed to, uint256 value)`.
+/// Blank lines and lines starting with `#` or `//` are ignored.
+///
+/// # Arguments
+///
+/// * `text` - The signatures
+///
+/// # Returns
+///
+/// * `Result<JsonAbi>` - The parsed ABI, or an error naming the first invalid line
+pub fn parse_human_readable(text: &str) -> Result<JsonAbi> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim().trim_end_matches(';'))
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+        .collect();
+    for line in &lines {
+        JsonAbi::parse([*line])
+            .map_err(|e| Error::InvalidAbi(format!("Invalid signature {:?}: {}", line, e)))?;
+    }
+    JsonAbi::parse(lines).map_err(|e| Error::InvalidAbi(e.to_string()))
+}
+
+/// Loads all ABI files from a directory and its subdirectories.
+///
+/// JSON ABIs are read from `.abi` files, Foundry (`out/`) and Hardhat
+/// (`artifacts/`) build artifacts from `.json` files, and human-readable ABIs
+/// from `.habi` files. JSON files without an `abi` key are skipped. When
+/// several files share a name, `.abi` wins over an artifact, which wins over
+/// `.habi`.
 ///
 /// # Arguments
 ///
@@ -33,25 +77,319 @@ impl std::fmt::Display for MethodType {
 ///
 /// # Returns
 ///
-/// * `Result<HashMap<String, JsonAbi>>` - Map of filenames to parsed ABIs, or an error
+/// * `Result<HashMap<String, JsonAbi>>` - Map of file stems to parsed ABIs, or an error
 pub fn load_abis(abi_dir: &Path) -> Result<HashMap<String, JsonAbi>> {
-    let mut abis = HashMap::new();
-    for entry in std::fs::read_dir(abi_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.extension().map_or(false, |ext| ext == "abi") {
-            let content = std::fs::read_to_string(&path)?;
-            let abi: JsonAbi = serde_json::from_str(&content)
-                .map_err(|e| Error::InvalidAbi(format!("Failed to parse ABI: {}", e)))?;
-            let name = path
-                .file_name()
-                .ok_or_else(|| Error::InvalidAbi("Invalid ABI filename".to_string()))?
-                .to_string_lossy()
-                .to_string();
-            abis.insert(name, abi);
+    let mut found = HashMap::new();
+    collect_abis(abi_dir, &mut found)?;
+    Ok(found
+        .into_iter()
+        .map(|(name, (_, abi))| (name, abi))
+        .collect())
+}
+
+/// Adds the ABIs found below a directory, keyed by name with their precedence.
+fn collect_abis(dir: &Path, found: &mut HashMap<String, (u8, JsonAbi)>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_abis(&path, found)?;
+            continue;
         }
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        if !matches!(extension, Some("abi" | "json" | HUMAN_READABLE_EXTENSION)) {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let (precedence, abi) = match extension {
+            Some("abi") => (
+                0,
+                serde_json::from_str(&content)
+                    .map_err(|e| Error::InvalidAbi(format!("Failed to parse ABI: {}", e)))?,
+            ),
+            Some("json") => match parse_artifact(&content)? {
+                Some(artifact) => (1, artifact.abi),
+                None => continue,
+            },
+            _ => (2, parse_human_readable(&content)?),
+        };
+        let name = path
+            .file_stem()
+            .ok_or_else(|| Error::InvalidAbi("Invalid ABI filename".to_string()))?
+            .to_string_lossy()
+            .to_string();
+        if found
+            .get(&name)
+            .is_none_or(|(existing, _)| precedence < *existing)
+        {
+            found.insert(name, (precedence, abi));
+        }
+    }
+    Ok(())
+}
+
+/// Contract build artifact produced by Foundry or Hardhat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Artifact {
+    /// The contract ABI
+    pub abi: JsonAbi,
+    /// Creation bytecode, when present and fully linked
+    pub bytecode: Option<Bytes>,
+    /// Runtime bytecode, when present and fully linked
+    pub deployed_bytecode: Option<Bytes>,
+}
+
+/// Parses a Foundry or Hardhat artifact.
+///
+/// Foundry nests bytecode as `{"object": "0x…"}` while Hardhat stores the hex
+/// string directly; both are accepted. Bytecode with unlinked library
+/// placeholders is left out.
+///
+/// # Arguments
+///
+/// * `json` - The artifact file contents
+///
+/// # Returns
+///
+/// * `Result<Option<Artifact>>` - The artifact, `None` if the JSON has no `abi` key, or an error
+pub fn parse_artifact(json: &str) -> Result<Option<Artifact>> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let Some(abi) = value.get("abi") else {
+        return Ok(None);
+    };
+    let abi = serde_json::from_value(abi.clone())
+        .map_err(|e| Error::InvalidAbi(format!("Failed to parse artifact ABI: {}", e)))?;
+    let bytecode = |key: &str| {
+        let code = value.get(key)?;
+        let hex = code
+            .get("object")
+            .unwrap_or(code)
+            .as_str()?
+            .parse::<Bytes>()
+            .ok()?;
+        (!hex.is_empty()).then_some(hex)
+    };
+    Ok(Some(Artifact {
+        abi,
+        bytecode: bytecode("bytecode"),
+        deployed_bytecode: bytecode("deployedBytecode"),
+    }))
+}
+
+/// Writes an ABI to `<abi_dir>/<name>.abi`, where [`load_abis`] picks it up.
+///
+/// # Arguments
+///
+/// * `abi_dir` - Path to the directory containing ABI files
+/// * `name` - Contract name, used as the file stem
+/// * `abi` - The ABI to store
+///
+/// # Returns
+///
+/// * `Result<PathBuf>` - Path of the written file, or an error
+pub fn save_abi(abi_dir: &Path, name: &str, abi: &JsonAbi) -> Result<PathBuf> {
+    std::fs::create_dir_all(abi_dir)?;
+    let path = abi_dir.join(format!("{}.abi", name));
+    std::fs::write(&path, serde_json::to_string_pretty(abi)?)?;
+    Ok(path)
+}
+
+/// Writes a human-readable ABI to `<abi_dir>/<name>.habi`.
+///
+/// # Arguments
+///
+/// * `abi_dir` - Path to the directory containing ABI files
+/// * `name` - Contract name, used as the file stem
+/// * `signatures` - One signature per line
+///
+/// # Returns
+///
+/// * `Result<PathBuf>` - Path of the written file, or an error
+pub fn save_human_readable(abi_dir: &Path, name: &str, signatures: &[String]) -> Result<PathBuf> {
+    std::fs::create_dir_all(abi_dir)?;
+    let path = abi_dir.join(format!("{}.{}", name, HUMAN_READABLE_EXTENSION));
+    std::fs::write(&path, signatures.join("\n") + "\n")?;
+    Ok(path)
+}
+
+/// Where an imported ABI came from, stored next to it as `<name>.meta`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiOrigin {
+    /// Name of the source the ABI was fetched from (e.g. `etherscan`, `sourcify`)
+    pub source: String,
+    /// Chain the contract is deployed on
+    pub chain_id: u64,
+    /// Address the ABI was fetched for
+    pub address: Address,
+    /// When the ABI was fetched, in seconds since the Unix epoch
+    pub fetched_at: u64,
+}
+
+impl AbiOrigin {
+    /// Records an ABI fetched now from a source.
+    pub fn new(source: &str, chain_id: u64, address: Address) -> Self {
+        Self {
+            source: source.to_string(),
+            chain_id,
+            address,
+            fetched_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Writes the origin to `<abi_dir>/<name>.meta`.
+    ///
+    /// # Arguments
+    ///
+    /// * `abi_dir` - Path to the directory containing ABI files
+    /// * `name` - Contract name, used as the file stem
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error during saving
+    pub fn save(&self, abi_dir: &Path, name: &str) -> Result<()> {
+        std::fs::write(
+            abi_dir.join(format!("{}.meta", name)),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Imports the ABI of a verified contract from the block explorers, trying
+/// Etherscan when an API key is given and falling back to Sourcify.
+///
+/// # Arguments
+///
+/// * `chain_id` - Chain the contract is deployed on
+/// * `address` - Address of the contract
+/// * `etherscan_api_key` - Optional Etherscan API key
+/// * `cache_dir` - Directory where fetched ABIs are cached
+///
+/// # Returns
+///
+/// * `Result<(JsonAbi, AbiOrigin)>` - The ABI and where it came from, or an error if no explorer knows it
+pub async fn import_from_explorers(
+    chain_id: u64,
+    address: Address,
+    etherscan_api_key: Option<&str>,
+    cache_dir: Option<&Path>,
+) -> Result<(JsonAbi, AbiOrigin)> {
+    let (source, abi) = source::explorer_sources(etherscan_api_key, cache_dir)
+        .resolve_from(&AbiQuery::by_address(address, chain_id))
+        .await?
+        .ok_or_else(|| {
+            Error::InvalidAbi(format!(
+                "No verified contract at {} on chain {}",
+                address, chain_id
+            ))
+        })?;
+    Ok((abi, AbiOrigin::new(&source, chain_id, address)))
+}
+
+/// Fetches the ABI of a verified contract from Etherscan.
+///
+/// # Arguments
+///
+/// * `chain_id` - Chain the contract is deployed on
+/// * `address` - Address of the contract
+/// * `api_key` - Etherscan API key
+///
+/// # Returns
+///
+/// * `Result<JsonAbi>` - The ABI, or an error if the contract is not verified
+pub async fn fetch_from_explorer(
+    chain_id: u64,
+    address: Address,
+    api_key: &str,
+) -> Result<JsonAbi> {
+    EtherscanSource::new(api_key)
+        .resolve(&AbiQuery::by_address(address, chain_id))
+        .await?
+        .ok_or_else(|| {
+            Error::InvalidAbi(format!(
+                "No verified contract at {} on chain {}",
+                address, chain_id
+            ))
+        })
+}
+
+/// Finds a function of an ABI by name or by full signature.
+///
+/// A name matches the first overload declared; a signature such as
+/// `transfer(address,uint256)` selects an exact overload.
+///
+/// # Arguments
+///
+/// * `abi` - The ABI to search
+/// * `method` - A function name or signature
+///
+/// # Returns
+///
+/// * `Result<&Function>` - The function or an error if none matches
+pub fn find_function<'a>(abi: &'a JsonAbi, method: &str) -> Result<&'a Function> {
+    let found = if method.contains('(') {
+        abi.functions().find(|f| f.signature() == method)
+    } else {
+        abi.function(method).and_then(|overloads| overloads.first())
+    };
+    found.ok_or_else(|| Error::InvalidFunction(format!("Method {} not found", method)))
+}
+
+/// Finds the functions of the loaded ABIs that calldata starts with the selector of.
+///
+/// # Arguments
+///
+/// * `abis` - The loaded ABIs by contract name
+/// * `calldata` - The calldata, selector first
+///
+/// # Returns
+///
+/// * `Vec<(&Function, Vec<&str>)>` - Each matching signature once, with the
+///   names of the ABIs declaring it, sorted by signature
+pub fn find_by_selector<'a>(
+    abis: &'a HashMap<String, JsonAbi>,
+    calldata: &[u8],
+) -> Vec<(&'a Function, Vec<&'a str>)> {
+    let Some(selector) = calldata.get(..4) else {
+        return Vec::new();
+    };
+    let mut matches: Vec<(&Function, Vec<&str>)> = Vec::new();
+    for (name, abi) in abis {
+        for function in abi.functions().filter(|f| f.selector() == selector) {
+            match matches
+                .iter_mut()
+                .find(|(found, _)| found.signature() == function.signature())
+            {
+                Some((_, names)) => names.push(name),
+                None => matches.push((function, vec![name])),
+            }
+        }
+    }
+    for (_, names) in &mut matches {
+        names.sort();
     }
-    Ok(abis)
+    matches.sort_by_key(|(function, _)| function.signature());
+    matches
+}
+
+/// Describes a function for selection lists as `name(type1,type2) -> (ret)`,
+/// leaving out the arrow when the function returns nothing.
+///
+/// # Arguments
+///
+/// * `function` - The function to describe
+///
+/// # Returns
+///
+/// * `String` - The signature followed by the output types
+pub fn signature_label(function: &Function) -> String {
+    if function.outputs.is_empty() {
+        return function.signature();
+    }
+    let outputs: Vec<_> = function.outputs.iter().map(|p| p.selector_type()).collect();
+    format!("{} -> ({})", function.signature(), outputs.join(","))
 }
 
 /// Gets methods from an ABI filtered by the specified method type.
@@ -63,14 +401,15 @@ pub fn load_abis(abi_dir: &Path) -> Result<HashMap<String, JsonAbi>> {
 ///
 /// # Returns
 ///
-/// * `HashMap<String, Function>` - Map of method names to Function objects
+/// * `HashMap<String, Function>` - Map of method signatures, e.g.
+///   `transfer(address,uint256)`, to Function objects, so overloads are kept apart
 pub fn get_methods_by_type(abi: &JsonAbi, method_type: MethodType) -> HashMap<String, Function> {
     let mut read_methods = HashMap::new();
     let mut write_methods = HashMap::new();
     let mut all_methods = HashMap::new();
 
     for function in abi.functions() {
-        let name = function.name.clone();
+        let name = function.signature();
         if matches!(
             function.state_mutability,
             StateMutability::View | StateMutability::Pure
@@ -89,7 +428,24 @@ pub fn get_methods_by_type(abi: &JsonAbi, method_type: MethodType) -> HashMap<St
     }
 }
 
-/// Parses an array or slice input string into a vector of Bytes.
+/// Parses a single parameter input string into an ABI value.
+///
+/// Every Solidity type is supported, including sized integers, fixed bytes,
+/// fixed and dynamic arrays and nested tuples.
+///
+/// # Arguments
+///
+/// * `param_type` - The Solidity type of the parameter (e.g., "uint8[3]" or "(address,uint256)[]")
+/// * `input` - The user input for the parameter
+///
+/// # Returns
+///
+/// * `Result<DynSolValue>` - The parsed value, or an error
+pub fn parse_param_input(param_type: &str, input: &str) -> Result<DynSolValue> {
+    codec::coerce(&codec::parse_type(param_type)?, input)
+}
+
+/// Parses an array or slice input string into an array value.
 ///
 /// # Arguments
 ///
@@ -98,105 +454,320 @@ pub fn get_methods_by_type(abi: &JsonAbi, method_type: MethodType) -> HashMap<St
 ///
 /// # Returns
 ///
-/// * `Result<Vec<Bytes>>` - Vector of parsed elements as Bytes, or an error
-pub fn parse_array_or_slice_input(input: &str, param_type: &str) -> Result<Vec<Bytes>> {
-    let input = input.trim().trim_matches(|c| c == '[' || c == ']');
-    let parts: Vec<&str> = input.split(',').map(str::trim).collect();
+/// * `Result<DynSolValue>` - The parsed array, or an error
+pub fn parse_array_or_slice_input(input: &str, param_type: &str) -> Result<DynSolValue> {
+    parse_param_input(&format!("{}[]", param_type), input)
+}
 
-    let mut result = Vec::with_capacity(parts.len());
-    for part in parts {
-        match param_type {
-            "address" => {
-                let addr = Address::parse_checksummed(part, None)
-                    .map_err(|_| Error::InvalidAddress(format!("Invalid address: {}", part)))?;
-                result.push(addr.to_vec().into());
-            }
-            "uint256" | "int256" => {
-                let num = U256::from_str_radix(part, 10)
-                    .map_err(|_| Error::InvalidArguments(format!("Invalid number: {}", part)))?;
-                result.push(num.to_be_bytes::<32>().into());
-            }
-            "bool" => {
-                let b = part
-                    .parse::<bool>()
-                    .map_err(|_| Error::InvalidArguments(format!("Invalid boolean: {}", part)))?;
-                result.push(Bytes::from_static(if b { &[1] } else { &[0] }));
-            }
-            "string" => {
-                result.push(Bytes::copy_from_slice(part.as_bytes()));
-            }
-            "bytes" => {
-                let bytes = hex::decode(part.trim_start_matches("0x"))
-                    .map_err(|_| Error::InvalidArguments(format!("Invalid hex: {}", part)))?;
-                result.push(Bytes::copy_from_slice(&bytes));
-            }
-            _ => {
-                return Err(Error::InvalidArguments(format!(
-                    "Unsupported array type: {}",
-                    param_type
-                )))
+/// Parses a tuple input string into a tuple value.
+///
+/// # Arguments
+///
+/// * `input` - The input string representing a tuple (e.g., "(1, true, 0x1234)")
+/// * `param_types` - The types of elements in the tuple
+///
+/// # Returns
+///
+/// * `Result<DynSolValue>` - The parsed tuple, or an error
+pub fn parse_tuple_input(input: &str, param_types: &[String]) -> Result<DynSolValue> {
+    parse_param_input(&format!("({})", param_types.join(",")), input)
+}
+
+/// Describes the elements of an array parameter as a parameter, e.g. the
+/// `tuple` with the same components for a `tuple[]`.
+///
+/// # Arguments
+///
+/// * `param` - The parameter
+///
+/// # Returns
+///
+/// * `Option<Param>` - The element parameter, or `None` if the parameter is not an array
+pub fn element_param(param: &Param) -> Option<Param> {
+    let element = param.ty.strip_suffix(']')?.rsplit_once('[')?.0;
+    Some(Param {
+        ty: element.to_string(),
+        name: param.name.clone(),
+        components: param.components.clone(),
+        internal_type: None,
+    })
+}
+
+/// Splits a tuple input such as `(1, "a, b", (2, 3))`, or an array input
+/// such as `[1, 2]`, into the inputs of its components, the reverse of
+/// [`join_tuple_input`] and [`join_array_input`]. JSON arrays are split
+/// into their elements; otherwise commas inside nested tuples, arrays and
+/// quoted strings do not split, and quotes around a component are removed.
+///
+/// # Arguments
+///
+/// * `input` - The tuple input, in parentheses, or array input, in brackets
+///
+/// # Returns
+///
+/// * `Option<Vec<String>>` - The component inputs, or `None` if the input is
+///   not in parentheses or brackets, or its brackets and quotes are unbalanced
+pub fn split_list_input(input: &str) -> Option<Vec<String>> {
+    let input = input.trim();
+    if let Ok(serde_json::Value::Array(values)) = serde_json::from_str(input) {
+        return Some(
+            values
+                .into_iter()
+                .map(|value| match value {
+                    serde_json::Value::String(s) => s,
+                    other => other.to_string(),
+                })
+                .collect(),
+        );
+    }
+    let inner = input
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .or_else(|| input.strip_prefix('[').and_then(|s| s.strip_suffix(']')))?;
+    if inner.trim().is_empty() {
+        return Some(Vec::new());
+    }
+    let mut components = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    for c in inner.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(' | '[') => depth += 1,
+            (None, ')' | ']') => depth = depth.checked_sub(1)?,
+            (None, ',') if depth == 0 => {
+                components.push(unquote(&current));
+                current.clear();
+                continue;
             }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if depth != 0 || quote.is_some() {
+        return None;
+    }
+    components.push(unquote(&current));
+    Some(components)
+}
+
+/// Trims a component input and removes the quotes around it, if any.
+fn unquote(input: &str) -> String {
+    let input = input.trim();
+    for q in ['"', '\''] {
+        if let Some(inner) = input.strip_prefix(q).and_then(|s| s.strip_suffix(q)) {
+            return inner.to_string();
         }
     }
-    Ok(result)
+    input.to_string()
+}
+
+/// Quotes the input of a `string` so commas, brackets and parentheses in it
+/// are kept when it is part of a tuple or array input.
+fn quote_input(ty: &DynSolType, input: &str) -> String {
+    match ty {
+        DynSolType::String if input.contains('"') => format!("'{}'", input),
+        DynSolType::String => format!("\"{}\"", input),
+        _ => input.to_string(),
+    }
 }
 
-/// Parses a tuple input string into a vector of Bytes.
+/// Assembles the inputs of tuple components into a tuple input, quoting
+/// `string` components.
 ///
 /// # Arguments
 ///
-/// * `input` - The input string representing a tuple (e.g., "(1, true, 0x1234)")
-/// * `param_types` - The types of elements in the tuple
+/// * `types` - The component types
+/// * `components` - One input per component
 ///
 /// # Returns
 ///
-/// * `Result<Vec<Bytes>>` - Vector of parsed elements as Bytes, or an error
-pub fn parse_tuple_input(input: &str, param_types: &[String]) -> Result<Vec<Bytes>> {
-    let input = input.trim().trim_matches(|c| c == '(' || c == ')');
-    let parts: Vec<&str> = input.split(',').map(str::trim).collect();
+/// * `String` - The tuple input, e.g. `(1, "a, b")`
+pub fn join_tuple_input(types: &[DynSolType], components: &[String]) -> String {
+    let components: Vec<String> = types
+        .iter()
+        .zip(components)
+        .map(|(ty, input)| quote_input(ty, input))
+        .collect();
+    format!("({})", components.join(", "))
+}
 
-    if parts.len() != param_types.len() {
-        return Err(Error::InvalidArguments(format!(
-            "Tuple input length mismatch: expected {}, got {}",
-            param_types.len(),
-            parts.len()
-        )));
+/// Assembles the inputs of array elements into an array input. `string`
+/// arrays are written as JSON, so any string is kept as entered.
+///
+/// # Arguments
+///
+/// * `element` - The element type
+/// * `elements` - One input per element
+///
+/// # Returns
+///
+/// * `String` - The array input, e.g. `["a, b","c"]`
+pub fn join_array_input(element: &DynSolType, elements: &[String]) -> String {
+    if *element == DynSolType::String {
+        return serde_json::Value::from(elements.to_vec()).to_string();
     }
+    let elements: Vec<String> = elements
+        .iter()
+        .map(|input| quote_input(element, input))
+        .collect();
+    format!("[{}]", elements.join(", "))
+}
 
-    let mut result = Vec::with_capacity(parts.len());
-    for (part, param_type) in parts.iter().zip(param_types) {
-        match param_type.as_str() {
-            "address" => {
-                let addr = Address::parse_checksummed(part, None)
-                    .map_err(|_| Error::InvalidAddress(format!("Invalid address: {}", part)))?;
-                result.push(addr.to_vec().into());
-            }
-            "uint256" | "int256" => {
-                let num = U256::from_str_radix(part, 10)
-                    .map_err(|_| Error::InvalidArguments(format!("Invalid number: {}", part)))?;
-                result.push(num.to_be_bytes::<32>().into());
-            }
-            "bool" => {
-                let b = part
-                    .parse::<bool>()
-                    .map_err(|_| Error::InvalidArguments(format!("Invalid boolean: {}", part)))?;
-                result.push(Bytes::from_static(if b { &[1] } else { &[0] }));
-            }
-            "string" => {
-                result.push(Bytes::copy_from_slice(part.as_bytes()));
-            }
-            "bytes" => {
-                let bytes = hex::decode(part.trim_start_matches("0x"))
-                    .map_err(|_| Error::InvalidArguments(format!("Invalid hex: {}", part)))?;
-                result.push(Bytes::copy_from_slice(&bytes));
-            }
-            _ => {
-                return Err(Error::InvalidArguments(format!(
-                    "Unsupported tuple type: {}",
-                    param_type
-                )))
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, I256, U256};
+
+    #[test]
+    fn test_parse_foundry_and_hardhat_artifacts() {
+        let foundry = r#"{"abi":[{"type":"function","name":"owner","inputs":[],"outputs":[],"stateMutability":"view"}],"bytecode":{"object":"0x6080","linkReferences":{}},"deployedBytecode":{"object":"0x__$1234$__"}}"#;
+        let hardhat = r#"{"_format":"hh-sol-artifact-1","contractName":"Token","abi":[],"bytecode":"0x6080","deployedBytecode":"0x"}"#;
+
+        let artifact = parse_artifact(foundry).unwrap().unwrap();
+        assert!(artifact.abi.function("owner").is_some());
+        assert_eq!(artifact.bytecode, Some(Bytes::from_static(&[0x60, 0x80])));
+        assert_eq!(artifact.deployed_bytecode, None);
+
+        let artifact = parse_artifact(hardhat).unwrap().unwrap();
+        assert_eq!(artifact.bytecode, Some(Bytes::from_static(&[0x60, 0x80])));
+        assert_eq!(artifact.deployed_bytecode, None);
+        assert!(parse_artifact(r#"{"_format":"hh-sol-dbg-1"}"#)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_human_readable() {
+        let abi = parse_human_readable(
+            "# token\nfunction transfer(address to, uint256 amount) returns (bool);\n\nevent Transfer(address indexed from, address indexed to, uint256 value)\n",
+        )
+        .unwrap();
+        let transfer = find_function(&abi, "transfer").unwrap();
+        assert_eq!(transfer.signature(), "transfer(address,uint256)");
+        assert_eq!(transfer.outputs.len(), 1);
+        assert_eq!(
+            signature_label(transfer),
+            "transfer(address,uint256) -> (bool)"
+        );
+        assert!(abi.event("Transfer").is_some());
+        assert!(parse_human_readable("function transfer(address").is_err());
+    }
+
+    #[test]
+    fn test_methods_keep_overloads() {
+        let abi = parse_human_readable(
+            "function safeTransferFrom(address from, address to, uint256 tokenId)\nfunction safeTransferFrom(address from, address to, uint256 tokenId, bytes data)\nfunction ownerOf(uint256 tokenId) view returns (address)",
+        )
+        .unwrap();
+        let writes = get_methods_by_type(&abi, MethodType::Write);
+        assert_eq!(writes.len(), 2);
+        assert_eq!(
+            writes["safeTransferFrom(address,address,uint256,bytes)"]
+                .inputs
+                .len(),
+            4
+        );
+        let reads = get_methods_by_type(&abi, MethodType::Read);
+        assert_eq!(reads.keys().collect::<Vec<_>>(), vec!["ownerOf(uint256)"]);
+        assert_eq!(get_methods_by_type(&abi, MethodType::All).len(), 3);
+    }
+
+    #[test]
+    fn test_find_by_selector() {
+        let token = parse_human_readable("function transfer(address to, uint256 amount)").unwrap();
+        let vault =
+            parse_human_readable("function transfer(address, uint256)\nfunction deposit(uint256)")
+                .unwrap();
+        let abis = HashMap::from([("Token".to_string(), token), ("Vault".to_string(), vault)]);
+        let calldata = codec::encode_calldata(
+            find_function(&abis["Token"], "transfer").unwrap(),
+            &["0x0000000000000000000000000000000000000001", "5"],
+        )
+        .unwrap();
+
+        let matches = find_by_selector(&abis, &calldata);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0.signature(), "transfer(address,uint256)");
+        assert_eq!(matches[0].1, vec!["Token", "Vault"]);
+        assert!(find_by_selector(&abis, &[0xa9, 0x05]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_inputs_of_any_type() {
+        assert_eq!(
+            parse_param_input("uint8", "255").unwrap(),
+            DynSolValue::Uint(U256::from(255), 8)
+        );
+        assert!(parse_param_input("uint8", "256").is_err());
+        assert_eq!(
+            parse_param_input("int128", "-1").unwrap(),
+            DynSolValue::Int(I256::MINUS_ONE, 128)
+        );
+        assert_eq!(
+            parse_param_input("bytes4", "0x095ea7b3").unwrap(),
+            DynSolValue::FixedBytes(B256::right_padding_from(&[0x09, 0x5e, 0xa7, 0xb3]), 4)
+        );
+        assert!(matches!(
+            parse_param_input("uint16[2][]", "[[1, 2], [3, 4]]").unwrap(),
+            DynSolValue::Array(rows) if rows.len() == 2
+        ));
+        assert!(parse_param_input("uint16[2]", "[1, 2, 3]").is_err());
+
+        let tuple = parse_tuple_input(
+            "(0x0000000000000000000000000000000000000001, (1, [true, false]))",
+            &["address".to_string(), "(uint256,bool[])".to_string()],
+        )
+        .unwrap();
+        assert_eq!(tuple.as_tuple().map(<[_]>::len), Some(2));
+        assert_eq!(
+            parse_array_or_slice_input("[1, 2, 3]", "uint32").unwrap(),
+            DynSolValue::Array(
+                (1..=3)
+                    .map(|n| DynSolValue::Uint(U256::from(n), 32))
+                    .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn test_list_input_components() {
+        let types = [
+            DynSolType::Uint(256),
+            DynSolType::String,
+            DynSolType::Tuple(vec![DynSolType::Bool, DynSolType::String]),
+        ];
+        let components = vec![
+            "1".to_string(),
+            "a, (b)".to_string(),
+            "(true, \"x\")".to_string(),
+        ];
+        let input = join_tuple_input(&types, &components);
+        assert_eq!(input, r#"(1, "a, (b)", (true, "x"))"#);
+        assert!(codec::coerce(&DynSolType::Tuple(types.to_vec()), &input).is_ok());
+        assert_eq!(split_list_input(&input), Some(components));
+        assert_eq!(split_list_input("()"), Some(Vec::new()));
+        assert_eq!(split_list_input("(1, [2)"), None);
+        assert_eq!(split_list_input("1, 2"), None);
+
+        let elements = vec!["a, b".to_string(), r#"say "hi""#.to_string()];
+        let input = join_array_input(&DynSolType::String, &elements);
+        assert_eq!(input, r#"["a, b","say \"hi\""]"#);
+        assert_eq!(split_list_input(&input), Some(elements));
+        assert_eq!(
+            split_list_input("[1, (2, 3)]"),
+            Some(vec!["1".to_string(), "(2, 3)".to_string()])
+        );
+        let ty = DynSolType::Array(Box::new(DynSolType::String));
+        assert_eq!(
+            codec::coerce(&ty, &input).unwrap(),
+            DynSolValue::Array(vec![
+                DynSolValue::String("a, b".to_string()),
+                DynSolValue::String(r#"say "hi""#.to_string()),
+            ])
+        );
     }
-    Ok(result)
 }
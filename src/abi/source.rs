@@ -0,0 +1,671 @@
+/// src/abi/source.rs
+use crate::error::{Error, Result};
+use alloy::json_abi::JsonAbi;
+use alloy::primitives::Address;
+use alloy::transports::http::reqwest;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// ABIs of common token standards bundled with the binary, by name.
+pub const STANDARDS: &[(&str, &str)] = &[
+    ("ERC20", include_str!("standards/ERC20.json")),
+    ("ERC721", include_str!("standards/ERC721.json")),
+    ("ERC1155", include_str!("standards/ERC1155.json")),
+];
+
+/// Describes the contract whose ABI is requested.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct AbiQuery {
+    /// Contract name, e.g. the ABI file stem or a standard name
+    pub name: Option<String>,
+    /// Deployed contract address
+    pub address: Option<Address>,
+    /// Chain the contract is deployed on
+    pub chain_id: Option<u64>,
+}
+
+impl AbiQuery {
+    /// Creates a query for a contract name.
+    pub fn by_name(name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a query for a deployed contract.
+    pub fn by_address(address: Address, chain_id: u64) -> Self {
+        Self {
+            address: Some(address),
+            chain_id: Some(chain_id),
+            ..Self::default()
+        }
+    }
+
+    /// Returns a key identifying the query, used for caching.
+    fn cache_key(&self) -> Option<String> {
+        match (self.address, self.chain_id, &self.name) {
+            (Some(address), Some(chain_id), _) => Some(format!("{}/{}", chain_id, address)),
+            (_, _, Some(name)) => Some(format!("name/{}", name)),
+            _ => None,
+        }
+    }
+}
+
+/// A place ABIs can be obtained from.
+#[async_trait]
+pub trait AbiSource: Send + Sync {
+    /// Returns a short label identifying the source in logs and errors.
+    fn name(&self) -> &str;
+
+    /// Lists every ABI the source can enumerate.
+    ///
+    /// Remote sources that can only answer lookups return an empty map.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, JsonAbi>>` - Map of contract names to ABIs, or an error
+    async fn list(&self) -> Result<HashMap<String, JsonAbi>> {
+        Ok(HashMap::new())
+    }
+
+    /// Looks up the ABI of a contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The contract to look up
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<JsonAbi>>` - The ABI, `None` if the source does not know it, or an error
+    async fn resolve(&self, query: &AbiQuery) -> Result<Option<JsonAbi>>;
+}
+
+impl std::fmt::Debug for dyn AbiSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AbiSource").field(&self.name()).finish()
+    }
+}
+
+/// Reads `<name>.abi`, `<name>.json` artifact and `<name>.habi` files from a directory.
+#[derive(Debug, Clone)]
+pub struct DirectorySource {
+    dir: PathBuf,
+}
+
+impl DirectorySource {
+    /// Creates a source reading ABI files from a directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl AbiSource for DirectorySource {
+    fn name(&self) -> &str {
+        "directory"
+    }
+
+    async fn list(&self) -> Result<HashMap<String, JsonAbi>> {
+        super::load_abis(&self.dir)
+    }
+
+    async fn resolve(&self, query: &AbiQuery) -> Result<Option<JsonAbi>> {
+        let Some(name) = &query.name else {
+            return Ok(None);
+        };
+        let path = self.dir.join(format!("{}.abi", name));
+        if path.is_file() {
+            return parse_abi(&std::fs::read_to_string(path)?).map(Some);
+        }
+        let path = self.dir.join(format!("{}.json", name));
+        if path.is_file() {
+            if let Some(artifact) = super::parse_artifact(&std::fs::read_to_string(path)?)? {
+                return Ok(Some(artifact.abi));
+            }
+        }
+        let path = self
+            .dir
+            .join(format!("{}.{}", name, super::HUMAN_READABLE_EXTENSION));
+        if path.is_file() {
+            return super::parse_human_readable(&std::fs::read_to_string(path)?).map(Some);
+        }
+        Ok(None)
+    }
+}
+
+/// Serves the ABIs of common token standards bundled in [`STANDARDS`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbeddedSource;
+
+#[async_trait]
+impl AbiSource for EmbeddedSource {
+    fn name(&self) -> &str {
+        "embedded"
+    }
+
+    async fn list(&self) -> Result<HashMap<String, JsonAbi>> {
+        STANDARDS
+            .iter()
+            .map(|(name, json)| Ok((name.to_string(), parse_abi(json)?)))
+            .collect()
+    }
+
+    async fn resolve(&self, query: &AbiQuery) -> Result<Option<JsonAbi>> {
+        let Some(name) = &query.name else {
+            return Ok(None);
+        };
+        STANDARDS
+            .iter()
+            .find(|(standard, _)| standard.eq_ignore_ascii_case(name))
+            .map(|(_, json)| parse_abi(json))
+            .transpose()
+    }
+}
+
+/// Fetches ABIs from an HTTP registry.
+///
+/// The URL template may contain the `{name}`, `{address}` and `{chain_id}`
+/// placeholders, e.g. `https://abis.example.com/{chain_id}/{address}.json`.
+/// The registry must answer with a JSON ABI, or 404 when it does not know the
+/// contract.
+#[derive(Debug, Clone)]
+pub struct HttpRegistrySource {
+    template: String,
+    client: reqwest::Client,
+}
+
+impl HttpRegistrySource {
+    /// Creates a source fetching ABIs from a URL template.
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: template.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Builds the URL for a query, or `None` if a placeholder cannot be filled.
+    fn url(&self, query: &AbiQuery) -> Option<String> {
+        let mut url = self.template.clone();
+        let replacements = [
+            ("{name}", query.name.clone()),
+            ("{address}", query.address.map(|a| a.to_checksum(None))),
+            ("{chain_id}", query.chain_id.map(|c| c.to_string())),
+        ];
+        for (placeholder, value) in replacements {
+            if url.contains(placeholder) {
+                url = url.replace(placeholder, &value?);
+            }
+        }
+        Some(url)
+    }
+}
+
+#[async_trait]
+impl AbiSource for HttpRegistrySource {
+    fn name(&self) -> &str {
+        "registry"
+    }
+
+    async fn resolve(&self, query: &AbiQuery) -> Result<Option<JsonAbi>> {
+        let Some(url) = self.url(query) else {
+            return Ok(None);
+        };
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to fetch ABI from {}: {}", url, e)))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Error::Other(format!(
+                "ABI registry returned {} for {}",
+                response.status(),
+                url
+            )));
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to read ABI from {}: {}", url, e)))?;
+        parse_abi(&body).map(Some)
+    }
+}
+
+/// Default endpoint of the Etherscan multichain API.
+pub const ETHERSCAN_API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// Source fetching verified ABIs from Etherscan by address and chain.
+///
+/// Only queries carrying both an address and a chain ID are answered;
+/// unverified contracts resolve to `None`.
+#[derive(Debug, Clone)]
+pub struct EtherscanSource {
+    api_key: String,
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl EtherscanSource {
+    /// Creates a source using the given API key.
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            base_url: ETHERSCAN_API_URL.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Uses another Etherscan-compatible API endpoint.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl AbiSource for EtherscanSource {
+    fn name(&self) -> &str {
+        "etherscan"
+    }
+
+    async fn resolve(&self, query: &AbiQuery) -> Result<Option<JsonAbi>> {
+        let (Some(address), Some(chain_id)) = (query.address, query.chain_id) else {
+            return Ok(None);
+        };
+        let mut url = url::Url::parse(&self.base_url).map_err(|e| {
+            Error::InvalidConfig(format!("Invalid explorer URL {}: {}", self.base_url, e))
+        })?;
+        url.query_pairs_mut()
+            .append_pair("chainid", &chain_id.to_string())
+            .append_pair("module", "contract")
+            .append_pair("action", "getabi")
+            .append_pair("address", &address.to_checksum(None))
+            .append_pair("apikey", &self.api_key);
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to fetch ABI from Etherscan: {}", e)))?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to read Etherscan response: {}", e)))?;
+        parse_etherscan_response(&body)
+    }
+}
+
+/// Default endpoint of the Sourcify repository.
+pub const SOURCIFY_URL: &str = "https://repo.sourcify.dev";
+
+/// Source fetching ABIs of contracts verified on Sourcify.
+///
+/// Full matches are preferred over partial ones. Only queries carrying both
+/// an address and a chain ID are answered.
+#[derive(Debug, Clone)]
+pub struct SourcifySource {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl Default for SourcifySource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SourcifySource {
+    /// Creates a source using the public Sourcify repository.
+    pub fn new() -> Self {
+        Self {
+            base_url: SOURCIFY_URL.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Uses another Sourcify repository.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl AbiSource for SourcifySource {
+    fn name(&self) -> &str {
+        "sourcify"
+    }
+
+    async fn resolve(&self, query: &AbiQuery) -> Result<Option<JsonAbi>> {
+        let (Some(address), Some(chain_id)) = (query.address, query.chain_id) else {
+            return Ok(None);
+        };
+        for kind in ["full_match", "partial_match"] {
+            let url = format!(
+                "{}/contracts/{}/{}/{}/metadata.json",
+                self.base_url,
+                kind,
+                chain_id,
+                address.to_checksum(None)
+            );
+            let response =
+                self.client.get(&url).send().await.map_err(|e| {
+                    Error::Other(format!("Failed to fetch ABI from Sourcify: {}", e))
+                })?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                continue;
+            }
+            if !response.status().is_success() {
+                return Err(Error::Other(format!(
+                    "Sourcify returned {} for {}",
+                    response.status(),
+                    url
+                )));
+            }
+            let body = response
+                .text()
+                .await
+                .map_err(|e| Error::Other(format!("Failed to read Sourcify metadata: {}", e)))?;
+            let metadata: serde_json::Value = serde_json::from_str(&body)?;
+            return serde_json::from_value(metadata["output"]["abi"].clone())
+                .map(Some)
+                .map_err(|e| Error::InvalidAbi(format!("Failed to parse ABI: {}", e)));
+        }
+        Ok(None)
+    }
+}
+
+/// Tries a list of sources in order.
+///
+/// Listing merges every source, earlier sources winning on name clashes.
+/// Resolution returns the first ABI found; a failing source is skipped, and
+/// its error is only returned if no later source knows the contract.
+#[derive(Debug, Default)]
+pub struct ChainedSource {
+    sources: Vec<Arc<dyn AbiSource>>,
+}
+
+impl ChainedSource {
+    /// Creates an empty chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a source, consulted after the ones already in the chain.
+    pub fn with<S: AbiSource + 'static>(mut self, source: S) -> Self {
+        self.sources.push(Arc::new(source));
+        self
+    }
+
+    /// Appends an already shared source.
+    pub fn with_shared(mut self, source: Arc<dyn AbiSource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+}
+
+#[async_trait]
+impl AbiSource for ChainedSource {
+    fn name(&self) -> &str {
+        "chain"
+    }
+
+    async fn list(&self) -> Result<HashMap<String, JsonAbi>> {
+        let mut abis = HashMap::new();
+        for source in self.sources.iter().rev() {
+            abis.extend(source.list().await?);
+        }
+        Ok(abis)
+    }
+
+    async fn resolve(&self, query: &AbiQuery) -> Result<Option<JsonAbi>> {
+        Ok(self.resolve_from(query).await?.map(|(_, abi)| abi))
+    }
+}
+
+impl ChainedSource {
+    /// Resolves an ABI like [`AbiSource::resolve`], also returning the name of
+    /// the source that found it.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The contract to look up
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<(String, JsonAbi)>>` - The source name and ABI, `None` if no source knows it, or an error
+    pub async fn resolve_from(&self, query: &AbiQuery) -> Result<Option<(String, JsonAbi)>> {
+        let mut last_error = None;
+        for source in &self.sources {
+            match source.resolve(query).await {
+                Ok(Some(abi)) => return Ok(Some((source.name().to_string(), abi))),
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::debug!(source = source.name(), error = %e, "ABI source failed");
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Caches the ABIs resolved by another source, in memory and optionally on disk.
+#[derive(Debug)]
+pub struct CachedSource<S> {
+    inner: S,
+    dir: Option<PathBuf>,
+    memory: Mutex<HashMap<String, JsonAbi>>,
+}
+
+impl<S: AbiSource> CachedSource<S> {
+    /// Wraps a source with an in-memory cache.
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            dir: None,
+            memory: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Also persists resolved ABIs as JSON files under a directory.
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    fn cache_path(&self, key: &str) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| cache_file(dir, key))
+    }
+}
+
+#[async_trait]
+impl<S: AbiSource> AbiSource for CachedSource<S> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn list(&self) -> Result<HashMap<String, JsonAbi>> {
+        self.inner.list().await
+    }
+
+    async fn resolve(&self, query: &AbiQuery) -> Result<Option<JsonAbi>> {
+        let Some(key) = query.cache_key() else {
+            return self.inner.resolve(query).await;
+        };
+        if let Some(abi) = self
+            .memory
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+        {
+            return Ok(Some(abi.clone()));
+        }
+        if let Some(path) = self.cache_path(&key).filter(|p| p.is_file()) {
+            let abi = parse_abi(&std::fs::read_to_string(path)?)?;
+            self.remember(key, &abi);
+            return Ok(Some(abi));
+        }
+
+        let resolved = self.inner.resolve(query).await?;
+        if let Some(abi) = &resolved {
+            if let Some(path) = self.cache_path(&key) {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, serde_json::to_string_pretty(abi)?)?;
+            }
+            self.remember(key, abi);
+        }
+        Ok(resolved)
+    }
+}
+
+impl<S> CachedSource<S> {
+    fn remember(&self, key: String, abi: &JsonAbi) {
+        self.memory
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, abi.clone());
+    }
+}
+
+/// Returns the default source chain: the ABI directory, then the bundled
+/// standards, then the registry if one is configured, cached under `cache_dir`.
+///
+/// # Arguments
+///
+/// * `abi_dir` - Directory holding the user's ABI files
+/// * `registry` - Optional URL template of an HTTP ABI registry
+/// * `cache_dir` - Directory where remotely resolved ABIs are cached
+///
+/// # Returns
+///
+/// * `Arc<dyn AbiSource>` - The source chain
+pub fn default_sources(
+    abi_dir: &Path,
+    registry: Option<&str>,
+    cache_dir: Option<&Path>,
+) -> Arc<dyn AbiSource> {
+    let mut chain = ChainedSource::new()
+        .with(DirectorySource::new(abi_dir))
+        .with(EmbeddedSource);
+    if let Some(template) = registry {
+        let mut cached = CachedSource::new(HttpRegistrySource::new(template));
+        if let Some(dir) = cache_dir {
+            cached = cached.with_dir(dir);
+        }
+        chain = chain.with(cached);
+    }
+    Arc::new(chain)
+}
+
+/// Returns the sources used to import verified ABIs by address: Etherscan if
+/// an API key is given, then Sourcify, each cached under `cache_dir`.
+///
+/// # Arguments
+///
+/// * `etherscan_api_key` - Optional Etherscan API key
+/// * `cache_dir` - Directory where fetched ABIs are cached
+///
+/// # Returns
+///
+/// * `ChainedSource` - The explorer chain
+pub fn explorer_sources(
+    etherscan_api_key: Option<&str>,
+    cache_dir: Option<&Path>,
+) -> ChainedSource {
+    fn cached<S: AbiSource>(source: S, cache_dir: Option<&Path>) -> CachedSource<S> {
+        match cache_dir {
+            Some(dir) => CachedSource::new(source).with_dir(dir),
+            None => CachedSource::new(source),
+        }
+    }
+
+    let mut chain = ChainedSource::new();
+    if let Some(api_key) = etherscan_api_key {
+        chain = chain.with(cached(EtherscanSource::new(api_key), cache_dir));
+    }
+    chain.with(cached(SourcifySource::new(), cache_dir))
+}
+
+/// Parses a JSON ABI.
+fn parse_abi(json: &str) -> Result<JsonAbi> {
+    serde_json::from_str(json).map_err(|e| Error::InvalidAbi(format!("Failed to parse ABI: {}", e)))
+}
+
+/// Parses a `getabi` response, whose `result` holds the ABI as a JSON string.
+fn parse_etherscan_response(body: &str) -> Result<Option<JsonAbi>> {
+    let response: serde_json::Value = serde_json::from_str(body)?;
+    let result = response["result"].as_str().unwrap_or_default();
+    if response["status"] == "1" {
+        return parse_abi(result).map(Some);
+    }
+    if result.contains("not verified") {
+        return Ok(None);
+    }
+    Err(Error::Other(format!("Etherscan error: {}", result)))
+}
+
+/// Maps a cache key to a file below the cache directory.
+fn cache_file(dir: &Path, key: &str) -> PathBuf {
+    let mut path = dir.to_path_buf();
+    for part in key.split('/') {
+        path.push(part);
+    }
+    path.set_extension("json");
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_chain_prefers_earlier_sources() {
+        let dir = std::env::temp_dir().join(format!("inachus-abi-source-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ERC20.abi"), "[]").unwrap();
+
+        let chain = ChainedSource::new()
+            .with(DirectorySource::new(&dir))
+            .with(EmbeddedSource);
+        let listed = chain.list().await.unwrap();
+        let resolved = chain.resolve(&AbiQuery::by_name("ERC721")).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(listed["ERC20"].functions().count(), 0);
+        assert!(listed["ERC1155"].function("balanceOfBatch").is_some());
+        assert!(resolved.unwrap().function("ownerOf").is_some());
+        assert!(chain
+            .resolve(&AbiQuery::by_name("Unknown"))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_etherscan_response() {
+        let verified = r#"{"status":"1","message":"OK","result":"[{\"type\":\"function\",\"name\":\"owner\",\"inputs\":[],\"outputs\":[],\"stateMutability\":\"view\"}]"}"#;
+        let unverified =
+            r#"{"status":"0","message":"NOTOK","result":"Contract source code not verified"}"#;
+        let invalid_key = r#"{"status":"0","message":"NOTOK","result":"Invalid API Key"}"#;
+
+        assert!(parse_etherscan_response(verified)
+            .unwrap()
+            .unwrap()
+            .function("owner")
+            .is_some());
+        assert!(parse_etherscan_response(unverified).unwrap().is_none());
+        assert!(parse_etherscan_response(invalid_key).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+/// src/address_book.rs
+use crate::error::{Error, Result};
+use alloy::json_abi::Function;
+use alloy::primitives::Address;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+lazy_static! {
+    static ref ADDRESS_REGEXP: Regex = Regex::new(r"\b0x[0-9a-fA-F]{40}\b").unwrap();
+}
+
+/// Aliases for addresses, the `[address_book]` table of `config.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AddressBook {
+    entries: BTreeMap<String, Address>,
+}
+
+impl AddressBook {
+    /// Creates an empty address book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the aliases and their addresses, sorted by alias.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, Address)> {
+        self.entries
+            .iter()
+            .map(|(alias, address)| (alias.as_str(), *address))
+    }
+
+    /// Returns whether the book holds no alias.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds an alias, replacing the address it pointed to if it existed.
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - Letters, digits, `_`, `-` or `.`, not starting with `0x`
+    /// * `address` - The address the alias stands for
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error if the alias is invalid
+    pub fn insert(&mut self, alias: &str, address: Address) -> Result<()> {
+        validate_alias(alias)?;
+        self.entries.insert(alias.to_string(), address);
+        Ok(())
+    }
+
+    /// Removes an alias, returning the address it pointed to.
+    pub fn remove(&mut self, alias: &str) -> Option<Address> {
+        self.entries.remove(alias)
+    }
+
+    /// Returns the address of an alias.
+    pub fn get(&self, alias: &str) -> Option<Address> {
+        self.entries.get(alias).copied()
+    }
+
+    /// Returns the alias of an address, the first in alphabetical order if it has several.
+    pub fn alias_of(&self, address: Address) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(_, a)| **a == address)
+            .map(|(alias, _)| alias.as_str())
+    }
+
+    /// Returns a copy of the book labelling the addresses it has no alias
+    /// for with the given names, e.g. their ENS names, to annotate results.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The names, by address
+    ///
+    /// # Returns
+    ///
+    /// * `AddressBook` - The book with the names added
+    pub fn with_names(&self, names: HashMap<Address, String>) -> Self {
+        let mut book = self.clone();
+        for (address, name) in names {
+            if self.alias_of(address).is_none() {
+                book.entries.insert(name, address);
+            }
+        }
+        book
+    }
+
+    /// Validates every alias of the book.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error naming the first invalid alias
+    pub fn validate(&self) -> Result<()> {
+        self.entries
+            .keys()
+            .try_for_each(|alias| validate_alias(alias))
+    }
+
+    /// Replaces an alias by its checksummed address, leaving anything else untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - An alias or an address as typed by the user
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The address the alias stands for, or the trimmed input
+    pub fn resolve(&self, input: &str) -> String {
+        let input = input.trim();
+        match self.get(input) {
+            Some(address) => address.to_checksum(None),
+            None => input.to_string(),
+        }
+    }
+
+    /// Resolves the aliases given for the `address` parameters of a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function the arguments are for
+    /// * `args` - One raw argument per function input
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - The arguments with aliases replaced by addresses
+    pub fn resolve_args(&self, function: &Function, args: &[String]) -> Vec<String> {
+        function
+            .inputs
+            .iter()
+            .zip(args)
+            .map(|(input, arg)| match input.ty.as_str() {
+                "address" => self.resolve(arg),
+                _ => arg.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the aliases starting with the given input, ignoring case.
+    pub fn suggest(&self, input: &str) -> Vec<String> {
+        let input = input.trim().to_lowercase();
+        self.entries
+            .keys()
+            .filter(|alias| alias.to_lowercase().starts_with(&input))
+            .cloned()
+            .collect()
+    }
+
+    /// Annotates every known address of a text with its alias, e.g. `0x… (treasury)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to annotate, e.g. a rendered result
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The annotated text
+    pub fn annotate(&self, text: &str) -> String {
+        if self.is_empty() {
+            return text.to_string();
+        }
+        ADDRESS_REGEXP
+            .replace_all(text, |caps: &Captures| {
+                let alias = Address::from_str(&caps[0])
+                    .ok()
+                    .and_then(|address| self.alias_of(address));
+                match alias {
+                    Some(alias) => format!("{} ({})", &caps[0], alias),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+
+    /// Writes the book into the `[address_book]` table of a configuration file,
+    /// keeping the rest of the file and its comments as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_path` - Path to `config.toml`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error during saving
+    pub fn save(&self, config_path: &Path) -> Result<()> {
+        let mut doc = fs::read_to_string(config_path)?
+            .parse::<toml_edit::DocumentMut>()
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        let table = doc
+            .entry("address_book")
+            .or_insert(toml_edit::table())
+            .as_table_mut()
+            .ok_or_else(|| Error::InvalidConfig("address_book must be a table".to_string()))?;
+        table.retain(|alias, _| self.entries.contains_key(alias));
+        for (alias, address) in &self.entries {
+            table[alias.as_str()] = toml_edit::value(address.to_checksum(None));
+        }
+        fs::write(config_path, doc.to_string())?;
+        Ok(())
+    }
+}
+
+/// Validates an alias: letters, digits, `_`, `-` or `.`, not starting with `0x`.
+///
+/// # Arguments
+///
+/// * `alias` - The alias to validate
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if the alias is invalid
+pub fn validate_alias(alias: &str) -> Result<()> {
+    if alias.is_empty()
+        || alias.starts_with("0x")
+        || !alias
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(Error::InvalidConfig(format!(
+            "Invalid alias {:?}: use letters, digits, '_', '-' or '.', not starting with 0x",
+            alias
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_annotate_and_save() {
+        let treasury = Address::repeat_byte(0x11);
+        let mut book = AddressBook::new();
+        book.insert("treasury", treasury).unwrap();
+        assert!(book.insert("0xabc", treasury).is_err());
+        assert!(book.insert("two words", treasury).is_err());
+
+        assert_eq!(book.resolve(" treasury "), treasury.to_checksum(None));
+        assert_eq!(book.resolve("0x01"), "0x01");
+        assert_eq!(book.suggest("TRE"), vec!["treasury"]);
+        let function = Function::parse("transfer(address to, uint256 amount)").unwrap();
+        assert_eq!(
+            book.resolve_args(&function, &["treasury".to_string(), "5".to_string()]),
+            vec![treasury.to_checksum(None), "5".to_string()]
+        );
+        assert_eq!(
+            book.annotate(&format!("to: {}, from: {}", treasury, Address::ZERO)),
+            format!("to: {} (treasury), from: {}", treasury, Address::ZERO)
+        );
+
+        let path = std::env::temp_dir().join(format!("inachus-book-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "# my config\nchain_id = 1\n\n[address_book]\nold = \"0x0000000000000000000000000000000000000001\"\n",
+        )
+        .unwrap();
+        book.save(&path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# my config\nchain_id = 1\n"));
+        let saved: BTreeMap<String, AddressBook> =
+            toml::from_str(&content.replace("chain_id = 1\n", "")).unwrap();
+        assert_eq!(saved["address_book"], book);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,290 @@
+/// src/approval.rs
+use crate::abi::source::{explorer_sources, AbiQuery};
+use crate::context::GlobalContext;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::Function;
+use alloy::primitives::{Address, U256};
+use colored::Colorize;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How long the spender's verification status is looked up for.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Signatures of the calls granting an allowance, with the index of the
+/// spender argument, of the amount argument, and of the flag granting
+/// every token, if any.
+const APPROVALS: [(&str, usize, Option<usize>, Option<usize>); 5] = [
+    ("approve(address,uint256)", 0, Some(1), None),
+    ("increaseAllowance(address,uint256)", 0, Some(1), None),
+    ("setApprovalForAll(address,bool)", 0, None, Some(1)),
+    (
+        "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)",
+        1,
+        Some(2),
+        None,
+    ),
+    // DAI-style permit: `allowed` grants an unlimited allowance.
+    (
+        "permit(address,address,uint256,uint256,bool,uint8,bytes32,bytes32)",
+        1,
+        None,
+        Some(4),
+    ),
+];
+
+/// An allowance granted by a call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Approval {
+    /// Signature of the granting function
+    pub function: String,
+    /// Address allowed to spend
+    pub spender: Address,
+    /// Amount or token ID allowed, `None` when every token is
+    pub amount: Option<U256>,
+}
+
+impl Approval {
+    /// Detects an allowance granted by a call to `approve`, `increaseAllowance`,
+    /// `setApprovalForAll` or an ERC-2612 or DAI-style `permit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The called function
+    /// * `args` - One value per function input
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Approval>` - The allowance, `None` if the call grants none or revokes one
+    pub fn detect(function: &Function, args: &[DynSolValue]) -> Option<Self> {
+        let signature = function.signature();
+        let (_, spender, amount, all) = APPROVALS
+            .iter()
+            .find(|(approval, ..)| *approval == signature)?;
+        let DynSolValue::Address(spender) = args.get(*spender)? else {
+            return None;
+        };
+        let amount = match (amount, all) {
+            (Some(index), _) => match args.get(*index)? {
+                DynSolValue::Uint(amount, _) => Some(*amount),
+                _ => return None,
+            },
+            (None, Some(index)) => match args.get(*index)? {
+                DynSolValue::Bool(true) => None,
+                _ => return None,
+            },
+            (None, None) => return None,
+        };
+        Some(Self {
+            function: signature,
+            spender: *spender,
+            amount,
+        })
+    }
+
+    /// Whether the spender may spend every token, through an allowance of
+    /// 2^256-1 or an approval for all.
+    pub fn is_unlimited(&self) -> bool {
+        self.amount.unwrap_or(U256::MAX) == U256::MAX
+    }
+}
+
+/// Whether the spender's source code is verified on a block explorer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// Verified on the named explorer
+    Verified(String),
+    /// Known to no explorer
+    Unverified,
+    /// The explorers could not be reached in time
+    Unknown,
+}
+
+/// What is known about the spender of an approval, shown before sending
+/// approvals that are unlimited or go to an unknown spender.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalWarning {
+    /// The approval
+    pub approval: Approval,
+    /// Address book alias or known contract name of the spender, if any
+    pub spender_name: Option<String>,
+    /// Size of the spender's code in bytes, `None` if it could not be read
+    pub code_size: Option<usize>,
+    /// Verification status of the spender's code, `None` for accounts without code
+    pub verification: Option<Verification>,
+}
+
+impl ApprovalWarning {
+    /// Checks an approval, looking up the spender's code and its verification
+    /// on Etherscan and Sourcify when it is unlimited or goes to a spender
+    /// neither in the address book nor among the known contracts.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The session
+    /// * `approval` - The approval about to be sent
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ApprovalWarning>` - The warning, `None` for a limited approval to a known spender
+    pub async fn check(ctx: &GlobalContext, approval: Approval) -> Option<Self> {
+        let chain_id = ctx.chain_id.parse::<u64>().unwrap_or_default();
+        let spender_name = match ctx.address_book.alias_of(approval.spender) {
+            Some(alias) => Some(alias.to_string()),
+            None => ctx
+                .contracts
+                .iter()
+                .find(|info| {
+                    info.address_on(chain_id)
+                        .and_then(|address| Address::from_str(address).ok())
+                        == Some(approval.spender)
+                })
+                .map(|info| info.name.clone()),
+        };
+        if spender_name.is_some() && !approval.is_unlimited() {
+            return None;
+        }
+
+        let code_size = match ctx.read_context() {
+            Ok(read) => read
+                .get_code(approval.spender)
+                .await
+                .ok()
+                .map(|code| code.len()),
+            Err(_) => None,
+        };
+        let verification = match code_size {
+            Some(0) => None,
+            _ => {
+                let sources =
+                    explorer_sources(ctx.etherscan_api_key.as_deref(), Some(&ctx.abi_cache_dir()));
+                let query = AbiQuery::by_address(approval.spender, chain_id);
+                Some(
+                    match tokio::time::timeout(LOOKUP_TIMEOUT, sources.resolve_from(&query)).await {
+                        Ok(Ok(Some((source, _)))) => Verification::Verified(source),
+                        Ok(Ok(None)) => Verification::Unverified,
+                        Ok(Err(_)) | Err(_) => Verification::Unknown,
+                    },
+                )
+            }
+        };
+        Some(Self {
+            approval,
+            spender_name,
+            code_size,
+            verification,
+        })
+    }
+}
+
+/// Detects the allowance granted by a call and checks it; see
+/// [`Approval::detect`] and [`ApprovalWarning::check`].
+///
+/// # Arguments
+///
+/// * `ctx` - The session
+/// * `function` - The called function
+/// * `args` - One value per function input
+///
+/// # Returns
+///
+/// * `Option<ApprovalWarning>` - The warning to show before sending, if any
+pub async fn check_call(
+    ctx: &GlobalContext,
+    function: &Function,
+    args: &[DynSolValue],
+) -> Option<ApprovalWarning> {
+    let approval = Approval::detect(function, args)?;
+    ApprovalWarning::check(ctx, approval).await
+}
+
+impl fmt::Display for ApprovalWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let spender = self.approval.spender;
+        match self.approval.amount {
+            None => writeln!(
+                f,
+                "{} {} may transfer every token of this contract you own",
+                "Warning: approval for all.".red().bold(),
+                spender
+            )?,
+            Some(U256::MAX) => writeln!(
+                f,
+                "{} {} may spend your whole balance, now and later",
+                "Warning: unlimited approval (2^256-1).".red().bold(),
+                spender
+            )?,
+            Some(_) => writeln!(
+                f,
+                "{}",
+                "Warning: approval to an unknown spender.".red().bold()
+            )?,
+        }
+        match self.spender_name {
+            Some(ref name) => writeln!(f, "  Spender: {} ({})", spender, name.green())?,
+            None => writeln!(
+                f,
+                "  Spender: {} {}",
+                spender,
+                "unknown: not in the address book nor a known contract"
+                    .red()
+                    .bold()
+            )?,
+        }
+        let code = match self.code_size {
+            Some(0) => "none, an externally owned account".yellow().to_string(),
+            Some(size) => format!("{} bytes", size),
+            None => "could not be read".yellow().to_string(),
+        };
+        write!(f, "  Code: {}", code)?;
+        if let Some(ref verification) = self.verification {
+            let verification = match verification {
+                Verification::Verified(source) => format!("verified on {}", source).green(),
+                Verification::Unverified => "not verified".red().bold(),
+                Verification::Unknown => "unknown, the explorers did not answer".yellow(),
+            };
+            write!(f, "\n  Source: {}", verification)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_approval() {
+        let spender: Address = "0x000000000022D473030F116dDEE9F6B43aC78BA3"
+            .parse()
+            .unwrap();
+        let approve = Function::parse("approve(address,uint256)").unwrap();
+        let args = |amount: U256| {
+            vec![
+                DynSolValue::Address(spender),
+                DynSolValue::Uint(amount, 256),
+            ]
+        };
+
+        let unlimited = Approval::detect(&approve, &args(U256::MAX)).unwrap();
+        assert_eq!(unlimited.spender, spender);
+        assert!(unlimited.is_unlimited());
+        let limited = Approval::detect(&approve, &args(U256::from(100))).unwrap();
+        assert_eq!(limited.amount, Some(U256::from(100)));
+        assert!(!limited.is_unlimited());
+
+        let for_all = Function::parse("setApprovalForAll(address,bool)").unwrap();
+        let grant = Approval::detect(
+            &for_all,
+            &[DynSolValue::Address(spender), DynSolValue::Bool(true)],
+        )
+        .unwrap();
+        assert!(grant.is_unlimited());
+        let revoke = [DynSolValue::Address(spender), DynSolValue::Bool(false)];
+        assert!(Approval::detect(&for_all, &revoke).is_none());
+
+        let transfer = Function::parse("transfer(address,uint256)").unwrap();
+        assert!(Approval::detect(&transfer, &args(U256::MAX)).is_none());
+    }
+}
@@ -0,0 +1,371 @@
+/// src/batch.rs
+use crate::codec;
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use crate::multicall::{self, Call};
+use crate::result::CallResult;
+use alloy::json_abi::Function;
+use alloy::primitives::Address;
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Calls run at once when none is configured.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Calls per Multicall3 batch when none is configured.
+pub const DEFAULT_CHUNK_SIZE: usize = 500;
+
+/// How the calls of a batch are sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchMode {
+    /// One `eth_call` per row, at most this many in flight
+    Concurrent(usize),
+    /// Rows grouped into Multicall3 `aggregate3` calls of at most this many
+    Multicall(usize),
+}
+
+/// File format of batch inputs and results, picked by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchFormat {
+    /// Comma-separated values, one row per line
+    Csv,
+    /// A JSON array
+    Json,
+}
+
+impl BatchFormat {
+    /// Returns the format of a file: JSON for `.json`, CSV otherwise.
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => BatchFormat::Json,
+            _ => BatchFormat::Csv,
+        }
+    }
+}
+
+/// Reads the argument rows of a batch from a CSV or JSON file.
+///
+/// A CSV file has one row per line, with an optional header naming the
+/// function inputs. A JSON file is an array whose items are arrays of
+/// arguments, objects keyed by input name, or plain values for functions
+/// taking one argument.
+///
+/// # Arguments
+///
+/// * `path` - The input file
+/// * `function` - The function the rows are arguments of
+///
+/// # Returns
+///
+/// * `Result<Vec<Vec<String>>>` - One row of arguments per call, or an error
+///   naming the first malformed row
+pub fn read_rows(path: &Path, function: &Function) -> Result<Vec<Vec<String>>> {
+    let content = std::fs::read_to_string(path)?;
+    let rows = match BatchFormat::of(path) {
+        BatchFormat::Csv => parse_csv(&content, function),
+        BatchFormat::Json => parse_json(&serde_json::from_str(&content)?, function)?,
+    };
+    for (i, row) in rows.iter().enumerate() {
+        if row.len() != function.inputs.len() {
+            return Err(Error::InvalidArguments(format!(
+                "Row {} has {} values, {} takes {}",
+                i + 1,
+                row.len(),
+                function.signature(),
+                function.inputs.len()
+            )));
+        }
+    }
+    Ok(rows)
+}
+
+/// Parses CSV rows, skipping empty lines and a header naming the inputs.
+fn parse_csv(content: &str, function: &Function) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_csv_line)
+        .collect();
+    let names: Vec<&str> = function.inputs.iter().map(|i| i.name.as_str()).collect();
+    let is_header = |row: &Vec<String>| {
+        !names.iter().all(|name| name.is_empty())
+            && row.iter().map(String::as_str).eq(names.iter().copied())
+    };
+    if rows.first().is_some_and(is_header) {
+        rows.remove(0);
+    }
+    rows
+}
+
+/// Splits a CSV line into trimmed fields; double-quoted fields may hold
+/// commas and `""` escapes.
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Reads rows from a JSON array.
+fn parse_json(content: &Value, function: &Function) -> Result<Vec<Vec<String>>> {
+    let scalar = |value: &Value| match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let items = content
+        .as_array()
+        .ok_or_else(|| Error::InvalidArguments("Batch input must be a JSON array".to_string()))?;
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            Value::Array(values) => Ok(values.iter().map(scalar).collect()),
+            Value::Object(fields) => function
+                .inputs
+                .iter()
+                .map(|input| {
+                    fields.get(&input.name).map(scalar).ok_or_else(|| {
+                        Error::InvalidArguments(format!("Row {} has no {}", i + 1, input.name))
+                    })
+                })
+                .collect(),
+            value => Ok(vec![scalar(value)]),
+        })
+        .collect()
+}
+
+/// Calls a read-only function once per argument row.
+///
+/// Every row is coerced before anything is sent. A failing call does not
+/// fail the batch; its slot holds the error instead.
+///
+/// # Arguments
+///
+/// * `read` - The read context to call through
+/// * `address` - The contract to call
+/// * `function` - The function to call
+/// * `rows` - One row of arguments per call
+/// * `mode` - How the calls are sent
+/// * `progress` - Called with the number of calls done so far
+///
+/// # Returns
+///
+/// * `Result<Vec<Result<CallResult>>>` - One result per row in order, or an
+///   error if a row does not coerce or a Multicall3 batch fails
+pub async fn execute(
+    read: &ReadContext,
+    address: Address,
+    function: &Function,
+    rows: &[Vec<String>],
+    mode: BatchMode,
+    progress: impl Fn(usize),
+) -> Result<Vec<Result<CallResult>>> {
+    let args = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            codec::coerce_args(function, row)
+                .map_err(|e| Error::InvalidArguments(format!("Row {}: {}", i + 1, e)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut results = Vec::with_capacity(args.len());
+    match mode {
+        BatchMode::Concurrent(concurrency) => {
+            let mut calls = stream::iter(&args)
+                .map(|args| read.call(address, function, args))
+                .buffered(concurrency.max(1));
+            while let Some(result) = calls.next().await {
+                results.push(result);
+                progress(results.len());
+            }
+        }
+        BatchMode::Multicall(chunk_size) => {
+            for chunk in args.chunks(chunk_size.max(1)) {
+                let calls: Vec<Call> = chunk
+                    .iter()
+                    .map(|args| Call {
+                        target: address,
+                        function,
+                        args: args.clone(),
+                    })
+                    .collect();
+                results.extend(multicall::aggregate(read, &calls).await?);
+                progress(results.len());
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Renders batch results as CSV: the arguments, then one column per output,
+/// then the error of failed calls.
+///
+/// # Arguments
+///
+/// * `function` - The called function
+/// * `rows` - The argument rows
+/// * `results` - One result per row
+///
+/// # Returns
+///
+/// * `String` - The CSV document, header included
+pub fn to_csv(function: &Function, rows: &[Vec<String>], results: &[Result<CallResult>]) -> String {
+    let column = |name: &str, prefix: &str, i: usize| match name {
+        "" => format!("{}{}", prefix, i),
+        name => name.to_string(),
+    };
+    let mut header: Vec<String> = function
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| column(&input.name, "arg", i))
+        .collect();
+    header.extend(
+        function
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(i, output)| column(&output.name, "output", i)),
+    );
+    header.push("error".to_string());
+
+    let mut csv = csv_line(&header);
+    for (row, result) in rows.iter().zip(results) {
+        let mut fields = row.clone();
+        match result {
+            Ok(result) => {
+                fields.extend(
+                    result
+                        .values
+                        .iter()
+                        .map(|value| match codec::to_json(value) {
+                            Value::String(s) => s,
+                            other => other.to_string(),
+                        }),
+                );
+                fields.push(String::new());
+            }
+            Err(e) => {
+                fields.extend(function.outputs.iter().map(|_| String::new()));
+                fields.push(e.to_string());
+            }
+        }
+        csv.push_str(&csv_line(&fields));
+    }
+    csv
+}
+
+/// Joins fields into a CSV line, quoting those that need it.
+fn csv_line(fields: &[String]) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|field| match field.contains([',', '"', '\n']) {
+            true => format!("\"{}\"", field.replace('"', "\"\"")),
+            false => field.clone(),
+        })
+        .collect();
+    quoted.join(",") + "\n"
+}
+
+/// Converts batch results into JSON.
+///
+/// # Arguments
+///
+/// * `function` - The called function
+/// * `rows` - The argument rows
+/// * `results` - One result per row
+///
+/// # Returns
+///
+/// * `Value` - An object with the function, counts, and per row the arguments
+///   with either the call result or the error
+pub fn to_json(function: &Function, rows: &[Vec<String>], results: &[Result<CallResult>]) -> Value {
+    let items: Vec<Value> = rows
+        .iter()
+        .zip(results)
+        .map(|(row, result)| match result {
+            Ok(result) => json!({ "args": row, "result": result.to_json(), "error": null }),
+            Err(e) => json!({ "args": row, "result": null, "error": e.to_string() }),
+        })
+        .collect();
+    json!({
+        "function": function.signature(),
+        "total": results.len(),
+        "failed": results.iter().filter(|result| result.is_err()).count(),
+        "rows": items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixtures, MockRpc, TEST_CONTRACT};
+    use alloy::primitives::{Bytes, U256};
+
+    #[tokio::test]
+    async fn test_batch_from_csv() {
+        let abi = fixtures::erc20_abi();
+        let balance_of = &abi.function("balanceOf").unwrap()[0];
+        let holder = Address::repeat_byte(0x11).to_string();
+        let csv = format!(
+            "{}\n{}\n\n\"{}\"\n",
+            balance_of.inputs[0].name, holder, holder
+        );
+        let rows = parse_csv(&csv, balance_of);
+        assert_eq!(rows, vec![vec![holder.clone()], vec![holder.clone()]]);
+        assert_eq!(
+            parse_csv_line(r#"a, "b,c" ,"d""e""#),
+            vec!["a", "b,c", "d\"e"]
+        );
+        let json_rows = parse_json(&json!([holder, [holder]]), balance_of).unwrap();
+        assert_eq!(json_rows, rows);
+
+        let mock = MockRpc::new();
+        mock.respond("eth_call", Bytes::from(U256::from(7).to_be_bytes_vec()));
+        let done = std::cell::Cell::new(0);
+        let results = execute(
+            &mock.read_context(),
+            TEST_CONTRACT,
+            balance_of,
+            &rows,
+            BatchMode::Concurrent(2),
+            |count| done.set(count),
+        )
+        .await
+        .unwrap();
+        assert_eq!(done.get(), 2);
+        assert_eq!(mock.calls_to("eth_call").len(), 2);
+
+        let rendered = to_csv(balance_of, &rows, &results);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with(&format!("{},7,", holder)));
+        assert_eq!(to_json(balance_of, &rows, &results)["failed"], 0);
+
+        let bad = vec![vec!["not an address".to_string()]];
+        assert!(execute(
+            &mock.read_context(),
+            TEST_CONTRACT,
+            balance_of,
+            &bad,
+            BatchMode::Multicall(10),
+            |_| {},
+        )
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,224 @@
+/// src/bytecode.rs
+use alloy::json_abi::JsonAbi;
+use alloy::primitives::{Address, Selector};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Largest runtime bytecode a contract may deploy, per EIP-170.
+pub const MAX_CODE_SIZE: usize = 24_576;
+
+const PUSH1: u8 = 0x60;
+const PUSH4: u8 = 0x63;
+const PUSH32: u8 = 0x7f;
+const DUP1: u8 = 0x80;
+const DUP16: u8 = 0x8f;
+const EQ: u8 = 0x14;
+
+/// Prefix of an EIP-1167 minimal proxy, followed by the 20-byte implementation.
+const MINIMAL_PROXY_PREFIX: [u8; 10] = [0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d, 0x3d, 0x36, 0x3d, 0x73];
+
+/// What a contract's runtime bytecode reveals compared with its ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BytecodeReport {
+    /// Size of the runtime bytecode, in bytes
+    pub size: usize,
+    /// Implementation an EIP-1167 minimal proxy delegates to
+    pub minimal_proxy: Option<Address>,
+    /// Selectors the function dispatcher compares calldata with
+    pub selectors: BTreeSet<Selector>,
+    /// Selectors in the bytecode that the ABI does not declare, with the
+    /// signature another loaded ABI gives them, if any
+    pub undeclared: Vec<(Selector, Option<String>)>,
+    /// Signatures the ABI declares that the bytecode does not dispatch
+    pub missing: Vec<String>,
+}
+
+impl BytecodeReport {
+    /// Scans runtime bytecode and compares its selectors with an ABI.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The runtime bytecode
+    /// * `abi` - The ABI of the contract, if one is loaded
+    /// * `known` - Signatures of every loaded function by selector, to name undeclared ones
+    ///
+    /// # Returns
+    ///
+    /// * `BytecodeReport` - The size, selectors and differences with the ABI
+    pub fn new(code: &[u8], abi: Option<&JsonAbi>, known: &HashMap<Selector, String>) -> Self {
+        let selectors = scan_selectors(code);
+        let declared: HashMap<Selector, String> = abi
+            .map(|abi| {
+                abi.functions()
+                    .map(|function| (function.selector(), function.signature()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let undeclared = selectors
+            .iter()
+            .filter(|selector| !declared.contains_key(*selector))
+            .map(|selector| (*selector, known.get(selector).cloned()))
+            .collect();
+        let mut missing: Vec<String> = declared
+            .iter()
+            .filter(|(selector, _)| !selectors.contains(*selector))
+            .map(|(_, signature)| signature.clone())
+            .collect();
+        missing.sort();
+        Self {
+            size: code.len(),
+            minimal_proxy: minimal_proxy_target(code),
+            selectors,
+            undeclared,
+            missing,
+        }
+    }
+}
+
+impl fmt::Display for BytecodeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Size: {} bytes ({:.1}% of the {} byte limit)",
+            self.size,
+            self.size as f64 * 100.0 / MAX_CODE_SIZE as f64,
+            MAX_CODE_SIZE
+        )?;
+        if let Some(implementation) = self.minimal_proxy {
+            write!(f, "\nEIP-1167 minimal proxy to {}", implementation)?;
+        }
+        write!(f, "\nSelectors found: {}", self.selectors.len())?;
+        if !self.undeclared.is_empty() {
+            write!(f, "\nIn the bytecode, not in the ABI:")?;
+            for (selector, signature) in &self.undeclared {
+                match signature {
+                    Some(signature) => write!(f, "\n  {} {}", selector, signature)?,
+                    None => write!(f, "\n  {}", selector)?,
+                }
+            }
+        }
+        if !self.missing.is_empty() {
+            write!(f, "\nIn the ABI, not in the bytecode:")?;
+            for signature in &self.missing {
+                write!(f, "\n  {}", signature)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Extracts the function selectors of a dispatcher from runtime bytecode.
+///
+/// Instructions are walked so push data is never mistaken for code, and a
+/// 4-byte push counts as a selector when it is compared with `EQ`, right
+/// away or after a `DUP`, as Solidity and Vyper dispatchers do. Selectors
+/// reached through a jump table or computed at runtime are not found.
+///
+/// # Arguments
+///
+/// * `code` - The runtime bytecode
+///
+/// # Returns
+///
+/// * `BTreeSet<Selector>` - The selectors found
+pub fn scan_selectors(code: &[u8]) -> BTreeSet<Selector> {
+    let mut selectors = BTreeSet::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        let data = match opcode {
+            PUSH1..=PUSH32 => usize::from(opcode - PUSH1) + 1,
+            _ => 0,
+        };
+        let next = pc + 1 + data;
+        if opcode == PUSH4 && next <= code.len() {
+            let compared = match code.get(next) {
+                Some(&EQ) => true,
+                Some(&(DUP1..=DUP16)) => code.get(next + 1) == Some(&EQ),
+                _ => false,
+            };
+            let selector = Selector::from_slice(&code[pc + 1..next]);
+            if compared && selector != Selector::ZERO && selector != Selector::repeat_byte(0xff) {
+                selectors.insert(selector);
+            }
+        }
+        pc = next;
+    }
+    selectors
+}
+
+/// Returns the implementation of an EIP-1167 minimal proxy.
+///
+/// # Arguments
+///
+/// * `code` - The runtime bytecode
+///
+/// # Returns
+///
+/// * `Option<Address>` - The implementation, or `None` if the code is not a minimal proxy
+pub fn minimal_proxy_target(code: &[u8]) -> Option<Address> {
+    let rest = code.strip_prefix(&MINIMAL_PROXY_PREFIX)?;
+    rest.get(..20).map(Address::from_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::hex;
+
+    #[test]
+    fn test_scan_and_compare() {
+        let abi = JsonAbi::parse([
+            "function transfer(address,uint256) returns (bool)",
+            "function totalSupply() view returns (uint256)",
+        ])
+        .unwrap();
+        // DUP1 PUSH4 transfer EQ PUSH2 JUMPI, PUSH4 mint DUP2 EQ, a PUSH32 whose
+        // data looks like a comparison, and a PUSH4 mask followed by AND
+        let code = hex::decode(concat!(
+            "80",
+            "63a9059cbb",
+            "14",
+            "610040",
+            "57",
+            "6340c10f19",
+            "81",
+            "14",
+            "7f",
+            "6318160ddd140000000000000000000000000000000000000000000000000000",
+            "63ffffffff",
+            "16"
+        ))
+        .unwrap();
+
+        let selectors = scan_selectors(&code);
+        assert_eq!(
+            selectors.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            vec!["0x40c10f19", "0xa9059cbb"]
+        );
+
+        let mut known = HashMap::new();
+        known.insert(
+            Selector::from_slice(&hex::decode("40c10f19").unwrap()),
+            "mint(address,uint256)".to_string(),
+        );
+        let report = BytecodeReport::new(&code, Some(&abi), &known);
+        assert_eq!(report.size, code.len());
+        assert_eq!(report.undeclared.len(), 1);
+        assert_eq!(
+            report.undeclared[0].1.as_deref(),
+            Some("mint(address,uint256)")
+        );
+        assert_eq!(report.missing, vec!["totalSupply()"]);
+        assert_eq!(report.minimal_proxy, None);
+
+        let implementation = Address::repeat_byte(0xbe);
+        let proxy = [
+            &MINIMAL_PROXY_PREFIX[..],
+            implementation.as_slice(),
+            &hex::decode("5af43d82803e903d91602b57fd5bf3").unwrap(),
+        ]
+        .concat();
+        assert_eq!(minimal_proxy_target(&proxy), Some(implementation));
+    }
+}
@@ -0,0 +1,64 @@
+/// src/cancel.rs
+use crate::error::{Error, Result};
+use std::future::Future;
+use std::time::Duration;
+
+pub use tokio_util::sync::CancellationToken;
+
+/// Per-operation timeouts applied to RPC requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Timeout for read-only calls and queries
+    pub call: Duration,
+    /// Timeout for broadcasting a transaction
+    pub send: Duration,
+    /// Timeout for waiting on a transaction receipt
+    pub receipt: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            call: Duration::from_secs(30),
+            send: Duration::from_secs(60),
+            receipt: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Runs an operation until it completes, the token is cancelled, or the timeout elapses.
+///
+/// Cancellation is only observed between polls of `operation`, so a request
+/// that already reached the node is not torn down halfway through a write.
+///
+/// # Arguments
+///
+/// * `operation` - Description of the operation, used in errors
+/// * `token` - Token that aborts the operation when cancelled
+/// * `timeout` - Maximum time the operation may take
+/// * `fut` - The operation itself
+///
+/// # Returns
+///
+/// * `Result<T>` - The operation's result, or a `Cancelled`/`Timeout` error
+pub async fn guard<T, F>(
+    operation: &str,
+    token: &CancellationToken,
+    timeout: Duration,
+    fut: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(Error::Cancelled(operation.to_string())),
+        result = tokio::time::timeout(timeout, fut) => match result {
+            Ok(result) => result,
+            Err(_) => Err(Error::Timeout {
+                operation: operation.to_string(),
+                after: timeout,
+            }),
+        },
+    }
+}
@@ -0,0 +1,169 @@
+/// src/client.rs
+use crate::abi;
+use crate::codec;
+use crate::context::{ReadContext, WriteContext};
+use crate::error::{Error, Result};
+use crate::result::{CallResult, EventLog};
+use crate::signer::Signer;
+use crate::transaction::TransactionOptions;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::Address;
+use alloy::providers::{DynProvider, Provider};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::TransactionReceipt;
+
+/// A contract bound to its ABI and address, for Rust tools embedding
+/// Inachus instead of running the binary. Methods are picked by name or
+/// signature and take their arguments as the command line does.
+///
+/// Needs none of the interactive dependencies, so it is available with
+/// `default-features = false`.
+#[derive(Debug, Clone)]
+pub struct ContractClient {
+    read: ReadContext,
+    write: Option<WriteContext>,
+    abi: JsonAbi,
+    address: Address,
+}
+
+impl ContractClient {
+    /// Creates a client that can call read-only methods; see
+    /// [`ContractClient::with_signer`] to send transactions.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The provider used for RPC requests
+    /// * `abi` - ABI of the contract
+    /// * `address` - Address of the contract
+    ///
+    /// # Returns
+    ///
+    /// * `ContractClient` - A read-only client
+    pub fn new(provider: DynProvider, abi: JsonAbi, address: Address) -> Self {
+        Self {
+            read: ReadContext::new(provider),
+            write: None,
+            abi,
+            address,
+        }
+    }
+
+    /// Signs and sends transactions with a signer, over the client's provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - Signer used for transactions, e.g. a `PrivateKeySigner`
+    /// * `chain_id` - Chain ID for transaction signing
+    ///
+    /// # Returns
+    ///
+    /// * `ContractClient` - The client, able to send transactions
+    pub fn with_signer(mut self, signer: impl Into<Signer>, chain_id: u64) -> Self {
+        let inner = self.read.provider().client();
+        let client = RpcClient::new(inner.transport().clone(), inner.is_local());
+        self.write = Some(WriteContext::from_client(client, signer, chain_id));
+        self
+    }
+
+    /// Sends transactions through an existing write context, e.g. one with
+    /// hooks, timeouts or a dry run configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `write` - The write context
+    ///
+    /// # Returns
+    ///
+    /// * `ContractClient` - The client, able to send transactions
+    pub fn with_write_context(mut self, write: WriteContext) -> Self {
+        self.write = Some(write);
+        self
+    }
+
+    /// Returns the address of the contract.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the ABI of the contract.
+    pub fn abi(&self) -> &JsonAbi {
+        &self.abi
+    }
+
+    /// Calls a read-only method with `eth_call` and decodes its outputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - A function name or signature, e.g. `balanceOf`
+    /// * `args` - One argument per function input, e.g. `1.5 ether` or an address
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CallResult>` - The decoded and raw outputs, or an error with
+    ///   the revert decoded against the ABI
+    pub async fn read<S: AsRef<str>>(&self, method: &str, args: &[S]) -> Result<CallResult> {
+        let function = self.function(method)?;
+        let values = codec::coerce_args(function, args)?;
+        self.read
+            .call(self.address, function, &values)
+            .await
+            .map_err(|e| e.with_abi(&self.abi))
+    }
+
+    /// Sends a transaction calling a state-changing method and waits for its
+    /// receipt. Hooks and simulation settings of the write context apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - A function name or signature, e.g. `transfer`
+    /// * `args` - One argument per function input
+    /// * `overrides` - Overrides for gas, fees, nonce, value and confirmations
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TransactionReceipt>` - The receipt, or an error if the client
+    ///   has no signer or the transaction could not be sent
+    pub async fn write<S: AsRef<str>>(
+        &self,
+        method: &str,
+        args: &[S],
+        overrides: &TransactionOptions,
+    ) -> Result<TransactionReceipt> {
+        let write = self.write.as_ref().ok_or_else(|| {
+            Error::InvalidConfig(
+                "ContractClient has no signer: create it with with_signer".to_string(),
+            )
+        })?;
+        let function = self.function(method)?;
+        let values = codec::coerce_args(function, args)?;
+        let pending = write
+            .send(self.address, function, &values, overrides)
+            .await
+            .map_err(|e| e.with_abi(&self.abi))?;
+        write.confirm(pending).await
+    }
+
+    /// Decodes the logs the contract emitted in a transaction. Logs of other
+    /// contracts and of events missing from the ABI are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `receipt` - The transaction's receipt
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<EventLog>` - The decoded logs, in emission order
+    pub fn decode_logs(&self, receipt: &TransactionReceipt) -> Vec<EventLog> {
+        receipt
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == self.address)
+            .filter_map(|log| EventLog::decode_any([&self.abi], log))
+            .collect()
+    }
+
+    fn function(&self, method: &str) -> Result<&Function> {
+        abi::find_function(&self.abi, method)
+    }
+}
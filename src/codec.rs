@@ -0,0 +1,227 @@
+/// src/codec.rs
+use crate::error::Result;
+use alloy::dyn_abi::{DecodedEvent, DynSolType, DynSolValue};
+use alloy::json_abi::{Event, EventParam, Function, JsonAbi, Param};
+use alloy::primitives::utils::Unit;
+use alloy::primitives::{Bytes, LogData, B256, U256};
+
+pub use abi_codec::CodecError;
+
+/// Parses a Solidity type string such as `uint256[]` or `(address,bool)`.
+///
+/// # Arguments
+///
+/// * `ty` - The type string
+///
+/// # Returns
+///
+/// * `Result<DynSolType>` - The parsed type, or an error
+pub fn parse_type(ty: &str) -> Result<DynSolType> {
+    Ok(abi_codec::parse_type(ty)?)
+}
+
+/// Resolves the full type of an ABI parameter, including tuple components.
+///
+/// # Arguments
+///
+/// * `param` - The ABI parameter
+///
+/// # Returns
+///
+/// * `Result<DynSolType>` - The resolved type, or an error
+pub fn param_type(param: &Param) -> Result<DynSolType> {
+    Ok(abi_codec::param_type(param)?)
+}
+
+/// Resolves the full type of an event parameter, including tuple components.
+///
+/// # Arguments
+///
+/// * `param` - The event parameter
+///
+/// # Returns
+///
+/// * `Result<DynSolType>` - The resolved type, or an error
+pub fn event_param_type(param: &EventParam) -> Result<DynSolType> {
+    Ok(abi_codec::event_param_type(param)?)
+}
+
+/// Coerces a user-supplied string into a value of the given type.
+///
+/// This is the parsing rule applied to every argument typed in the CLI, so
+/// scripts using this function accept exactly the same inputs.
+///
+/// # Arguments
+///
+/// * `ty` - The target type
+/// * `input` - The string to coerce (e.g. `"[1, 2]"` for `uint8[]`)
+///
+/// # Returns
+///
+/// * `Result<DynSolValue>` - The coerced value, or an error
+pub fn coerce(ty: &DynSolType, input: &str) -> Result<DynSolValue> {
+    Ok(abi_codec::coerce(ty, input)?)
+}
+
+/// Parses an amount with an optional unit into base units: `1 ether`,
+/// `2.5 gwei`, `1e18`, `1_000_000` or `0x` hex. Every integer argument
+/// accepts these forms, bare numbers being in wei.
+///
+/// # Arguments
+///
+/// * `input` - The amount
+/// * `default_unit` - Unit of an amount without one
+///
+/// # Returns
+///
+/// * `Result<U256>` - The amount in base units, or an error if it is
+///   negative, has an unknown unit or is not a whole number of base units
+pub fn parse_amount(input: &str, default_unit: Unit) -> Result<U256> {
+    Ok(abi_codec::parse_amount(input, default_unit)?)
+}
+
+/// Coerces one string per function input into ABI values.
+///
+/// # Arguments
+///
+/// * `function` - The function whose inputs are being supplied
+/// * `args` - One string per function input
+///
+/// # Returns
+///
+/// * `Result<Vec<DynSolValue>>` - The coerced values, or an error
+pub fn coerce_args<S: AsRef<str>>(function: &Function, args: &[S]) -> Result<Vec<DynSolValue>> {
+    Ok(abi_codec::coerce_args(function, args)?)
+}
+
+/// Encodes calldata for a function from string arguments.
+///
+/// # Arguments
+///
+/// * `function` - The function to call
+/// * `args` - One string per function input
+///
+/// # Returns
+///
+/// * `Result<Bytes>` - Selector followed by the ABI-encoded arguments
+pub fn encode_calldata<S: AsRef<str>>(function: &Function, args: &[S]) -> Result<Bytes> {
+    Ok(abi_codec::encode_calldata(function, args)?)
+}
+
+/// Encodes calldata for a function from already coerced values.
+///
+/// # Arguments
+///
+/// * `function` - The function to call
+/// * `values` - One value per function input
+///
+/// # Returns
+///
+/// * `Result<Bytes>` - Selector followed by the ABI-encoded arguments
+pub fn encode_values(function: &Function, values: &[DynSolValue]) -> Result<Bytes> {
+    Ok(abi_codec::encode_values(function, values)?)
+}
+
+/// Decodes calldata produced for a function, checking its selector.
+///
+/// # Arguments
+///
+/// * `function` - The function the calldata targets
+/// * `calldata` - Selector followed by the encoded arguments
+///
+/// # Returns
+///
+/// * `Result<Vec<DynSolValue>>` - The decoded arguments, or an error
+pub fn decode_input(function: &Function, calldata: &[u8]) -> Result<Vec<DynSolValue>> {
+    Ok(abi_codec::decode_input(function, calldata)?)
+}
+
+/// Decodes the return data of a function call.
+///
+/// # Arguments
+///
+/// * `function` - The function that was called
+/// * `data` - The raw return data
+///
+/// # Returns
+///
+/// * `Result<Vec<DynSolValue>>` - The decoded outputs, or an error
+pub fn decode_output(function: &Function, data: &[u8]) -> Result<Vec<DynSolValue>> {
+    Ok(abi_codec::decode_output(function, data)?)
+}
+
+/// Decodes a log emitted by an event.
+///
+/// # Arguments
+///
+/// * `event` - The event definition
+/// * `log` - The log's topics and data
+///
+/// # Returns
+///
+/// * `Result<DecodedEvent>` - The decoded indexed and body values, or an error
+pub fn decode_log(event: &Event, log: &LogData) -> Result<DecodedEvent> {
+    Ok(abi_codec::decode_log(event, log)?)
+}
+
+/// Encodes a value as the topic of an indexed event parameter.
+///
+/// Value types are stored as their 32-byte word; strings, bytes, arrays and
+/// tuples are stored as the keccak256 hash of their packed encoding.
+///
+/// # Arguments
+///
+/// * `value` - The indexed value
+///
+/// # Returns
+///
+/// * `B256` - The topic to filter logs on
+pub fn encode_topic(value: &DynSolValue) -> B256 {
+    abi_codec::encode_topic(value)
+}
+
+/// Decodes revert data into the name and arguments of the error it encodes.
+///
+/// The builtin `Error(string)` and `Panic(uint256)` are always recognised;
+/// custom errors are looked up by selector in the given ABI.
+///
+/// # Arguments
+///
+/// * `data` - The raw revert data
+/// * `abi` - The ABI declaring custom errors, if known
+///
+/// # Returns
+///
+/// * `Option<(String, Vec<DynSolValue>)>` - The error name and arguments, or `None` if unrecognised
+pub fn decode_revert(data: &[u8], abi: Option<&JsonAbi>) -> Option<(String, Vec<DynSolValue>)> {
+    abi_codec::decode_revert(data, abi)
+}
+
+/// Describes a Solidity panic code (e.g. `0x11` for arithmetic overflow).
+///
+/// # Arguments
+///
+/// * `code` - The `Panic(uint256)` code
+///
+/// # Returns
+///
+/// * `&'static str` - A short description of the panic
+pub fn panic_reason(code: U256) -> &'static str {
+    abi_codec::panic_reason(code)
+}
+
+/// Converts a decoded value into JSON.
+///
+/// Integers become decimal strings, byte values and addresses become
+/// 0x-prefixed hex, and arrays and tuples become JSON arrays.
+///
+/// # Arguments
+///
+/// * `value` - The value to convert
+///
+/// # Returns
+///
+/// * `serde_json::Value` - The JSON representation
+pub fn to_json(value: &DynSolValue) -> serde_json::Value {
+    abi_codec::to_json(value)
+}
@@ -1,56 +1,488 @@
 /// src/config.rs
+use crate::address_book::AddressBook;
+use crate::cancel::Timeouts;
 use crate::error::{Error, Result};
+use crate::hooks::HookConfig;
+use crate::migrate;
+use crate::output::OutputFormat;
+use crate::policy::PolicyConfig;
+use crate::relay::RelayConfig;
+use crate::safe::SafeConfig;
+use crate::server::ServerConfig;
+use crate::signer::{LedgerConfig, MnemonicConfig};
+use crate::transaction::GasConfig;
+use crate::transport::{RetryConfig, RpcUrls};
 use crate::validation;
+use crate::wallet::WalletConfig;
+use alloy::primitives::Address;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Environment variable selecting the profile when `--profile` is not given.
+pub const PROFILE_ENV: &str = "INACHUS_PROFILE";
+
+/// Environment variable overriding `rpc_url`, several URLs being separated by commas.
+pub const RPC_URL_ENV: &str = "INACHUS_RPC_URL";
+
+/// Environment variable overriding `chain_id`.
+pub const CHAIN_ID_ENV: &str = "INACHUS_CHAIN_ID";
+
+/// Environment variable overriding `output`, `text` or `json`.
+pub const OUTPUT_ENV: &str = "INACHUS_OUTPUT";
+
+/// Environment variable overriding `mode`, `normal`, `read-only` or `dry-run`.
+pub const MODE_ENV: &str = "INACHUS_MODE";
+
+/// What a session may do with transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OperatingMode {
+    /// Transactions are signed and sent
+    #[default]
+    Normal,
+    /// No signer is loaded: write methods and signing steps are hidden, and
+    /// nothing is signed or sent
+    ReadOnly,
+    /// Writes go through their whole flow up to the simulation and preview,
+    /// but are never signed or sent
+    DryRun,
+}
+
+impl std::fmt::Display for OperatingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperatingMode::Normal => write!(f, "normal"),
+            OperatingMode::ReadOnly => write!(f, "read-only"),
+            OperatingMode::DryRun => write!(f, "dry-run"),
+        }
+    }
+}
+
 /// Represents the application configuration.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct Config {
+    /// Schema version of the file, upgraded by [`crate::migrate::migrate`]
+    pub version: u32,
     /// Directory containing ABI files
     pub abi_dir: PathBuf,
-    /// Ethereum RPC URL
-    pub rpc_url: String,
+    /// Ethereum RPC URL, or a list of endpoints to fail over between
+    pub rpc_url: RpcUrls,
     /// Optional private key for transaction signing
     pub private_key: Option<String>,
+    /// Optional encrypted keystore used instead of `private_key`, relative to the data directory
+    pub keystore: Option<PathBuf>,
+    /// Optional Ledger device used instead of `private_key` and `keystore`
+    pub ledger: Option<LedgerConfig>,
+    /// Optional BIP-39 mnemonic whose derived accounts can be used instead of `private_key`
+    pub mnemonic: Option<MnemonicConfig>,
+    /// Named wallets the sender can be switched to during a session
+    pub wallets: Vec<WalletConfig>,
+    /// Whether transactions are sent, only simulated, or not even offered
+    pub mode: OperatingMode,
+    /// Retries of RPC requests failing for transient reasons
+    pub retry: RetryConfig,
+    /// Optional Safe that write transactions are proposed to instead of being sent
+    pub safe: Option<SafeConfig>,
+    /// Optional private relay that write transactions are sent through instead of the public mempool
+    pub private_relay: Option<RelayConfig>,
     /// Chain ID for transaction signing
     pub chain_id: u64,
     /// Wait time for transaction confirmation (e.g., "30s")
     pub wait_time: String,
+    /// Timeout for read-only calls (e.g., "30s")
+    pub call_timeout: String,
+    /// Timeout for broadcasting a transaction (e.g., "1m")
+    pub send_timeout: String,
     /// Optional name of the current contract
     pub contract_name: Option<String>,
     /// Optional address of the current contract
     pub contract_address: Option<String>,
+    /// Optional URL template of an HTTP ABI registry (e.g. `https://abis.example.com/{chain_id}/{address}.json`)
+    pub abi_registry: Option<String>,
+    /// Optional Etherscan API key used to import verified ABIs
+    pub etherscan_api_key: Option<String>,
+    /// Keys filling `${NAME}` placeholders in chain list RPC URLs and in this
+    /// file, before environment variables
+    pub rpc_api_keys: HashMap<String, String>,
+    /// Default gas settings for write transactions
+    pub gas: GasConfig,
+    /// Refuse to send a transaction whose simulation reverts, the same as
+    /// `policy.require_simulation`, which either one turns on
+    pub simulate_before_send: bool,
+    /// Repeat view calls against a second RPC endpoint and flag diverging results
+    pub paranoid: bool,
+    /// Endpoint view calls are cross-checked against in paranoid mode,
+    /// defaults to the first fallback of `rpc_url`
+    pub cross_check_rpc_url: Option<String>,
+    /// Blocks to wait for after a transaction is included before showing its receipt
+    pub confirmations: u64,
+    /// Aliases usable wherever an address is asked for
+    pub address_book: AddressBook,
+    /// How results are printed, `text` or `json`
+    pub output: OutputFormat,
+    /// Rules every transaction is checked against before it is signed
+    pub policy: PolicyConfig,
+    /// External commands run before sending and after each receipt
+    pub hooks: Vec<HookConfig>,
+    /// Settings of the HTTP server mode
+    pub server: ServerConfig,
+    /// CREATE2 factory deterministic deployments go through, defaults to the
+    /// deterministic deployment proxy at `0x4e59b44847b379578588920cA78FbF26c0B4956C`
+    pub create2_factory: Option<Address>,
+    /// File of the known contract deployments, relative to the data directory
+    pub contracts_file: PathBuf,
+    /// Profile applied when none is selected with `--profile`
+    pub profile: Option<String>,
+    /// Named profiles, each with its own chain, signer and contracts
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+/// A named set of settings overriding the top-level ones, e.g. `mainnet-ops`,
+/// `staging` or `local-anvil`. Anything left unset is inherited.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// RPC URL, or list of endpoints, of the profile
+    pub rpc_url: Option<RpcUrls>,
+    /// Chain ID of the profile
+    pub chain_id: Option<u64>,
+    /// Private key of the profile; setting any signer replaces all inherited ones
+    pub private_key: Option<String>,
+    /// Encrypted keystore of the profile, relative to the data directory
+    pub keystore: Option<PathBuf>,
+    /// Ledger device of the profile
+    pub ledger: Option<LedgerConfig>,
+    /// BIP-39 mnemonic of the profile
+    pub mnemonic: Option<MnemonicConfig>,
+    /// Named wallets of the profile, replacing the inherited ones
+    pub wallets: Option<Vec<WalletConfig>>,
+    /// Name of the contract selected at startup
+    pub contract_name: Option<String>,
+    /// Address of the contract selected at startup
+    pub contract_address: Option<String>,
+    /// File of the profile's contract deployments, relative to the data directory
+    pub contracts_file: Option<PathBuf>,
+}
+
+impl ProfileConfig {
+    /// Whether the profile sets a signer of its own.
+    fn has_signer(&self) -> bool {
+        self.private_key.is_some()
+            || self.keystore.is_some()
+            || self.ledger.is_some()
+            || self.mnemonic.is_some()
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: migrate::CONFIG_VERSION,
             abi_dir: PathBuf::from("./abis"),
-            rpc_url: "http://localhost:8545".to_string(),
+            rpc_url: RpcUrls::default(),
             private_key: None,
+            keystore: None,
+            ledger: None,
+            mnemonic: None,
+            wallets: Vec::new(),
+            mode: OperatingMode::default(),
+            retry: RetryConfig::default(),
+            safe: None,
+            private_relay: None,
             chain_id: 1,
             wait_time: "30s".to_string(),
+            call_timeout: "30s".to_string(),
+            send_timeout: "1m".to_string(),
             contract_name: None,
             contract_address: None,
+            abi_registry: None,
+            etherscan_api_key: None,
+            rpc_api_keys: HashMap::new(),
+            gas: GasConfig::default(),
+            simulate_before_send: false,
+            paranoid: false,
+            cross_check_rpc_url: None,
+            confirmations: 1,
+            address_book: AddressBook::new(),
+            output: OutputFormat::default(),
+            policy: PolicyConfig::default(),
+            hooks: Vec::new(),
+            server: ServerConfig::default(),
+            create2_factory: None,
+            contracts_file: PathBuf::from(crate::CONTRACTS_FILE),
+            profile: None,
+            profiles: BTreeMap::new(),
         }
     }
 }
 
 impl Config {
+    /// Returns the per-operation timeouts described by the configuration.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Timeouts>` - The parsed timeouts or an error if one is malformed
+    pub fn timeouts(&self) -> Result<Timeouts> {
+        Ok(Timeouts {
+            call: validation::parse_duration("call_timeout", &self.call_timeout)?,
+            send: validation::parse_duration("send_timeout", &self.send_timeout)?,
+            receipt: validation::parse_duration("wait_time", &self.wait_time)?,
+        })
+    }
+
+    /// Returns the endpoint view calls are cross-checked against in paranoid
+    /// mode: `cross_check_rpc_url`, or else the first fallback of `rpc_url`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The endpoint, `None` if paranoid mode is off or none is configured
+    pub fn cross_check_endpoint(&self) -> Option<String> {
+        match self.paranoid {
+            true => self
+                .cross_check_rpc_url
+                .clone()
+                .or_else(|| self.rpc_url.fallbacks().into_iter().next()),
+            false => None,
+        }
+    }
+
+    /// Applies a profile over the top-level settings.
+    ///
+    /// A profile setting any signer replaces all inherited ones, and one
+    /// setting the contract name or address replaces both, so that settings
+    /// of different profiles are never mixed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the profile, the configured `profile` when `None`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Config>` - The configuration with the profile applied, unchanged
+    ///   if no profile is selected, or an error if the profile is not defined
+    pub fn with_profile(&self, name: Option<&str>) -> Result<Self> {
+        let Some(name) = name.or(self.profile.as_deref()) else {
+            return Ok(self.clone());
+        };
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            let defined: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            Error::InvalidConfig(match defined.is_empty() {
+                true => format!("Unknown profile {}: no profiles are defined", name),
+                false => format!(
+                    "Unknown profile {}, expected one of {}",
+                    name,
+                    defined.join(", ")
+                ),
+            })
+        })?;
+
+        let mut config = self.clone();
+        if let Some(ref rpc_url) = profile.rpc_url {
+            config.rpc_url = rpc_url.clone();
+        }
+        if let Some(chain_id) = profile.chain_id {
+            config.chain_id = chain_id;
+        }
+        if profile.has_signer() {
+            config.private_key = profile.private_key.clone();
+            config.keystore = profile.keystore.clone();
+            config.ledger = profile.ledger.clone();
+            config.mnemonic = profile.mnemonic.clone();
+        }
+        if let Some(ref wallets) = profile.wallets {
+            config.wallets = wallets.clone();
+        }
+        if profile.contract_name.is_some() || profile.contract_address.is_some() {
+            config.contract_name = profile.contract_name.clone();
+            config.contract_address = profile.contract_address.clone();
+        }
+        if let Some(ref contracts_file) = profile.contracts_file {
+            config.contracts_file = contracts_file.clone();
+        }
+        config.profile = Some(name.to_string());
+        Ok(config)
+    }
+
     /// Loads a configuration from a file.
     ///
+    /// `${NAME}` in any string value is replaced with the environment variable
+    /// `NAME`, so that secrets such as `private_key = "${PK}"` can stay out of
+    /// the file; a key of the `[rpc_api_keys]` table is used first, which is
+    /// how a saved chain list RPC URL such as `${INFURA_API_KEY}` is filled.
+    /// Hook commands are left as they are, their shell expanding variables
+    /// itself.
+    ///
     /// # Arguments
     ///
     /// * `path` - Path to the configuration file
     ///
     /// # Returns
     ///
-    /// * `Result<Config>` - The loaded configuration or an error
+    /// * `Result<Config>` - The loaded configuration, or an error if it is
+    ///   malformed or refers to an unset variable
     pub fn from_file(path: &Path) -> Result<Self> {
-        let content = fs::read_to_string(path)?;
-        toml::from_str(&content).map_err(Error::from)
+        Self::from_files(&[path.to_path_buf()])
+    }
+
+    /// Loads a configuration from files layered over each other, each one's
+    /// settings replacing those of the files before it; tables are merged
+    /// key by key, while lists are replaced as a whole. Environment
+    /// variables are expanded once merged; see [`Config::from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The files, from the most general to the most specific
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Config>` - The merged configuration or an error
+    pub fn from_files(paths: &[PathBuf]) -> Result<Self> {
+        let mut value = toml::Value::Table(toml::Table::new());
+        for path in paths {
+            let content = fs::read_to_string(path)?;
+            let layer: toml::Value = toml::from_str(&content)
+                .map_err(|e| Error::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+            merge(&mut value, layer);
+        }
+        let keys = rpc_api_keys(&value);
+        expand_env(&mut value, "", &|name| {
+            keys.get(name).cloned().or_else(|| std::env::var(name).ok())
+        })?;
+        value.try_into().map_err(Error::from)
+    }
+
+    /// Returns the global configuration file: `inachus/config.toml` under
+    /// `$XDG_CONFIG_HOME` (`~/.config` by default) if it exists, else the
+    /// `config.toml` of the data directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - The Inachus data directory
+    ///
+    /// # Returns
+    ///
+    /// * `PathBuf` - Path of the global configuration
+    pub fn global_path(data_dir: &Path) -> PathBuf {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".config")));
+        config_home
+            .map(|dir| dir.join("inachus").join(crate::CONFIG_FILE))
+            .filter(|path| path.is_file())
+            .unwrap_or_else(|| data_dir.join(crate::CONFIG_FILE))
+    }
+
+    /// Finds the project-local configuration, `.inachus/config.toml` in a
+    /// directory or the nearest of its ancestors. The data directory is not
+    /// a project and is skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to search from, usually the current one
+    /// * `data_dir` - The Inachus data directory
+    ///
+    /// # Returns
+    ///
+    /// * `Option<PathBuf>` - Path of the local configuration, if any
+    pub fn local_path(dir: &Path, data_dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|dir| dir.join(crate::INACHUS_DIR))
+            .filter(|local| local != data_dir)
+            .map(|local| local.join(crate::CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Applies the settings given as environment variables, which take
+    /// precedence over every configuration file: [`RPC_URL_ENV`],
+    /// [`CHAIN_ID_ENV`], [`OUTPUT_ENV`] and [`MODE_ENV`].
+    ///
+    /// # Arguments
+    ///
+    /// * `lookup` - Returns the value of a variable, if set
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error if a value is malformed
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        let lookup = |name: &str| lookup(name).filter(|value| !value.trim().is_empty());
+        if let Some(rpc_url) = lookup(RPC_URL_ENV) {
+            let urls: Vec<String> = rpc_url
+                .split(',')
+                .map(|url| url.trim().to_string())
+                .collect();
+            self.rpc_url = match urls.len() {
+                1 => RpcUrls::One(urls[0].clone()),
+                _ => RpcUrls::Many(urls),
+            };
+        }
+        if let Some(chain_id) = lookup(CHAIN_ID_ENV) {
+            self.chain_id = chain_id
+                .trim()
+                .parse()
+                .map_err(|_| Error::InvalidChainId(format!("{}={}", CHAIN_ID_ENV, chain_id)))?;
+        }
+        if let Some(output) = lookup(OUTPUT_ENV) {
+            self.output = match output.trim() {
+                "text" => OutputFormat::Text,
+                "json" => OutputFormat::Json,
+                _ => {
+                    return Err(Error::InvalidConfig(format!(
+                        "{}={} is neither text nor json",
+                        OUTPUT_ENV, output
+                    )))
+                }
+            };
+        }
+        if let Some(mode) = lookup(MODE_ENV) {
+            self.mode = match mode.trim() {
+                "normal" => OperatingMode::Normal,
+                "read-only" => OperatingMode::ReadOnly,
+                "dry-run" => OperatingMode::DryRun,
+                _ => {
+                    return Err(Error::InvalidConfig(format!(
+                        "{}={} is not one of normal, read-only and dry-run",
+                        MODE_ENV, mode
+                    )))
+                }
+            };
+        }
+        Ok(())
+    }
+
+    /// Removes every signer and wallet, so that a read-only session neither
+    /// prompts for a passphrase nor can sign anything.
+    pub fn clear_signers(&mut self) {
+        self.private_key = None;
+        self.keystore = None;
+        self.ledger = None;
+        self.mnemonic = None;
+        self.wallets.clear();
+    }
+
+    /// Writes a chain ID and RPC URL into a configuration file, keeping the
+    /// rest of the file and its comments as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to `config.toml`
+    /// * `chain_id` - The chain ID
+    /// * `rpc_url` - The RPC URL of the chain
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error during saving
+    pub fn save_chain(path: &Path, chain_id: u64, rpc_url: &str) -> Result<()> {
+        let mut doc = fs::read_to_string(path)?
+            .parse::<toml_edit::DocumentMut>()
+            .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+        doc["chain_id"] = toml_edit::value(chain_id as i64);
+        doc["rpc_url"] = toml_edit::value(rpc_url);
+        fs::write(path, doc.to_string())?;
+        Ok(())
     }
 
     /// Saves the configuration to a file.
@@ -63,8 +495,7 @@ impl Config {
     ///
     /// * `Result<()>` - Success or an error during saving
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
-        let content = toml::to_string_pretty(self)
-            .map_err(|e| Error::Other(format!("Failed to serialize config: {}", e)))?;
+        let content = toml::to_string_pretty(self)?;
         fs::write(path, content)?;
         Ok(())
     }
@@ -75,17 +506,91 @@ impl Config {
     ///
     /// * `Result<()>` - Success if the configuration is valid, or an error
     pub fn validate(&self) -> Result<()> {
-        validation::validate_rpc_url(&self.rpc_url)?;
+        if self.version > migrate::CONFIG_VERSION {
+            return Err(Error::InvalidConfig(format!(
+                "version {} is newer than the {} this version of Inachus reads",
+                self.version,
+                migrate::CONFIG_VERSION
+            )));
+        }
+
+        if self.rpc_url.urls().is_empty() {
+            return Err(Error::InvalidConfig("rpc_url is an empty list".to_string()));
+        }
+        for url in self.rpc_url.urls() {
+            validation::validate_rpc_url(url)?;
+        }
+
+        if let Some(ref url) = self.cross_check_rpc_url {
+            validation::validate_rpc_url(url)?;
+        }
+        if self.paranoid && self.cross_check_endpoint().is_none() {
+            return Err(Error::InvalidConfig(
+                "paranoid mode needs cross_check_rpc_url or a second rpc_url endpoint".to_string(),
+            ));
+        }
 
         validation::validate_chain_id(&self.chain_id.to_string())?;
 
         validation::validate_wait_time(&self.wait_time)
             .map_err(|e| Error::InvalidWaitTime(format!("Invalid wait time: {}", e)))?;
 
+        self.timeouts()?;
+        self.retry.backoff()?;
+        self.gas.options().validate()?;
+        if let Some(ref safe) = self.safe {
+            safe.address()?;
+        }
+        if let Some(ref relay) = self.private_relay {
+            relay.auth_signer()?;
+        }
+
         if let Some(ref pk) = self.private_key {
             validation::validate_private_key(pk)?;
         }
 
+        let signers = [
+            self.private_key.is_some(),
+            self.keystore.is_some(),
+            self.ledger.is_some(),
+            self.mnemonic.is_some(),
+        ];
+        if signers.iter().filter(|set| **set).count() > 1 {
+            return Err(Error::InvalidConfig(
+                "Only one of private_key, keystore, ledger and mnemonic can be set".to_string(),
+            ));
+        }
+
+        if let Some(ref mnemonic) = self.mnemonic {
+            if mnemonic.accounts == 0 {
+                return Err(Error::InvalidConfig(
+                    "mnemonic.accounts must be at least 1".to_string(),
+                ));
+            }
+        }
+
+        self.address_book.validate()?;
+        self.policy.policy(&self.address_book)?;
+
+        if let Some(ref profile) = self.profile {
+            if !self.profiles.contains_key(profile) {
+                return Err(Error::InvalidConfig(format!(
+                    "profile {} is not defined",
+                    profile
+                )));
+            }
+        }
+
+        for (i, wallet) in self.wallets.iter().enumerate() {
+            wallet.validate()?;
+            if self.wallets[..i].iter().any(|w| w.name == wallet.name) {
+                return Err(Error::InvalidConfig(format!(
+                    "Wallet {} is defined twice",
+                    wallet.name
+                )));
+            }
+        }
+
         if let Some(ref name) = self.contract_name {
             if name.is_empty() {
                 return Err(Error::InvalidContract(
@@ -110,16 +615,177 @@ impl Config {
     }
 }
 
+/// Merges a configuration layer into another, recursively for tables.
+fn merge(base: &mut toml::Value, layer: toml::Value) {
+    match (base, layer) {
+        (toml::Value::Table(base), toml::Value::Table(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+/// Top-level keys whose values are not expanded by [`expand_env`].
+const UNEXPANDED_KEYS: [&str; 1] = ["hooks"];
+
+/// Reads the `[rpc_api_keys]` table of a configuration value, before it is
+/// expanded, skipping values that are not strings.
+fn rpc_api_keys(value: &toml::Value) -> HashMap<String, String> {
+    value
+        .get("rpc_api_keys")
+        .and_then(toml::Value::as_table)
+        .map(|table| {
+            table
+                .iter()
+                .filter_map(|(name, key)| Some((name.clone(), key.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Replaces `${NAME}` placeholders in the strings of a configuration value,
+/// at any depth, with `lookup(NAME)`.
+///
+/// # Arguments
+///
+/// * `value` - The value, expanded in place
+/// * `path` - Key path of the value, e.g. `profiles.staging.rpc_url`, for errors
+/// * `lookup` - Returns the value of a variable, if set
+///
+/// # Returns
+///
+/// * `Result<()>` - Success, or an error naming the first unset variable and
+///   where it is used
+fn expand_env(
+    value: &mut toml::Value,
+    path: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<()> {
+    match value {
+        toml::Value::String(s) if s.contains("${") => *s = expand_placeholders(s, path, lookup)?,
+        toml::Value::Array(values) => {
+            for (i, value) in values.iter_mut().enumerate() {
+                expand_env(value, &format!("{}[{}]", path, i), lookup)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                if path.is_empty() && UNEXPANDED_KEYS.contains(&key.as_str()) {
+                    continue;
+                }
+                let path = match path.is_empty() {
+                    true => key.clone(),
+                    false => format!("{}.{}", path, key),
+                };
+                expand_env(value, &path, lookup)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Replaces each `${NAME}` of a string with `lookup(NAME)`; see [`expand_env`].
+fn expand_placeholders(
+    template: &str,
+    path: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..].find('}').ok_or_else(|| {
+            Error::InvalidConfig(format!("{} has an unterminated ${{ placeholder", path))
+        })? + start;
+        let name = &rest[start + 2..end];
+        let value = lookup(name)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                Error::InvalidConfig(format!(
+                    "{} refers to the environment variable {}, which is not set",
+                    path, name
+                ))
+            })?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
 /// Represents information about a contract.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractInfo {
     /// Name of the contract
     pub name: String,
-    /// Address of the deployed contract
+    /// Address used on chains without an entry in `deployments`, if any
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub address: String,
+    /// Address of the deployed contract on each chain, by chain ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub deployments: BTreeMap<u64, String>,
 }
 
 impl ContractInfo {
+    /// Creates the information of a contract deployed on one chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the contract
+    /// * `chain_id` - The chain it is deployed on
+    /// * `address` - Its address on that chain
+    ///
+    /// # Returns
+    ///
+    /// * `ContractInfo` - The contract information
+    pub fn new(name: &str, chain_id: u64, address: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            address: String::new(),
+            deployments: BTreeMap::from([(chain_id, address.to_string())]),
+        }
+    }
+
+    /// Returns the address of the contract on a chain: its deployment there,
+    /// else the address used on any chain, if set.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - The chain
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&str>` - The address, or `None` if the contract is not known on the chain
+    pub fn address_on(&self, chain_id: u64) -> Option<&str> {
+        self.deployments
+            .get(&chain_id)
+            .map(String::as_str)
+            .or(Some(self.address.as_str()).filter(|address| !address.is_empty()))
+    }
+
+    /// Records the address of the contract on a chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - The chain
+    /// * `address` - The address of the deployment
+    pub fn set_address(&mut self, chain_id: u64, address: &str) {
+        self.deployments.insert(chain_id, address.to_string());
+    }
+
+    /// Returns the IDs of the chains the contract has a deployment on.
+    pub fn chains(&self) -> Vec<u64> {
+        self.deployments.keys().copied().collect()
+    }
+
     /// Loads all contract information from a file.
     ///
     /// # Arguments
@@ -162,15 +828,21 @@ impl ContractInfo {
                 "Contract name cannot be empty".to_string(),
             ));
         }
-        if self.address.is_empty() {
+        if self.address.is_empty() && self.deployments.is_empty() {
             return Err(Error::InvalidContract(
                 "Contract address cannot be empty".to_string(),
             ));
         }
-        if !self.address.starts_with("0x") {
-            return Err(Error::InvalidContract(
-                "Contract address must start with 0x".to_string(),
-            ));
+        let addresses = self
+            .deployments
+            .values()
+            .chain(Some(&self.address).filter(|address| !address.is_empty()));
+        for address in addresses {
+            if !address.starts_with("0x") {
+                return Err(Error::InvalidContract(
+                    "Contract address must start with 0x".to_string(),
+                ));
+            }
         }
         Ok(())
     }
@@ -191,3 +863,184 @@ pub fn validate_contract_infos(infos: &[ContractInfo]) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        let config: Config = toml::from_str(
+            r#"
+            rpc_url = "https://eth.llamarpc.com"
+            chain_id = 1
+            private_key = "0x0123456789012345678901234567890123456789012345678901234567890123"
+            contract_name = "TetherToken"
+            contract_address = "0xdAC17F958D2ee523a2206206994597C13D831ec7"
+            profile = "mainnet-ops"
+
+            [profiles.mainnet-ops]
+            ledger = { derivation_path = "m/44'/60'/0'/0/0" }
+
+            [profiles.local-anvil]
+            rpc_url = "http://127.0.0.1:8545"
+            chain_id = 31337
+            contract_name = "Counter"
+            contract_address = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+            contracts_file = "contracts.anvil.json"
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let ops = config.with_profile(None).unwrap();
+        assert_eq!(ops.profile.as_deref(), Some("mainnet-ops"));
+        assert!(ops.private_key.is_none());
+        assert!(ops.ledger.is_some());
+        assert_eq!(ops.rpc_url.primary(), "https://eth.llamarpc.com");
+        ops.validate().unwrap();
+
+        let anvil = config.with_profile(Some("local-anvil")).unwrap();
+        assert_eq!(anvil.chain_id, 31337);
+        assert_eq!(anvil.rpc_url.primary(), "http://127.0.0.1:8545");
+        assert!(anvil.private_key.is_some());
+        assert_eq!(anvil.contract_name.as_deref(), Some("Counter"));
+        assert_eq!(anvil.contracts_file, PathBuf::from("contracts.anvil.json"));
+
+        assert!(config.with_profile(Some("staging")).is_err());
+        let mut unknown = config.clone();
+        unknown.profile = Some("staging".to_string());
+        assert!(unknown.validate().is_err());
+    }
+
+    #[test]
+    fn test_contract_deployments() {
+        let infos: Vec<ContractInfo> = serde_json::from_str(
+            r#"[
+                { "name": "TetherToken", "address": "0xdAC17F958D2ee523a2206206994597C13D831ec7" },
+                { "name": "Counter", "deployments": { "31337": "0x5FbDB2315678afecb367f032d93F642f64180aa3" } }
+            ]"#,
+        )
+        .unwrap();
+        validate_contract_infos(&infos).unwrap();
+        let (tether, counter) = (&infos[0], &infos[1]);
+        assert_eq!(
+            tether.address_on(10),
+            Some("0xdAC17F958D2ee523a2206206994597C13D831ec7")
+        );
+        assert_eq!(
+            counter.address_on(31337),
+            Some("0x5FbDB2315678afecb367f032d93F642f64180aa3")
+        );
+        assert_eq!(counter.address_on(1), None);
+
+        let mut counter = counter.clone();
+        counter.set_address(10, "0x1111111111111111111111111111111111111111");
+        assert_eq!(counter.chains(), vec![10, 31337]);
+        let json = serde_json::to_value(&counter).unwrap();
+        assert_eq!(
+            json["deployments"]["10"],
+            "0x1111111111111111111111111111111111111111"
+        );
+        assert!(json.get("address").is_none());
+        assert!(ContractInfo::new("Counter", 1, "5FbDB2")
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_config_layering() {
+        let root = std::env::temp_dir().join(format!("inachus-layers-{}", std::process::id()));
+        let data_dir = root.join("home").join(crate::INACHUS_DIR);
+        let project = root.join("project");
+        let nested = project.join("src").join("contracts");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::create_dir_all(project.join(crate::INACHUS_DIR)).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+        let global = data_dir.join(crate::CONFIG_FILE);
+        let local = project.join(crate::INACHUS_DIR).join(crate::CONFIG_FILE);
+        fs::write(
+            &global,
+            "rpc_url = \"https://eth.llamarpc.com\"\nchain_id = 1\nwait_time = \"5s\"\n[gas]\ngas_limit = 100000\n",
+        )
+        .unwrap();
+        fs::write(
+            &local,
+            "rpc_url = \"http://127.0.0.1:8545\"\nchain_id = 31337\n[gas]\ntx_type = \"legacy\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(Config::local_path(&nested, &data_dir), Some(local.clone()));
+        assert_eq!(Config::local_path(&data_dir, &data_dir), None);
+        let mut config = Config::from_files(&[global, local]).unwrap();
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(config.rpc_url.primary(), "http://127.0.0.1:8545");
+        assert_eq!(config.chain_id, 31337);
+        assert_eq!(config.wait_time, "5s");
+        assert_eq!(config.gas.gas_limit, Some(100000));
+        assert!(config.gas.tx_type.is_some());
+
+        let env = |name: &str| match name {
+            RPC_URL_ENV => Some("https://a.example, https://b.example".to_string()),
+            CHAIN_ID_ENV => Some("10".to_string()),
+            MODE_ENV => Some("dry-run".to_string()),
+            _ => None,
+        };
+        config.apply_env(env).unwrap();
+        assert_eq!(config.rpc_url.fallbacks(), vec!["https://b.example"]);
+        assert_eq!(config.chain_id, 10);
+        assert_eq!(config.mode, OperatingMode::DryRun);
+        assert!(config
+            .apply_env(|name| (name == CHAIN_ID_ENV).then(|| "ten".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_env_expansion() {
+        let mut value: toml::Value = toml::from_str(
+            r#"
+            rpc_url = ["${MAINNET_RPC}", "https://eth.llamarpc.com"]
+            private_key = "${PK}"
+            etherscan_api_key = "${ETHERSCAN_API_KEY}"
+            [[hooks]]
+            stage = "post_receipt"
+            command = "curl -d @- ${WEBHOOK}"
+            "#,
+        )
+        .unwrap();
+        let lookup = |name: &str| match name {
+            "MAINNET_RPC" => Some("https://mainnet.example/v3/key".to_string()),
+            "PK" => Some("0x01".to_string()),
+            _ => None,
+        };
+        let error = expand_env(&mut value.clone(), "", &lookup).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid config: etherscan_api_key refers to the environment variable ETHERSCAN_API_KEY, which is not set"
+        );
+
+        value.as_table_mut().unwrap().remove("etherscan_api_key");
+        expand_env(&mut value, "", &lookup).unwrap();
+        let config: Config = value.try_into().unwrap();
+        assert_eq!(config.rpc_url.primary(), "https://mainnet.example/v3/key");
+        assert_eq!(config.private_key.as_deref(), Some("0x01"));
+        assert_eq!(config.hooks[0].command, "curl -d @- ${WEBHOOK}");
+
+        let value: toml::Value = toml::from_str(
+            r#"
+            rpc_url = "https://mainnet.infura.io/v3/${INFURA_API_KEY}"
+            [rpc_api_keys]
+            INFURA_API_KEY = "abc"
+            "#,
+        )
+        .unwrap();
+        let keys = rpc_api_keys(&value);
+        assert_eq!(keys.get("INFURA_API_KEY").map(String::as_str), Some("abc"));
+
+        assert!(expand_placeholders("https://x/${KEY", "rpc_url", &lookup).is_err());
+        assert_eq!(
+            expand_placeholders("${PK}${PK}", "private_key", &lookup).unwrap(),
+            "0x010x01"
+        );
+    }
+}
@@ -1,9 +1,56 @@
 /// src/context.rs
+use crate::abi::{
+    self,
+    source::{AbiQuery, AbiSource},
+};
+use crate::address_book::AddressBook;
+use crate::cancel::{self, CancellationToken, Timeouts};
+use crate::codec;
+use crate::config::{ContractInfo, OperatingMode};
+use crate::create2;
+use crate::cross_check::CrossChecker;
+use crate::ens;
 use crate::error::{Error, Result};
-use alloy::json_abi::JsonAbi;
-use alloy::primitives::{Address, U256};
+use crate::events::{Event, EventBus};
+use crate::fork::{self, DevNode, ForkSession};
+use crate::format;
+use crate::hooks::{Hooks, PendingSend};
+use crate::journal::{Journal, JournalEntry};
+use crate::l1_fee;
+use crate::nonce::Replacement;
+use crate::offline::{SignedTransaction, UnsignedTransaction};
+use crate::output::OutputFormat;
+use crate::proxy::Facet;
+use crate::relay::RelayConfig;
+use crate::result::{CallDiff, CallResult, ExecutionResult};
+use crate::safe::SafeConfig;
+use crate::signer::Signer;
+use crate::step::Capabilities;
+use crate::trace::CallFrame;
+use crate::transaction::{
+    AccessListEstimate, GasConfig, TransactionOptions, TransactionPreview, TxType,
+};
+use crate::transport::{Backoff, FailoverTransport, RetryTransport, RpcEndpoint};
+use crate::usage::{LastCall, Usage};
+use crate::validation;
+use crate::wallet::WalletConfig;
+use alloy::consensus::TxEnvelope;
+use alloy::dyn_abi::DynSolValue;
+use alloy::eips::BlockId;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::network::{Ethereum, TransactionBuilder};
+use alloy::primitives::{Address, Bytes, TxHash, B256, U256};
+use alloy::providers::{
+    DynProvider, PendingTransactionBuilder, PendingTransactionError, Provider, ProviderBuilder,
+};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::{Filter, Log, Transaction, TransactionReceipt, TransactionRequest};
+use alloy::transports::BoxTransport;
+use chain_info::{ChainRegistry, RollupStack};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
 
 /// Global context holding the application's state and configuration.
 #[derive(Debug, Clone)]
@@ -12,68 +59,836 @@ pub struct GlobalContext {
     pub abis_dir: PathBuf,
     /// Map of contract names to their parsed ABIs
     pub abis: HashMap<String, JsonAbi>,
-    /// User's private key for transaction signing
-    pub private_key: String,
+    /// Signer transactions are sent with: a private key, keystore, mnemonic,
+    /// Ledger device, KMS key or any other [`crate::signer::InachusSigner`]
+    pub signer: Option<Signer>,
+    /// Named wallets the sender can be switched to
+    pub wallets: Vec<WalletConfig>,
+    /// Signers of the wallets unlocked so far, by name
+    pub unlocked_wallets: HashMap<String, Signer>,
     /// Ethereum RPC URL
     pub rpc_url: String,
+    /// Further endpoints `rpc_url` fails over to, when configured as a list
+    pub fallback_rpc_urls: Vec<String>,
+    /// Backoff of RPC requests failing for transient reasons
+    pub retry: Backoff,
     /// Chain ID for transaction signing
     pub chain_id: String,
     /// Name of the current contract being interacted with
     pub contract_name: String,
     /// Address of the current contract being interacted with
     pub contract_address: Address,
+    /// Bus on which workflow lifecycle events are published
+    pub events: EventBus,
+    /// Directory holding the configuration and persisted state
+    pub data_dir: PathBuf,
+    /// Configuration file settings changed during the session are saved to:
+    /// the project-local one if any, else the global one
+    pub config_path: PathBuf,
+    /// Known contract deployments, persisted in `contracts.json`
+    pub contracts: Vec<ContractInfo>,
+    /// Token cancelling the operations of the running step
+    pub cancel: CancellationToken,
+    /// Per-operation timeouts for RPC requests
+    pub timeouts: Timeouts,
+    /// RPC client used instead of connecting to `rpc_url`: the shared WebSocket
+    /// or IPC connection, or a mock in tests
+    pub client: Option<RpcClient>,
+    /// Where ABIs are listed and resolved from
+    pub abi_source: Arc<dyn AbiSource>,
+    /// Hooks run around every transaction sent from this context
+    pub hooks: Hooks,
+    /// Etherscan API key used to import verified ABIs
+    pub etherscan_api_key: Option<String>,
+    /// Keys filling `${NAME}` placeholders in chain list RPC URLs
+    pub rpc_api_keys: HashMap<String, String>,
+    /// Facets of the contracts loaded as diamonds, by contract name
+    pub facets: HashMap<String, Vec<Facet>>,
+    /// Default gas settings for write transactions
+    pub gas: GasConfig,
+    /// Endpoint view calls are cross-checked against, `None` unless in paranoid mode
+    pub cross_check_rpc_url: Option<String>,
+    /// Whether transactions are sent, only simulated, or not even offered
+    pub mode: OperatingMode,
+    /// Simulate every transaction right before it is signed, on every write
+    /// path, and refuse those that revert; see `policy.require_simulation`
+    pub require_simulation: bool,
+    /// Blocks to wait for after a transaction is included before showing its receipt
+    pub confirmations: u64,
+    /// Aliases usable wherever an address is asked for
+    pub address_book: AddressBook,
+    /// How results are printed
+    pub output: OutputFormat,
+    /// Known chains, searched when switching chain
+    pub chains: ChainRegistry,
+    /// Safe that write transactions are proposed to instead of being sent
+    pub safe: Option<SafeConfig>,
+    /// Private relay write transactions can be sent through
+    pub private_relay: Option<RelayConfig>,
+    /// CREATE2 factory deterministic deployments go through
+    pub create2_factory: Address,
+    /// File of the known contract deployments, relative to `data_dir`
+    pub contracts_file: PathBuf,
+    /// Configuration profile the session was loaded with, if any
+    pub profile: Option<String>,
+    /// Names of the configuration profiles the session can switch to
+    pub profiles: Vec<String>,
+    /// Local anvil fork the session is repointed at, if any
+    pub fork: Option<ForkSession>,
+    /// Development node (anvil, Hardhat) the session is connected to, if any
+    pub dev_node: Option<DevNode>,
+    /// Account a development node impersonates, which calls are made and
+    /// transactions sent from instead of the signer's
+    pub impersonating: Option<Address>,
+}
+
+/// Builds a [`GlobalContext`], e.g. a partial one without a contract or
+/// signer for a library or the setup wizard.
+///
+/// Nothing is connected: providers are created on first use by
+/// [`GlobalContext::read_context`] and [`GlobalContext::write_context`], or
+/// opened once with [`GlobalContext::connect`] for WebSocket and IPC endpoints.
+#[derive(Debug, Clone, Default)]
+pub struct GlobalContextBuilder {
+    abis_dir: Option<PathBuf>,
+    abis: HashMap<String, JsonAbi>,
+    rpc_url: Option<String>,
+    chain_id: Option<u64>,
+    contract: Option<(String, String)>,
+    signer: Option<Signer>,
+    data_dir: Option<PathBuf>,
+    client: Option<RpcClient>,
+    mode: OperatingMode,
+}
+
+impl GlobalContextBuilder {
+    /// Sets the directory containing ABI files, `abis` in the data directory by default.
+    pub fn abis_dir(mut self, abis_dir: impl Into<PathBuf>) -> Self {
+        self.abis_dir = Some(abis_dir.into());
+        self
+    }
+
+    /// Adds parsed ABIs, by contract name.
+    pub fn abis(mut self, abis: HashMap<String, JsonAbi>) -> Self {
+        self.abis.extend(abis);
+        self
+    }
+
+    /// Sets the RPC endpoint: an `http(s)://` or `ws(s)://` URL, or an IPC path.
+    pub fn rpc_url(mut self, rpc_url: &str) -> Self {
+        self.rpc_url = Some(rpc_url.to_string());
+        self
+    }
+
+    /// Sets the chain ID transactions are signed for, 1 by default.
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Selects the contract interacted with.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the contract, under which its ABI is loaded
+    /// * `address` - Checksummed address of the contract
+    pub fn contract(mut self, name: &str, address: &str) -> Self {
+        self.contract = Some((name.to_string(), address.to_string()));
+        self
+    }
+
+    /// Sets the signer transactions are sent with.
+    pub fn signer(mut self, signer: impl Into<Signer>) -> Self {
+        self.signer = Some(signer.into());
+        self
+    }
+
+    /// Sets the directory holding the configuration and persisted state,
+    /// `~/.inachus` by default.
+    pub fn data_dir(mut self, data_dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(data_dir.into());
+        self
+    }
+
+    /// Uses an existing RPC client instead of connecting to the RPC URL.
+    pub fn client(mut self, client: RpcClient) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets whether transactions are sent, only simulated, or not even offered.
+    pub fn mode(mut self, mode: OperatingMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Validates the settings and builds the context.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<GlobalContext>` - The context, or an error if the RPC URL or
+    ///   the contract address is invalid
+    pub fn build(self) -> Result<GlobalContext> {
+        if let Some(ref rpc_url) = self.rpc_url {
+            RpcEndpoint::parse(rpc_url)?;
+        }
+        let (contract_name, contract_address) = match self.contract {
+            Some((name, address)) => {
+                let address = Address::parse_checksummed(&address, None)
+                    .map_err(|_| Error::InvalidAddress(address.clone()))?;
+                (name, address)
+            }
+            None => (String::new(), Address::ZERO),
+        };
+        let data_dir = self.data_dir.unwrap_or_else(crate::data_dir);
+        let abis_dir = self
+            .abis_dir
+            .unwrap_or_else(|| data_dir.join(crate::ABI_DIR));
+
+        Ok(GlobalContext {
+            abi_source: abi::source::default_sources(&abis_dir, None, None),
+            abis_dir,
+            abis: self.abis,
+            signer: self.signer,
+            wallets: Vec::new(),
+            unlocked_wallets: HashMap::new(),
+            rpc_url: self.rpc_url.unwrap_or_default(),
+            fallback_rpc_urls: Vec::new(),
+            retry: Backoff::default(),
+            chain_id: self.chain_id.unwrap_or(1).to_string(),
+            contract_name,
+            contract_address,
+            events: EventBus::new(),
+            config_path: data_dir.join(crate::CONFIG_FILE),
+            data_dir,
+            contracts: Vec::new(),
+            cancel: CancellationToken::new(),
+            timeouts: Timeouts::default(),
+            client: self.client,
+            hooks: Hooks::new(),
+            etherscan_api_key: None,
+            rpc_api_keys: HashMap::new(),
+            facets: HashMap::new(),
+            gas: GasConfig::default(),
+            cross_check_rpc_url: None,
+            mode: self.mode,
+            require_simulation: false,
+            confirmations: 1,
+            address_book: AddressBook::new(),
+            output: OutputFormat::default(),
+            chains: ChainRegistry::default(),
+            safe: None,
+            private_relay: None,
+            create2_factory: create2::DEFAULT_FACTORY,
+            contracts_file: PathBuf::from(crate::CONTRACTS_FILE),
+            profile: None,
+            profiles: Vec::new(),
+            fork: None,
+            dev_node: None,
+            impersonating: None,
+        })
+    }
 }
 
 impl GlobalContext {
-    /// Creates a new GlobalContext with the provided values.
+    /// Starts building a context; every field is optional.
+    ///
+    /// # Returns
+    ///
+    /// * `GlobalContextBuilder` - A builder validating the context at `build()`
+    pub fn builder() -> GlobalContextBuilder {
+        GlobalContextBuilder::default()
+    }
+
+    /// Returns the directory where remotely fetched ABIs are cached.
+    pub fn abi_cache_dir(&self) -> PathBuf {
+        self.data_dir.join("cache").join(crate::ABI_DIR)
+    }
+
+    /// Returns the path of the file storing known contract deployments.
+    pub fn contracts_path(&self) -> PathBuf {
+        self.data_dir.join(&self.contracts_file)
+    }
+
+    /// Persists the known contract deployments to `contracts_file`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error during saving
+    pub fn save_contracts(&self) -> Result<()> {
+        ContractInfo::save_all(&self.contracts, &self.contracts_path())
+    }
+
+    /// Returns the journal of sent transactions, `history.jsonl`.
+    pub fn journal(&self) -> Journal {
+        Journal::new(self.data_dir.join(crate::HISTORY_FILE))
+    }
+
+    /// Records a sent transaction in the journal. Nothing is recorded for
+    /// results that broadcast nothing, and a failure to write is only logged,
+    /// the transaction being out already.
     ///
     /// # Arguments
     ///
-    /// * `abis_dir` - Path to the directory containing ABI files
-    /// * `abis` - Map of contract names to their parsed ABIs
-    /// * `rpc_url` - Ethereum RPC URL
-    /// * `private_key` - User's private key for transaction signing
-    /// * `chain_id` - Chain ID for transaction signing
-    /// * `contract_name` - Name of the current contract
-    /// * `contract_address` - Address of the current contract
+    /// * `contract` - Name of the contract's ABI
+    /// * `address` - Target contract address
+    /// * `function` - The called function
+    /// * `args` - Arguments as entered, address book aliases resolved
+    /// * `options` - Options the transaction was sent with
+    /// * `result` - The result of sending the transaction
+    pub fn record(
+        &self,
+        contract: &str,
+        address: Address,
+        function: &Function,
+        args: &[String],
+        options: &TransactionOptions,
+        result: &ExecutionResult,
+    ) {
+        self.append_to_journal(JournalEntry::from_result(
+            self.chain_id.parse().unwrap_or_default(),
+            contract,
+            address,
+            &function.signature(),
+            args,
+            options.value(),
+            result,
+        ));
+    }
+
+    /// Records a sent transaction carrying raw calldata, e.g. a native
+    /// transfer, in the journal. It is recorded without a contract name, so
+    /// the history lists it but cannot send it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Target address
+    /// * `label` - What the transaction does, recorded as its function
+    /// * `options` - Options the transaction was sent with
+    /// * `result` - The result of sending the transaction
+    pub fn record_calldata(
+        &self,
+        address: Address,
+        label: &str,
+        options: &TransactionOptions,
+        result: &ExecutionResult,
+    ) {
+        self.append_to_journal(JournalEntry::from_result(
+            self.chain_id.parse().unwrap_or_default(),
+            "",
+            address,
+            label,
+            &[],
+            options.value(),
+            result,
+        ));
+    }
+
+    fn append_to_journal(&self, entry: Option<JournalEntry>) {
+        if let Some(entry) = entry {
+            if let Err(e) = self.journal().append(&entry) {
+                tracing::warn!(hash = %entry.hash, error = %e, "could not record the transaction");
+            }
+        }
+    }
+
+    /// Returns the path of the method usage statistics, `usage.json`.
+    pub fn usage_path(&self) -> PathBuf {
+        self.data_dir.join(crate::USAGE_FILE)
+    }
+
+    /// Reads the method usage statistics, empty if they cannot be read.
+    pub fn usage(&self) -> Usage {
+        Usage::load(&self.usage_path()).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "could not read method usage");
+            Usage::default()
+        })
+    }
+
+    /// Counts an execution of a method of the active contract for the
+    /// favorites of the method selector, and remembers it as the last call.
+    /// Nothing is counted for cancelled transactions, and a failure to write
+    /// is only logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The executed function
+    /// * `args` - Arguments as entered, address book aliases resolved
+    /// * `options` - Options the method was executed with
+    /// * `result` - The result of executing it
+    pub fn record_usage(
+        &self,
+        function: &Function,
+        args: &[String],
+        options: &TransactionOptions,
+        result: &ExecutionResult,
+    ) {
+        if matches!(result, ExecutionResult::Cancelled) {
+            return;
+        }
+        let mut usage = self.usage();
+        usage.record(LastCall {
+            chain_id: self.chain_id.parse().unwrap_or_default(),
+            contract: self.contract_name.clone(),
+            address: self.contract_address,
+            function: function.signature(),
+            args: args.to_vec(),
+            value: options.value(),
+        });
+        if let Err(e) = usage.save(&self.usage_path()) {
+            tracing::warn!(error = %e, "could not record method usage");
+        }
+    }
+
+    /// Returns the block explorer page of a sent transaction, when the
+    /// current chain lists an explorer.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of executing a method
     ///
     /// # Returns
     ///
-    /// * `Result<GlobalContext>` - A new GlobalContext or an error if validation fails
-    pub fn new(
-        abis_dir: PathBuf,
-        abis: HashMap<String, JsonAbi>,
-        rpc_url: &str,
-        private_key: &str,
-        chain_id: &str,
-        contract_name: &str,
-        contract_address: &str,
-    ) -> Result<Self> {
-        U256::from_str_radix(chain_id, 10)
-            .map_err(|_| Error::InvalidChainId(chain_id.to_string()))?;
+    /// * `Option<String>` - The transaction URL, or `None` for calls and unknown explorers
+    pub fn explorer_url(&self, result: &ExecutionResult) -> Option<String> {
+        let hash = match result {
+            ExecutionResult::Submitted { hash } => *hash,
+            ExecutionResult::Confirmed(receipt) => receipt.transaction_hash,
+            ExecutionResult::Call(_)
+            | ExecutionResult::Proposed { .. }
+            | ExecutionResult::Exported(_)
+            | ExecutionResult::Simulated(_)
+            | ExecutionResult::Cancelled => return None,
+        };
+        let chain_id = self.chain_id.parse().ok()?;
+        self.chains.get(chain_id).ok()?.tx_url(hash)
+    }
 
-        if !private_key
-            .trim_start_matches("0x")
-            .chars()
-            .all(|c| c.is_ascii_hexdigit())
-        {
-            return Err(Error::InvalidPrivateKey(
-                "Invalid private key format".to_string(),
-            ));
+    /// Returns the address book extended with the ENS names of the addresses
+    /// among decoded values, to annotate them when results are printed as text.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The decoded values about to be displayed
+    ///
+    /// # Returns
+    ///
+    /// * `AddressBook` - The book, with the verified primary names of the
+    ///   addresses it has no alias for
+    pub async fn labelled_address_book<'a>(
+        &self,
+        values: impl IntoIterator<Item = &'a DynSolValue>,
+    ) -> AddressBook {
+        let unknown: Vec<Address> = format::addresses(values)
+            .into_iter()
+            .filter(|address| self.address_book.alias_of(*address).is_none())
+            .collect();
+        if self.output != OutputFormat::Text || unknown.is_empty() {
+            return self.address_book.clone();
         }
+        match self.read_context() {
+            Ok(read) => self
+                .address_book
+                .with_names(ens::reverse_names(&read, &unknown).await),
+            Err(_) => self.address_book.clone(),
+        }
+    }
 
-        let contract_address = Address::parse_checksummed(contract_address, None)
-            .map_err(|_| Error::InvalidAddress(contract_address.to_string()))?;
+    /// Returns the capabilities of the session, used to filter workflow steps.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            signer: self.mode != OperatingMode::ReadOnly
+                && (self.signer.is_some() || self.impersonating.is_some()),
+            broadcast: self.mode == OperatingMode::Normal,
+            wallets: !self.wallets.is_empty(),
+            profiles: !self.profiles.is_empty(),
+            dev_node: self.dev_node.is_some(),
+            pubsub: false,
+        }
+    }
 
-        Ok(Self {
-            abis_dir,
-            abis,
-            private_key: private_key.to_string(),
-            rpc_url: rpc_url.to_string(),
-            chain_id: chain_id.to_string(),
-            contract_name: contract_name.to_string(),
-            contract_address,
+    /// Reloads all ABIs listed by `abi_source`, emitting an `AbiLoaded` event for each.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error if a source could not be listed
+    pub async fn reload_abis(&mut self) -> Result<()> {
+        self.abis = self.abi_source.list().await?;
+        for (name, abi) in &self.abis {
+            self.emit_abi_loaded(name, abi);
+        }
+        Ok(())
+    }
+
+    /// Resolves an ABI through `abi_source` and registers it under a name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name to register the ABI under
+    /// * `query` - The contract to look up
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool>` - Whether an ABI was found, or an error
+    pub async fn resolve_abi(&mut self, name: &str, query: &AbiQuery) -> Result<bool> {
+        let Some(abi) = self.abi_source.resolve(query).await? else {
+            return Ok(false);
+        };
+        self.register_abi(name, abi);
+        Ok(true)
+    }
+
+    /// Registers an ABI under a name, emitting an `AbiLoaded` event.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name to register the ABI under
+    /// * `abi` - The ABI
+    pub fn register_abi(&mut self, name: &str, abi: JsonAbi) {
+        self.emit_abi_loaded(name, &abi);
+        self.abis.insert(name.to_string(), abi);
+    }
+
+    fn emit_abi_loaded(&self, name: &str, abi: &JsonAbi) {
+        self.events.emit(Event::AbiLoaded {
+            name: name.to_string(),
+            functions: abi.functions().count(),
+            events: abi.events().count(),
+        });
+    }
+
+    /// Opens the RPC client shared by every context created afterwards,
+    /// retrying transient failures with the `retry` backoff. WebSocket and IPC
+    /// endpoints are connected once; with fallbacks, every endpoint is
+    /// health-checked first. Local endpoints are checked for a development
+    /// node, and any impersonation of the previous node ends.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error if no endpoint can be reached
+    pub async fn connect(&mut self) -> Result<()> {
+        if self.client.is_some() {
+            return Ok(());
+        }
+        let (transport, is_local) = if !self.fallback_rpc_urls.is_empty() {
+            let urls: Vec<&str> = std::iter::once(self.rpc_url.as_str())
+                .chain(self.fallback_rpc_urls.iter().map(String::as_str))
+                .collect();
+            let (transport, health) = FailoverTransport::connect(&urls, self.timeouts).await?;
+            for check in &health {
+                match check.latency {
+                    Some(latency) => {
+                        tracing::info!(endpoint = %check.endpoint, ?latency, "RPC endpoint healthy")
+                    }
+                    None => tracing::warn!(endpoint = %check.endpoint, "RPC endpoint unhealthy"),
+                }
+            }
+            (BoxTransport::new(transport), false)
+        } else {
+            let client = RpcEndpoint::parse(&self.rpc_url)?.connect().await?;
+            (client.transport().clone(), client.is_local())
+        };
+        self.client = Some(RetryTransport::new(transport, self.retry).into_client(is_local));
+        self.impersonating = None;
+        self.dev_node = match is_local {
+            true => fork::detect(&self.read_context()?).await.map(DevNode::new),
+            false => None,
+        };
+        Ok(())
+    }
+
+    /// Creates a read-only context connected to the configured RPC endpoint.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ReadContext>` - A new ReadContext or an error
+    pub fn read_context(&self) -> Result<ReadContext> {
+        let read = match &self.client {
+            Some(client) => ReadContext::from_client(client.clone()),
+            None => ReadContext::connect(&self.rpc_url)?,
+        };
+        Ok(read
+            .with_cancellation(self.cancel.clone())
+            .with_timeouts(self.timeouts)
+            .with_sender(self.impersonating))
+    }
+
+    /// Creates the checker view calls are repeated with in paranoid mode.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<CrossChecker>>` - The checker comparing `rpc_url` with
+    ///   `cross_check_rpc_url`, `None` unless in paranoid mode, or an error if
+    ///   an endpoint is malformed
+    pub fn cross_checker(&self) -> Result<Option<CrossChecker>> {
+        let Some(ref url) = self.cross_check_rpc_url else {
+            return Ok(None);
+        };
+        let secondary = ReadContext::connect(url)?
+            .with_cancellation(self.cancel.clone())
+            .with_timeouts(self.timeouts)
+            .with_sender(self.impersonating);
+        let endpoints = [
+            crate::status::endpoint_label(&self.rpc_url),
+            crate::status::endpoint_label(url),
+        ];
+        Ok(Some(CrossChecker::new(
+            self.read_context()?,
+            secondary,
+            endpoints,
+        )))
+    }
+
+    /// Returns the signer transactions are sent with: the impersonated account
+    /// if any, or else `signer`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Signer>` - The signer, `None` if none is configured
+    pub fn active_signer(&self) -> Option<Signer> {
+        match self.impersonating {
+            Some(address) => Some(Signer::impersonated(address)),
+            None => self.signer.clone(),
+        }
+    }
+
+    /// Creates a write context using the active signer.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<WriteContext>` - A new WriteContext or an error if no valid signer
+    ///   is configured or the session is read-only
+    pub fn write_context(&self) -> Result<WriteContext> {
+        if self.mode == OperatingMode::ReadOnly {
+            return Err(Error::ModeForbids {
+                mode: OperatingMode::ReadOnly,
+                reason: "nothing can be signed or sent".to_string(),
+            });
+        }
+        let signer = self
+            .active_signer()
+            .ok_or_else(|| Error::InvalidPrivateKey("No private key configured".to_string()))?;
+        let chain_id = self
+            .chain_id
+            .parse::<u64>()
+            .map_err(|_| Error::InvalidChainId(self.chain_id.clone()))?;
+        let write = match &self.client {
+            Some(client) => WriteContext::from_client(client.clone(), signer, chain_id),
+            None => WriteContext::connect(&self.rpc_url, signer, chain_id)?,
+        };
+        Ok(write
+            .with_cancellation(self.cancel.clone())
+            .with_timeouts(self.timeouts)
+            .with_hooks(self.hooks.clone())
+            .with_required_simulation(self.require_simulation)
+            .with_dry_run(self.mode == OperatingMode::DryRun)
+            .with_events(self.events.clone()))
+    }
+
+    /// Broadcasts a transaction signed elsewhere with `eth_sendRawTransaction`
+    /// and emits a `TxSubmitted` event.
+    ///
+    /// The pre-send hooks and the required simulation run on it first, as on
+    /// a call made from its signer. A signed transaction cannot be changed,
+    /// so a hook changing its options refuses it.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The imported signed transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PendingTransactionBuilder<Ethereum>>` - The pending transaction,
+    ///   or an error if the mode, a hook or the simulation refuses it
+    pub async fn send_raw_transaction(
+        &self,
+        tx: &SignedTransaction,
+    ) -> Result<PendingTransactionBuilder<Ethereum>> {
+        match self.mode {
+            OperatingMode::Normal => {}
+            OperatingMode::ReadOnly => {
+                return Err(Error::ModeForbids {
+                    mode: self.mode,
+                    reason: "nothing can be signed or sent".to_string(),
+                })
+            }
+            OperatingMode::DryRun => {
+                return Err(Error::ModeForbids {
+                    mode: self.mode,
+                    reason: "transactions are never signed or sent".to_string(),
+                })
+            }
+        }
+        let read = self.read_context()?;
+        let options = tx.options();
+        let request = tx.request();
+        let contract = request
+            .to
+            .and_then(|kind| kind.to().copied())
+            .unwrap_or_default();
+        let (_, checked) = check_request(
+            &read,
+            &self.hooks,
+            &self.events,
+            self.require_simulation,
+            "raw transaction",
+            request,
+            &options,
+        )
+        .await?;
+        if checked != options {
+            return Err(Error::Vetoed {
+                hook: "pre-send hooks".to_string(),
+                reason: "a signed transaction cannot be changed".to_string(),
+            });
+        }
+        let operation = "sending raw transaction";
+        let pending = cancel::guard(operation, &read.cancel, read.timeouts.send, async {
+            read.provider
+                .send_raw_transaction(&tx.raw)
+                .await
+                .map_err(|e| Error::rpc(operation, e))
         })
+        .await?;
+        self.events.emit(Event::TxSubmitted {
+            hash: *pending.tx_hash(),
+            contract,
+            function: "raw transaction".to_string(),
+        });
+        Ok(pending)
+    }
+
+    /// Switches to another chain, reconnecting to its RPC endpoint and
+    /// emitting a `ChainSwitched` event. The active contract follows to its
+    /// deployment on the new chain, when one is known.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - ID of the chain
+    /// * `rpc_url` - RPC endpoint of the chain, replacing `rpc_url`, its
+    ///   fallbacks and the cross-check endpoint
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error if the endpoint cannot be reached
+    pub async fn switch_chain(&mut self, chain_id: u64, rpc_url: &str) -> Result<()> {
+        let client = self.client.take();
+        let previous = std::mem::replace(&mut self.rpc_url, rpc_url.to_string());
+        let fallbacks = std::mem::take(&mut self.fallback_rpc_urls);
+        let cross_check = self.cross_check_rpc_url.take();
+        if let Err(e) = self.connect().await {
+            self.client = client;
+            self.rpc_url = previous;
+            self.fallback_rpc_urls = fallbacks;
+            self.cross_check_rpc_url = cross_check;
+            return Err(e);
+        }
+        self.chain_id = chain_id.to_string();
+        self.events.emit(Event::ChainSwitched {
+            chain_id,
+            rpc_url: self.rpc_url.clone(),
+        });
+        let contract_name = self.contract_name.clone();
+        if let Some(address) = self.deployment(&contract_name, chain_id) {
+            self.set_contract(&contract_name, address);
+        }
+        Ok(())
+    }
+
+    /// Switches the active contract, emitting a `ContractSelected` event.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_name` - Name of the contract's ABI
+    /// * `contract_address` - Address of the deployed contract
+    pub fn set_contract(&mut self, contract_name: &str, contract_address: Address) {
+        self.contract_name = contract_name.to_string();
+        self.contract_address = contract_address;
+        self.events.emit(Event::ContractSelected {
+            name: self.contract_name.clone(),
+            address: contract_address,
+        });
+    }
+
+    /// Looks up the address of a contract by name.
+    ///
+    /// The current contract wins over the known deployments.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the contract's ABI
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Address>` - The address or an error if the contract is unknown
+    pub fn address_of(&self, name: &str) -> Result<Address> {
+        if name == self.contract_name {
+            return Ok(self.contract_address);
+        }
+        let chain_id = self
+            .chain_id
+            .parse::<u64>()
+            .map_err(|_| Error::InvalidChainId(self.chain_id.clone()))?;
+        let address = self
+            .contracts
+            .iter()
+            .find(|info| info.name == name)
+            .and_then(|info| info.address_on(chain_id))
+            .ok_or_else(|| {
+                Error::InvalidContract(format!(
+                    "No address known for contract {} on chain {}",
+                    name, chain_id
+                ))
+            })?;
+        Address::from_str(address).map_err(|_| Error::InvalidAddress(address.to_string()))
+    }
+
+    /// Looks up the deployment of a known contract on a chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the contract's ABI
+    /// * `chain_id` - The chain
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Address>` - The address, or `None` if no deployment is known there
+    pub fn deployment(&self, name: &str, chain_id: u64) -> Option<Address> {
+        self.contracts
+            .iter()
+            .find(|info| info.name == name)
+            .and_then(|info| info.address_on(chain_id))
+            .and_then(|address| Address::from_str(address).ok())
+    }
+
+    /// Resolves the ABI, address and function a method targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract` - Name of the contract's ABI, the current contract when `None`
+    /// * `address` - Address or address book alias overriding the contract's known address
+    /// * `method` - A function name or signature
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(&JsonAbi, &Function, Address)>` - The target or an error if any part is unknown
+    pub fn target(
+        &self,
+        contract: Option<&str>,
+        address: Option<&str>,
+        method: &str,
+    ) -> Result<(&JsonAbi, &Function, Address)> {
+        let name = contract.unwrap_or(&self.contract_name);
+        let abi = self.abis.get(name).ok_or_else(|| {
+            Error::InvalidContract(format!("ABI not found for contract: {}", name))
+        })?;
+        let address = match address.map(|address| self.address_book.resolve(address)) {
+            Some(address) => {
+                Address::from_str(&address).map_err(|_| Error::InvalidAddress(address.clone()))?
+            }
+            None => self.address_of(name)?,
+        };
+        Ok((abi, abi::find_function(abi, method)?, address))
     }
 
     /// Gets the ABI for the current contract.
@@ -92,45 +907,1349 @@ impl GlobalContext {
 }
 
 /// Context for read-only operations.
-#[derive(Debug, Clone)]
+///
+/// Holds only a provider, so nothing reachable from it can sign.
+#[derive(Clone)]
 pub struct ReadContext {
-    /// The global context
-    pub global: GlobalContext,
+    provider: DynProvider,
+    cancel: CancellationToken,
+    timeouts: Timeouts,
+    from: Option<Address>,
+}
+
+impl std::fmt::Debug for ReadContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadContext").finish_non_exhaustive()
+    }
 }
 
 impl ReadContext {
-    /// Creates a new ReadContext with the provided global context.
+    /// Creates a new ReadContext around an existing provider.
     ///
     /// # Arguments
     ///
-    /// * `global` - The global context
+    /// * `provider` - The provider used for RPC requests
     ///
     /// # Returns
     ///
     /// * `ReadContext` - A new ReadContext
-    pub fn new(global: GlobalContext) -> Self {
-        Self { global }
+    pub fn new(provider: DynProvider) -> Self {
+        Self {
+            provider,
+            cancel: CancellationToken::new(),
+            timeouts: Timeouts::default(),
+            from: None,
+        }
     }
-}
 
-/// Context for write operations.
-#[derive(Debug, Clone)]
-pub struct WriteContext {
-    /// The global context
-    pub global: GlobalContext,
-}
+    /// Aborts this context's operations when the given token is cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancel` - The cancellation token
+    ///
+    /// # Returns
+    ///
+    /// * `ReadContext` - The updated context
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
 
-impl WriteContext {
-    /// Creates a new WriteContext with the provided global context.
+    /// Sets the timeouts applied to this context's operations.
     ///
     /// # Arguments
     ///
-    /// * `global` - The global context
+    /// * `timeouts` - The per-operation timeouts
     ///
     /// # Returns
     ///
-    /// * `WriteContext` - A new WriteContext
-    pub fn new(global: GlobalContext) -> Self {
-        Self { global }
+    /// * `ReadContext` - The updated context
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Sets the account read-only calls are made from, e.g. an impersonated
+    /// owner whose view functions check `msg.sender`.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The account, `None` to leave `from` out of calls
+    ///
+    /// # Returns
+    ///
+    /// * `ReadContext` - The updated context
+    pub fn with_sender(mut self, from: Option<Address>) -> Self {
+        self.from = from;
+        self
+    }
+
+    /// Returns the token cancelling this context's operations.
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    /// Returns the timeouts applied to this context's operations.
+    pub fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+
+    /// Creates a new ReadContext connected to an HTTP RPC endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_url` - Ethereum RPC URL
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ReadContext>` - A new ReadContext or an error if the URL is invalid
+    ///   or not HTTP; WebSocket and IPC clients come from [`RpcEndpoint::connect`]
+    pub fn connect(rpc_url: &str) -> Result<Self> {
+        let provider = ProviderBuilder::new().on_http(http_url(rpc_url)?);
+        Ok(Self::new(provider.erased()))
+    }
+
+    /// Creates a new ReadContext on top of an existing RPC client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The RPC client, e.g. one backed by [`crate::testing::MockRpc`]
+    ///
+    /// # Returns
+    ///
+    /// * `ReadContext` - A new ReadContext
+    pub fn from_client(client: RpcClient) -> Self {
+        Self::new(ProviderBuilder::new().on_client(client).erased())
+    }
+
+    /// Returns the underlying provider.
+    pub fn provider(&self) -> &DynProvider {
+        &self.provider
+    }
+
+    /// Calls a read-only function with `eth_call` and decodes its outputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CallResult>` - The decoded and raw outputs or an error
+    pub async fn call(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+    ) -> Result<CallResult> {
+        self.call_at(to, function, args, BlockId::latest()).await
+    }
+
+    /// Calls a read-only function against the state of a given block.
+    ///
+    /// Blocks older than the node keeps state for need an archive node.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `block` - Block number, hash or tag to read the state at
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CallResult>` - The decoded and raw outputs or an error
+    pub async fn call_at(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        block: BlockId,
+    ) -> Result<CallResult> {
+        let mut tx = TransactionRequest::default()
+            .with_to(to)
+            .with_input(codec::encode_values(function, args)?);
+        if let Some(from) = self.from {
+            tx.set_from(from);
+        }
+        let operation = format!("calling {}", function.signature());
+        let output = cancel::guard(&operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .call(tx)
+                .block(block)
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        })
+        .await?;
+        let values = codec::decode_output(function, &output)?;
+        Ok(CallResult::new(function, values, output))
+    }
+
+    /// Calls a read-only function at two blocks and compares the outputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `blocks` - The two blocks, each a number, hash or tag
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CallDiff>` - The outputs at both blocks, or an error
+    pub async fn compare(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        blocks: [&str; 2],
+    ) -> Result<CallDiff> {
+        let (before, after) = futures::try_join!(
+            self.call_at(to, function, args, validation::parse_block_id(blocks[0])?),
+            self.call_at(to, function, args, validation::parse_block_id(blocks[1])?),
+        )?;
+        Ok(CallDiff {
+            blocks: blocks.map(str::to_string),
+            before,
+            after,
+        })
+    }
+
+    /// Fetches the logs matching a filter with `eth_getLogs`.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The address, topics and block range to match
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Log>>` - The matching logs or an error
+    pub async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>> {
+        let operation = "fetching logs".to_string();
+        cancel::guard(&operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .get_logs(filter)
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        })
+        .await
+    }
+
+    /// Fills in nonce, gas and fees the way the transaction would be sent.
+    ///
+    /// Overrides in `options` are used as-is; everything else is queried
+    /// from the node.
+    ///
+    /// No key is needed, so transactions can be prepared for an account
+    /// whose key is kept offline.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Address the transaction is sent from
+    /// * `chain_id` - Chain the transaction is signed for
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TransactionPreview>` - The preview, or an error if estimation fails
+    pub async fn preview(
+        &self,
+        from: Address,
+        chain_id: u64,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<TransactionPreview> {
+        let calldata = codec::encode_values(function, args)?;
+        let mut preview = self
+            .preview_calldata(from, chain_id, to, &function.signature(), calldata, options)
+            .await?;
+        preview.args = function
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| match input.name.as_str() {
+                "" => format!("[{}]", i),
+                name => name.to_string(),
+            })
+            .zip(args.iter().cloned())
+            .collect();
+        Ok(preview)
+    }
+
+    /// Fills in nonce, gas and fees for a transaction carrying raw calldata,
+    /// e.g. a deployment through a factory taking no function selector.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Address the transaction is sent from
+    /// * `chain_id` - Chain the transaction is signed for
+    /// * `to` - Address of the contract
+    /// * `label` - What the transaction does, shown in place of a function signature
+    /// * `calldata` - The calldata
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TransactionPreview>` - The preview, with no decoded arguments,
+    ///   or an error if estimation fails
+    pub async fn preview_calldata(
+        &self,
+        from: Address,
+        chain_id: u64,
+        to: Address,
+        label: &str,
+        calldata: Bytes,
+        options: &TransactionOptions,
+    ) -> Result<TransactionPreview> {
+        let tx = options.apply(
+            TransactionRequest::default()
+                .with_from(from)
+                .with_to(to)
+                .with_input(calldata.clone()),
+        );
+        let operation = format!("estimating {}", label);
+        let provider = &self.provider;
+        let rpc = |e| Error::rpc(&operation, e);
+        let (nonce, gas_limit, (max_fee_per_gas, max_priority_fee_per_gas)) =
+            cancel::guard(&operation, &self.cancel, self.timeouts.call, async {
+                let nonce = match options.nonce() {
+                    Some(nonce) => nonce,
+                    None => provider.get_transaction_count(from).await.map_err(rpc)?,
+                };
+                let gas_limit = match options.gas_limit() {
+                    Some(gas_limit) => gas_limit,
+                    None => provider.estimate_gas(tx).await.map_err(rpc)?,
+                };
+                let legacy = options.gas_price().is_some()
+                    || matches!(options.tx_type(), Some(TxType::Legacy | TxType::Eip2930));
+                let fees = match (
+                    options.gas_price(),
+                    options.max_fee_per_gas(),
+                    options.max_priority_fee_per_gas(),
+                ) {
+                    (Some(gas_price), _, _) => (gas_price, None),
+                    _ if legacy => (provider.get_gas_price().await.map_err(rpc)?, None),
+                    (_, Some(max_fee), Some(priority_fee)) => (max_fee, Some(priority_fee)),
+                    (_, max_fee, priority_fee) => {
+                        let estimate = provider.estimate_eip1559_fees(None).await.map_err(rpc)?;
+                        (
+                            max_fee.unwrap_or(estimate.max_fee_per_gas),
+                            Some(priority_fee.unwrap_or(estimate.max_priority_fee_per_gas)),
+                        )
+                    }
+                };
+                Ok((nonce, gas_limit, fees))
+            })
+            .await?;
+        let mut preview = TransactionPreview {
+            chain_id,
+            from,
+            to,
+            function: label.to_string(),
+            args: Vec::new(),
+            calldata,
+            value: options.value(),
+            nonce,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            l1_data_fee: None,
+        };
+        if let Some(stack) = RollupStack::of(chain_id) {
+            match l1_fee::estimate(self, stack, &preview).await {
+                Ok(fee) => preview.l1_data_fee = Some(fee),
+                Err(e) => tracing::warn!(error = %e, "could not estimate the L1 data fee"),
+            }
+        }
+        Ok(preview)
+    }
+
+    /// Waits for the receipt of a sent transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `pending` - The pending transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TransactionReceipt>` - The receipt or an error
+    pub async fn wait_for_receipt(
+        &self,
+        pending: PendingTransactionBuilder<Ethereum>,
+    ) -> Result<TransactionReceipt> {
+        let operation = format!("waiting for receipt of {}", pending.tx_hash());
+        cancel::guard(&operation, &self.cancel, self.timeouts.receipt, async {
+            pending.get_receipt().await.map_err(|e| match e {
+                PendingTransactionError::TransportError(e) => Error::rpc(&operation, e),
+                e => Error::Other(format!("Failed while {}: {}", operation, e)),
+            })
+        })
+        .await
+    }
+
+    /// Fetches the number of the latest block.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64>` - The block number or an error
+    pub async fn get_block_number(&self) -> Result<u64> {
+        let operation = "fetching block number";
+        cancel::guard(operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .get_block_number()
+                .await
+                .map_err(|e| Error::rpc(operation, e))
+        })
+        .await
+    }
+
+    /// Fetches the number and hash of a block.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - Block number, hash or tag
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<(u64, B256)>>` - The number and hash, `None` if the node
+    ///   does not know the block, or an error
+    pub async fn get_block_hash(&self, block: BlockId) -> Result<Option<(u64, B256)>> {
+        let operation = "fetching block";
+        cancel::guard(operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .get_block(block)
+                .await
+                .map(|block| block.map(|block| (block.header.number, block.header.hash)))
+                .map_err(|e| Error::rpc(operation, e))
+        })
+        .await
+    }
+
+    /// Fetches the receipt of a transaction, if it was included.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - Hash of the transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<TransactionReceipt>>` - The receipt, `None` while pending, or an error
+    pub async fn get_transaction_receipt(
+        &self,
+        hash: TxHash,
+    ) -> Result<Option<TransactionReceipt>> {
+        let operation = format!("fetching receipt of {}", hash);
+        cancel::guard(&operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .get_transaction_receipt(hash)
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        })
+        .await
+    }
+
+    /// Fetches a transaction by hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - Hash of the transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Transaction>>` - The transaction, `None` if the node does not know it, or an error
+    pub async fn get_transaction(&self, hash: TxHash) -> Result<Option<Transaction>> {
+        let operation = format!("fetching transaction {}", hash);
+        cancel::guard(&operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .get_transaction_by_hash(hash)
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        })
+        .await
+    }
+
+    /// Estimates EIP-1559 fees from recent blocks.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(u128, u128)>` - The max fee and max priority fee per gas, in wei, or an error
+    pub async fn estimate_fees(&self) -> Result<(u128, u128)> {
+        let operation = "estimating fees";
+        let estimate = cancel::guard(operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .estimate_eip1559_fees(None)
+                .await
+                .map_err(|e| Error::rpc(operation, e))
+        })
+        .await?;
+        Ok((estimate.max_fee_per_gas, estimate.max_priority_fee_per_gas))
+    }
+
+    /// Fetches the nonce of an account's next transaction with `eth_getTransactionCount`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the account
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64>` - The nonce or an error
+    pub async fn get_transaction_count(&self, address: Address) -> Result<u64> {
+        let operation = format!("fetching nonce of {}", address);
+        cancel::guard(&operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .get_transaction_count(address)
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        })
+        .await
+    }
+
+    /// Fetches the native balance of an account with `eth_getBalance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the account
+    ///
+    /// # Returns
+    ///
+    /// * `Result<U256>` - The balance in wei or an error
+    pub async fn get_balance(&self, address: Address) -> Result<U256> {
+        let operation = format!("fetching balance of {}", address);
+        cancel::guard(&operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .get_balance(address)
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        })
+        .await
+    }
+
+    /// Fetches the runtime bytecode of an account with `eth_getCode`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the account
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Bytes>` - The bytecode, empty for accounts without code, or an error
+    pub async fn get_code(&self, address: Address) -> Result<Bytes> {
+        let operation = format!("fetching code of {}", address);
+        cancel::guard(&operation, &self.cancel, self.timeouts.call, async {
+            self.provider
+                .get_code_at(address)
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        })
+        .await
+    }
+}
+
+/// Context for write operations.
+///
+/// Holds a provider and a signer; only this context exposes methods that
+/// broadcast transactions.
+#[derive(Clone)]
+pub struct WriteContext {
+    read: ReadContext,
+    signer: Signer,
+    chain_id: u64,
+    hooks: Hooks,
+    require_simulation: bool,
+    dry_run: bool,
+    events: EventBus,
+}
+
+impl std::fmt::Debug for WriteContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteContext")
+            .field("address", &self.signer.address())
+            .field("chain_id", &self.chain_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WriteContext {
+    /// Creates a new WriteContext connected to an HTTP RPC endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_url` - Ethereum RPC URL
+    /// * `signer` - Signer used for transactions, e.g. a `PrivateKeySigner`
+    /// * `chain_id` - Chain ID for transaction signing
+    ///
+    /// # Returns
+    ///
+    /// * `Result<WriteContext>` - A new WriteContext or an error if the URL is invalid
+    ///   or not HTTP; WebSocket and IPC clients come from [`RpcEndpoint::connect`]
+    pub fn connect(rpc_url: &str, signer: impl Into<Signer>, chain_id: u64) -> Result<Self> {
+        let signer = signer.into();
+        let url = http_url(rpc_url)?;
+        // The node itself sends the transactions of impersonated accounts.
+        let provider = match signer.signs_transactions() {
+            true => ProviderBuilder::new()
+                .wallet(signer.wallet())
+                .on_http(url)
+                .erased(),
+            false => ProviderBuilder::new().on_http(url).erased(),
+        };
+        Ok(Self {
+            read: ReadContext::new(provider),
+            signer,
+            chain_id,
+            hooks: Hooks::new(),
+            require_simulation: false,
+            dry_run: false,
+            events: EventBus::new(),
+        })
+    }
+
+    /// Creates a new WriteContext on top of an existing RPC client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The RPC client, e.g. one backed by [`crate::testing::MockRpc`]
+    /// * `signer` - Signer used for transactions, e.g. a `PrivateKeySigner`
+    /// * `chain_id` - Chain ID for transaction signing
+    ///
+    /// # Returns
+    ///
+    /// * `WriteContext` - A new WriteContext
+    pub fn from_client(client: RpcClient, signer: impl Into<Signer>, chain_id: u64) -> Self {
+        let signer = signer.into();
+        let provider = match signer.signs_transactions() {
+            true => ProviderBuilder::new()
+                .wallet(signer.wallet())
+                .on_client(client)
+                .erased(),
+            false => ProviderBuilder::new().on_client(client).erased(),
+        };
+        Self {
+            read: ReadContext::new(provider),
+            signer,
+            chain_id,
+            hooks: Hooks::new(),
+            require_simulation: false,
+            dry_run: false,
+            events: EventBus::new(),
+        }
+    }
+
+    /// Aborts this context's operations when the given token is cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancel` - The cancellation token
+    ///
+    /// # Returns
+    ///
+    /// * `WriteContext` - The updated context
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.read = self.read.with_cancellation(cancel);
+        self
+    }
+
+    /// Sets the timeouts applied to this context's operations.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeouts` - The per-operation timeouts
+    ///
+    /// # Returns
+    ///
+    /// * `WriteContext` - The updated context
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.read = self.read.with_timeouts(timeouts);
+        self
+    }
+
+    /// Sets the hooks run before sending and after each receipt.
+    ///
+    /// # Arguments
+    ///
+    /// * `hooks` - The transaction hooks
+    ///
+    /// # Returns
+    ///
+    /// * `WriteContext` - The updated context
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Sets whether every transaction is simulated once the pre-send hooks
+    /// let it through, and refused with `Error::Vetoed` if it reverts.
+    ///
+    /// # Arguments
+    ///
+    /// * `required` - Whether the simulation is required
+    ///
+    /// # Returns
+    ///
+    /// * `WriteContext` - The updated context
+    pub fn with_required_simulation(mut self, required: bool) -> Self {
+        self.require_simulation = required;
+        self
+    }
+
+    /// Sets whether this context is a dry run, refusing with `Error::ModeForbids`
+    /// to sign or send any transaction; simulations and previews still work.
+    ///
+    /// # Arguments
+    ///
+    /// * `dry_run` - Whether transactions are never signed or sent
+    ///
+    /// # Returns
+    ///
+    /// * `WriteContext` - The updated context
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets the bus on which this context publishes `TxSubmitted`,
+    /// `TxConfirmed` and `SimulationFailed` events.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The event bus
+    ///
+    /// # Returns
+    ///
+    /// * `WriteContext` - The updated context
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Returns the read-only view of this context.
+    pub fn reader(&self) -> &ReadContext {
+        &self.read
+    }
+
+    /// Returns the address transactions are sent from.
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// Returns the signer transactions are signed with.
+    pub fn signer(&self) -> &Signer {
+        &self.signer
+    }
+
+    /// Returns the chain ID transactions are signed for.
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Returns the hooks run before sending and after each receipt.
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
+    /// Returns the bus on which transaction events are published.
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Simulates a transaction with `eth_call` from the signer's address.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CallResult>` - The decoded outputs, or an error if the transaction would revert
+    pub async fn simulate(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<CallResult> {
+        let tx = options.apply(
+            TransactionRequest::default()
+                .with_from(self.address())
+                .with_to(to)
+                .with_input(codec::encode_values(function, args)?),
+        );
+        let operation = format!("simulating {}", function.signature());
+        let output = cancel::guard(
+            &operation,
+            &self.read.cancel,
+            self.read.timeouts.call,
+            async {
+                self.read
+                    .provider
+                    .call(tx)
+                    .await
+                    .map_err(|e| Error::rpc(&operation, e))
+            },
+        )
+        .await?;
+        let values = codec::decode_output(function, &output)?;
+        Ok(CallResult::new(function, values, output))
+    }
+
+    /// Simulates a transaction, then previews it the way it would be sent,
+    /// which is where a dry run stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<UnsignedTransaction>` - The transaction that would be signed, or an
+    ///   error if it would revert or estimation fails
+    pub async fn dry_run_send(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<UnsignedTransaction> {
+        self.simulate(to, function, args, options).await?;
+        let preview = self.preview(to, function, args, options).await?;
+        Ok(UnsignedTransaction::from_preview(&preview))
+    }
+
+    /// Traces a transaction with `debug_traceCall` and the `callTracer`.
+    ///
+    /// The node must expose the `debug` namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CallFrame>` - The root call frame with every sub-call, or an error
+    pub async fn trace(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<CallFrame> {
+        let tx = options.apply(
+            TransactionRequest::default()
+                .with_from(self.address())
+                .with_to(to)
+                .with_input(codec::encode_values(function, args)?),
+        );
+        let operation = format!("tracing {}", function.signature());
+        cancel::guard(
+            &operation,
+            &self.read.cancel,
+            self.read.timeouts.call,
+            async {
+                self.read
+                    .provider
+                    .raw_request(
+                        "debug_traceCall".into(),
+                        (tx, "latest", serde_json::json!({ "tracer": "callTracer" })),
+                    )
+                    .await
+                    .map_err(|e| Error::rpc(&operation, e))
+            },
+        )
+        .await
+    }
+
+    /// Fills in nonce, gas and fees the way the transaction would be sent.
+    ///
+    /// Overrides in `options` are used as-is; everything else is queried
+    /// from the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TransactionPreview>` - The preview, or an error if estimation fails
+    pub async fn preview(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<TransactionPreview> {
+        self.read
+            .preview(self.address(), self.chain_id, to, function, args, options)
+            .await
+    }
+
+    /// Generates an EIP-2930 access list for a transaction with
+    /// `eth_createAccessList`, and estimates its gas without and with the list.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AccessListEstimate>` - The list and both estimates, or an error
+    ///   if the node does not support it or the transaction would revert
+    pub async fn create_access_list(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<AccessListEstimate> {
+        let tx = options.apply(
+            TransactionRequest::default()
+                .with_from(self.address())
+                .with_to(to)
+                .with_input(codec::encode_values(function, args)?),
+        );
+        let operation = format!("creating an access list for {}", function.signature());
+        let provider = &self.read.provider;
+        let rpc = |e| Error::rpc(&operation, e);
+        cancel::guard(
+            &operation,
+            &self.read.cancel,
+            self.read.timeouts.call,
+            async {
+                let result = provider.create_access_list(&tx).await.map_err(rpc)?;
+                if let Some(error) = result.error {
+                    return Err(Error::Other(format!("{} failed: {}", operation, error)));
+                }
+                let gas_without = provider.estimate_gas(tx.clone()).await.map_err(rpc)?;
+                let gas_with = provider
+                    .estimate_gas(tx.clone().with_access_list(result.access_list.clone()))
+                    .await
+                    .map_err(rpc)?;
+                Ok(AccessListEstimate {
+                    access_list: result.access_list,
+                    gas_without,
+                    gas_with,
+                })
+            },
+        )
+        .await
+    }
+
+    /// Signs and broadcasts a transaction calling a state-changing function.
+    ///
+    /// Pre-send hooks run first and may change the options or block the
+    /// transaction with `Error::Vetoed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - Overrides for gas, fees, nonce, value and confirmations
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PendingTransactionBuilder<Ethereum>>` - The pending transaction or an error
+    pub async fn send(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<PendingTransactionBuilder<Ethereum>> {
+        let (pending, _) = self
+            .send_with_final_options(to, function, args, options)
+            .await?;
+        Ok(pending)
+    }
+
+    /// Signs and broadcasts a transaction calling a state-changing function,
+    /// like [`WriteContext::send`], also returning the options the pre-send
+    /// hooks left, e.g. to record the value actually sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - Overrides for gas, fees, nonce, value and confirmations
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(PendingTransactionBuilder<Ethereum>, TransactionOptions)>` - The
+    ///   pending transaction and the options it was sent with, or an error
+    pub async fn send_with_final_options(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<(PendingTransactionBuilder<Ethereum>, TransactionOptions)> {
+        let calldata = codec::encode_values(function, args)?;
+        self.broadcast(to, &function.signature(), calldata, options)
+            .await
+    }
+
+    /// Fills in nonce, gas and fees for a transaction carrying raw calldata.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `label` - What the transaction does, shown in place of a function signature
+    /// * `calldata` - The calldata
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TransactionPreview>` - The preview, or an error if estimation fails
+    pub async fn preview_calldata(
+        &self,
+        to: Address,
+        label: &str,
+        calldata: Bytes,
+        options: &TransactionOptions,
+    ) -> Result<TransactionPreview> {
+        self.read
+            .preview_calldata(self.address(), self.chain_id, to, label, calldata, options)
+            .await
+    }
+
+    /// Signs and broadcasts a transaction carrying raw calldata, e.g. a
+    /// deployment through a factory taking no function selector.
+    ///
+    /// Pre-send hooks run first and see `label` as the function, which the
+    /// `TxSubmitted` event emitted once it is broadcast carries too.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `label` - What the transaction does, shown in place of a function signature
+    /// * `calldata` - The calldata
+    /// * `options` - Overrides for gas, fees, nonce, value and confirmations
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PendingTransactionBuilder<Ethereum>>` - The pending transaction or an error
+    pub async fn send_calldata(
+        &self,
+        to: Address,
+        label: &str,
+        calldata: Bytes,
+        options: &TransactionOptions,
+    ) -> Result<PendingTransactionBuilder<Ethereum>> {
+        let (pending, _) = self.broadcast(to, label, calldata, options).await?;
+        Ok(pending)
+    }
+
+    /// Runs the pre-send hooks, then signs and broadcasts the transaction
+    /// they let through, returning it with the options they left.
+    async fn broadcast(
+        &self,
+        to: Address,
+        label: &str,
+        calldata: Bytes,
+        options: &TransactionOptions,
+    ) -> Result<(PendingTransactionBuilder<Ethereum>, TransactionOptions)> {
+        let (tx, options) = self.prepare(to, label, calldata, options).await?;
+        let operation = format!("sending {}", label);
+        let pending = cancel::guard(
+            &operation,
+            &self.read.cancel,
+            self.read.timeouts.send,
+            async {
+                self.read
+                    .provider
+                    .send_transaction(tx)
+                    .await
+                    .map_err(|e| Error::rpc(&operation, e))
+            },
+        )
+        .await?;
+        self.events.emit(Event::TxSubmitted {
+            hash: *pending.tx_hash(),
+            contract: to,
+            function: label.to_string(),
+        });
+        let confirmations = options.confirmations();
+        Ok((pending.with_required_confirmations(confirmations), options))
+    }
+
+    /// Signs a transaction calling a state-changing function without
+    /// broadcasting it, e.g. to hand it to a private relay.
+    ///
+    /// Pre-send hooks run first, as for [`WriteContext::send`]; the nonce,
+    /// gas and fees not set in `options` are then filled in from the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `options` - Overrides for gas, fees, nonce and value
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TxEnvelope>` - The signed transaction or an error
+    pub async fn sign(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        options: &TransactionOptions,
+    ) -> Result<TxEnvelope> {
+        let calldata = codec::encode_values(function, args)?;
+        let (tx, options) = self
+            .prepare(to, &function.signature(), calldata, options)
+            .await?;
+        let filled = self.preview(to, function, args, &options).await?;
+        let tx = tx.with_nonce(filled.nonce).with_gas_limit(filled.gas_limit);
+        let tx = match filled.max_priority_fee_per_gas {
+            Some(priority_fee) => tx
+                .with_max_fee_per_gas(filled.max_fee_per_gas)
+                .with_max_priority_fee_per_gas(priority_fee),
+            None => tx.with_gas_price(filled.max_fee_per_gas),
+        };
+        tx.build(&self.signer.wallet())
+            .await
+            .map_err(|e| Error::Other(format!("Failed to sign {}: {}", function.signature(), e)))
+    }
+
+    /// Sends a no-op transaction to the signer itself, taking over a nonce
+    /// to cancel the transaction pending at it or to fill a gap.
+    ///
+    /// Pre-send hooks do not run: the transaction calls nothing and moves no funds.
+    ///
+    /// # Arguments
+    ///
+    /// * `replacement` - The nonce and fees to use
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PendingTransactionBuilder<Ethereum>>` - The pending transaction or an error
+    pub async fn send_replacement(
+        &self,
+        replacement: &Replacement,
+    ) -> Result<PendingTransactionBuilder<Ethereum>> {
+        self.ensure_not_dry_run()?;
+        let tx = TransactionRequest::default()
+            .with_from(self.address())
+            .with_to(self.address())
+            .with_chain_id(self.chain_id)
+            .with_nonce(replacement.nonce)
+            .with_gas_limit(21_000)
+            .with_max_fee_per_gas(replacement.max_fee_per_gas)
+            .with_max_priority_fee_per_gas(replacement.max_priority_fee_per_gas);
+        let operation = format!("replacing nonce {}", replacement.nonce);
+        let pending = cancel::guard(
+            &operation,
+            &self.read.cancel,
+            self.read.timeouts.send,
+            async {
+                self.read
+                    .provider
+                    .send_transaction(tx)
+                    .await
+                    .map_err(|e| Error::rpc(&operation, e))
+            },
+        )
+        .await?;
+        self.events.emit(Event::TxSubmitted {
+            hash: *pending.tx_hash(),
+            contract: self.address(),
+            function: operation,
+        });
+        Ok(pending)
+    }
+
+    /// Refuses to go on signing or sending in a dry run.
+    fn ensure_not_dry_run(&self) -> Result<()> {
+        match self.dry_run {
+            true => Err(Error::ModeForbids {
+                mode: OperatingMode::DryRun,
+                reason: "transactions are never signed or sent".to_string(),
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Runs the pre-send hooks and builds the request they let through,
+    /// simulating it first when the simulation is required.
+    async fn prepare(
+        &self,
+        to: Address,
+        label: &str,
+        calldata: Bytes,
+        options: &TransactionOptions,
+    ) -> Result<(TransactionRequest, TransactionOptions)> {
+        self.ensure_not_dry_run()?;
+        self.check_before_send(self.address(), to, label, calldata, options)
+            .await
+    }
+
+    /// Runs the pre-send hooks on a call made from an address and, when the
+    /// simulation is required, simulates the request they let through. Safe
+    /// proposals are checked this way, from the Safe, before being signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Address the call is made from
+    /// * `to` - Address of the contract
+    /// * `label` - The function signature, or a description of raw calldata
+    /// * `calldata` - The encoded call
+    /// * `options` - The options the call would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(TransactionRequest, TransactionOptions)>` - The request and the
+    ///   options as the hooks left them, or `Error::Vetoed` if a hook or the
+    ///   simulation refuses the call
+    pub async fn check_before_send(
+        &self,
+        from: Address,
+        to: Address,
+        label: &str,
+        calldata: Bytes,
+        options: &TransactionOptions,
+    ) -> Result<(TransactionRequest, TransactionOptions)> {
+        let request = TransactionRequest::default()
+            .with_from(from)
+            .with_to(to)
+            .with_chain_id(self.chain_id)
+            .with_input(calldata);
+        check_request(
+            &self.read,
+            &self.hooks,
+            &self.events,
+            self.require_simulation,
+            label,
+            request,
+            options,
+        )
+        .await
+    }
+
+    /// Waits for the receipt of a sent transaction, emits a `TxConfirmed`
+    /// event, then runs post-receipt hooks.
+    ///
+    /// # Arguments
+    ///
+    /// * `pending` - The pending transaction returned by [`WriteContext::send`]
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TransactionReceipt>` - The receipt or an error
+    pub async fn confirm(
+        &self,
+        pending: PendingTransactionBuilder<Ethereum>,
+    ) -> Result<TransactionReceipt> {
+        let receipt = self.read.wait_for_receipt(pending).await?;
+        self.events.emit(Event::TxConfirmed {
+            hash: receipt.transaction_hash,
+            block_number: receipt.block_number,
+            gas_used: receipt.gas_used,
+            success: receipt.status(),
+        });
+        self.hooks.after_receipt(&receipt).await;
+        Ok(receipt)
+    }
+}
+
+/// Runs the pre-send hooks on a transaction request and, when the simulation
+/// is required, simulates the request with the options they left, emitting
+/// `SimulationFailed` if it fails.
+///
+/// # Arguments
+///
+/// * `read` - Context the simulation runs through
+/// * `hooks` - The pre-send hooks
+/// * `events` - Bus a failed simulation is reported on
+/// * `require_simulation` - Whether a reverting simulation refuses the transaction
+/// * `label` - The function signature, or a description of the transaction
+/// * `request` - The request, with its sender, target, chain and input set
+/// * `options` - The options the transaction would be sent with
+///
+/// # Returns
+///
+/// * `Result<(TransactionRequest, TransactionOptions)>` - The request and the
+///   options as the hooks left them, or `Error::Vetoed` if a hook or the
+///   simulation refuses the transaction
+async fn check_request(
+    read: &ReadContext,
+    hooks: &Hooks,
+    events: &EventBus,
+    require_simulation: bool,
+    label: &str,
+    request: TransactionRequest,
+    options: &TransactionOptions,
+) -> Result<(TransactionRequest, TransactionOptions)> {
+    let mut options = options.clone();
+    let pending_send = PendingSend {
+        from: request.from.unwrap_or_default(),
+        to: request
+            .to
+            .and_then(|kind| kind.to().copied())
+            .unwrap_or_default(),
+        chain_id: request.chain_id.unwrap_or_default(),
+        function: label.to_string(),
+        calldata: request.input.input().cloned().unwrap_or_default(),
+    };
+    hooks.before_send(&pending_send, &mut options).await?;
+    let tx = options.apply(request);
+    if require_simulation {
+        let operation = format!("simulating {}", label);
+        cancel::guard(&operation, &read.cancel, read.timeouts.call, async {
+            read.provider
+                .call(tx.clone())
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        })
+        .await
+        .map_err(|e| match e {
+            Error::Cancelled(_) | Error::Timeout { .. } => e,
+            e => {
+                events.emit(Event::SimulationFailed {
+                    function: label.to_string(),
+                    reason: e.to_string(),
+                });
+                Error::Vetoed {
+                    hook: "policy".to_string(),
+                    reason: format!("the simulation failed: {}", e),
+                }
+            }
+        })?;
+    }
+    Ok((tx, options))
+}
+
+/// Parses an HTTP RPC URL, reporting a configuration error for other endpoints.
+fn http_url(rpc_url: &str) -> Result<url::Url> {
+    match RpcEndpoint::parse(rpc_url)? {
+        RpcEndpoint::Http(url) => Ok(url),
+        endpoint => Err(Error::InvalidConfig(format!(
+            "{} needs a persistent connection: connect it with RpcEndpoint::connect",
+            endpoint
+        ))),
     }
 }
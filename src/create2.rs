@@ -0,0 +1,224 @@
+/// src/create2.rs
+use crate::cancel;
+use crate::codec;
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::{Function, JsonAbi, StateMutability};
+use alloy::network::TransactionBuilder;
+use alloy::primitives::{address, keccak256, Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use std::str::FromStr;
+
+/// The deterministic deployment proxy, deployed at the same address on most
+/// chains. It takes a 32-byte salt followed by the init code as calldata and
+/// returns the address of the created contract.
+pub const DEFAULT_FACTORY: Address = address!("4e59b44847b379578588920cA78FbF26c0B4956C");
+
+/// A contract deployment through a CREATE2 factory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Create2Deployment {
+    /// The factory creating the contract
+    pub factory: Address,
+    /// The salt
+    pub salt: B256,
+    /// Creation bytecode followed by the encoded constructor arguments
+    pub init_code: Bytes,
+}
+
+impl Create2Deployment {
+    /// Returns the keccak256 hash of the init code.
+    pub fn init_code_hash(&self) -> B256 {
+        keccak256(&self.init_code)
+    }
+
+    /// Returns the address the contract will be created at.
+    pub fn address(&self) -> Address {
+        compute_address(self.factory, self.salt, self.init_code_hash())
+    }
+
+    /// Returns the calldata sent to the factory: the salt, then the init code.
+    pub fn calldata(&self) -> Bytes {
+        [self.salt.as_slice(), &self.init_code].concat().into()
+    }
+
+    /// Asks the factory, with `eth_call`, where it would create the contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - Read context of the current chain
+    /// * `from` - Address the deployment would be sent from
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Address>` - The address returned by the factory, or an error
+    ///   if the creation would fail
+    pub async fn simulate(&self, read: &ReadContext, from: Address) -> Result<Address> {
+        let operation = format!("simulating the deployment through {}", self.factory);
+        let tx = TransactionRequest::default()
+            .with_from(from)
+            .with_to(self.factory)
+            .with_input(self.calldata());
+        let output = cancel::guard(
+            &operation,
+            read.cancellation(),
+            read.timeouts().call,
+            async {
+                read.provider()
+                    .call(tx)
+                    .await
+                    .map_err(|e| Error::rpc(&operation, e))
+            },
+        )
+        .await?;
+        // The deterministic deployment proxy returns the bare 20 bytes; other
+        // factories return an ABI-encoded address.
+        match output.len() {
+            20 => Ok(Address::from_slice(&output)),
+            32 => Ok(Address::from_word(B256::from_slice(&output))),
+            _ => Err(Error::Other(format!(
+                "Factory {} returned {} instead of an address",
+                self.factory, output
+            ))),
+        }
+    }
+}
+
+/// Computes the address of a contract created with CREATE2, per EIP-1014.
+///
+/// # Arguments
+///
+/// * `deployer` - The contract executing CREATE2, e.g. the factory
+/// * `salt` - The salt
+/// * `init_code_hash` - keccak256 hash of the init code
+///
+/// # Returns
+///
+/// * `Address` - The address of the created contract
+pub fn compute_address(deployer: Address, salt: B256, init_code_hash: B256) -> Address {
+    deployer.create2(salt, init_code_hash)
+}
+
+/// Parses a salt: a hex value of at most 32 bytes, left-padded with zeros,
+/// or a decimal number.
+///
+/// # Arguments
+///
+/// * `input` - The salt as entered
+///
+/// # Returns
+///
+/// * `Result<B256>` - The salt, or an error if it is neither
+pub fn parse_salt(input: &str) -> Result<B256> {
+    let input = input.trim();
+    let invalid = || Error::InvalidArguments(format!("Invalid salt: {}", input));
+    match input.strip_prefix("0x") {
+        Some(hex) if hex.len() <= 64 => {
+            let hex = format!("{:0>64}", hex);
+            B256::from_str(&hex).map_err(|_| invalid())
+        }
+        Some(_) => Err(invalid()),
+        None => U256::from_str_radix(input, 10)
+            .map(B256::from)
+            .map_err(|_| invalid()),
+    }
+}
+
+/// Builds init code from creation bytecode and constructor arguments.
+///
+/// # Arguments
+///
+/// * `bytecode` - The creation bytecode
+/// * `abi` - The ABI declaring the constructor, if any
+/// * `args` - One value per constructor input, as entered
+///
+/// # Returns
+///
+/// * `Result<Bytes>` - The bytecode followed by the ABI-encoded arguments, or
+///   an error if the arguments do not match the constructor
+pub fn init_code<S: AsRef<str>>(bytecode: &[u8], abi: &JsonAbi, args: &[S]) -> Result<Bytes> {
+    let constructor = constructor_function(abi);
+    let values = codec::coerce_args(&constructor, args)?;
+    let encoded = DynSolValue::Tuple(values).abi_encode_params();
+    Ok([bytecode, &encoded].concat().into())
+}
+
+/// Describes the constructor of an ABI as a function, so its arguments can
+/// be prompted for and coerced like a function's. An ABI without a
+/// constructor gets one taking no argument.
+///
+/// # Arguments
+///
+/// * `abi` - The contract ABI
+///
+/// # Returns
+///
+/// * `Function` - A function named `constructor` with the constructor inputs
+pub fn constructor_function(abi: &JsonAbi) -> Function {
+    Function {
+        name: "constructor".to_string(),
+        inputs: abi
+            .constructor
+            .as_ref()
+            .map(|constructor| constructor.inputs.clone())
+            .unwrap_or_default(),
+        outputs: Vec::new(),
+        state_mutability: abi
+            .constructor
+            .as_ref()
+            .map(|constructor| constructor.state_mutability)
+            .unwrap_or(StateMutability::NonPayable),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::hex;
+
+    #[test]
+    fn test_create2_address() {
+        // Examples from EIP-1014
+        let salt = parse_salt("0").unwrap();
+        assert_eq!(salt, B256::ZERO);
+        assert_eq!(
+            compute_address(Address::ZERO, salt, keccak256([0x00])),
+            address!("4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38")
+        );
+        assert_eq!(
+            compute_address(
+                address!("deadbeef00000000000000000000000000000000"),
+                salt,
+                keccak256([0x00])
+            ),
+            address!("B928f69Bb1D91Cd65274e3c79d8986362984fDA3")
+        );
+        assert_eq!(
+            parse_salt("0xcafe").unwrap(),
+            B256::from(U256::from(0xcafe))
+        );
+        assert!(parse_salt(&format!("0x{}", "00".repeat(33))).is_err());
+        assert!(parse_salt("salt").is_err());
+
+        let abi = JsonAbi::parse(["constructor(address owner, uint256 supply)"]).unwrap();
+        let owner = Address::repeat_byte(0x11);
+        let code = init_code(&[0x60, 0x80], &abi, &[owner.to_string(), "5".to_string()]).unwrap();
+        assert_eq!(code.len(), 2 + 64);
+        assert_eq!(&code[14..34], owner.as_slice());
+        assert!(init_code(&[0x60], &JsonAbi::new(), &["1"]).is_err());
+
+        let deployment = Create2Deployment {
+            factory: DEFAULT_FACTORY,
+            salt,
+            init_code: code,
+        };
+        let calldata = deployment.calldata();
+        assert_eq!(&calldata[..32], salt.as_slice());
+        assert_eq!(hex::encode(&calldata[32..34]), "6080");
+        assert_eq!(
+            deployment.address(),
+            DEFAULT_FACTORY.create2(salt, deployment.init_code_hash())
+        );
+    }
+}
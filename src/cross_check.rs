@@ -0,0 +1,204 @@
+/// src/cross_check.rs
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use crate::result::{CallDiff, CallResult};
+use alloy::dyn_abi::DynSolValue;
+use alloy::eips::BlockId;
+use alloy::json_abi::Function;
+use alloy::primitives::{Address, B256};
+use colored::Colorize;
+use std::fmt;
+
+/// Repeats view calls against a second RPC endpoint in paranoid mode, to
+/// catch a stale or forked node returning wrong data.
+#[derive(Debug, Clone)]
+pub struct CrossChecker {
+    primary: ReadContext,
+    secondary: ReadContext,
+    endpoints: [String; 2],
+}
+
+impl CrossChecker {
+    /// Creates a checker comparing two endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `primary` - Read context of the session's endpoint
+    /// * `secondary` - Read context of the endpoint calls are checked against
+    /// * `endpoints` - Labels of both endpoints, shown when they disagree
+    pub fn new(primary: ReadContext, secondary: ReadContext, endpoints: [String; 2]) -> Self {
+        Self {
+            primary,
+            secondary,
+            endpoints,
+        }
+    }
+
+    /// Calls a read-only function on both endpoints at the same block. A tag
+    /// such as `latest` is resolved to a number on the primary endpoint
+    /// first, so that a secondary lagging a block behind is not mistaken for
+    /// a divergence.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Address of the contract
+    /// * `function` - The function to call
+    /// * `args` - One value per function input
+    /// * `block` - Block number, hash or tag to read the state at
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CrossCheck>` - Both outputs and block hashes, or an error if
+    ///   either endpoint failed
+    pub async fn call_at(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[DynSolValue],
+        block: BlockId,
+    ) -> Result<CrossCheck> {
+        let (number, primary_hash) = self
+            .primary
+            .get_block_hash(block)
+            .await?
+            .ok_or_else(|| Error::Other(format!("{} has no such block", self.endpoints[0])))?;
+        let at = BlockId::number(number);
+        let secondary_error =
+            |e: Error| Error::Other(format!("cross-checking on {}: {}", self.endpoints[1], e));
+        let (before, after, secondary) = futures::try_join!(
+            self.primary.call_at(to, function, args, at),
+            async {
+                self.secondary
+                    .call_at(to, function, args, at)
+                    .await
+                    .map_err(secondary_error)
+            },
+            async {
+                self.secondary
+                    .get_block_hash(at)
+                    .await
+                    .map_err(secondary_error)
+            },
+        )?;
+        Ok(CrossCheck {
+            block: number,
+            hashes: [Some(primary_hash), secondary.map(|(_, hash)| hash)],
+            diff: CallDiff {
+                blocks: self.endpoints.clone(),
+                before,
+                after,
+            },
+        })
+    }
+}
+
+/// The same call on two endpoints at the same block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossCheck {
+    /// Block both calls were made at
+    pub block: u64,
+    /// Hash of the block on each endpoint, `None` if it did not know it
+    pub hashes: [Option<B256>; 2],
+    /// Outputs on each endpoint, labelled by endpoint
+    pub diff: CallDiff,
+}
+
+impl CrossCheck {
+    /// Whether the endpoints disagree, on the outputs or on the block itself.
+    pub fn diverges(&self) -> bool {
+        self.hashes[0] != self.hashes[1] || !self.diff.changed().is_empty()
+    }
+
+    /// Returns the result on the primary endpoint.
+    pub fn into_result(self) -> CallResult {
+        self.diff.before
+    }
+}
+
+impl fmt::Display for CrossCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [primary, secondary] = &self.diff.blocks;
+        if !self.diverges() {
+            let checked = format!(
+                "Cross-checked on {}: same result at block {}",
+                secondary, self.block
+            );
+            return write!(f, "{}", checked.dimmed());
+        }
+        write!(
+            f,
+            "{} {} and {} disagree at block {}",
+            "Warning:".red().bold(),
+            primary,
+            secondary,
+            self.block
+        )?;
+        if self.hashes[0] != self.hashes[1] {
+            let hash = |hash: Option<B256>| match hash {
+                Some(hash) => hash.to_string(),
+                None => "unknown".to_string(),
+            };
+            write!(
+                f,
+                "\n  Block hash: {} on {}, {} on {}; one of them is behind or on another fork",
+                hash(self.hashes[0]),
+                primary,
+                hash(self.hashes[1]),
+                secondary
+            )?;
+        }
+        if !self.diff.changed().is_empty() {
+            write!(f, "\n{}", self.diff)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Bytes, U256};
+
+    #[test]
+    fn test_cross_check_divergence() {
+        let function = Function::parse("balanceOf(address) returns (uint256)").unwrap();
+        let result = |balance: u64| {
+            CallResult::new(
+                &function,
+                vec![DynSolValue::Uint(U256::from(balance), 256)],
+                Bytes::new(),
+            )
+        };
+        let hash = B256::repeat_byte(0xab);
+        let mut check = CrossCheck {
+            block: 100,
+            hashes: [Some(hash), Some(hash)],
+            diff: CallDiff {
+                blocks: [
+                    "https://a.example".to_string(),
+                    "https://b.example".to_string(),
+                ],
+                before: result(5),
+                after: result(5),
+            },
+        };
+        assert!(!check.diverges());
+        assert_eq!(
+            text::strip_ansi(&check.to_string()),
+            "Cross-checked on https://b.example: same result at block 100"
+        );
+
+        check.diff.after = result(7);
+        assert!(check.diverges());
+        let rendered = text::strip_ansi(&check.to_string());
+        assert!(rendered.starts_with("Warning: https://a.example and https://b.example disagree"));
+        assert!(rendered.contains("balanceOf(address) at https://a.example -> https://b.example"));
+        assert!(!rendered.contains("Block hash"));
+
+        check.diff.after = result(5);
+        check.hashes[1] = None;
+        assert!(check.diverges());
+        assert!(text::strip_ansi(&check.to_string()).contains("Block hash:"));
+        assert_eq!(check.into_result(), result(5));
+    }
+}
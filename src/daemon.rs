@@ -0,0 +1,512 @@
+/// src/daemon.rs
+use crate::cancel::CancellationToken;
+use crate::codec;
+use crate::context::{GlobalContext, ReadContext, WriteContext};
+use crate::error::{Error, Result};
+use crate::output::Output;
+use crate::result::ExecutionResult;
+use crate::safe;
+use crate::transaction::TransactionOptions;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// File name of the daemon socket inside the data directory.
+pub const SOCKET_FILE: &str = "daemon.sock";
+
+/// JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for malformed parameters.
+const INVALID_PARAMS: i64 = -32602;
+/// JSON-RPC error code for requests that are not valid JSON-RPC.
+const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC error code for failed operations.
+const OPERATION_FAILED: i64 = -32000;
+
+/// Pause after a failed `accept`, e.g. when out of file descriptors.
+#[cfg(unix)]
+const ACCEPT_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Returns the default socket path, `$HOME/.inachus/daemon.sock`.
+pub fn default_socket_path() -> PathBuf {
+    crate::data_dir().join(SOCKET_FILE)
+}
+
+/// A JSON-RPC 2.0 request sent to the daemon, one per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    /// Protocol version, always `2.0`
+    pub jsonrpc: String,
+    /// Request identifier echoed in the response
+    pub id: Value,
+    /// Method to invoke
+    pub method: String,
+    /// Method parameters
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// A JSON-RPC 2.0 response sent by the daemon, one per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    /// Protocol version, always `2.0`
+    pub jsonrpc: String,
+    /// Identifier of the answered request
+    pub id: Value,
+    /// Result of a successful request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// Error of a failed request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+/// Error object of a failed JSON-RPC request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    /// JSON-RPC error code
+    pub code: i64,
+    /// Human-readable message
+    pub message: String,
+    /// Structured error as a versioned [`Output`], when the operation failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<Error> for RpcError {
+    fn from(e: Error) -> Self {
+        Self {
+            code: OPERATION_FAILED,
+            message: e.to_string(),
+            data: Some(Output::from(&e).to_json()),
+        }
+    }
+}
+
+/// Parameters of the `call`, `send` and `encode` methods.
+#[derive(Debug, Clone, Deserialize)]
+struct MethodParams {
+    contract: Option<String>,
+    address: Option<String>,
+    method: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    options: TransactionOptions,
+    #[serde(default)]
+    wait: bool,
+}
+
+/// Long-running process keeping providers, signer and ABIs warm.
+///
+/// Supported methods: `ping`, `status`, `contracts`, `call`, `send` and
+/// `encode`. `call`, `send` and `encode` take `{"contract", "address",
+/// "method", "args"}`; `send` also accepts `options` and `wait`.
+#[derive(Debug)]
+pub struct Daemon {
+    ctx: GlobalContext,
+    read: ReadContext,
+    write: Option<WriteContext>,
+}
+
+impl Daemon {
+    /// Creates a daemon from a loaded context, connecting its providers once.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context with configuration, ABIs and contracts loaded
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Daemon>` - The daemon or an error if a provider cannot be created
+    pub fn new(ctx: GlobalContext) -> Result<Self> {
+        let read = ctx.read_context()?;
+        let write = if ctx.capabilities().signer {
+            Some(ctx.write_context()?)
+        } else {
+            None
+        };
+        Ok(Self { ctx, read, write })
+    }
+
+    /// Handles one request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The JSON-RPC request
+    ///
+    /// # Returns
+    ///
+    /// * `Response` - The JSON-RPC response
+    pub async fn handle(&self, request: Request) -> Response {
+        let outcome = match self.dispatch(&request.method, request.params).await {
+            Ok(Some(result)) => Ok(result),
+            Ok(None) => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method: {}", request.method),
+            )),
+            Err(e) => Err(e),
+        };
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Response {
+            jsonrpc: "2.0".to_string(),
+            id: request.id,
+            result,
+            error,
+        }
+    }
+
+    async fn dispatch(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> std::result::Result<Option<Value>, RpcError> {
+        let result = match method {
+            "ping" => json!("pong"),
+            "status" => json!({
+                "rpc_url": self.ctx.rpc_url,
+                "chain_id": self.ctx.chain_id,
+                "contract": self.ctx.contract_name,
+                "signer": self.write.as_ref().map(|w| w.address().to_checksum(None)),
+                "abis": self.ctx.abis.len(),
+            }),
+            "contracts" => serde_json::to_value(&self.ctx.contracts)
+                .map_err(|e| RpcError::from(Error::from(e)))?,
+            "call" | "send" | "encode" => {
+                let params: MethodParams = serde_json::from_value(params)
+                    .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+                match method {
+                    "call" => self.call(&params).await?,
+                    "send" => self.send(&params).await?,
+                    _ => self.encode(&params)?,
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(result))
+    }
+
+    /// Resolves the ABI, address and function targeted by a request.
+    fn target(&self, params: &MethodParams) -> Result<(&JsonAbi, &Function, Address)> {
+        self.ctx.target(
+            params.contract.as_deref(),
+            params.address.as_deref(),
+            &params.method,
+        )
+    }
+
+    async fn call(&self, params: &MethodParams) -> Result<Value> {
+        let (abi, function, address) = self.target(params)?;
+        let args = codec::coerce_args(function, &params.args)?;
+        let result = self
+            .read
+            .call(address, function, &args)
+            .await
+            .map_err(|e| e.with_abi(abi))?;
+        Ok(ExecutionResult::Call(result).to_json())
+    }
+
+    async fn send(&self, params: &MethodParams) -> Result<Value> {
+        let write = self
+            .write
+            .as_ref()
+            .ok_or_else(|| Error::InvalidPrivateKey("No private key configured".to_string()))?;
+        let (abi, function, address) = self.target(params)?;
+        let args = codec::coerce_args(function, &params.args)?;
+        if self.ctx.safe.is_some() {
+            let result = safe::propose(&self.ctx, address, function, &args, &params.options, false)
+                .await
+                .map_err(|e| e.with_abi(abi))?;
+            return Ok(result.to_json());
+        }
+        let pending = write
+            .send(address, function, &args, &params.options)
+            .await
+            .map_err(|e| e.with_abi(abi))?;
+        let result = if params.wait {
+            ExecutionResult::Confirmed(Box::new(write.confirm(pending).await?))
+        } else {
+            ExecutionResult::Submitted {
+                hash: *pending.tx_hash(),
+            }
+        };
+        self.ctx.record(
+            params
+                .contract
+                .as_deref()
+                .unwrap_or(&self.ctx.contract_name),
+            address,
+            function,
+            &params.args,
+            &params.options,
+            &result,
+        );
+        Ok(result.to_json())
+    }
+
+    fn encode(&self, params: &MethodParams) -> Result<Value> {
+        let (_, function, _) = self.target(params)?;
+        Ok(json!({ "calldata": codec::encode_calldata(function, &params.args)?.to_string() }))
+    }
+
+    /// Serves requests on a Unix socket until the token is cancelled.
+    ///
+    /// The socket is created with owner-only permissions before anyone can
+    /// connect to it. A stale socket left by a previous run is replaced; a
+    /// live one makes this fail. A failed `accept` is logged and the daemon
+    /// keeps serving.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the Unix socket
+    /// * `shutdown` - Token stopping the daemon when cancelled
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success once shut down, or an error
+    #[cfg(unix)]
+    pub async fn serve(self, path: &Path, shutdown: CancellationToken) -> Result<()> {
+        if path.exists() {
+            if tokio::net::UnixStream::connect(path).await.is_ok() {
+                return Err(Error::Other(format!(
+                    "A daemon is already listening on {}",
+                    path.display()
+                )));
+            }
+            std::fs::remove_file(path)?;
+        }
+        let listener = bind_private(path)?;
+        tracing::info!(socket = %path.display(), "daemon listening");
+
+        let daemon = Arc::new(self);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                accepted = listener.accept() => {
+                    let stream = match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "daemon failed to accept a connection");
+                            tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                            continue;
+                        }
+                    };
+                    let daemon = daemon.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = daemon.serve_connection(stream).await {
+                            tracing::debug!(error = %e, "daemon connection closed");
+                        }
+                    });
+                }
+            }
+        }
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    async fn serve_connection(&self, stream: tokio::net::UnixStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => self.handle(request).await,
+                Err(e) => Response {
+                    jsonrpc: "2.0".to_string(),
+                    id: Value::Null,
+                    result: None,
+                    error: Some(RpcError::new(INVALID_REQUEST, e.to_string())),
+                },
+            };
+            let mut encoded = serde_json::to_string(&response)?;
+            encoded.push('\n');
+            writer.write_all(encoded.as_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Binds a Unix socket only its owner can connect to.
+///
+/// The socket is bound inside a fresh directory with mode 0700, restricted to
+/// mode 0600 and only then moved to `path`, so that it is never reachable by
+/// other users while its permissions are still the umask's.
+///
+/// # Arguments
+///
+/// * `path` - Path of the Unix socket
+///
+/// # Returns
+///
+/// * `Result<tokio::net::UnixListener>` - The listener bound at `path`, or an error
+#[cfg(unix)]
+fn bind_private(path: &Path) -> Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::Other(format!("Invalid socket path {}", path.display())))?;
+    let staging = path.with_file_name(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+    let bound = (|| -> Result<tokio::net::UnixListener> {
+        let staged = staging.join(file_name);
+        let listener = tokio::net::UnixListener::bind(&staged)?;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    })();
+    std::fs::remove_dir_all(&staging)?;
+    bound
+}
+
+/// Thin client sending requests to a running daemon, used by `inachus rpc`.
+#[derive(Debug, Clone)]
+pub struct DaemonClient {
+    path: PathBuf,
+}
+
+impl DaemonClient {
+    /// Creates a client for the daemon listening on a socket.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Sends a request and waits for its result.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The daemon method
+    /// * `params` - The method parameters
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Value>` - The result, or an error if the daemon is unreachable or the request failed
+    #[cfg(unix)]
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let stream = tokio::net::UnixStream::connect(&self.path)
+            .await
+            .map_err(|e| {
+                Error::Other(format!(
+                    "Cannot reach the daemon at {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })?;
+        let (reader, mut writer) = stream.into_split();
+        let request = Request {
+            jsonrpc: "2.0".to_string(),
+            id: json!(1),
+            method: method.to_string(),
+            params,
+        };
+        let mut encoded = serde_json::to_string(&request)?;
+        encoded.push('\n');
+        writer.write_all(encoded.as_bytes()).await?;
+
+        let line = BufReader::new(reader)
+            .lines()
+            .next_line()
+            .await?
+            .ok_or_else(|| Error::Other("The daemon closed the connection".to_string()))?;
+        let response: Response = serde_json::from_str(&line)?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(Error::Other(error.message)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Ok(Value::Null),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixtures, MockRpc};
+
+    #[tokio::test]
+    async fn test_handle_encode_and_unknown_method() {
+        let daemon = Daemon::new(fixtures::context(&MockRpc::new())).unwrap();
+        let request = |method: &str, params: Value| Request {
+            jsonrpc: "2.0".to_string(),
+            id: json!(7),
+            method: method.to_string(),
+            params,
+        };
+
+        let response = daemon
+            .handle(request(
+                "encode",
+                json!({ "method": "approve", "args": ["0x0000000000000000000000000000000000000001", "1"] }),
+            ))
+            .await;
+        let calldata = response.result.unwrap()["calldata"].clone();
+        assert!(calldata.as_str().unwrap().starts_with("0x095ea7b3"));
+
+        let response = daemon.handle(request("frobnicate", Value::Null)).await;
+        assert_eq!(response.id, json!(7));
+        assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_serve_socket_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("inachus-daemon-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SOCKET_FILE);
+        let daemon = Daemon::new(fixtures::context(&MockRpc::new())).unwrap();
+        let shutdown = CancellationToken::new();
+        let server = tokio::spawn({
+            let path = path.clone();
+            let shutdown = shutdown.clone();
+            async move { daemon.serve(&path, shutdown).await }
+        });
+
+        let client = DaemonClient::new(&path);
+        let mut pong = client.request("ping", Value::Null).await;
+        for _ in 0..50 {
+            if pong.is_ok() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            pong = client.request("ping", Value::Null).await;
+        }
+        assert_eq!(pong.unwrap(), json!("pong"));
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let entries: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        shutdown.cancel();
+        server.await.unwrap().unwrap();
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,120 @@
+/// src/editor.rs
+use crate::error::{Error, Result};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What to type at a prompt to enter its value in the editor instead.
+pub const EDIT_COMMAND: &str = ":edit";
+
+/// Editor used when neither `VISUAL` nor `EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Whether an input asks for the editor.
+pub fn is_edit_command(input: &str) -> bool {
+    input.trim() == EDIT_COMMAND
+}
+
+/// Opens the user's editor on a template and returns what was saved.
+///
+/// The editor is `$VISUAL`, else `$EDITOR`, else `vi`, and may include
+/// arguments, e.g. `code --wait`. Lines starting with `#` are instructions
+/// and are removed from the result.
+///
+/// # Arguments
+///
+/// * `template` - The initial content, instructions included
+/// * `extension` - Extension of the edited file, for syntax highlighting, e.g. `json`
+///
+/// # Returns
+///
+/// * `Result<String>` - The saved content without instructions, trimmed, or
+///   an error if the editor cannot be run or exits with an error
+pub fn edit(template: &str, extension: &str) -> Result<String> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let path = std::env::temp_dir().join(format!(
+        "inachus-{}-{}.{}",
+        std::process::id(),
+        nanos,
+        extension
+    ));
+    std::fs::write(&path, template)?;
+    let result = run_editor(&path).and_then(|_| Ok(std::fs::read_to_string(&path)?));
+    let _ = std::fs::remove_file(&path);
+    Ok(strip_instructions(&result?))
+}
+
+fn run_editor(path: &std::path::Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|e| Error::Other(format!("Cannot run editor {}: {}", program, e)))?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(Error::Other(format!(
+            "Editor {} exited with {}",
+            program, status
+        ))),
+    }
+}
+
+/// Builds the template of an input: instruction lines, then the initial value.
+///
+/// # Arguments
+///
+/// * `instructions` - What to enter, one instruction per line
+/// * `initial` - The value to start from
+///
+/// # Returns
+///
+/// * `String` - The template
+pub fn template(instructions: &[&str], initial: &str) -> String {
+    let mut template: String = instructions
+        .iter()
+        .chain(&["Lines starting with # are ignored; save and quit to continue."])
+        .map(|line| format!("# {}\n", line))
+        .collect();
+    template.push_str(initial);
+    template.push('\n');
+    template
+}
+
+/// Removes the instruction lines of edited content and trims it.
+fn strip_instructions(content: &str) -> String {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_instructions() {
+        let template = template(&["Enter data (bytes)"], "0x1234");
+        assert!(template.starts_with("# Enter data (bytes)\n# Lines starting"));
+        assert!(template.ends_with("\n0x1234\n"));
+        assert_eq!(strip_instructions(&template), "0x1234");
+        assert_eq!(
+            strip_instructions("# note\n[\n  \"a\",\n  \"b\"\n]\n\n"),
+            "[\n  \"a\",\n  \"b\"\n]"
+        );
+        assert!(is_edit_command(" :edit "));
+        assert!(!is_edit_command("edit"));
+    }
+}
@@ -0,0 +1,183 @@
+/// src/ens.rs
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::Function;
+use alloy::primitives::{address, keccak256, Address, B256};
+use std::collections::HashMap;
+
+/// The ENS registry, at the same address on mainnet and its test networks.
+pub const ENS_REGISTRY: Address = address!("00000000000C2E074eC69A0dFb2997BA6C7d2e1e");
+
+/// Whether an input looks like an ENS name rather than an address or alias:
+/// dot-separated labels, none of them empty.
+pub fn is_name(input: &str) -> bool {
+    let input = input.trim();
+    !input.starts_with("0x")
+        && input.contains('.')
+        && input
+            .split('.')
+            .all(|label| !label.is_empty() && !label.contains(char::is_whitespace))
+}
+
+/// Computes the EIP-137 namehash of an ENS name.
+///
+/// Names are lowercased but not otherwise normalized, which covers ASCII
+/// names; names needing full UTS-46 normalization may hash differently.
+///
+/// # Arguments
+///
+/// * `name` - The name, e.g. `vitalik.eth`
+///
+/// # Returns
+///
+/// * `B256` - The namehash
+pub fn namehash(name: &str) -> B256 {
+    let name = name.trim().to_lowercase();
+    name.rsplit('.')
+        .filter(|label| !label.is_empty())
+        .fold(B256::ZERO, |node, label| {
+            keccak256([node.as_slice(), keccak256(label.as_bytes()).as_slice()].concat())
+        })
+}
+
+/// Resolves an ENS name to the address its resolver returns.
+///
+/// # Arguments
+///
+/// * `read` - Read context of a chain where the ENS registry is deployed
+/// * `name` - The name to resolve
+///
+/// # Returns
+///
+/// * `Result<Address>` - The address, or an error if the name has no
+///   resolver or no address
+pub async fn resolve(read: &ReadContext, name: &str) -> Result<Address> {
+    let node = DynSolValue::FixedBytes(namehash(name), 32);
+    let resolver = lookup(read, ENS_REGISTRY, "resolver", &node).await?;
+    if resolver.is_zero() {
+        return Err(Error::InvalidAddress(format!(
+            "{} has no ENS resolver",
+            name
+        )));
+    }
+    let address = lookup(read, resolver, "addr", &node).await?;
+    if address.is_zero() {
+        return Err(Error::InvalidAddress(format!(
+            "{} does not resolve to an address",
+            name
+        )));
+    }
+    Ok(address)
+}
+
+/// Looks up the primary ENS name of an address through its reverse record,
+/// keeping it only if the name resolves back to the address.
+///
+/// # Arguments
+///
+/// * `read` - Read context of a chain where the ENS registry is deployed
+/// * `address` - The address
+///
+/// # Returns
+///
+/// * `Result<Option<String>>` - The name, `None` if the address has no
+///   verified primary name, or an error if the registry cannot be called
+pub async fn reverse_resolve(read: &ReadContext, address: Address) -> Result<Option<String>> {
+    let node = DynSolValue::FixedBytes(namehash(&reverse_name(address)), 32);
+    let resolver = lookup(read, ENS_REGISTRY, "resolver", &node).await?;
+    if resolver.is_zero() {
+        return Ok(None);
+    }
+    let function = Function::parse("function name(bytes32) view returns (string)")
+        .map_err(|e| Error::InvalidAbi(e.to_string()))?;
+    let result = read
+        .call(resolver, &function, std::slice::from_ref(&node))
+        .await?;
+    let name = match result.values.first() {
+        Some(DynSolValue::String(name)) if is_name(name) => name.clone(),
+        _ => return Ok(None),
+    };
+    match resolve(read, &name).await {
+        Ok(resolved) if resolved == address => Ok(Some(name)),
+        _ => Ok(None),
+    }
+}
+
+/// Returns the name of the reverse record of an address, e.g. `ab…ab.addr.reverse`.
+fn reverse_name(address: Address) -> String {
+    format!("{:x}.addr.reverse", address)
+}
+
+/// Looks up the primary ENS names of addresses, concurrently; addresses
+/// without a verified name, or whose lookup fails, are left out.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the current chain
+/// * `addresses` - The addresses
+///
+/// # Returns
+///
+/// * `HashMap<Address, String>` - The names found, by address
+pub async fn reverse_names(read: &ReadContext, addresses: &[Address]) -> HashMap<Address, String> {
+    let lookups = addresses.iter().map(|&address| async move {
+        let name = reverse_resolve(read, address).await.ok().flatten()?;
+        Some((address, name))
+    });
+    futures::future::join_all(lookups)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Calls a `name(bytes32) returns (address)` function.
+async fn lookup(
+    read: &ReadContext,
+    to: Address,
+    name: &str,
+    node: &DynSolValue,
+) -> Result<Address> {
+    let function = Function::parse(&format!(
+        "function {}(bytes32) view returns (address)",
+        name
+    ))
+    .map_err(|e| Error::InvalidAbi(e.to_string()))?;
+    let result = read.call(to, &function, std::slice::from_ref(node)).await?;
+    match result.values.first() {
+        Some(DynSolValue::Address(address)) => Ok(*address),
+        _ => Err(Error::Other(format!("{} returned no address", name))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::b256;
+
+    #[test]
+    fn test_namehash() {
+        assert_eq!(namehash(""), B256::ZERO);
+        assert_eq!(
+            namehash("eth"),
+            b256!("93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae")
+        );
+        assert_eq!(
+            namehash("Foo.eth"),
+            b256!("de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f")
+        );
+        assert!(is_name("vitalik.eth"));
+        assert!(!is_name("treasury"));
+        assert!(!is_name("0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e"));
+        assert!(!is_name("foo..eth"));
+        assert_eq!(
+            reverse_name(Address::repeat_byte(0xab)),
+            format!("{}.addr.reverse", "ab".repeat(20))
+        );
+        assert_eq!(
+            namehash("addr.reverse"),
+            b256!("91d1777781884d03a6757a803996e38de2a42967fb37eeaca72729271025a9e2")
+        );
+    }
+}
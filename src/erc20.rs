@@ -0,0 +1,254 @@
+/// src/erc20.rs
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::utils::{format_units, parse_units};
+use alloy::primitives::{Address, U256};
+use std::fmt;
+
+/// The ERC-20 functions an ABI must declare to be detected as a token;
+/// `name`, `symbol` and `decimals` are optional in the standard and read
+/// separately.
+const REQUIRED: [&str; 5] = [
+    "totalSupply()",
+    "balanceOf(address)",
+    "transfer(address,uint256)",
+    "approve(address,uint256)",
+    "allowance(address,address)",
+];
+
+/// Returns the standard ERC-20 ABI, used when the loaded ABI of a token is
+/// incomplete or was asserted to be ERC-20.
+pub fn abi() -> JsonAbi {
+    JsonAbi::parse([
+        "function name() view returns (string)",
+        "function symbol() view returns (string)",
+        "function decimals() view returns (uint8)",
+        "function totalSupply() view returns (uint256)",
+        "function balanceOf(address account) view returns (uint256)",
+        "function allowance(address owner, address spender) view returns (uint256)",
+        "function transfer(address to, uint256 amount) returns (bool)",
+        "function transferFrom(address from, address to, uint256 amount) returns (bool)",
+        "function approve(address spender, uint256 amount) returns (bool)",
+        "event Transfer(address indexed from, address indexed to, uint256 value)",
+        "event Approval(address indexed owner, address indexed spender, uint256 value)",
+    ])
+    .expect("the ERC-20 ABI parses")
+}
+
+/// Returns a function of the standard ERC-20 ABI.
+///
+/// # Arguments
+///
+/// * `name` - The function name, e.g. `transfer`
+///
+/// # Returns
+///
+/// * `Result<Function>` - The function, or an error if ERC-20 has none by that name
+pub fn function(name: &str) -> Result<Function> {
+    abi()
+        .function(name)
+        .and_then(|functions| functions.first().cloned())
+        .ok_or_else(|| Error::InvalidFunction(format!("ERC-20 has no {} function", name)))
+}
+
+/// Whether an ABI declares the ERC-20 balance, transfer and allowance functions.
+pub fn is_erc20(abi: &JsonAbi) -> bool {
+    let signatures: Vec<String> = abi.functions().map(|f| f.signature()).collect();
+    REQUIRED
+        .iter()
+        .all(|required| signatures.iter().any(|signature| signature == required))
+}
+
+/// Metadata and supply of a token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    /// The token address
+    pub address: Address,
+    /// Name, if the token implements `name()` returning a string
+    pub name: Option<String>,
+    /// Symbol, if the token implements `symbol()` returning a string
+    pub symbol: Option<String>,
+    /// Decimals amounts are scaled by
+    pub decimals: u8,
+    /// Total supply, in base units
+    pub total_supply: U256,
+}
+
+impl TokenInfo {
+    /// Reads the metadata and supply of a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - Read context of the current chain
+    /// * `address` - The token address
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TokenInfo>` - The token, or an error if `decimals()` or
+    ///   `totalSupply()` cannot be read
+    pub async fn fetch(read: &ReadContext, address: Address) -> Result<Self> {
+        let call = |name: &'static str| async move {
+            let result = read.call(address, &function(name)?, &[]).await?;
+            result
+                .values
+                .into_iter()
+                .next()
+                .ok_or_else(|| Error::Other(format!("{} returned nothing", name)))
+        };
+        let (name, symbol, decimals, total_supply) = futures::join!(
+            call("name"),
+            call("symbol"),
+            call("decimals"),
+            call("totalSupply")
+        );
+        let string = |value: Result<DynSolValue>| match value {
+            Ok(DynSolValue::String(s)) => Some(s),
+            _ => None,
+        };
+        let not_erc20 = |what: &str| {
+            Error::InvalidContract(format!(
+                "{} returned no {}: not an ERC-20 token",
+                address, what
+            ))
+        };
+        let decimals = match decimals {
+            Ok(DynSolValue::Uint(decimals, _)) => {
+                u8::try_from(decimals).map_err(|_| not_erc20("decimals"))?
+            }
+            _ => return Err(not_erc20("decimals")),
+        };
+        let total_supply = match total_supply {
+            Ok(DynSolValue::Uint(supply, _)) => supply,
+            _ => return Err(not_erc20("total supply")),
+        };
+        Ok(Self {
+            address,
+            name: string(name),
+            symbol: string(symbol),
+            decimals,
+            total_supply,
+        })
+    }
+
+    /// Formats an amount of the token, e.g. `1.5 USDC`.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount in base units
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The amount scaled by the decimals, followed by the symbol
+    pub fn format(&self, amount: U256) -> String {
+        match &self.symbol {
+            Some(symbol) => format!("{} {}", format_amount(amount, self.decimals), symbol),
+            None => format_amount(amount, self.decimals),
+        }
+    }
+}
+
+impl fmt::Display for TokenInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = [
+            ("Address", self.address.to_string()),
+            ("Name", self.name.clone().unwrap_or_else(|| "-".to_string())),
+            (
+                "Symbol",
+                self.symbol.clone().unwrap_or_else(|| "-".to_string()),
+            ),
+            ("Decimals", self.decimals.to_string()),
+            ("Total supply", self.format(self.total_supply)),
+        ];
+        write!(f, "{}", text::table(&rows))
+    }
+}
+
+/// Formats an amount of base units scaled by decimals, without trailing zeros.
+///
+/// # Arguments
+///
+/// * `amount` - The amount in base units
+/// * `decimals` - Decimals of the token
+///
+/// # Returns
+///
+/// * `String` - The scaled amount, e.g. `1.5`
+pub fn format_amount(amount: U256, decimals: u8) -> String {
+    let formatted = format_units(amount, decimals).unwrap_or_else(|_| amount.to_string());
+    match formatted.contains('.') {
+        true => formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+        false => formatted,
+    }
+}
+
+/// Parses a human amount of a token into base units; `max` is the largest
+/// amount, the usual unlimited approval.
+///
+/// # Arguments
+///
+/// * `input` - The amount, e.g. `1.5`
+/// * `decimals` - Decimals of the token
+///
+/// # Returns
+///
+/// * `Result<U256>` - The amount in base units, or an error if it is not a
+///   non-negative number with at most `decimals` decimals
+pub fn parse_amount(input: &str, decimals: u8) -> Result<U256> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("max") {
+        return Ok(U256::MAX);
+    }
+    let invalid =
+        |reason: String| Error::InvalidArguments(format!("Invalid amount {}: {}", input, reason));
+    if let Some((_, fraction)) = input.split_once('.') {
+        if fraction.len() > decimals as usize {
+            return Err(invalid(format!("the token has {} decimals", decimals)));
+        }
+    }
+    let amount = parse_units(input, decimals).map_err(|e| invalid(e.to_string()))?;
+    if amount.is_negative() {
+        return Err(invalid("must not be negative".to_string()));
+    }
+    Ok(amount.get_absolute())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixtures, MockRpc, TEST_CONTRACT};
+    use alloy::primitives::Bytes;
+
+    #[tokio::test]
+    async fn test_token_amounts() {
+        assert!(is_erc20(&abi()));
+        assert!(is_erc20(&fixtures::erc20_abi()));
+        assert!(!is_erc20(
+            &JsonAbi::parse(["function totalSupply() view returns (uint256)"]).unwrap()
+        ));
+
+        assert_eq!(parse_amount("1.5", 6).unwrap(), U256::from(1_500_000));
+        assert_eq!(parse_amount("max", 18).unwrap(), U256::MAX);
+        assert!(parse_amount("0.0000001", 6).is_err());
+        assert!(parse_amount("-1", 6).is_err());
+        assert_eq!(format_amount(U256::from(1_500_000), 6), "1.5");
+        assert_eq!(format_amount(U256::from(2_000_000), 6), "2");
+        assert_eq!(format_amount(U256::from(7), 0), "7");
+
+        // Every call returns the same word: 6 decodes as decimals and supply,
+        // but not as a string, so name and symbol are left out.
+        let mock = MockRpc::new();
+        mock.respond("eth_call", Bytes::from(U256::from(6).to_be_bytes_vec()));
+        let token = TokenInfo::fetch(&mock.read_context(), TEST_CONTRACT)
+            .await
+            .unwrap();
+        assert_eq!(token.decimals, 6);
+        assert_eq!(token.total_supply, U256::from(6));
+        assert_eq!(token.name, None);
+        assert_eq!(token.format(U256::from(1_000_000)), "1");
+    }
+}
@@ -1,8 +1,54 @@
 /// src/error.rs
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::JsonAbi;
+use alloy::primitives::Bytes;
+use alloy::transports::TransportError;
+
+use crate::config::OperatingMode;
 
 /// Represents the result type for Inachus operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Boxed error used as the source of wrapped third-party failures.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Broad failure class of an [`Error`], for callers that only need to know
+/// who is at fault rather than the exact variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The user supplied an invalid value or aborted a prompt.
+    UserInput,
+    /// The RPC endpoint failed or returned an error response.
+    Rpc,
+    /// The EVM reverted the call or transaction.
+    Revert,
+    /// A signer failed to produce a signature.
+    Signing,
+    /// The configuration or a persisted file is invalid.
+    Config,
+    /// A local filesystem operation failed.
+    Io,
+    /// The session's operating mode forbids the operation.
+    Mode,
+    /// Anything that does not fit the categories above.
+    Other,
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::UserInput => write!(f, "user input"),
+            ErrorKind::Rpc => write!(f, "rpc"),
+            ErrorKind::Revert => write!(f, "revert"),
+            ErrorKind::Signing => write!(f, "signing"),
+            ErrorKind::Config => write!(f, "config"),
+            ErrorKind::Io => write!(f, "io"),
+            ErrorKind::Mode => write!(f, "mode"),
+            ErrorKind::Other => write!(f, "other"),
+        }
+    }
+}
+
 /// Represents errors that can occur during Inachus operations.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -38,9 +84,80 @@ pub enum Error {
     #[error("Invalid arguments: {0}")]
     InvalidArguments(String),
 
-    /// Error from the Ethereum provider.
-    #[error("Provider error: {0}")]
-    Provider(String),
+    /// Error related to an invalid configuration value.
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
+    /// Interactive prompt failed or was aborted by the user.
+    #[cfg(feature = "interactive")]
+    #[error("Prompt error: {0}")]
+    Prompt(#[from] inquire::InquireError),
+
+    /// Error returned by the RPC transport or the node.
+    #[error("RPC error while {context}: {source}")]
+    Rpc {
+        /// What Inachus was doing when the request failed
+        context: String,
+        /// The underlying transport error
+        #[source]
+        source: TransportError,
+    },
+
+    /// The EVM reverted, carrying the raw revert data and the decoded error.
+    #[error("Execution reverted{}", revert_reason(.name, .args))]
+    Revert {
+        /// Raw revert data returned by the node
+        data: Bytes,
+        /// Name of the decoded error (`Error`, `Panic` or a custom error), if recognised
+        name: Option<String>,
+        /// Arguments of the decoded error
+        args: Vec<DynSolValue>,
+    },
+
+    /// A signer failed to produce a signature.
+    #[error("Signing error while {context}: {source}")]
+    Signing {
+        /// What Inachus was signing when the signer failed
+        context: String,
+        /// The underlying signer error
+        #[source]
+        source: BoxError,
+    },
+
+    /// A hook or policy blocked the transaction.
+    #[error("Transaction blocked by {hook}: {reason}")]
+    Vetoed {
+        /// Name of the hook that blocked the transaction
+        hook: String,
+        /// Why the transaction was blocked
+        reason: String,
+    },
+
+    /// The operating mode, read-only or dry run, forbids signing or sending.
+    #[error("Not allowed in {mode} mode: {reason}")]
+    ModeForbids {
+        /// The mode the session or context runs in
+        mode: OperatingMode,
+        /// What the mode prevents
+        reason: String,
+    },
+
+    /// A script assertion did not hold.
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+
+    /// The operation was cancelled by the user.
+    #[error("Cancelled while {0}")]
+    Cancelled(String),
+
+    /// The operation did not complete in time.
+    #[error("Timed out after {} while {operation}", humantime::format_duration(*.after))]
+    Timeout {
+        /// What Inachus was doing when the timeout elapsed
+        operation: String,
+        /// The timeout that elapsed
+        after: std::time::Duration,
+    },
 
     /// IO error during file operations.
     #[error("IO error: {0}")]
@@ -50,10 +167,18 @@ pub enum Error {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
-    /// TOML parsing or serialization error.
+    /// TOML parsing error.
     #[error("TOML error: {0}")]
     Toml(#[from] toml::de::Error),
 
+    /// TOML serialization error.
+    #[error("TOML serialization error: {0}")]
+    TomlSer(#[from] toml::ser::Error),
+
+    /// YAML parsing error.
+    #[error("YAML error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
     /// Hexadecimal conversion error.
     #[error("Hex error: {0}")]
     Hex(#[from] hex::FromHexError),
@@ -63,8 +188,199 @@ pub enum Error {
     Other(String),
 }
 
-impl From<alloy::primitives::Bytes> for Error {
-    fn from(e: alloy::primitives::Bytes) -> Self {
-        Error::Other(format!("Bytes error: {:?}", e))
+impl Error {
+    /// Wraps a transport error with a description of the failed operation.
+    ///
+    /// Error responses carrying revert data are turned into [`Error::Revert`]
+    /// so the raw bytes stay available to the caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - What was being done when the request failed (e.g. "estimating gas")
+    /// * `source` - The transport error
+    ///
+    /// # Returns
+    ///
+    /// * `Error` - Either a `Revert` or an `Rpc` error
+    pub fn rpc(context: impl Into<String>, source: TransportError) -> Self {
+        if let Some(payload) = source.as_error_resp() {
+            if let Some(data) = payload.as_revert_data() {
+                return Error::revert(data, None);
+            }
+        }
+        Error::Rpc {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Builds a revert error, decoding the revert data when possible.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The raw revert data
+    /// * `abi` - The ABI of the called contract, used to decode custom errors
+    ///
+    /// # Returns
+    ///
+    /// * `Error` - A `Revert` error
+    pub fn revert(data: Bytes, abi: Option<&JsonAbi>) -> Self {
+        let (name, args) = match crate::codec::decode_revert(&data, abi) {
+            Some((name, args)) => (Some(name), args),
+            None => (None, Vec::new()),
+        };
+        Error::Revert { data, name, args }
+    }
+
+    /// Decodes a custom error in a revert using the called contract's ABI.
+    ///
+    /// Other errors, and reverts that are already decoded, are returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `abi` - The ABI of the contract that reverted
+    ///
+    /// # Returns
+    ///
+    /// * `Error` - The error, with the custom error decoded if the ABI declares it
+    pub fn with_abi(self, abi: &JsonAbi) -> Self {
+        match self {
+            Error::Revert {
+                data, name: None, ..
+            } => Error::revert(data, Some(abi)),
+            e => e,
+        }
+    }
+
+    /// Wraps a signer error with a description of what was being signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - What was being signed (e.g. "signing transaction")
+    /// * `source` - The signer error
+    ///
+    /// # Returns
+    ///
+    /// * `Error` - A `Signing` error
+    pub fn signing(
+        context: impl Into<String>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        Error::Signing {
+            context: context.into(),
+            source: Box::new(source),
+        }
+    }
+
+    /// Returns the failure class of this error.
+    ///
+    /// # Returns
+    ///
+    /// * `ErrorKind` - The category the error belongs to
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::InvalidContract(_)
+            | Error::InvalidAddress(_)
+            | Error::InvalidFunction(_)
+            | Error::InvalidArguments(_)
+            | Error::Cancelled(_)
+            | Error::Hex(_) => ErrorKind::UserInput,
+            #[cfg(feature = "interactive")]
+            Error::Prompt(_) => ErrorKind::UserInput,
+            Error::InvalidPrivateKey(_)
+            | Error::InvalidChainId(_)
+            | Error::InvalidWaitTime(_)
+            | Error::InvalidAbi(_)
+            | Error::InvalidConfig(_)
+            | Error::Json(_)
+            | Error::Toml(_)
+            | Error::TomlSer(_)
+            | Error::Yaml(_) => ErrorKind::Config,
+            Error::Rpc { .. } | Error::Timeout { .. } => ErrorKind::Rpc,
+            Error::Revert { .. } => ErrorKind::Revert,
+            Error::Signing { .. } => ErrorKind::Signing,
+            Error::Io(_) => ErrorKind::Io,
+            Error::ModeForbids { .. } => ErrorKind::Mode,
+            Error::Vetoed { .. } | Error::AssertionFailed(_) | Error::Other(_) => ErrorKind::Other,
+        }
+    }
+
+    /// Returns the raw revert data if this error is a revert.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&Bytes>` - The revert data, or `None` for other errors
+    pub fn revert_data(&self) -> Option<&Bytes> {
+        match self {
+            Error::Revert { data, .. } => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Returns the name and arguments of the decoded revert error.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(&str, &[DynSolValue])>` - The decoded error, or `None` if not a decoded revert
+    pub fn revert_error(&self) -> Option<(&str, &[DynSolValue])> {
+        match self {
+            Error::Revert {
+                name: Some(name),
+                args,
+                ..
+            } => Some((name, args)),
+            _ => None,
+        }
+    }
+}
+
+/// Renders a decoded revert, e.g. `: insufficient funds` or `: Panic(0x11): arithmetic overflow or underflow`.
+fn revert_reason(name: &Option<String>, args: &[DynSolValue]) -> String {
+    match (name.as_deref(), args) {
+        (None, _) => String::new(),
+        (Some("Error"), [DynSolValue::String(reason)]) => format!(": {}", reason),
+        (Some("Panic"), [DynSolValue::Uint(code, _)]) => format!(
+            ": Panic(0x{:x}): {}",
+            code,
+            crate::codec::panic_reason(*code)
+        ),
+        (Some(name), args) => format!(
+            ": {}({})",
+            name,
+            args.iter()
+                .map(|arg| match crate::codec::to_json(arg) {
+                    serde_json::Value::String(s) => s,
+                    value => value.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+impl From<abi_codec::CodecError> for Error {
+    fn from(e: abi_codec::CodecError) -> Self {
+        use abi_codec::CodecError;
+        match e {
+            CodecError::InvalidType { .. } | CodecError::Decode { .. } => {
+                Error::InvalidAbi(e.to_string())
+            }
+            CodecError::InvalidArgument { .. }
+            | CodecError::ArgumentCount { .. }
+            | CodecError::SelectorMismatch { .. }
+            | CodecError::Encode(_) => Error::InvalidArguments(e.to_string()),
+        }
+    }
+}
+
+impl From<TransportError> for Error {
+    fn from(e: TransportError) -> Self {
+        Error::rpc("sending request", e)
+    }
+}
+
+impl From<alloy::signers::Error> for Error {
+    fn from(e: alloy::signers::Error) -> Self {
+        Error::signing("signing", e)
     }
 }
@@ -0,0 +1,224 @@
+/// src/events.rs
+use alloy::primitives::{Address, TxHash};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Lifecycle events emitted while a workflow runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// An ABI was parsed and registered
+    AbiLoaded {
+        /// Name under which the ABI is registered
+        name: String,
+        /// Number of functions declared by the ABI
+        functions: usize,
+        /// Number of events declared by the ABI
+        events: usize,
+    },
+    /// The active contract changed
+    ContractSelected {
+        /// Name of the contract
+        name: String,
+        /// Address of the contract
+        address: Address,
+    },
+    /// A read-only call is about to be sent
+    CallStarted {
+        /// Target contract address
+        contract: Address,
+        /// Signature of the called function
+        function: String,
+    },
+    /// A read-only call returned successfully
+    CallCompleted {
+        /// Target contract address
+        contract: Address,
+        /// Signature of the called function
+        function: String,
+    },
+    /// A transaction was broadcast
+    TxSubmitted {
+        /// Hash of the transaction
+        hash: TxHash,
+        /// Target contract address
+        contract: Address,
+        /// Signature of the called function
+        function: String,
+    },
+    /// A transaction was included in a block
+    TxConfirmed {
+        /// Hash of the transaction
+        hash: TxHash,
+        /// Block the transaction was included in
+        block_number: Option<u64>,
+        /// Gas used by the transaction
+        gas_used: u64,
+        /// Whether the transaction succeeded
+        success: bool,
+    },
+    /// The session switched to another chain
+    ChainSwitched {
+        /// ID of the new chain
+        chain_id: u64,
+        /// RPC endpoint of the new chain
+        rpc_url: String,
+    },
+    /// A simulation of a call or transaction failed
+    SimulationFailed {
+        /// Signature of the simulated function
+        function: String,
+        /// Why the simulation failed
+        reason: String,
+    },
+}
+
+/// Callback invoked for every emitted event.
+pub type Subscriber = Arc<dyn Fn(&Event) + Send + Sync>;
+
+/// Identifies a subscription so it can be removed later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// In-process event bus dispatching [`Event`]s to registered subscribers.
+///
+/// Cloning the bus is cheap and every clone shares the same subscribers.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<RwLock<Vec<(SubscriptionId, Subscriber)>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscribers", &self.len())
+            .finish()
+    }
+}
+
+impl EventBus {
+    /// Creates an event bus without subscribers.
+    ///
+    /// # Returns
+    ///
+    /// * `EventBus` - A new, empty event bus
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a subscriber that will receive every subsequent event.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscriber` - Callback invoked for each event
+    ///
+    /// # Returns
+    ///
+    /// * `SubscriptionId` - Identifier to pass to [`EventBus::unsubscribe`]
+    pub fn subscribe<F>(&self, subscriber: F) -> SubscriptionId
+    where
+        F: Fn(&Event) + Send + Sync + 'static,
+    {
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.subscribers
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((id, Arc::new(subscriber)));
+        id
+    }
+
+    /// Removes a previously registered subscriber.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Identifier returned by [`EventBus::subscribe`]
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether a subscriber was removed
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscribers = self.subscribers.write().unwrap_or_else(|e| e.into_inner());
+        let before = subscribers.len();
+        subscribers.retain(|(sub_id, _)| *sub_id != id);
+        subscribers.len() != before
+    }
+
+    /// Dispatches an event to all subscribers in registration order.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to dispatch
+    pub fn emit(&self, event: Event) {
+        let subscribers: Vec<Subscriber> = self
+            .subscribers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(_, sub)| sub.clone())
+            .collect();
+        for subscriber in subscribers {
+            subscriber(&event);
+        }
+    }
+
+    /// Returns the number of registered subscribers.
+    pub fn len(&self) -> usize {
+        self.subscribers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    /// Returns whether no subscriber is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Subscriber that forwards every event to `tracing`.
+///
+/// # Arguments
+///
+/// * `event` - The event to log
+pub fn log_event(event: &Event) {
+    match event {
+        Event::SimulationFailed { function, reason } => {
+            tracing::warn!(%function, %reason, "simulation failed")
+        }
+        Event::TxConfirmed {
+            success: false,
+            hash,
+            ..
+        } => {
+            tracing::warn!(%hash, "transaction reverted")
+        }
+        _ => tracing::debug!(?event, "workflow event"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_subscribe_and_unsubscribe() {
+        let bus = EventBus::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let id = bus.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let event = Event::SimulationFailed {
+            function: "transfer(address,uint256)".to_string(),
+            reason: "insufficient balance".to_string(),
+        };
+        bus.emit(event.clone());
+        assert!(bus.unsubscribe(id));
+        bus.emit(event.clone());
+
+        assert_eq!(*seen.lock().unwrap(), vec![event]);
+        assert!(bus.is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+/// src/export.rs
+use crate::error::{Error, Result};
+use crate::output::Output;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Clipboard commands tried in order, each with the environment variable
+/// that must be set for it to apply, if any.
+const CLIPBOARD_COMMANDS: [(&str, &[&str], Option<&str>); 5] = [
+    ("pbcopy", &[], None),
+    ("clip.exe", &[], None),
+    ("wl-copy", &[], Some("WAYLAND_DISPLAY")),
+    ("xclip", &["-selection", "clipboard"], Some("DISPLAY")),
+    ("xsel", &["--clipboard", "--input"], Some("DISPLAY")),
+];
+
+/// Renders a result for export: its structured output as pretty JSON,
+/// holding both the raw data and the decoded values.
+///
+/// # Arguments
+///
+/// * `output` - The result as structured output
+///
+/// # Returns
+///
+/// * `String` - The JSON document, ending with a newline
+pub fn to_document(output: &Output) -> String {
+    let json =
+        serde_json::to_string_pretty(&output.to_json()).unwrap_or_else(|_| output.to_string());
+    format!("{}\n", json)
+}
+
+/// Writes a result to a file as JSON; see [`to_document`].
+///
+/// # Arguments
+///
+/// * `path` - The file, created or overwritten
+/// * `output` - The result as structured output
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if the file cannot be written
+pub fn write_file(path: &Path, output: &Output) -> Result<()> {
+    std::fs::write(path, to_document(output))
+        .map_err(|e| Error::Other(format!("Cannot write {}: {}", path.display(), e)))
+}
+
+/// Copies a result to the system clipboard as JSON; see [`to_document`].
+///
+/// The clipboard is reached through `pbcopy` on macOS, `clip.exe` on
+/// Windows and WSL, and `wl-copy`, `xclip` or `xsel` on Linux, whichever is
+/// installed.
+///
+/// # Arguments
+///
+/// * `output` - The result as structured output
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error if no clipboard command succeeded
+pub fn copy_to_clipboard(output: &Output) -> Result<()> {
+    let document = to_document(output);
+    let applicable = CLIPBOARD_COMMANDS
+        .iter()
+        .filter(|(_, _, env)| env.iter().all(|env| std::env::var_os(env).is_some()));
+    for (program, args, _) in applicable {
+        if pipe_to(program, args, &document).is_ok() {
+            return Ok(());
+        }
+    }
+    Err(Error::Other(
+        "No clipboard available: install pbcopy, wl-copy, xclip or xsel".to_string(),
+    ))
+}
+
+/// Runs a command with text on its standard input.
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(Error::Other(format!("{} exited with {}", program, status))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::CallResult;
+    use alloy::dyn_abi::DynSolValue;
+    use alloy::json_abi::Function;
+    use alloy::primitives::{Bytes, U256};
+
+    #[test]
+    fn test_export_document() {
+        let function = Function::parse("totalSupply() returns (uint256 supply)").unwrap();
+        let raw = Bytes::from(U256::from(7).to_be_bytes_vec());
+        let result = CallResult::new(&function, vec![DynSolValue::Uint(U256::from(7), 256)], raw);
+        let document = to_document(&Output::from(&result));
+        assert!(document.ends_with("}\n"));
+
+        let path = std::env::temp_dir().join(format!("inachus-export-{}.json", std::process::id()));
+        write_file(&path, &Output::from(&result)).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written["kind"], "call");
+        assert_eq!(written["data"]["outputs"][0]["value"], "7");
+        assert_eq!(written["data"]["raw"], result.raw.to_string());
+    }
+}
@@ -0,0 +1,463 @@
+/// src/fork.rs
+use crate::cancel;
+use crate::context::{GlobalContext, ReadContext};
+use crate::error::{Error, Result};
+use crate::events::Event;
+use crate::relay::RelayConfig;
+use crate::safe::SafeConfig;
+use crate::transport::RpcEndpoint;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use serde_json::{json, Value};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::{Child, Command};
+
+/// Environment variable naming the anvil binary, `anvil` from `PATH` by default.
+pub const ANVIL_ENV: &str = "INACHUS_ANVIL";
+
+/// How long anvil is given to fetch the forked state and start answering.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay between two readiness checks of a starting anvil.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A local anvil node, killed when the last reference to it is dropped.
+#[derive(Debug)]
+pub struct AnvilNode {
+    /// HTTP endpoint of the node
+    url: String,
+    /// The anvil process
+    _child: Child,
+}
+
+impl AnvilNode {
+    /// Starts anvil forking a chain on a free local port and waits until it
+    /// answers requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `fork_url` - RPC endpoint of the chain to fork
+    /// * `block` - Block to fork at, the latest if `None`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AnvilNode>` - The running node, or an error if anvil is not
+    ///   installed, exits early or does not answer in time
+    pub async fn spawn(fork_url: &str, block: Option<u64>) -> Result<Self> {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")?
+            .local_addr()?
+            .port();
+        let binary = std::env::var(ANVIL_ENV).unwrap_or_else(|_| "anvil".to_string());
+        let mut child = Command::new(&binary)
+            .args(anvil_args(fork_url, port, block))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => Error::InvalidConfig(format!(
+                    "{} not found: install Foundry (https://getfoundry.sh) or set {}",
+                    binary, ANVIL_ENV
+                )),
+                _ => Error::Io(e),
+            })?;
+
+        let url = format!("http://127.0.0.1:{}", port);
+        let endpoint = RpcEndpoint::parse(&url)?;
+        let started = Instant::now();
+        loop {
+            if let Some(status) = child.try_wait()? {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    let _ = pipe.read_to_string(&mut stderr).await;
+                }
+                return Err(Error::Other(format!(
+                    "anvil exited with {}: {}",
+                    status,
+                    stderr.trim()
+                )));
+            }
+            if endpoint.chain_id().await.is_ok() {
+                break;
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                return Err(Error::Timeout {
+                    operation: format!("starting anvil on {}", url),
+                    after: STARTUP_TIMEOUT,
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        // Keeps anvil from blocking on a full pipe.
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::debug!(target: "anvil", "{}", line);
+                }
+            });
+        }
+        Ok(Self { url, _child: child })
+    }
+
+    /// Returns the HTTP endpoint of the node.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Arguments anvil is started with.
+fn anvil_args(fork_url: &str, port: u16, block: Option<u64>) -> Vec<String> {
+    let mut args = vec![
+        "--fork-url".to_string(),
+        fork_url.to_string(),
+        "--port".to_string(),
+        port.to_string(),
+    ];
+    if let Some(block) = block {
+        args.push("--fork-block-number".to_string());
+        args.push(block.to_string());
+    }
+    args
+}
+
+/// A development node, which can impersonate accounts, snapshot its state
+/// and move its clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevNodeKind {
+    /// Foundry's anvil
+    Anvil,
+    /// Hardhat Network
+    Hardhat,
+}
+
+impl DevNodeKind {
+    /// Recognizes a development node from its `web3_clientVersion`, e.g.
+    /// `anvil/v1.0.0` or `HardhatNetwork/2.22.0/@ethereumjs/vm/5.9.3`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_version` - The node's client version
+    ///
+    /// # Returns
+    ///
+    /// * `Option<DevNodeKind>` - The kind of node, `None` for any other client
+    pub fn from_client_version(client_version: &str) -> Option<Self> {
+        let client = client_version.split('/').next()?.to_ascii_lowercase();
+        match client.as_str() {
+            "anvil" => Some(Self::Anvil),
+            "hardhatnetwork" => Some(Self::Hardhat),
+            _ => None,
+        }
+    }
+}
+
+/// The development node the session is connected to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevNode {
+    /// The kind of node
+    pub kind: DevNodeKind,
+    /// Snapshots taken so far, oldest first
+    pub snapshots: Vec<StateSnapshot>,
+}
+
+impl DevNode {
+    /// Creates a node without any snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of node
+    ///
+    /// # Returns
+    ///
+    /// * `DevNode` - The node
+    pub fn new(kind: DevNodeKind) -> Self {
+        Self {
+            kind,
+            snapshots: Vec::new(),
+        }
+    }
+}
+
+/// Asks a local node for its client version, recognizing anvil and Hardhat.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the node
+///
+/// # Returns
+///
+/// * `Option<DevNodeKind>` - The kind of node, `None` for other nodes or if it does not answer
+pub async fn detect(read: &ReadContext) -> Option<DevNodeKind> {
+    match request(read, "web3_clientVersion", json!([])).await {
+        Ok(Value::String(version)) => DevNodeKind::from_client_version(&version),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::debug!(error = %e, "web3_clientVersion is not available");
+            None
+        }
+    }
+}
+
+/// A state snapshot taken on a development node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateSnapshot {
+    /// ID returned by `evm_snapshot`
+    pub id: U256,
+    /// Latest block when the snapshot was taken
+    pub block: u64,
+}
+
+/// A session repointed at a local fork, with what it was connected to before.
+#[derive(Debug, Clone)]
+pub struct ForkSession {
+    /// The anvil node
+    pub node: Arc<AnvilNode>,
+    /// Block the chain was forked at, the latest at the time if `None`
+    pub block: Option<u64>,
+    /// RPC URL of the forked chain
+    origin_rpc_url: String,
+    /// Fallbacks of the forked chain's RPC URL
+    origin_fallback_rpc_urls: Vec<String>,
+    /// Endpoint view calls were cross-checked against, in paranoid mode
+    origin_cross_check_rpc_url: Option<String>,
+    /// Safe proposals go to, set aside while forked
+    origin_safe: Option<SafeConfig>,
+    /// Private relay, set aside while forked
+    origin_private_relay: Option<RelayConfig>,
+}
+
+impl ForkSession {
+    /// Returns the RPC URL of the forked chain.
+    pub fn origin(&self) -> &str {
+        &self.origin_rpc_url
+    }
+}
+
+/// Forks the current chain with anvil and repoints the session at the fork.
+/// The Safe and private relay are set aside while forked, so that nothing
+/// sent to the fork reaches the real chain.
+///
+/// # Arguments
+///
+/// * `ctx` - The session, which must not be forked already
+/// * `block` - Block to fork at, the latest if `None`
+///
+/// # Returns
+///
+/// * `Result<()>` - Success, or an error if anvil could not be started
+pub async fn start(ctx: &mut GlobalContext, block: Option<u64>) -> Result<()> {
+    if ctx.fork.is_some() {
+        return Err(Error::InvalidConfig(
+            "The session is already connected to a fork".to_string(),
+        ));
+    }
+    let chain_id = ctx.chain_id.parse::<u64>().unwrap_or_default();
+    let node = AnvilNode::spawn(&ctx.rpc_url, block).await?;
+    let origin_rpc_url = ctx.rpc_url.clone();
+    let origin_fallback_rpc_urls = ctx.fallback_rpc_urls.clone();
+    let origin_cross_check_rpc_url = ctx.cross_check_rpc_url.clone();
+    ctx.switch_chain(chain_id, node.url()).await?;
+    if ctx.dev_node.is_none() {
+        ctx.dev_node = Some(DevNode::new(DevNodeKind::Anvil));
+    }
+    ctx.fork = Some(ForkSession {
+        node: Arc::new(node),
+        block,
+        origin_rpc_url,
+        origin_fallback_rpc_urls,
+        origin_cross_check_rpc_url,
+        origin_safe: ctx.safe.take(),
+        origin_private_relay: ctx.private_relay.take(),
+    });
+    Ok(())
+}
+
+/// Stops the fork and reconnects the session to the forked chain, restoring
+/// its Safe and private relay. Does nothing if the session is not forked.
+///
+/// # Arguments
+///
+/// * `ctx` - The session
+///
+/// # Returns
+///
+/// * `Result<()>` - Success, or an error if the forked chain cannot be reached
+pub async fn stop(ctx: &mut GlobalContext) -> Result<()> {
+    let Some(fork) = ctx.fork.take() else {
+        return Ok(());
+    };
+    ctx.client = None;
+    ctx.rpc_url = fork.origin_rpc_url;
+    ctx.fallback_rpc_urls = fork.origin_fallback_rpc_urls;
+    ctx.cross_check_rpc_url = fork.origin_cross_check_rpc_url;
+    ctx.safe = fork.origin_safe;
+    ctx.private_relay = fork.origin_private_relay;
+    ctx.connect().await?;
+    ctx.events.emit(Event::ChainSwitched {
+        chain_id: ctx.chain_id.parse().unwrap_or_default(),
+        rpc_url: ctx.rpc_url.clone(),
+    });
+    Ok(())
+}
+
+/// Sends a development node request.
+async fn request(read: &ReadContext, method: &'static str, params: Value) -> Result<Value> {
+    let operation = format!("calling {}", method);
+    cancel::guard(
+        &operation,
+        read.cancellation(),
+        read.timeouts().call,
+        async {
+            read.provider()
+                .raw_request::<_, Value>(method.into(), params)
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        },
+    )
+    .await
+}
+
+/// Parses a hex quantity returned by a development node.
+fn parse_quantity(value: &Value) -> Result<U256> {
+    value
+        .as_str()
+        .and_then(|quantity| U256::from_str_radix(quantity.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| Error::Other(format!("Expected a hex quantity, got {}", value)))
+}
+
+/// Makes the node accept unsigned transactions from an account, with
+/// `anvil_impersonateAccount` or `hardhat_impersonateAccount`.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the node
+/// * `kind` - The kind of node
+/// * `address` - The account
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error
+pub async fn impersonate(read: &ReadContext, kind: DevNodeKind, address: Address) -> Result<()> {
+    let method = match kind {
+        DevNodeKind::Anvil => "anvil_impersonateAccount",
+        DevNodeKind::Hardhat => "hardhat_impersonateAccount",
+    };
+    request(read, method, json!([address])).await?;
+    Ok(())
+}
+
+/// Stops impersonating an account, with `anvil_stopImpersonatingAccount` or
+/// `hardhat_stopImpersonatingAccount`.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the node
+/// * `kind` - The kind of node
+/// * `address` - The account
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error
+pub async fn stop_impersonating(
+    read: &ReadContext,
+    kind: DevNodeKind,
+    address: Address,
+) -> Result<()> {
+    let method = match kind {
+        DevNodeKind::Anvil => "anvil_stopImpersonatingAccount",
+        DevNodeKind::Hardhat => "hardhat_stopImpersonatingAccount",
+    };
+    request(read, method, json!([address])).await?;
+    Ok(())
+}
+
+/// Snapshots the node's state with `evm_snapshot`.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the node
+///
+/// # Returns
+///
+/// * `Result<StateSnapshot>` - The snapshot or an error
+pub async fn snapshot(read: &ReadContext) -> Result<StateSnapshot> {
+    let id = parse_quantity(&request(read, "evm_snapshot", json!([])).await?)?;
+    let block = read.get_block_number().await?;
+    Ok(StateSnapshot { id, block })
+}
+
+/// Reverts the node's state to a snapshot with `evm_revert`. The snapshot
+/// and every later one can no longer be reverted to.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the node
+/// * `id` - ID of the snapshot
+///
+/// # Returns
+///
+/// * `Result<()>` - Success, or an error if the node does not know the snapshot
+pub async fn revert(read: &ReadContext, id: U256) -> Result<()> {
+    match request(read, "evm_revert", json!([id])).await? {
+        Value::Bool(true) => Ok(()),
+        _ => Err(Error::Other(format!("Unknown snapshot {}", id))),
+    }
+}
+
+/// Moves the node's clock forward with `evm_increaseTime` and mines a block
+/// with the new timestamp.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the node
+/// * `duration` - How far to move the clock
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or an error
+pub async fn increase_time(read: &ReadContext, duration: Duration) -> Result<()> {
+    request(read, "evm_increaseTime", json!([duration.as_secs()])).await?;
+    request(read, "evm_mine", json!([])).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anvil_args_and_dev_nodes() {
+        assert_eq!(
+            anvil_args("https://eth.llamarpc.com", 8545, Some(19_000_000)),
+            vec![
+                "--fork-url",
+                "https://eth.llamarpc.com",
+                "--port",
+                "8545",
+                "--fork-block-number",
+                "19000000"
+            ]
+        );
+        assert_eq!(anvil_args("http://localhost:8545", 1, None).len(), 4);
+        assert_eq!(parse_quantity(&json!("0x1a")).unwrap(), U256::from(26));
+        assert!(parse_quantity(&json!(true)).is_err());
+        assert_eq!(
+            DevNodeKind::from_client_version("anvil/v1.0.0"),
+            Some(DevNodeKind::Anvil)
+        );
+        assert_eq!(
+            DevNodeKind::from_client_version("HardhatNetwork/2.22.0/@ethereumjs/vm/5.9.3"),
+            Some(DevNodeKind::Hardhat)
+        );
+        assert_eq!(
+            DevNodeKind::from_client_version("Geth/v1.14.0-stable"),
+            None
+        );
+    }
+}
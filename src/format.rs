@@ -0,0 +1,317 @@
+/// src/format.rs
+use crate::abi;
+use crate::erc20;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::Param;
+use alloy::primitives::{Address, U256};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Integers from this many wei on are also shown in gwei.
+const GWEI_HINT_FROM: u64 = 1_000_000;
+
+/// Integers from this many wei on are shown in ether rather than gwei.
+const ETHER_HINT_FROM: u64 = 1_000_000_000_000_000;
+
+/// Decimals kept in unit hints; longer amounts are truncated and marked `~`.
+const HINT_DECIMALS: usize = 6;
+
+/// Range of integers read as Unix timestamps, 2001-09-09 to 2100-01-01.
+const TIMESTAMPS: std::ops::RangeInclusive<u64> = 1_000_000_000..=4_102_444_800;
+
+/// Name fragments of the integers that hold timestamps, compared in lowercase.
+const TIMESTAMP_NAMES: [&str; 6] = ["time", "deadline", "expir", "date", "until", "after"];
+
+/// Formats a decoded value for reading.
+///
+/// Integers are decimal, with an ether or gwei hint when large and a UTC
+/// date when their name suggests a timestamp; bytes are 0x-hex and addresses
+/// checksummed. Arrays and structs are rendered as an indented tree, one
+/// element or field per line.
+///
+/// # Arguments
+///
+/// * `value` - The value
+/// * `param` - Its ABI parameter, naming the fields of structs, if known
+/// * `name` - Its name, e.g. `deadline`, used to recognize timestamps
+///
+/// # Returns
+///
+/// * `String` - The value on one line, or the lines of its elements or fields
+pub fn format_value(value: &DynSolValue, param: Option<&Param>, name: &str) -> String {
+    match children(value, param) {
+        Some(children) if children.is_empty() => "[]".to_string(),
+        Some(children) => children
+            .iter()
+            .map(|(label, param, child)| {
+                let name = match label.starts_with('[') {
+                    true => name,
+                    false => label.as_str(),
+                };
+                field(label, child, param.as_ref(), name)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => format_scalar(value, name),
+    }
+}
+
+/// Formats a named value as `name: value`, its elements or fields indented
+/// on the following lines; see [`format_value`].
+///
+/// # Arguments
+///
+/// * `name` - The name, e.g. an output name or `[0]`
+/// * `value` - The value
+/// * `param` - Its ABI parameter, if known
+///
+/// # Returns
+///
+/// * `String` - The formatted field
+pub fn format_field(name: &str, value: &DynSolValue, param: Option<&Param>) -> String {
+    field(name, value, param, name)
+}
+
+fn field(label: &str, value: &DynSolValue, param: Option<&Param>, name: &str) -> String {
+    let formatted = format_value(value, param, name);
+    match is_tree(value) && formatted != "[]" {
+        true => format!("{}:\n{}", label, indent(&formatted, "  ")),
+        false => format!("{}: {}", label, formatted),
+    }
+}
+
+/// Whether a value is rendered as a tree: an array or a struct.
+pub fn is_tree(value: &DynSolValue) -> bool {
+    children(value, None).is_some()
+}
+
+/// Prefixes every line of a text.
+pub fn indent(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| format!("{}{}", prefix, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the elements or fields of an array or struct with their labels
+/// and parameters, or `None` for any other value.
+fn children<'a>(
+    value: &'a DynSolValue,
+    param: Option<&Param>,
+) -> Option<Vec<(String, Option<Param>, &'a DynSolValue)>> {
+    match value {
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            let element = param.and_then(abi::element_param);
+            Some(
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| (format!("[{}]", i), element.clone(), value))
+                    .collect(),
+            )
+        }
+        DynSolValue::Tuple(values) => Some(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let component = param.and_then(|param| param.components.get(i));
+                    let label = match component.filter(|c| !c.name.is_empty()) {
+                        Some(component) => component.name.clone(),
+                        None => format!("[{}]", i),
+                    };
+                    (label, component.cloned(), value)
+                })
+                .collect(),
+        ),
+        DynSolValue::CustomStruct {
+            prop_names, tuple, ..
+        } => Some(
+            prop_names
+                .iter()
+                .zip(tuple)
+                .enumerate()
+                .map(|(i, (name, value))| {
+                    let component = param.and_then(|param| param.components.get(i)).cloned();
+                    (name.clone(), component, value)
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Formats a value that is neither an array nor a struct.
+fn format_scalar(value: &DynSolValue, name: &str) -> String {
+    match value {
+        DynSolValue::Uint(n, bits) => match uint_hint(*n, *bits, name) {
+            Some(hint) => format!("{} ({})", n, hint),
+            None => n.to_string(),
+        },
+        DynSolValue::Int(n, _) => n.to_string(),
+        DynSolValue::Bool(b) => b.to_string(),
+        DynSolValue::Address(address) => address.to_checksum(None),
+        DynSolValue::FixedBytes(word, size) => format!("0x{}", hex::encode(&word[..*size])),
+        DynSolValue::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        DynSolValue::String(s) => s.clone(),
+        DynSolValue::Function(function) => function.to_string(),
+        tree => abi_codec::to_json(tree).to_string(),
+    }
+}
+
+/// Explains an integer: its date if it is named like a timestamp, the
+/// largest value of its type, or its amount in ether or gwei.
+fn uint_hint(n: U256, bits: usize, name: &str) -> Option<String> {
+    if bits >= 64 && n == U256::MAX >> (256 - bits) {
+        return Some(format!("max uint{}", bits));
+    }
+    if let Some(date) = timestamp_hint(n, name) {
+        return Some(date);
+    }
+    if n < U256::from(GWEI_HINT_FROM) {
+        return None;
+    }
+    let (decimals, unit) = match n < U256::from(ETHER_HINT_FROM) {
+        true => (9, "gwei"),
+        false => (18, "ether"),
+    };
+    let amount = erc20::format_amount(n, decimals);
+    match amount.split_once('.') {
+        Some((_, fraction)) if fraction.len() > HINT_DECIMALS => {
+            let truncated = n / U256::from(10u64.pow((decimals as usize - HINT_DECIMALS) as u32));
+            Some(format!(
+                "~{} {}",
+                erc20::format_amount(truncated, HINT_DECIMALS as u8),
+                unit
+            ))
+        }
+        _ => Some(format!("{} {}", amount, unit)),
+    }
+}
+
+/// Renders an integer as a UTC date if its name suggests a timestamp and it
+/// falls within [`TIMESTAMPS`].
+fn timestamp_hint(n: U256, name: &str) -> Option<String> {
+    let lowercase = name.to_lowercase();
+    let named = TIMESTAMP_NAMES
+        .iter()
+        .any(|fragment| lowercase.contains(fragment))
+        || name.ends_with("At")
+        || lowercase.ends_with("_at");
+    if !named {
+        return None;
+    }
+    let seconds = u64::try_from(n).ok().filter(|s| TIMESTAMPS.contains(s))?;
+    let date = humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(seconds));
+    Some(date.to_string().replacen('T', " ", 1).replace('Z', " UTC"))
+}
+
+/// Collects the addresses found in values, at any depth, without duplicates.
+///
+/// # Arguments
+///
+/// * `values` - The values
+///
+/// # Returns
+///
+/// * `Vec<Address>` - The addresses, in order of first appearance
+pub fn addresses<'a>(values: impl IntoIterator<Item = &'a DynSolValue>) -> Vec<Address> {
+    let mut found = Vec::new();
+    for value in values {
+        match value {
+            DynSolValue::Address(address) if !found.contains(address) => found.push(*address),
+            DynSolValue::Array(values)
+            | DynSolValue::FixedArray(values)
+            | DynSolValue::Tuple(values)
+            | DynSolValue::CustomStruct { tuple: values, .. } => {
+                for address in addresses(values) {
+                    if !found.contains(&address) {
+                        found.push(address);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::json_abi::Function;
+    use alloy::primitives::Bytes;
+
+    #[test]
+    fn test_format_values() {
+        let uint = |n: u128| DynSolValue::Uint(U256::from(n), 256);
+        assert_eq!(format_scalar(&uint(42), "balance"), "42");
+        assert_eq!(
+            format_scalar(&uint(1_500_000_000_000_000_000), "balance"),
+            "1500000000000000000 (1.5 ether)"
+        );
+        assert_eq!(
+            format_scalar(&uint(30_000_000_000), "gasPrice"),
+            "30000000000 (30 gwei)"
+        );
+        assert_eq!(
+            format_scalar(&uint(1_234_567_890_123_456_789), ""),
+            "1234567890123456789 (~1.234567 ether)"
+        );
+        assert_eq!(
+            format_scalar(&DynSolValue::Uint(U256::MAX, 256), "allowance"),
+            format!("{} (max uint256)", U256::MAX)
+        );
+        assert_eq!(
+            format_scalar(&uint(1_700_000_000), "deadline"),
+            "1700000000 (2023-11-14 22:13:20 UTC)"
+        );
+        assert_eq!(
+            format_scalar(&uint(1_700_000_000), "amount"),
+            "1700000000 (1.7 gwei)"
+        );
+        assert_eq!(
+            format_scalar(&DynSolValue::Bytes(vec![0xab, 0xcd]), ""),
+            "0xabcd"
+        );
+
+        let function: Function = serde_json::from_value(serde_json::json!({
+            "type": "function",
+            "name": "getOrder",
+            "inputs": [],
+            "outputs": [{
+                "name": "order",
+                "type": "tuple",
+                "components": [
+                    { "name": "maker", "type": "address" },
+                    { "name": "amounts", "type": "uint256[]" },
+                    { "name": "createdAt", "type": "uint64" }
+                ]
+            }],
+            "stateMutability": "view"
+        }))
+        .unwrap();
+        let maker = Address::repeat_byte(0xaa);
+        let order = DynSolValue::Tuple(vec![
+            DynSolValue::Address(maker),
+            DynSolValue::Array(vec![uint(1), uint(2)]),
+            DynSolValue::Uint(U256::from(1_700_000_000), 64),
+        ]);
+        let result = crate::result::CallResult::new(&function, vec![order.clone()], Bytes::new());
+        assert_eq!(
+            format_field("order", &order, function.outputs.first()),
+            format!(
+                "order:\n  maker: {}\n  amounts:\n    [0]: 1\n    [1]: 2\n  createdAt: 1700000000 (2023-11-14 22:13:20 UTC)",
+                maker.to_checksum(None)
+            )
+        );
+        assert_eq!(result.to_string().lines().count(), 6);
+        assert_eq!(
+            format_field("empty", &DynSolValue::Array(vec![]), None),
+            "empty: []"
+        );
+        assert_eq!(
+            addresses([&order, &DynSolValue::Address(maker)]),
+            vec![maker]
+        );
+    }
+}
@@ -0,0 +1,348 @@
+/// src/hooks.rs
+use crate::error::{Error, Result};
+use crate::transaction::TransactionOptions;
+use alloy::primitives::{Address, Bytes};
+use alloy::rpc::types::TransactionReceipt;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+/// A transaction about to be signed and broadcast.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PendingSend {
+    /// Address the transaction is sent from
+    pub from: Address,
+    /// Target contract address
+    pub to: Address,
+    /// Chain the transaction is signed for
+    pub chain_id: u64,
+    /// Signature of the called function, or what a transaction carrying raw
+    /// calldata does
+    pub function: String,
+    /// Encoded calldata
+    pub calldata: Bytes,
+}
+
+/// What a pre-send hook decided.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookDecision {
+    /// Let the transaction go through, with any option changes applied
+    Proceed,
+    /// Block the transaction for the given reason
+    Veto(String),
+}
+
+/// Callback invoked around transaction broadcast.
+#[async_trait]
+pub trait TransactionHook: Send + Sync {
+    /// Returns a short label identifying the hook in logs and errors.
+    fn name(&self) -> &str;
+
+    /// Runs before a transaction is broadcast. The hook may change the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction about to be sent
+    /// * `options` - The transaction options, which the hook may modify
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HookDecision>` - Whether the transaction may proceed, or an error
+    async fn before_send(
+        &self,
+        tx: &PendingSend,
+        options: &mut TransactionOptions,
+    ) -> Result<HookDecision> {
+        let _ = (tx, options);
+        Ok(HookDecision::Proceed)
+    }
+
+    /// Runs once the receipt of a broadcast transaction is available.
+    ///
+    /// # Arguments
+    ///
+    /// * `receipt` - The transaction receipt
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error, which is logged but does not fail the send
+    async fn after_receipt(&self, receipt: &TransactionReceipt) -> Result<()> {
+        let _ = receipt;
+        Ok(())
+    }
+}
+
+/// Ordered list of transaction hooks.
+///
+/// Cloning is cheap and clones share the registered hooks.
+#[derive(Clone, Default)]
+pub struct Hooks {
+    hooks: Vec<Arc<dyn TransactionHook>>,
+}
+
+impl std::fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.hooks.iter().map(|h| h.name()))
+            .finish()
+    }
+}
+
+impl Hooks {
+    /// Creates an empty hook list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a hook, run after the ones already registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The hook to register
+    pub fn register<H: TransactionHook + 'static>(&mut self, hook: H) {
+        self.hooks.push(Arc::new(hook));
+    }
+
+    /// Returns the number of registered hooks.
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// Returns whether no hook is registered.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Runs every pre-send hook in order, stopping at the first veto.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction about to be sent
+    /// * `options` - The transaction options, which hooks may modify
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success, `Error::Vetoed` if a hook blocked the transaction, or an error
+    pub async fn before_send(
+        &self,
+        tx: &PendingSend,
+        options: &mut TransactionOptions,
+    ) -> Result<()> {
+        for hook in &self.hooks {
+            if let HookDecision::Veto(reason) = hook.before_send(tx, options).await? {
+                return Err(Error::Vetoed {
+                    hook: hook.name().to_string(),
+                    reason,
+                });
+            }
+        }
+        options.validate()
+    }
+
+    /// Runs every post-receipt hook in order. Failures are logged and skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `receipt` - The transaction receipt
+    pub async fn after_receipt(&self, receipt: &TransactionReceipt) {
+        for hook in &self.hooks {
+            if let Err(e) = hook.after_receipt(receipt).await {
+                tracing::warn!(hook = hook.name(), error = %e, "post-receipt hook failed");
+            }
+        }
+    }
+}
+
+/// When a [`CommandHook`] runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookStage {
+    /// Before the transaction is broadcast
+    PreSend,
+    /// After the transaction receipt is available
+    PostReceipt,
+}
+
+/// Hook configured in `config.toml` as an external command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookConfig {
+    /// When the command runs
+    pub stage: HookStage,
+    /// Shell command to run
+    pub command: String,
+}
+
+/// Hook running an external command through `sh -c`.
+///
+/// The command receives a JSON document on stdin: `{"stage", "transaction",
+/// "options"}` before sending, `{"stage", "receipt"}` after the receipt. A
+/// pre-send command vetoes the transaction by exiting with a non-zero status,
+/// its stderr being used as the reason, and may change the options by
+/// printing a JSON object on stdout; the fields it leaves out are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandHook {
+    stage: HookStage,
+    command: String,
+}
+
+impl CommandHook {
+    /// Creates a hook running a command at the given stage.
+    pub fn new(stage: HookStage, command: &str) -> Self {
+        Self {
+            stage,
+            command: command.to_string(),
+        }
+    }
+
+    /// Runs the command with a JSON document on stdin. A command exiting
+    /// without reading it is judged by its exit status alone.
+    async fn run(&self, input: serde_json::Value) -> Result<std::process::Output> {
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Err(e) = stdin.write_all(input.to_string().as_bytes()).await {
+                if e.kind() != std::io::ErrorKind::BrokenPipe {
+                    return Err(e.into());
+                }
+            }
+        }
+        Ok(child.wait_with_output().await?)
+    }
+}
+
+impl From<&HookConfig> for CommandHook {
+    fn from(config: &HookConfig) -> Self {
+        Self::new(config.stage, &config.command)
+    }
+}
+
+#[async_trait]
+impl TransactionHook for CommandHook {
+    fn name(&self) -> &str {
+        &self.command
+    }
+
+    async fn before_send(
+        &self,
+        tx: &PendingSend,
+        options: &mut TransactionOptions,
+    ) -> Result<HookDecision> {
+        if self.stage != HookStage::PreSend {
+            return Ok(HookDecision::Proceed);
+        }
+        let output = self
+            .run(json!({ "stage": self.stage, "transaction": tx, "options": options }))
+            .await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Ok(HookDecision::Veto(if stderr.is_empty() {
+                output.status.to_string()
+            } else {
+                stderr
+            }));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !stdout.trim().is_empty() {
+            let invalid = |e: serde_json::Error| {
+                Error::Other(format!(
+                    "Hook {} printed invalid options: {}",
+                    self.command, e
+                ))
+            };
+            let changes: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_str(&stdout).map_err(invalid)?;
+            let mut merged = serde_json::to_value(&*options)?;
+            if let Some(fields) = merged.as_object_mut() {
+                fields.extend(changes);
+            }
+            *options = serde_json::from_value(merged).map_err(invalid)?;
+        }
+        Ok(HookDecision::Proceed)
+    }
+
+    async fn after_receipt(&self, receipt: &TransactionReceipt) -> Result<()> {
+        if self.stage != HookStage::PostReceipt {
+            return Ok(());
+        }
+        let output = self
+            .run(json!({ "stage": self.stage, "receipt": receipt }))
+            .await?;
+        if !output.status.success() {
+            return Err(Error::Other(format!(
+                "Hook {} failed: {}",
+                self.command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    fn pending() -> PendingSend {
+        PendingSend {
+            from: Address::ZERO,
+            to: Address::ZERO,
+            chain_id: 1,
+            function: "transfer(address,uint256)".to_string(),
+            calldata: Bytes::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_command_hooks_veto_and_mutate() {
+        let mut options = TransactionOptions::new()
+            .with_value(U256::from(5))
+            .with_nonce(7);
+
+        let mut hooks = Hooks::new();
+        hooks.register(CommandHook::new(
+            HookStage::PreSend,
+            r#"cat > /dev/null; echo '{"gas_limit": 21000}'"#,
+        ));
+        hooks.before_send(&pending(), &mut options).await.unwrap();
+        assert_eq!(options.gas_limit(), Some(21_000));
+        assert_eq!(options.value(), U256::from(5));
+        assert_eq!(options.nonce(), Some(7));
+
+        hooks.register(CommandHook::new(
+            HookStage::PreSend,
+            "cat > /dev/null; echo 'outside business hours' >&2; exit 1",
+        ));
+        let err = hooks
+            .before_send(&pending(), &mut options)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Vetoed { reason, .. } if reason == "outside business hours"));
+    }
+
+    #[tokio::test]
+    async fn test_command_hook_veto_without_reading_stdin() {
+        let tx = PendingSend {
+            calldata: Bytes::from(vec![0; 1 << 20]),
+            ..pending()
+        };
+        let mut hooks = Hooks::new();
+        hooks.register(CommandHook::new(
+            HookStage::PreSend,
+            "echo 'all transactions denied' >&2; exit 1",
+        ));
+        let err = hooks
+            .before_send(&tx, &mut TransactionOptions::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Vetoed { reason, .. } if reason == "all transactions denied"));
+    }
+}
@@ -0,0 +1,287 @@
+/// src/journal.rs
+use crate::error::Result;
+use crate::result::ExecutionResult;
+use alloy::primitives::{Address, TxHash, U256};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Where a journaled transaction stood when it was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalStatus {
+    /// Broadcast, its receipt not available yet
+    Submitted,
+    /// Included and succeeded
+    Confirmed,
+    /// Included and reverted
+    Reverted,
+}
+
+impl fmt::Display for JournalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalStatus::Submitted => write!(f, "submitted"),
+            JournalStatus::Confirmed => write!(f, "confirmed"),
+            JournalStatus::Reverted => write!(f, "reverted"),
+        }
+    }
+}
+
+/// A sent transaction, one line of `history.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// When the transaction was sent, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Chain the transaction was sent on
+    pub chain_id: u64,
+    /// Name of the contract's ABI
+    pub contract: String,
+    /// Target contract address
+    pub address: Address,
+    /// Signature of the called function
+    pub function: String,
+    /// Arguments as entered, address book aliases resolved
+    pub args: Vec<String>,
+    /// Value sent along, in wei
+    #[serde(default)]
+    pub value: U256,
+    /// Hash of the transaction
+    pub hash: TxHash,
+    /// Status when recorded
+    pub status: JournalStatus,
+    /// Gas used, once included
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+}
+
+impl JournalEntry {
+    /// Describes a sent transaction, from the result of sending it.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - Chain the transaction was sent on
+    /// * `contract` - Name of the contract's ABI
+    /// * `address` - Target contract address
+    /// * `function` - Signature of the called function
+    /// * `args` - Arguments as entered
+    /// * `value` - Value sent along, in wei
+    /// * `result` - The result of sending the transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Option<JournalEntry>` - The entry, or `None` when nothing was broadcast
+    ///   (calls, proposals, exports and cancellations)
+    pub fn from_result(
+        chain_id: u64,
+        contract: &str,
+        address: Address,
+        function: &str,
+        args: &[String],
+        value: U256,
+        result: &ExecutionResult,
+    ) -> Option<Self> {
+        let (hash, status, gas_used) = match result {
+            ExecutionResult::Submitted { hash } => (*hash, JournalStatus::Submitted, None),
+            ExecutionResult::Confirmed(receipt) => (
+                receipt.transaction_hash,
+                match receipt.status() {
+                    true => JournalStatus::Confirmed,
+                    false => JournalStatus::Reverted,
+                },
+                Some(receipt.gas_used),
+            ),
+            ExecutionResult::Call(_)
+            | ExecutionResult::Proposed { .. }
+            | ExecutionResult::Exported(_)
+            | ExecutionResult::Simulated(_)
+            | ExecutionResult::Cancelled => return None,
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Some(Self {
+            timestamp,
+            chain_id,
+            contract: contract.to_string(),
+            address,
+            function: function.to_string(),
+            args: args.to_vec(),
+            value,
+            hash,
+            status,
+            gas_used,
+        })
+    }
+
+    /// Returns when the transaction was sent, as an RFC 3339 date.
+    pub fn date(&self) -> String {
+        humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(self.timestamp))
+            .to_string()
+    }
+
+    /// Returns the call as `Contract.function(args)`, arguments included, or
+    /// as `label to address` for a transaction recorded without a contract.
+    pub fn call(&self) -> String {
+        if self.contract.is_empty() {
+            return format!("{} to {}", self.function, self.address);
+        }
+        let name = self.function.split('(').next().unwrap_or(&self.function);
+        format!("{}.{}({})", self.contract, name, self.args.join(", "))
+    }
+}
+
+impl fmt::Display for JournalEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {}  {}  chain {}",
+            self.date(),
+            self.call(),
+            self.status,
+            self.chain_id
+        )
+    }
+}
+
+/// Append-only log of the transactions sent, one JSON object per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Opens the journal stored at a path, which need not exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the `history.jsonl` file
+    ///
+    /// # Returns
+    ///
+    /// * `Journal` - The journal
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry, creating the file if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The entry to record
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error during writing
+    pub fn append(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Reads every entry, oldest first. Lines that cannot be parsed, e.g. one
+    /// cut short by a crash, are skipped with a warning.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<JournalEntry>>` - The entries, empty when nothing was recorded yet
+    pub fn load(&self) -> Result<Vec<JournalEntry>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(|(i, line)| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    tracing::warn!(line = i + 1, error = %e, "skipping unreadable history entry");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_load() {
+        let path =
+            std::env::temp_dir().join(format!("inachus-history-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = Journal::new(&path);
+        assert!(journal.load().unwrap().is_empty());
+
+        let address = Address::repeat_byte(0x11);
+        let args = vec![Address::repeat_byte(0x22).to_string(), "100".to_string()];
+        let submitted = ExecutionResult::Submitted {
+            hash: TxHash::repeat_byte(0x33),
+        };
+        let entry = JournalEntry::from_result(
+            1,
+            "Token",
+            address,
+            "transfer(address,uint256)",
+            &args,
+            U256::ZERO,
+            &submitted,
+        )
+        .unwrap();
+        assert_eq!(entry.status, JournalStatus::Submitted);
+        assert_eq!(entry.call(), format!("Token.transfer({}, 100)", args[0]));
+        let transfer = JournalEntry::from_result(
+            1,
+            "",
+            address,
+            "Native transfer",
+            &[],
+            U256::from(5),
+            &submitted,
+        )
+        .unwrap();
+        assert_eq!(transfer.call(), format!("Native transfer to {}", address));
+        assert!(JournalEntry::from_result(
+            1,
+            "Token",
+            address,
+            "transfer(address,uint256)",
+            &args,
+            U256::ZERO,
+            &ExecutionResult::Cancelled,
+        )
+        .is_none());
+
+        journal.append(&entry).unwrap();
+        std::fs::write(
+            &path,
+            std::fs::read_to_string(&path).unwrap() + "{\"truncated\n",
+        )
+        .unwrap();
+        journal.append(&entry).unwrap();
+        assert_eq!(journal.load().unwrap(), vec![entry.clone(), entry]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
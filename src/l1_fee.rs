@@ -0,0 +1,141 @@
+/// src/l1_fee.rs
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use crate::offline::UnsignedTransaction;
+use crate::transaction::TransactionPreview;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::Function;
+use alloy::primitives::{address, Address, U256};
+use chain_info::RollupStack;
+
+/// OP-stack `GasPriceOracle` predeploy.
+pub const GAS_PRICE_ORACLE: Address = address!("420000000000000000000000000000000000000F");
+
+/// Arbitrum `NodeInterface` precompile, only reachable through `eth_call`.
+pub const NODE_INTERFACE: Address = address!("00000000000000000000000000000000000000C8");
+
+/// The fee a rollup charges for posting a transaction's data to L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1DataFee {
+    /// Rollup stack of the chain
+    pub stack: RollupStack,
+    /// Estimated fee, in wei
+    pub fee: U256,
+    /// L2 gas the fee is charged as, part of the gas limit, on Arbitrum;
+    /// `None` when it is charged on top of execution, on the OP-stack
+    pub gas: Option<u64>,
+}
+
+/// Estimates the L1 data fee of a transaction on a rollup, with
+/// `GasPriceOracle.getL1Fee` on the OP-stack and
+/// `NodeInterface.gasEstimateL1Component` on Arbitrum.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the rollup
+/// * `stack` - Rollup stack of the chain
+/// * `preview` - The transaction as it would be sent
+///
+/// # Returns
+///
+/// * `Result<L1DataFee>` - The fee, or an error if the oracle could not be called
+pub async fn estimate(
+    read: &ReadContext,
+    stack: RollupStack,
+    preview: &TransactionPreview,
+) -> Result<L1DataFee> {
+    match stack {
+        RollupStack::OpStack => {
+            let get_l1_fee = Function::parse("getL1Fee(bytes) returns (uint256)")
+                .map_err(|e| Error::InvalidFunction(e.to_string()))?;
+            // The oracle prices the unsigned transaction, padding it for the signature.
+            let rlp = UnsignedTransaction::from_preview(preview).rlp();
+            let result = read
+                .call(
+                    GAS_PRICE_ORACLE,
+                    &get_l1_fee,
+                    &[DynSolValue::Bytes(rlp.to_vec())],
+                )
+                .await?;
+            match result.values.first() {
+                Some(DynSolValue::Uint(fee, _)) => Ok(L1DataFee {
+                    stack,
+                    fee: *fee,
+                    gas: None,
+                }),
+                _ => Err(Error::InvalidAbi(
+                    "getL1Fee returned an unexpected value".to_string(),
+                )),
+            }
+        }
+        RollupStack::Arbitrum => {
+            let estimate_l1 = Function::parse(
+                "gasEstimateL1Component(address,bool,bytes) returns (uint64,uint256,uint256)",
+            )
+            .map_err(|e| Error::InvalidFunction(e.to_string()))?;
+            let args = [
+                DynSolValue::Address(preview.to),
+                DynSolValue::Bool(false),
+                DynSolValue::Bytes(preview.calldata.to_vec()),
+            ];
+            let result = read.call(NODE_INTERFACE, &estimate_l1, &args).await?;
+            match result.values.as_slice() {
+                [DynSolValue::Uint(gas, _), DynSolValue::Uint(base_fee, _), _] => Ok(L1DataFee {
+                    stack,
+                    fee: gas * base_fee,
+                    gas: Some(gas.saturating_to()),
+                }),
+                _ => Err(Error::InvalidAbi(
+                    "gasEstimateL1Component returned an unexpected value".to_string(),
+                )),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Bytes;
+
+    #[test]
+    fn test_l1_data_fee_in_preview() {
+        let mut preview = TransactionPreview {
+            chain_id: 8453,
+            from: Address::repeat_byte(0x22),
+            to: Address::repeat_byte(0x11),
+            function: "deposit()".to_string(),
+            args: vec![],
+            calldata: Bytes::from_static(&[0xd0, 0xe3, 0x0d, 0xb0]),
+            value: U256::ZERO,
+            nonce: 0,
+            gas_limit: 50_000,
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: Some(1),
+            l1_data_fee: None,
+        };
+        assert!(!text::strip_ansi(&preview.to_string()).contains("L1 data"));
+
+        preview.l1_data_fee = Some(L1DataFee {
+            stack: RollupStack::OpStack,
+            fee: U256::from(200_000),
+            gas: None,
+        });
+        assert_eq!(preview.execution_cost(), U256::from(500_000));
+        assert_eq!(preview.max_cost(), U256::from(700_000));
+        let rendered = text::strip_ansi(&preview.to_string());
+        assert!(rendered.contains("L1 data"));
+        assert!(rendered.contains("(OP-stack)"));
+
+        // Arbitrum charges L1 data as gas already counted in the limit.
+        preview.chain_id = 42161;
+        preview.l1_data_fee = Some(L1DataFee {
+            stack: RollupStack::Arbitrum,
+            fee: U256::from(80_000),
+            gas: Some(10_000),
+        });
+        assert_eq!(preview.execution_cost(), U256::from(400_000));
+        assert_eq!(preview.max_cost(), U256::from(500_000));
+        assert!(text::strip_ansi(&preview.to_string()).contains("as 10000 gas of the limit"));
+    }
+}
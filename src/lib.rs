@@ -1,15 +1,71 @@
 /// src/lib.rs
 pub mod abi;
+pub mod address_book;
+pub mod approval;
+pub mod batch;
+pub mod bytecode;
+pub mod cancel;
+pub mod client;
+pub mod codec;
 pub mod config;
 pub mod context;
+pub mod create2;
+pub mod cross_check;
+pub mod daemon;
+pub mod editor;
+pub mod ens;
+pub mod erc20;
 pub mod error;
+pub mod events;
+pub mod export;
+pub mod fork;
+pub mod format;
+pub mod hooks;
+pub mod journal;
+pub mod l1_fee;
+pub mod message;
+pub mod migrate;
+pub mod multicall;
+pub mod nft;
+pub mod nonce;
+pub mod offline;
+pub mod output;
+pub mod params;
+pub mod permit;
+pub mod policy;
+#[cfg(feature = "interactive")]
 pub mod prompt;
+pub mod proxy;
+pub mod relay;
+pub mod result;
+pub mod safe;
+pub mod script;
+pub mod server;
+#[cfg(feature = "interactive")]
+pub mod setup;
+pub mod signer;
+pub mod status;
 pub mod step;
+#[cfg(feature = "interactive")]
+pub mod steps;
+pub mod testing;
+pub mod trace;
+pub mod transaction;
+pub mod transport;
+pub mod usage;
 pub mod validation;
+pub mod wallet;
 
 use error::Result;
 
-pub use context::{GlobalContext, ReadContext, WriteContext};
+pub use address_book::AddressBook;
+pub use client::ContractClient;
+pub use context::{GlobalContext, GlobalContextBuilder, ReadContext, WriteContext};
+pub use events::{Event, EventBus};
+pub use result::{CallDiff, CallResult, EventLog, ExecutionResult};
+pub use signer::{InachusSigner, Signer};
+pub use step::{StepRegistry, WorkflowStep};
+pub use transaction::{GasConfig, TransactionOptions, TransactionPreview, TxType};
 
 /// Directory name for storing Inachus configuration and data.
 pub const INACHUS_DIR: &str = ".inachus";
@@ -17,6 +73,36 @@ pub const INACHUS_DIR: &str = ".inachus";
 /// Directory name for storing ABI files.
 pub const ABI_DIR: &str = "abis";
 
+/// File name of the configuration inside the Inachus directory.
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// File name of the known contract deployments inside the Inachus directory.
+pub const CONTRACTS_FILE: &str = "contracts.json";
+
+/// File name of the sent transaction journal inside the Inachus directory.
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// File name of the method usage statistics inside the Inachus directory.
+pub const USAGE_FILE: &str = "usage.json";
+
+/// File name of the cached chainid.network list inside the Inachus directory.
+pub const CHAINS_FILE: &str = "chains.json";
+
+/// File name of the user's custom chains inside the Inachus directory, merged
+/// over the chainid.network list.
+pub const CUSTOM_CHAINS_FILE: &str = "custom_chains.json";
+
+/// Returns the Inachus data directory, `~/.inachus` when a home directory exists.
+///
+/// # Returns
+///
+/// * `PathBuf` - Path to the Inachus directory
+pub fn data_dir() -> std::path::PathBuf {
+    dirs::home_dir()
+        .map(|home| home.join(INACHUS_DIR))
+        .unwrap_or_else(|| std::path::PathBuf::from(INACHUS_DIR))
+}
+
 /// Initializes the application environment, particularly logging.
 ///
 /// # Returns
@@ -29,6 +115,7 @@ pub fn init() -> Result<()> {
         .with_env_filter(
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
         )
+        .with_writer(std::io::stderr)
         .init();
 
     Ok(())
@@ -0,0 +1,153 @@
+/// src/message.rs
+use crate::error::{Error, Result};
+use crate::signer::Signer;
+use alloy::primitives::{hex, keccak256, Address, Bytes, PrimitiveSignature, B256};
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+
+/// A message signed with the EIP-191 `personal_sign` prefix, together with
+/// the address that signed it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedMessage {
+    /// The raw message bytes, before prefixing
+    pub message: Bytes,
+    /// The 65-byte signature
+    pub signature: PrimitiveSignature,
+    /// The address that signed the message
+    pub signer: Address,
+}
+
+/// Reads a message typed by the user: `0x`-prefixed hex is taken as raw
+/// bytes, anything else as UTF-8 text.
+///
+/// # Arguments
+///
+/// * `input` - The message as typed
+///
+/// # Returns
+///
+/// * `Bytes` - The message bytes
+pub fn parse_message(input: &str) -> Bytes {
+    match input.starts_with("0x") {
+        true => hex::decode(input)
+            .map(Bytes::from)
+            .unwrap_or_else(|_| Bytes::copy_from_slice(input.as_bytes())),
+        false => Bytes::copy_from_slice(input.as_bytes()),
+    }
+}
+
+/// Returns the EIP-712 digest of a struct under a domain.
+///
+/// # Arguments
+///
+/// * `domain_separator` - Hash of the EIP-712 domain
+/// * `struct_hash` - Hash of the typed struct
+///
+/// # Returns
+///
+/// * `B256` - `keccak256(0x1901 ‖ domain_separator ‖ struct_hash)`
+pub fn eip712_digest(domain_separator: B256, struct_hash: B256) -> B256 {
+    let mut payload = Vec::with_capacity(66);
+    payload.extend_from_slice(&[0x19, 0x01]);
+    payload.extend_from_slice(domain_separator.as_slice());
+    payload.extend_from_slice(struct_hash.as_slice());
+    keccak256(payload)
+}
+
+impl SignedMessage {
+    /// Signs a message with the active signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - The signer to sign with
+    /// * `message` - The raw message bytes
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SignedMessage>` - The signed message or a signing error
+    pub async fn sign(signer: &Signer, message: Bytes) -> Result<Self> {
+        let signature = signer.sign_message(&message).await?;
+        Ok(Self {
+            message,
+            signature,
+            signer: signer.address(),
+        })
+    }
+
+    /// Recovers the address that signed a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The raw message bytes
+    /// * `signature` - The 65-byte signature as hex
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SignedMessage>` - The message with its signer, or an error if the signature is malformed
+    pub fn recover(message: Bytes, signature: &str) -> Result<Self> {
+        let signature = PrimitiveSignature::from_str(signature.trim())
+            .map_err(|e| Error::InvalidArguments(format!("Invalid signature: {}", e)))?;
+        let signer = signature
+            .recover_address_from_msg(&message)
+            .map_err(|e| Error::InvalidArguments(format!("Cannot recover signer: {}", e)))?;
+        Ok(Self {
+            message,
+            signature,
+            signer,
+        })
+    }
+
+    /// Converts the signed message into JSON.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "signer": self.signer.to_string(),
+            "message": self.message.to_string(),
+            "signature": hex::encode_prefixed(self.signature.as_bytes()),
+        })
+    }
+}
+
+impl fmt::Display for SignedMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match std::str::from_utf8(&self.message) {
+            Ok(text) => format!("{:?}", text),
+            Err(_) => self.message.to_string(),
+        };
+        writeln!(f, "Message: {}", message)?;
+        writeln!(f, "Signer: {}", self.signer)?;
+        write!(
+            f,
+            "Signature: {}",
+            hex::encode_prefixed(self.signature.as_bytes())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TEST_PRIVATE_KEY;
+    use alloy::signers::local::PrivateKeySigner;
+
+    #[tokio::test]
+    async fn test_sign_and_recover_message() {
+        let key = PrivateKeySigner::from_str(TEST_PRIVATE_KEY).unwrap();
+        let signer = Signer::from(key.clone());
+
+        let signed = SignedMessage::sign(&signer, parse_message("hello"))
+            .await
+            .unwrap();
+        assert_eq!(signed.signer, key.address());
+        let signature = signed.to_json()["signature"].as_str().unwrap().to_string();
+
+        let recovered = SignedMessage::recover(parse_message("hello"), &signature).unwrap();
+        assert_eq!(recovered, signed);
+        let other = SignedMessage::recover(parse_message("hello!"), &signature).unwrap();
+        assert_ne!(other.signer, key.address());
+
+        assert_eq!(parse_message("0x1234"), Bytes::from(vec![0x12, 0x34]));
+        assert_eq!(parse_message("0xzz"), Bytes::from(b"0xzz".to_vec()));
+        assert!(SignedMessage::recover(parse_message("hello"), "0x12").is_err());
+    }
+}
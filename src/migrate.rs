@@ -0,0 +1,199 @@
+/// src/migrate.rs
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Schema version of `config.toml` and the contracts files it refers to
+/// written by this version of Inachus.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Version of configuration files predating the `version` field.
+const UNVERSIONED: u32 = 1;
+
+/// An upgrade of the configuration schema to the next version.
+struct Migration {
+    /// Version the migration upgrades to
+    to: u32,
+    /// Upgrades the configuration in place, and the files it refers to
+    apply: fn(&mut toml_edit::DocumentMut, &Path) -> Result<()>,
+}
+
+/// Migrations in order of the version they upgrade to.
+const MIGRATIONS: [Migration; 1] = [Migration {
+    to: 2,
+    apply: per_chain_deployments,
+}];
+
+/// A configuration file upgraded by [`migrate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migrated {
+    /// Version the file was at
+    pub from: u32,
+    /// Copy of the file before the upgrade
+    pub backup: PathBuf,
+}
+
+/// Upgrades a configuration file to [`CONFIG_VERSION`] in place, along with
+/// the contracts files it refers to, keeping a copy of each file changed
+/// next to it, e.g. `config.toml.v1.bak`.
+///
+/// # Arguments
+///
+/// * `path` - The configuration file
+/// * `data_dir` - The Inachus data directory, which contracts files are relative to
+///
+/// # Returns
+///
+/// * `Result<Option<Migrated>>` - The upgrade, `None` if the file is up to date,
+///   or an error if it is malformed or was written by a newer version
+pub fn migrate(path: &Path, data_dir: &Path) -> Result<Option<Migrated>> {
+    let mut doc = fs::read_to_string(path)?
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| Error::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+    let version = match doc.get("version") {
+        None => UNVERSIONED,
+        Some(version) => version
+            .as_integer()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                Error::InvalidConfig(format!("{}: version must be a number", path.display()))
+            })?,
+    };
+    if version > CONFIG_VERSION {
+        return Err(Error::InvalidConfig(format!(
+            "{} is at version {}, newer than the {} this version of Inachus reads",
+            path.display(),
+            version,
+            CONFIG_VERSION
+        )));
+    }
+    if version == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    let backup = backup(path, version)?;
+    for migration in MIGRATIONS.iter().filter(|migration| migration.to > version) {
+        (migration.apply)(&mut doc, data_dir)?;
+    }
+    doc["version"] = toml_edit::value(CONFIG_VERSION as i64);
+    fs::write(path, doc.to_string())?;
+    Ok(Some(Migrated {
+        from: version,
+        backup,
+    }))
+}
+
+/// Copies a file to `<file>.v<version>.bak`.
+fn backup(path: &Path, version: u32) -> Result<PathBuf> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    let backup = path.with_file_name(name);
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+/// Version 2: contracts files map chain IDs to addresses. The single
+/// `address` of each contract becomes its deployment on the chain configured
+/// alongside the file, the profile's chain for a profile's contracts file.
+fn per_chain_deployments(doc: &mut toml_edit::DocumentMut, data_dir: &Path) -> Result<()> {
+    let chain_id = doc
+        .get("chain_id")
+        .and_then(|item| item.as_integer())
+        .unwrap_or(1);
+    let contracts_file = |item: &toml_edit::Item| {
+        item.get("contracts_file")
+            .and_then(|file| file.as_str())
+            .map(str::to_string)
+    };
+    let mut files = vec![(
+        contracts_file(doc.as_item()).unwrap_or_else(|| crate::CONTRACTS_FILE.to_string()),
+        chain_id,
+    )];
+    if let Some(profiles) = doc.get("profiles").and_then(|item| item.as_table_like()) {
+        for (_, profile) in profiles.iter() {
+            if let Some(file) = contracts_file(profile) {
+                let chain_id = profile
+                    .get("chain_id")
+                    .and_then(|item| item.as_integer())
+                    .unwrap_or(chain_id);
+                files.push((file, chain_id));
+            }
+        }
+    }
+
+    for (file, chain_id) in files {
+        let path = data_dir.join(file);
+        if !path.is_file() {
+            continue;
+        }
+        let mut infos: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        let mut changed = false;
+        for info in infos.iter_mut().filter_map(|info| info.as_object_mut()) {
+            let Some(address) = info.remove("address") else {
+                continue;
+            };
+            changed = true;
+            if address.as_str().is_some_and(|address| !address.is_empty()) {
+                let deployments = info
+                    .entry("deployments")
+                    .or_insert_with(|| serde_json::json!({}));
+                if let Some(deployments) = deployments.as_object_mut() {
+                    deployments.entry(chain_id.to_string()).or_insert(address);
+                }
+            }
+        }
+        if changed {
+            backup(&path, UNVERSIONED)?;
+            fs::write(&path, serde_json::to_string_pretty(&infos)?)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, ContractInfo};
+
+    #[test]
+    fn test_migrate_config() {
+        let dir = std::env::temp_dir().join(format!("inachus-migrate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join(crate::CONFIG_FILE);
+        fs::write(
+            &config,
+            "# my settings\nrpc_url = \"https://eth.llamarpc.com\"\nchain_id = 1\n\n[profiles.anvil]\nchain_id = 31337\ncontracts_file = \"contracts.anvil.json\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join(crate::CONTRACTS_FILE),
+            r#"[{ "name": "TetherToken", "address": "0xdAC17F958D2ee523a2206206994597C13D831ec7" }]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("contracts.anvil.json"),
+            r#"[{ "name": "Counter", "address": "0x5FbDB2315678afecb367f032d93F642f64180aa3" }]"#,
+        )
+        .unwrap();
+
+        let migrated = migrate(&config, &dir).unwrap().unwrap();
+        assert_eq!(migrated.from, 1);
+        assert_eq!(migrated.backup, dir.join("config.toml.v1.bak"));
+        assert!(migrate(&config, &dir).unwrap().is_none());
+        let content = fs::read_to_string(&config).unwrap();
+        assert!(content.starts_with("# my settings\n"));
+        assert_eq!(Config::from_file(&config).unwrap().version, CONFIG_VERSION);
+
+        let tether = &ContractInfo::load_all(&dir.join(crate::CONTRACTS_FILE)).unwrap()[0];
+        let counter = &ContractInfo::load_all(&dir.join("contracts.anvil.json")).unwrap()[0];
+        assert!(dir.join("contracts.json.v1.bak").is_file());
+        assert_eq!(tether.chains(), vec![1]);
+        assert!(tether.address.is_empty());
+        assert_eq!(counter.chains(), vec![31337]);
+
+        fs::write(&config, "version = 99\n").unwrap();
+        let newer = migrate(&config, &dir);
+        let _ = fs::remove_dir_all(&dir);
+        assert!(newer.is_err());
+    }
+}
@@ -0,0 +1,122 @@
+/// src/multicall.rs
+use crate::codec;
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use crate::result::CallResult;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::Function;
+use alloy::primitives::{address, Address, Bytes};
+
+/// Address of the Multicall3 contract, identical on every chain it is deployed to.
+pub const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// A read-only call to batch through Multicall3.
+#[derive(Debug, Clone)]
+pub struct Call<'a> {
+    /// Address of the called contract
+    pub target: Address,
+    /// The function to call
+    pub function: &'a Function,
+    /// One value per function input
+    pub args: Vec<DynSolValue>,
+}
+
+/// Executes read-only calls in a single `eth_call` through Multicall3's `aggregate3`.
+///
+/// A failing call does not fail the batch; its slot holds the revert instead.
+///
+/// # Arguments
+///
+/// * `read` - The read context used to call Multicall3
+/// * `calls` - The calls to batch
+///
+/// # Returns
+///
+/// * `Result<Vec<Result<CallResult>>>` - One result per call in order, or an error if the batch itself failed
+pub async fn aggregate(read: &ReadContext, calls: &[Call<'_>]) -> Result<Vec<Result<CallResult>>> {
+    let aggregate3 = Function::parse(
+        "aggregate3((address,bool,bytes)[] calls) payable returns ((bool,bytes)[] returnData)",
+    )
+    .map_err(|e| Error::InvalidFunction(e.to_string()))?;
+    let batch = calls
+        .iter()
+        .map(|call| {
+            Ok(DynSolValue::Tuple(vec![
+                DynSolValue::Address(call.target),
+                DynSolValue::Bool(true),
+                DynSolValue::Bytes(codec::encode_values(call.function, &call.args)?.to_vec()),
+            ]))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let result = read
+        .call(
+            MULTICALL3_ADDRESS,
+            &aggregate3,
+            &[DynSolValue::Array(batch)],
+        )
+        .await?;
+    let returned = result
+        .single()
+        .and_then(|value| value.as_array())
+        .filter(|returned| returned.len() == calls.len())
+        .ok_or_else(|| Error::Other("Malformed aggregate3 result".to_string()))?;
+    Ok(calls
+        .iter()
+        .zip(returned)
+        .map(|(call, value)| match value.as_tuple() {
+            Some([DynSolValue::Bool(true), DynSolValue::Bytes(data)]) => {
+                let values = codec::decode_output(call.function, data)?;
+                Ok(CallResult::new(
+                    call.function,
+                    values,
+                    Bytes::copy_from_slice(data),
+                ))
+            }
+            Some([DynSolValue::Bool(false), DynSolValue::Bytes(data)]) => {
+                Err(Error::revert(Bytes::copy_from_slice(data), None))
+            }
+            _ => Err(Error::Other("Malformed aggregate3 result".to_string())),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixtures, MockRpc, TEST_CONTRACT};
+    use alloy::primitives::U256;
+
+    #[tokio::test]
+    async fn test_aggregate_splits_results() {
+        let abi = fixtures::erc20_abi();
+        let total_supply = &abi.function("totalSupply").unwrap()[0];
+        let returned = DynSolValue::Array(vec![
+            DynSolValue::Tuple(vec![
+                DynSolValue::Bool(true),
+                DynSolValue::Bytes(U256::from(42).to_be_bytes_vec()),
+            ]),
+            DynSolValue::Tuple(vec![DynSolValue::Bool(false), DynSolValue::Bytes(vec![])]),
+        ]);
+        let mock = MockRpc::new();
+        mock.respond(
+            "eth_call",
+            Bytes::from(DynSolValue::Tuple(vec![returned]).abi_encode_params()),
+        );
+        let call = Call {
+            target: TEST_CONTRACT,
+            function: total_supply,
+            args: vec![],
+        };
+
+        let results = aggregate(&mock.read_context(), &[call.clone(), call])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            results[0].as_ref().unwrap().single(),
+            Some(&DynSolValue::Uint(U256::from(42), 256))
+        );
+        assert!(matches!(results[1], Err(Error::Revert { .. })));
+        assert_eq!(mock.calls_to("eth_call").len(), 1);
+    }
+}
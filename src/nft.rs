@@ -0,0 +1,356 @@
+/// src/nft.rs
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::{Address, FixedBytes, B256, U256};
+use alloy::transports::http::reqwest;
+use base64::Engine;
+use serde_json::Value;
+use std::fmt;
+use std::time::Duration;
+
+/// Gateway `ipfs://` metadata and image URIs are fetched through.
+pub const IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+/// Gateway `ar://` URIs are fetched through.
+pub const ARWEAVE_GATEWAY: &str = "https://arweave.net/";
+
+/// How long fetching metadata may take.
+const METADATA_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A non-fungible token standard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NftStandard {
+    /// One owner per token, metadata at `tokenURI`
+    Erc721,
+    /// Balances per token and account, metadata at `uri`
+    Erc1155,
+}
+
+impl fmt::Display for NftStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NftStandard::Erc721 => write!(f, "ERC-721"),
+            NftStandard::Erc1155 => write!(f, "ERC-1155"),
+        }
+    }
+}
+
+impl NftStandard {
+    /// Returns the ERC-165 interface ID of the standard.
+    pub fn interface_id(&self) -> FixedBytes<4> {
+        match self {
+            NftStandard::Erc721 => FixedBytes::new([0x80, 0xac, 0x58, 0xcd]),
+            NftStandard::Erc1155 => FixedBytes::new([0xd9, 0xb6, 0x7a, 0x26]),
+        }
+    }
+
+    /// Returns the standard ABI, metadata extension included.
+    pub fn abi(&self) -> JsonAbi {
+        let signatures: &[&str] = match self {
+            NftStandard::Erc721 => &[
+                "function balanceOf(address owner) view returns (uint256)",
+                "function ownerOf(uint256 tokenId) view returns (address)",
+                "function tokenURI(uint256 tokenId) view returns (string)",
+                "function safeTransferFrom(address from, address to, uint256 tokenId)",
+                "function approve(address to, uint256 tokenId)",
+                "function setApprovalForAll(address operator, bool approved)",
+                "event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)",
+            ],
+            NftStandard::Erc1155 => &[
+                "function balanceOf(address account, uint256 id) view returns (uint256)",
+                "function uri(uint256 id) view returns (string)",
+                "function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes data)",
+                "function setApprovalForAll(address operator, bool approved)",
+                "event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value)",
+            ],
+        };
+        JsonAbi::parse(signatures.iter().copied()).expect("the NFT ABIs parse")
+    }
+
+    /// Returns a function of the standard ABI.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The function name, e.g. `ownerOf`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Function>` - The function, or an error if the standard has none by that name
+    pub fn function(&self, name: &str) -> Result<Function> {
+        self.abi()
+            .function(name)
+            .and_then(|functions| functions.first().cloned())
+            .ok_or_else(|| Error::InvalidFunction(format!("{} has no {} function", self, name)))
+    }
+
+    /// Returns the function reading the metadata URI of a token.
+    pub fn uri_function(&self) -> &'static str {
+        match self {
+            NftStandard::Erc721 => "tokenURI",
+            NftStandard::Erc1155 => "uri",
+        }
+    }
+}
+
+/// Recognizes an NFT contract from the functions its ABI declares.
+///
+/// # Arguments
+///
+/// * `abi` - The contract ABI
+///
+/// # Returns
+///
+/// * `Option<NftStandard>` - The standard, or `None` if the ABI matches neither
+pub fn detect(abi: &JsonAbi) -> Option<NftStandard> {
+    let has = |signature: &str| abi.functions().any(|f| f.signature() == signature);
+    if has("ownerOf(uint256)") && has("safeTransferFrom(address,address,uint256)") {
+        Some(NftStandard::Erc721)
+    } else if has("balanceOf(address,uint256)")
+        && has("safeTransferFrom(address,address,uint256,uint256,bytes)")
+    {
+        Some(NftStandard::Erc1155)
+    } else {
+        None
+    }
+}
+
+/// Asks a contract through ERC-165 which NFT standard it supports.
+///
+/// # Arguments
+///
+/// * `read` - Read context of the current chain
+/// * `address` - The contract
+///
+/// # Returns
+///
+/// * `Option<NftStandard>` - The standard, or `None` if the contract supports
+///   neither or does not implement ERC-165
+pub async fn detect_on_chain(read: &ReadContext, address: Address) -> Option<NftStandard> {
+    let supports =
+        Function::parse("function supportsInterface(bytes4) view returns (bool)").ok()?;
+    for standard in [NftStandard::Erc721, NftStandard::Erc1155] {
+        let id = DynSolValue::FixedBytes(
+            B256::right_padding_from(standard.interface_id().as_slice()),
+            4,
+        );
+        if let Ok(result) = read.call(address, &supports, &[id]).await {
+            if result.values.first() == Some(&DynSolValue::Bool(true)) {
+                return Some(standard);
+            }
+        }
+    }
+    None
+}
+
+/// Turns a metadata or image URI into a URL that can be fetched.
+///
+/// `ipfs://` and `ar://` URIs go through [`IPFS_GATEWAY`] and
+/// [`ARWEAVE_GATEWAY`]; the ERC-1155 `{id}` placeholder is replaced with the
+/// token ID as 64 lowercase hex digits. Other URIs are returned as they are.
+///
+/// # Arguments
+///
+/// * `uri` - The URI returned by the contract
+/// * `token_id` - The token the URI describes
+///
+/// # Returns
+///
+/// * `String` - The URL
+pub fn resolve_uri(uri: &str, token_id: U256) -> String {
+    let uri = uri.trim().replace("{id}", &format!("{:064x}", token_id));
+    if let Some(path) = uri.strip_prefix("ipfs://") {
+        format!("{}{}", IPFS_GATEWAY, path.trim_start_matches("ipfs/"))
+    } else if let Some(path) = uri.strip_prefix("ar://") {
+        format!("{}{}", ARWEAVE_GATEWAY, path)
+    } else {
+        uri
+    }
+}
+
+/// Fetches the metadata of a token: over HTTP, or decoded from a `data:`
+/// URI as on-chain collections return.
+///
+/// # Arguments
+///
+/// * `url` - The URL, as returned by [`resolve_uri`]
+///
+/// # Returns
+///
+/// * `Result<Value>` - The metadata JSON, or an error if it cannot be fetched or parsed
+pub async fn fetch_metadata(url: &str) -> Result<Value> {
+    if let Some(data) = url.strip_prefix("data:") {
+        return decode_data_uri(data);
+    }
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(Error::Other(format!("Cannot fetch metadata from {}", url)));
+    }
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(METADATA_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| Error::Other(format!("Failed to fetch {}: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(Error::Other(format!(
+            "{} returned {}",
+            url,
+            response.status()
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| Error::Other(format!("Invalid metadata at {}: {}", url, e)))
+}
+
+/// Decodes the JSON of a `data:` URI, given without its `data:` prefix.
+fn decode_data_uri(data: &str) -> Result<Value> {
+    let (header, payload) = data
+        .split_once(',')
+        .ok_or_else(|| Error::Other("Malformed data URI".to_string()))?;
+    let json = match header.ends_with(";base64") {
+        true => base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .map_err(|e| Error::Other(format!("Invalid base64 metadata: {}", e)))?,
+        false => payload.as_bytes().to_vec(),
+    };
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// The fields of token metadata shown to the user, per the ERC-721 and
+/// ERC-1155 metadata schemas and the common `attributes` extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NftMetadata {
+    /// Name of the token
+    pub name: Option<String>,
+    /// Description of the token
+    pub description: Option<String>,
+    /// Image URI
+    pub image: Option<String>,
+    /// Traits as type and value
+    pub attributes: Vec<(String, String)>,
+}
+
+impl NftMetadata {
+    /// Reads the known fields of metadata JSON, ignoring the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The metadata
+    ///
+    /// # Returns
+    ///
+    /// * `NftMetadata` - The fields found
+    pub fn from_json(json: &Value) -> Self {
+        let text = |value: &Value| match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let string = |key: &str| json.get(key).and_then(Value::as_str).map(str::to_string);
+        let attributes = json
+            .get("attributes")
+            .and_then(Value::as_array)
+            .map(|attributes| {
+                attributes
+                    .iter()
+                    .filter_map(|attribute| {
+                        let value = attribute.get("value")?;
+                        let trait_type = attribute
+                            .get("trait_type")
+                            .map(text)
+                            .unwrap_or_else(|| "-".to_string());
+                        Some((trait_type, text(value)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            name: string("name"),
+            description: string("description"),
+            image: string("image").or_else(|| string("image_url")),
+            attributes,
+        }
+    }
+}
+
+impl fmt::Display for NftMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = Vec::new();
+        if let Some(name) = &self.name {
+            rows.push(("Name".to_string(), name.clone()));
+        }
+        if let Some(description) = &self.description {
+            rows.push(("Description".to_string(), description.clone()));
+        }
+        if let Some(image) = &self.image {
+            let image = match image.starts_with("data:") {
+                true => "(inline)".to_string(),
+                false => image.clone(),
+            };
+            rows.push(("Image".to_string(), image));
+        }
+        for (trait_type, value) in &self.attributes {
+            rows.push((trait_type.clone(), value.clone()));
+        }
+        write!(f, "{}", text::table(&rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_metadata_uris() {
+        let erc1155 = JsonAbi::parse([
+            "function balanceOf(address,uint256) view returns (uint256)",
+            "function safeTransferFrom(address,address,uint256,uint256,bytes)",
+        ])
+        .unwrap();
+        assert_eq!(
+            detect(&NftStandard::Erc721.abi()),
+            Some(NftStandard::Erc721)
+        );
+        assert_eq!(detect(&erc1155), Some(NftStandard::Erc1155));
+        assert_eq!(detect(&JsonAbi::new()), None);
+
+        assert_eq!(
+            resolve_uri("ipfs://ipfs/Qm123/1.json", U256::from(1)),
+            "https://ipfs.io/ipfs/Qm123/1.json"
+        );
+        assert_eq!(
+            resolve_uri("https://example.com/{id}.json", U256::from(0x4ce)),
+            format!("https://example.com/{:0>64}.json", "4ce")
+        );
+
+        let metadata = json!({
+            "name": "Token #1",
+            "image": "ipfs://Qm456",
+            "attributes": [
+                { "trait_type": "Color", "value": "red" },
+                { "trait_type": "Level", "value": 3 },
+                { "display_type": "date" }
+            ]
+        });
+        let encoded = base64::engine::general_purpose::STANDARD.encode(metadata.to_string());
+        let decoded = decode_data_uri(&format!("application/json;base64,{}", encoded)).unwrap();
+        assert_eq!(decoded, metadata);
+        assert_eq!(
+            decode_data_uri(r#"application/json;utf8,{"name":"x"}"#).unwrap()["name"],
+            "x"
+        );
+
+        let parsed = NftMetadata::from_json(&decoded);
+        assert_eq!(parsed.name.as_deref(), Some("Token #1"));
+        assert_eq!(
+            parsed.attributes,
+            vec![
+                ("Color".to_string(), "red".to_string()),
+                ("Level".to_string(), "3".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+/// src/nonce.rs
+use crate::cancel;
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use alloy::primitives::{Address, TxHash};
+use alloy::providers::Provider;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Percentage by which a replacement outbids the transaction it replaces;
+/// nodes require at least 10%.
+pub const REPLACEMENT_BUMP_PERCENT: u128 = 20;
+
+/// A transaction of the account waiting in the node's pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolTransaction {
+    /// Hash of the transaction
+    pub hash: Option<TxHash>,
+    /// Max fee per gas, or gas price for legacy transactions
+    pub max_fee_per_gas: u128,
+    /// Max priority fee per gas, `None` for legacy transactions
+    pub max_priority_fee_per_gas: Option<u128>,
+}
+
+/// A no-op transaction, sent to self with no value, taking a nonce over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Replacement {
+    /// The nonce to take over
+    pub nonce: u64,
+    /// Max fee per gas, in wei
+    pub max_fee_per_gas: u128,
+    /// Max priority fee per gas, in wei
+    pub max_priority_fee_per_gas: u128,
+}
+
+/// Nonces of an account: what is mined, what is pending, and what waits in
+/// the pool behind a missing nonce.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NonceStatus {
+    /// The account
+    pub address: Address,
+    /// Nonce of the next transaction to be mined, as of the latest block
+    pub latest: u64,
+    /// Nonce after the transactions already pending
+    pub pending: u64,
+    /// The account's pool transactions by nonce, when the node exposes `txpool_contentFrom`
+    pub pool: BTreeMap<u64, PoolTransaction>,
+}
+
+impl NonceStatus {
+    /// Reads the latest and pending nonces of an account, and its pool
+    /// transactions when the node allows it.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - Read context of the current chain
+    /// * `address` - The account
+    ///
+    /// # Returns
+    ///
+    /// * `Result<NonceStatus>` - The nonces or an error
+    pub async fn fetch(read: &ReadContext, address: Address) -> Result<Self> {
+        let operation = format!("fetching nonces of {}", address);
+        let provider = read.provider();
+        let rpc = |e| Error::rpc(&operation, e);
+        let (latest, pending) = cancel::guard(
+            &operation,
+            read.cancellation(),
+            read.timeouts().call,
+            async {
+                let latest = provider
+                    .get_transaction_count(address)
+                    .latest()
+                    .await
+                    .map_err(rpc)?;
+                let pending = provider
+                    .get_transaction_count(address)
+                    .pending()
+                    .await
+                    .map_err(rpc)?;
+                Ok((latest, pending))
+            },
+        )
+        .await?;
+        let pool = match provider
+            .raw_request::<_, Value>("txpool_contentFrom".into(), (address,))
+            .await
+        {
+            Ok(content) => parse_txpool(&content),
+            Err(e) => {
+                tracing::debug!(error = %e, "txpool_contentFrom is not available");
+                BTreeMap::new()
+            }
+        };
+        Ok(Self {
+            address,
+            latest,
+            pending,
+            pool,
+        })
+    }
+
+    /// Returns the nonces of transactions sent but not mined yet.
+    pub fn stuck(&self) -> Vec<u64> {
+        (self.latest..self.pending).collect()
+    }
+
+    /// Returns the missing nonces that pool transactions queue behind.
+    pub fn gaps(&self) -> Vec<u64> {
+        match self.pool.keys().next_back() {
+            Some(&highest) if highest > self.pending => (self.pending..highest)
+                .filter(|nonce| !self.pool.contains_key(nonce))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether nothing is pending and no nonce is missing.
+    pub fn is_clean(&self) -> bool {
+        self.pending <= self.latest && self.gaps().is_empty()
+    }
+
+    /// Plans the no-op transactions clearing stuck nonces and filling gaps.
+    ///
+    /// A stuck nonce known from the pool is outbid by
+    /// [`REPLACEMENT_BUMP_PERCENT`]; every replacement pays at least the
+    /// current fee estimate.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_fee_per_gas` - Current max fee estimate, in wei
+    /// * `max_priority_fee_per_gas` - Current priority fee estimate, in wei
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Replacement>` - The replacements, by increasing nonce
+    pub fn replacements(
+        &self,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    ) -> Vec<Replacement> {
+        let mut nonces = self.stuck();
+        nonces.extend(self.gaps());
+        nonces
+            .into_iter()
+            .map(|nonce| {
+                let (max_fee, priority_fee) = match self.pool.get(&nonce) {
+                    Some(tx) => (
+                        max_fee_per_gas.max(bump(tx.max_fee_per_gas)),
+                        max_priority_fee_per_gas.max(bump(
+                            tx.max_priority_fee_per_gas.unwrap_or(tx.max_fee_per_gas),
+                        )),
+                    ),
+                    None => (max_fee_per_gas, max_priority_fee_per_gas),
+                };
+                Replacement {
+                    nonce,
+                    max_fee_per_gas: max_fee.max(priority_fee),
+                    max_priority_fee_per_gas: priority_fee,
+                }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for NonceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Nonce: {} latest, {} pending", self.latest, self.pending)?;
+        if self.pending > self.latest {
+            write!(
+                f,
+                " ({} transaction(s) not mined yet)",
+                self.pending - self.latest
+            )?;
+        }
+        let gaps = self.gaps();
+        if !gaps.is_empty() {
+            let gaps: Vec<String> = gaps.iter().map(|nonce| nonce.to_string()).collect();
+            write!(
+                f,
+                "\nMissing nonce(s) {}: later transactions are queued behind them",
+                gaps.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a fee outbidding `fee` by [`REPLACEMENT_BUMP_PERCENT`].
+pub fn bump(fee: u128) -> u128 {
+    (fee.saturating_mul(100 + REPLACEMENT_BUMP_PERCENT) / 100).saturating_add(1)
+}
+
+/// Reads the pending and queued transactions of a `txpool_contentFrom`
+/// response, keyed by nonce.
+///
+/// # Arguments
+///
+/// * `content` - The response, `{"pending": {nonce: tx}, "queued": {nonce: tx}}`
+///
+/// # Returns
+///
+/// * `BTreeMap<u64, PoolTransaction>` - The transactions by nonce
+pub fn parse_txpool(content: &Value) -> BTreeMap<u64, PoolTransaction> {
+    let quantity = |value: &Value| {
+        value
+            .as_str()
+            .and_then(|hex| u128::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+    };
+    ["pending", "queued"]
+        .iter()
+        .filter_map(|section| content[section].as_object())
+        .flat_map(|txs| txs.iter())
+        .filter_map(|(nonce, tx)| {
+            let max_fee_per_gas = quantity(&tx["maxFeePerGas"]).or(quantity(&tx["gasPrice"]))?;
+            Some((
+                nonce.parse().ok()?,
+                PoolTransaction {
+                    hash: tx["hash"].as_str().and_then(|hash| hash.parse().ok()),
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas: quantity(&tx["maxPriorityFeePerGas"]),
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_gaps_and_replacements() {
+        let content = json!({
+            "pending": {
+                "5": { "hash": format!("0x{}", "11".repeat(32)), "maxFeePerGas": "0x3e8", "maxPriorityFeePerGas": "0x64" },
+                "6": { "gasPrice": "0x7d0" }
+            },
+            "queued": {
+                "9": { "maxFeePerGas": "0x3e8", "maxPriorityFeePerGas": "0x64" }
+            }
+        });
+        let status = NonceStatus {
+            address: Address::ZERO,
+            latest: 5,
+            pending: 7,
+            pool: parse_txpool(&content),
+        };
+        assert_eq!(status.pool.len(), 3);
+        assert!(status.pool[&5].hash.is_some());
+        assert_eq!(status.stuck(), vec![5, 6]);
+        assert_eq!(status.gaps(), vec![7, 8]);
+        assert!(!status.is_clean());
+
+        let replacements = status.replacements(500, 50);
+        let nonces: Vec<u64> = replacements.iter().map(|r| r.nonce).collect();
+        assert_eq!(nonces, vec![5, 6, 7, 8]);
+        assert_eq!(replacements[0].max_fee_per_gas, bump(1000));
+        assert_eq!(replacements[0].max_priority_fee_per_gas, bump(100));
+        assert_eq!(replacements[1].max_priority_fee_per_gas, bump(2000));
+        assert_eq!(replacements[1].max_fee_per_gas, bump(2000));
+        assert_eq!(replacements[2].max_fee_per_gas, 500);
+        assert!(bump(1000) >= 1100);
+
+        let clean = NonceStatus {
+            latest: 3,
+            pending: 3,
+            ..NonceStatus::default()
+        };
+        assert!(clean.is_clean());
+        assert!(clean.replacements(1, 1).is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+/// src/offline.rs
+use crate::error::{Error, Result};
+use crate::transaction::{TransactionOptions, TransactionPreview};
+use alloy::consensus::{
+    SignableTransaction, Transaction, TxEip1559, TxEnvelope, TxLegacy, TypedTransaction,
+};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::network::TransactionBuilder;
+use alloy::primitives::{hex, Address, Bytes, TxKind, B256};
+use alloy::rpc::types::TransactionRequest;
+use serde_json::{json, Value};
+use std::fmt;
+
+/// A transaction with nonce, gas and fees filled in, exported to be signed
+/// on another, possibly air-gapped, machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsignedTransaction {
+    /// Address expected to sign the transaction
+    pub from: Address,
+    /// The transaction, EIP-1559 or legacy depending on its pricing
+    pub tx: TypedTransaction,
+}
+
+impl UnsignedTransaction {
+    /// Builds the transaction described by a preview.
+    ///
+    /// # Arguments
+    ///
+    /// * `preview` - The transaction as it would be sent
+    ///
+    /// # Returns
+    ///
+    /// * `UnsignedTransaction` - An EIP-1559 transaction, or a legacy one when
+    ///   the preview has no priority fee
+    pub fn from_preview(preview: &TransactionPreview) -> Self {
+        let tx = match preview.max_priority_fee_per_gas {
+            Some(max_priority_fee_per_gas) => TypedTransaction::Eip1559(TxEip1559 {
+                chain_id: preview.chain_id,
+                nonce: preview.nonce,
+                gas_limit: preview.gas_limit,
+                max_fee_per_gas: preview.max_fee_per_gas,
+                max_priority_fee_per_gas,
+                to: TxKind::Call(preview.to),
+                value: preview.value,
+                access_list: Default::default(),
+                input: preview.calldata.clone(),
+            }),
+            None => TypedTransaction::Legacy(TxLegacy {
+                chain_id: Some(preview.chain_id),
+                nonce: preview.nonce,
+                gas_price: preview.max_fee_per_gas,
+                gas_limit: preview.gas_limit,
+                to: TxKind::Call(preview.to),
+                value: preview.value,
+                input: preview.calldata.clone(),
+            }),
+        };
+        Self {
+            from: preview.from,
+            tx,
+        }
+    }
+
+    /// Returns the RLP encoding the signer signs, type byte included.
+    pub fn rlp(&self) -> Bytes {
+        let mut buf = Vec::new();
+        self.tx.encode_for_signing(&mut buf);
+        buf.into()
+    }
+
+    /// Returns the hash the signer signs, `keccak256` of [`UnsignedTransaction::rlp`].
+    pub fn signing_hash(&self) -> B256 {
+        self.tx.signature_hash()
+    }
+
+    /// Converts the transaction into JSON, with its fields in RPC naming.
+    pub fn to_json(&self) -> Value {
+        let tx = &self.tx;
+        let (tx_type, gas_price) = match tx {
+            TypedTransaction::Legacy(legacy) => ("legacy", Some(legacy.gas_price.to_string())),
+            _ => ("eip1559", None),
+        };
+        json!({
+            "type": tx_type,
+            "chainId": tx.chain_id(),
+            "from": self.from.to_string(),
+            "to": tx.to().map(|to| to.to_string()),
+            "value": tx.value().to_string(),
+            "nonce": tx.nonce(),
+            "gas": tx.gas_limit(),
+            "gasPrice": gas_price,
+            "maxFeePerGas": gas_price.is_none().then(|| tx.max_fee_per_gas().to_string()),
+            "maxPriorityFeePerGas": tx.max_priority_fee_per_gas().map(|fee| fee.to_string()),
+            "input": tx.input().to_string(),
+            "rlp": self.rlp().to_string(),
+            "signingHash": self.signing_hash().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for UnsignedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Unsigned transaction from {} (nonce {}, chain {})",
+            self.from,
+            self.tx.nonce(),
+            self.tx.chain_id().unwrap_or_default()
+        )?;
+        writeln!(f, "Signing hash: {}", self.signing_hash())?;
+        write!(f, "RLP: {}", self.rlp())
+    }
+}
+
+/// A signed transaction imported to be broadcast, decoded and checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedTransaction {
+    /// The EIP-2718 encoding as imported
+    pub raw: Bytes,
+    /// The decoded transaction and signature
+    pub envelope: TxEnvelope,
+    /// Address recovered from the signature
+    pub signer: Address,
+}
+
+impl SignedTransaction {
+    /// Decodes a signed raw transaction and recovers its signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The `0x`-prefixed hex encoding
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SignedTransaction>` - The decoded transaction, or an error if
+    ///   it is malformed or its signature invalid
+    pub fn decode(input: &str) -> Result<Self> {
+        let raw = hex::decode(input.trim())
+            .map(Bytes::from)
+            .map_err(|e| Error::InvalidArguments(format!("Invalid hex: {}", e)))?;
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_ref())
+            .map_err(|e| Error::InvalidArguments(format!("Invalid signed transaction: {}", e)))?;
+        let signer = envelope
+            .signature()
+            .recover_address_from_prehash(&envelope.signature_hash())
+            .map_err(|e| Error::InvalidArguments(format!("Cannot recover signer: {}", e)))?;
+        Ok(Self {
+            raw,
+            envelope,
+            signer,
+        })
+    }
+
+    /// Returns the hash the transaction will have once broadcast.
+    pub fn hash(&self) -> B256 {
+        *self.envelope.tx_hash()
+    }
+
+    /// Returns the chain the transaction was signed for, `None` for
+    /// pre-EIP-155 legacy transactions.
+    pub fn chain_id(&self) -> Option<u64> {
+        self.envelope.chain_id()
+    }
+
+    /// Returns the call the transaction makes, from its signer, without the
+    /// value, nonce, gas and fees of [`SignedTransaction::options`].
+    pub fn request(&self) -> TransactionRequest {
+        let tx = &self.envelope;
+        let request = TransactionRequest::default()
+            .with_from(self.signer)
+            .with_kind(tx.kind())
+            .with_input(tx.input().clone());
+        match self.chain_id() {
+            Some(chain_id) => request.with_chain_id(chain_id),
+            None => request,
+        }
+    }
+
+    /// Returns the value, nonce, gas and fees the transaction was signed with.
+    pub fn options(&self) -> TransactionOptions {
+        let tx = &self.envelope;
+        let options = TransactionOptions::new()
+            .with_value(tx.value())
+            .with_nonce(tx.nonce())
+            .with_gas_limit(tx.gas_limit());
+        match (tx.gas_price(), tx.max_priority_fee_per_gas()) {
+            (Some(gas_price), _) => options.with_gas_price(gas_price),
+            (None, Some(priority_fee)) => options
+                .with_max_fee_per_gas(tx.max_fee_per_gas())
+                .with_max_priority_fee_per_gas(priority_fee),
+            (None, None) => options,
+        }
+    }
+}
+
+impl fmt::Display for SignedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tx = &self.envelope;
+        writeln!(f, "Hash: {}", self.hash())?;
+        writeln!(f, "From: {}", self.signer)?;
+        match tx.to() {
+            Some(to) => writeln!(f, "To: {}", to)?,
+            None => writeln!(f, "To: (contract creation)")?,
+        }
+        writeln!(f, "Value: {} wei", tx.value())?;
+        writeln!(
+            f,
+            "Chain: {}",
+            self.chain_id()
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "any".to_string())
+        )?;
+        writeln!(f, "Nonce: {}", tx.nonce())?;
+        writeln!(f, "Gas limit: {}", tx.gas_limit())?;
+        write!(f, "Calldata: {}", tx.input())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TEST_PRIVATE_KEY;
+    use alloy::eips::eip2718::Encodable2718;
+    use alloy::network::TxSignerSync;
+    use alloy::primitives::U256;
+    use alloy::signers::local::PrivateKeySigner;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_export_sign_and_import() {
+        let key = PrivateKeySigner::from_str(TEST_PRIVATE_KEY).unwrap();
+        let preview = TransactionPreview {
+            chain_id: 1,
+            from: key.address(),
+            to: Address::repeat_byte(0x11),
+            function: "transfer(address,uint256)".to_string(),
+            args: vec![],
+            calldata: Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb]),
+            value: U256::from(5),
+            nonce: 7,
+            gas_limit: 60_000,
+            max_fee_per_gas: 30_000_000_000,
+            max_priority_fee_per_gas: Some(1_000_000_000),
+            l1_data_fee: None,
+        };
+        let unsigned = UnsignedTransaction::from_preview(&preview);
+        assert_eq!(unsigned.rlp()[0], 0x02);
+        let json = unsigned.to_json();
+        assert_eq!(json["type"], "eip1559");
+        assert_eq!(json["nonce"], 7);
+        assert_eq!(json["gasPrice"], Value::Null);
+
+        // What the offline machine does with the export
+        let TypedTransaction::Eip1559(mut tx) = unsigned.tx.clone() else {
+            panic!("expected an EIP-1559 transaction");
+        };
+        let signature = key.sign_transaction_sync(&mut tx).unwrap();
+        let raw = TxEnvelope::from(tx.into_signed(signature)).encoded_2718();
+
+        let signed = SignedTransaction::decode(&hex::encode_prefixed(&raw)).unwrap();
+        assert_eq!(signed.signer, key.address());
+        assert_eq!(signed.chain_id(), Some(1));
+        assert_eq!(signed.envelope.signature_hash(), unsigned.signing_hash());
+        assert!(SignedTransaction::decode("0x1234").is_err());
+
+        let legacy = UnsignedTransaction::from_preview(&TransactionPreview {
+            max_priority_fee_per_gas: None,
+            ..preview
+        });
+        assert_eq!(legacy.to_json()["type"], "legacy");
+        assert_eq!(legacy.to_json()["gasPrice"], "30000000000");
+    }
+}
@@ -0,0 +1,258 @@
+/// src/output.rs
+use crate::error::Error;
+use crate::message::SignedMessage;
+use crate::permit::SignedPermit;
+use crate::result::{CallDiff, CallResult, EventLog, ExecutionResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Version of the structured output schema described by [`JSON_SCHEMA`].
+///
+/// Adding a field or a new output kind keeps the version; removing or
+/// renaming a field, or changing its type or meaning, bumps it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema of the structured output for [`SCHEMA_VERSION`].
+pub const JSON_SCHEMA: &str = include_str!("../schema/output-v1.json");
+
+/// How results are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// One [`Output`] per line on stdout, other messages on stderr
+    Json,
+}
+
+/// What a structured output describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputKind {
+    /// Decoded result of a read-only call
+    Call,
+    /// Outputs of a read-only call compared at two blocks
+    Diff,
+    /// Results of a read-only call repeated over rows of arguments
+    Batch,
+    /// A broadcast transaction awaiting its receipt
+    Submitted,
+    /// A transaction included in a block
+    Confirmed,
+    /// A transaction proposed to a Safe
+    Proposed,
+    /// A transaction exported for offline signing
+    Unsigned,
+    /// A transaction simulated and previewed in a dry run, never sent
+    Simulated,
+    /// A transaction the user declined to send
+    Cancelled,
+    /// A decoded event log
+    Event,
+    /// Encoded calldata
+    Calldata,
+    /// A signed message, or one whose signer was recovered
+    Signature,
+    /// A signed ERC-2612 permit
+    Permit,
+    /// A failed operation
+    Error,
+}
+
+impl fmt::Display for OutputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputKind::Call => write!(f, "call"),
+            OutputKind::Diff => write!(f, "diff"),
+            OutputKind::Batch => write!(f, "batch"),
+            OutputKind::Submitted => write!(f, "submitted"),
+            OutputKind::Confirmed => write!(f, "confirmed"),
+            OutputKind::Proposed => write!(f, "proposed"),
+            OutputKind::Unsigned => write!(f, "unsigned"),
+            OutputKind::Simulated => write!(f, "simulated"),
+            OutputKind::Cancelled => write!(f, "cancelled"),
+            OutputKind::Event => write!(f, "event"),
+            OutputKind::Calldata => write!(f, "calldata"),
+            OutputKind::Signature => write!(f, "signature"),
+            OutputKind::Permit => write!(f, "permit"),
+            OutputKind::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Versioned envelope wrapping every machine-readable output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Output {
+    /// Schema version the output conforms to
+    pub schema_version: u32,
+    /// What the output describes
+    pub kind: OutputKind,
+    /// Kind-specific payload
+    pub data: Value,
+}
+
+impl Output {
+    /// Wraps a payload in an envelope for the current schema version.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - What the payload describes
+    /// * `data` - The kind-specific payload
+    ///
+    /// # Returns
+    ///
+    /// * `Output` - The versioned output
+    pub fn new(kind: OutputKind, data: Value) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            kind,
+            data,
+        }
+    }
+
+    /// Converts the output into JSON.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "schema_version": self.schema_version,
+            "kind": self.kind,
+            "data": self.data,
+        })
+    }
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+impl From<&CallResult> for Output {
+    fn from(call: &CallResult) -> Self {
+        Output::new(OutputKind::Call, call.to_json())
+    }
+}
+
+impl From<&CallDiff> for Output {
+    fn from(diff: &CallDiff) -> Self {
+        Output::new(OutputKind::Diff, diff.to_json())
+    }
+}
+
+impl From<&EventLog> for Output {
+    fn from(log: &EventLog) -> Self {
+        Output::new(OutputKind::Event, log.to_json())
+    }
+}
+
+impl From<&SignedMessage> for Output {
+    fn from(signed: &SignedMessage) -> Self {
+        Output::new(OutputKind::Signature, signed.to_json())
+    }
+}
+
+impl From<&SignedPermit> for Output {
+    fn from(signed: &SignedPermit) -> Self {
+        Output::new(OutputKind::Permit, signed.to_json())
+    }
+}
+
+impl From<&ExecutionResult> for Output {
+    fn from(result: &ExecutionResult) -> Self {
+        match result {
+            ExecutionResult::Call(call) => call.into(),
+            ExecutionResult::Submitted { hash } => {
+                Output::new(OutputKind::Submitted, json!({ "hash": hash.to_string() }))
+            }
+            ExecutionResult::Confirmed(receipt) => Output::new(
+                OutputKind::Confirmed,
+                json!({
+                    "hash": receipt.transaction_hash.to_string(),
+                    "block_number": receipt.block_number,
+                    "gas_used": receipt.gas_used,
+                    "success": receipt.status(),
+                    "effective_gas_price": receipt.effective_gas_price.to_string(),
+                }),
+            ),
+            ExecutionResult::Proposed {
+                safe,
+                safe_tx_hash,
+                nonce,
+                queue_url,
+            } => Output::new(
+                OutputKind::Proposed,
+                json!({
+                    "safe": safe.to_string(),
+                    "safe_tx_hash": safe_tx_hash.to_string(),
+                    "nonce": nonce.to_string(),
+                    "queue_url": queue_url,
+                }),
+            ),
+            ExecutionResult::Exported(tx) => Output::new(OutputKind::Unsigned, tx.to_json()),
+            ExecutionResult::Simulated(tx) => Output::new(OutputKind::Simulated, tx.to_json()),
+            ExecutionResult::Cancelled => Output::new(OutputKind::Cancelled, Value::Null),
+        }
+    }
+}
+
+impl From<&Error> for Output {
+    fn from(error: &Error) -> Self {
+        Output::new(
+            OutputKind::Error,
+            json!({
+                "category": error.kind().to_string(),
+                "message": error.to_string(),
+                "revert_data": error.revert_data().map(|data| data.to_string()),
+                "revert_error": error.revert_error().map(|(name, args)| json!({
+                    "name": name,
+                    "args": args.iter().map(crate::codec::to_json).collect::<Vec<_>>(),
+                })),
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Bytes;
+
+    #[test]
+    fn test_outputs_match_schema_kinds() {
+        let schema: Value = serde_json::from_str(JSON_SCHEMA).unwrap();
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        let kinds = schema["properties"]["kind"]["enum"].as_array().unwrap();
+
+        let error = Error::revert(Bytes::from_static(&[0x12, 0x34]), None);
+        let output = Output::from(&error).to_json();
+        assert_eq!(output["schema_version"], SCHEMA_VERSION);
+        assert!(kinds.contains(&output["kind"]));
+        assert_eq!(output["data"]["category"], "revert");
+        assert_eq!(output["data"]["revert_data"], "0x1234");
+        assert_eq!(output["data"]["revert_error"], Value::Null);
+
+        let cancelled = Output::from(&ExecutionResult::Cancelled).to_json();
+        assert!(kinds.contains(&cancelled["kind"]));
+        assert_eq!(cancelled["data"], Value::Null);
+
+        for kind in [
+            OutputKind::Diff,
+            OutputKind::Batch,
+            OutputKind::Event,
+            OutputKind::Calldata,
+            OutputKind::Signature,
+            OutputKind::Permit,
+            OutputKind::Proposed,
+            OutputKind::Unsigned,
+            OutputKind::Simulated,
+        ] {
+            assert!(kinds.contains(&json!(kind.to_string())));
+        }
+        let format: OutputFormat = serde_json::from_value(json!("json")).unwrap();
+        assert_eq!(format, OutputFormat::Json);
+    }
+}
@@ -0,0 +1,261 @@
+/// src/params.rs
+use crate::abi;
+use crate::error::{Error, Result};
+use alloy::json_abi::{Function, Param};
+use serde_json::Value;
+use std::path::Path;
+
+/// Returns the file an input refers to with the `@` prefix, e.g.
+/// `@params.json`, or `None` if the input is a value.
+pub fn file_reference(input: &str) -> Option<&Path> {
+    input
+        .trim()
+        .strip_prefix('@')
+        .filter(|path| !path.is_empty())
+        .map(Path::new)
+}
+
+/// Reads the value of one parameter from a JSON file.
+///
+/// # Arguments
+///
+/// * `path` - The JSON file
+/// * `param` - The parameter the file holds the value of
+///
+/// # Returns
+///
+/// * `Result<String>` - The value as a parameter input, or an error if the
+///   file cannot be read or does not fit the parameter
+pub fn read_param_file(path: &Path, param: &Param) -> Result<String> {
+    json_to_input(param, &read_json(path)?)
+}
+
+/// Reads the arguments of a function from a JSON file: an array with one
+/// value per input, or an object keyed by input name. A function with a
+/// single input also takes its value directly, including a struct given as
+/// an object keyed by field name.
+///
+/// # Arguments
+///
+/// * `path` - The JSON file
+/// * `function` - The function the arguments are for
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - One input per function input, or an error if
+///   the file cannot be read or does not fit the function
+pub fn read_params_file(path: &Path, function: &Function) -> Result<Vec<String>> {
+    params_from_json(function, &read_json(path)?)
+}
+
+/// Reads and parses a JSON file.
+fn read_json(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| Error::InvalidArguments(format!("Cannot read {}: {}", path.display(), e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| Error::InvalidArguments(format!("Invalid JSON in {}: {}", path.display(), e)))
+}
+
+/// Turns the JSON arguments of a function into parameter inputs; see
+/// [`read_params_file`].
+///
+/// # Arguments
+///
+/// * `function` - The function the arguments are for
+/// * `json` - The arguments
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - One input per function input, or an error if
+///   the arguments do not fit the function
+pub fn params_from_json(function: &Function, json: &Value) -> Result<Vec<String>> {
+    // An object not keyed by the name of a single struct input is the struct.
+    let struct_given_directly = match (function.inputs.as_slice(), json) {
+        ([input], Value::Object(fields)) => {
+            !input.components.is_empty() && !fields.contains_key(&input.name)
+        }
+        _ => false,
+    };
+    match json {
+        Value::Object(fields) if !struct_given_directly => function
+            .inputs
+            .iter()
+            .map(|input| {
+                let value = fields.get(&input.name).ok_or_else(|| {
+                    Error::InvalidArguments(format!("No argument named {}", input.name))
+                })?;
+                json_to_input(input, value)
+            })
+            .collect(),
+        Value::Array(values) if values.len() == function.inputs.len() => function
+            .inputs
+            .iter()
+            .zip(values)
+            .map(|(input, value)| json_to_input(input, value))
+            .collect(),
+        value if function.inputs.len() == 1 => Ok(vec![json_to_input(&function.inputs[0], value)?]),
+        Value::Array(values) => Err(Error::InvalidArguments(format!(
+            "{} expects {} arguments, got {}",
+            function.signature(),
+            function.inputs.len(),
+            values.len()
+        ))),
+        _ => Err(Error::InvalidArguments(
+            "Arguments must be a JSON array or object".to_string(),
+        )),
+    }
+}
+
+/// Turns the JSON value of a parameter into its input. Structs are given
+/// as objects keyed by field name or as arrays, at any depth.
+///
+/// # Arguments
+///
+/// * `param` - The parameter
+/// * `value` - Its JSON value
+///
+/// # Returns
+///
+/// * `Result<String>` - The parameter input, or an error if the value does
+///   not fit the parameter
+pub fn json_to_input(param: &Param, value: &Value) -> Result<String> {
+    match normalize(param, value)? {
+        Value::String(s) => Ok(s),
+        Value::Null | Value::Object(_) => Err(Error::InvalidArguments(format!(
+            "No value for {} ({})",
+            param.name, param.ty
+        ))),
+        other => Ok(other.to_string()),
+    }
+}
+
+/// Replaces the struct objects of a value with arrays of their fields in
+/// declaration order.
+fn normalize(param: &Param, value: &Value) -> Result<Value> {
+    if let Some(element) = abi::element_param(param) {
+        return match value {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| normalize(&element, value))
+                .collect::<Result<_>>()
+                .map(Value::Array),
+            _ => Err(Error::InvalidArguments(format!(
+                "{} ({}) must be a JSON array",
+                param.name, param.ty
+            ))),
+        };
+    }
+    if param.components.is_empty() {
+        return Ok(value.clone());
+    }
+    let fields = match value {
+        Value::Object(fields) => param
+            .components
+            .iter()
+            .map(|component| {
+                fields.get(&component.name).ok_or_else(|| {
+                    Error::InvalidArguments(format!(
+                        "{} has no field {}",
+                        param.name, component.name
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        Value::Array(values) if values.len() == param.components.len() => values.iter().collect(),
+        _ => {
+            return Err(Error::InvalidArguments(format!(
+                "{} must be a JSON object or an array of {} fields",
+                param.name,
+                param.components.len()
+            )))
+        }
+    };
+    param
+        .components
+        .iter()
+        .zip(fields)
+        .map(|(component, value)| normalize(component, value))
+        .collect::<Result<_>>()
+        .map(Value::Array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec;
+    use serde_json::json;
+
+    #[test]
+    fn test_params_from_json() {
+        let function: Function = serde_json::from_value(json!({
+            "type": "function",
+            "name": "fill",
+            "inputs": [
+                {
+                    "name": "orders",
+                    "type": "tuple[]",
+                    "components": [
+                        { "name": "maker", "type": "address" },
+                        { "name": "amounts", "type": "uint256[]" },
+                        {
+                            "name": "extra",
+                            "type": "tuple",
+                            "components": [
+                                { "name": "note", "type": "string" },
+                                { "name": "flag", "type": "bool" }
+                            ]
+                        }
+                    ]
+                },
+                { "name": "memo", "type": "string" }
+            ],
+            "outputs": [],
+            "stateMutability": "nonpayable"
+        }))
+        .unwrap();
+        let maker = "0x0000000000000000000000000000000000000001";
+        let order = json!({ "maker": maker, "amounts": [1, "2"], "extra": { "flag": true, "note": "a, \"b\"" } });
+        let by_name = json!({ "orders": [order.clone()], "memo": "x" });
+        let positional = json!([[[maker, [1, "2"], ["a, \"b\"", true]]], "x"]);
+
+        let params = params_from_json(&function, &by_name).unwrap();
+        assert_eq!(params, params_from_json(&function, &positional).unwrap());
+        assert_eq!(params[1], "x");
+        assert!(codec::encode_calldata(&function, &params).is_ok());
+
+        assert!(params_from_json(&function, &json!({ "orders": [] })).is_err());
+        assert!(params_from_json(&function, &json!([[order], "x", "y"])).is_err());
+        let unknown_field = json!({ "orders": [{ "maker": maker }], "memo": "x" });
+        assert!(params_from_json(&function, &unknown_field).is_err());
+
+        let submit: Function = serde_json::from_value(json!({
+            "type": "function",
+            "name": "submit",
+            "inputs": [{
+                "name": "order",
+                "type": "tuple",
+                "components": [
+                    { "name": "maker", "type": "address" },
+                    { "name": "amount", "type": "uint256" }
+                ]
+            }],
+            "outputs": [],
+            "stateMutability": "nonpayable"
+        }))
+        .unwrap();
+        let order = json!({ "maker": maker, "amount": 5 });
+        let direct = params_from_json(&submit, &order).unwrap();
+        assert_eq!(
+            direct,
+            params_from_json(&submit, &json!({ "order": order })).unwrap()
+        );
+        assert!(codec::encode_calldata(&submit, &direct).is_ok());
+
+        assert_eq!(
+            file_reference(" @params.json"),
+            Some(Path::new("params.json"))
+        );
+        assert_eq!(file_reference("@"), None);
+        assert_eq!(file_reference("0x01"), None);
+    }
+}
@@ -0,0 +1,242 @@
+/// src/permit.rs
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use crate::message::eip712_digest;
+use crate::signer::Signer;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::{b256, hex, keccak256, Address, PrimitiveSignature, B256, U256};
+use alloy::sol_types::SolValue;
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Signature of the ERC-2612 `permit` function.
+pub const PERMIT_SIGNATURE: &str = "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)";
+
+/// `keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)")`.
+pub const PERMIT_TYPEHASH: B256 =
+    b256!("6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9");
+
+/// An ERC-2612 approval, signed off-chain and submitted by anyone through `permit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permit {
+    /// Holder of the tokens, who signs the permit
+    pub owner: Address,
+    /// Address allowed to spend the tokens
+    pub spender: Address,
+    /// Allowance granted, in token base units
+    pub value: U256,
+    /// The owner's current `nonces` value
+    pub nonce: U256,
+    /// Unix timestamp after which the permit is rejected
+    pub deadline: U256,
+}
+
+impl Permit {
+    /// Returns the `permit` function of an ABI, if the token implements ERC-2612.
+    ///
+    /// # Arguments
+    ///
+    /// * `abi` - The token ABI
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&Function>` - The `permit` function
+    pub fn find(abi: &JsonAbi) -> Option<&Function> {
+        abi.function("permit")?
+            .iter()
+            .find(|function| function.signature() == PERMIT_SIGNATURE)
+    }
+
+    /// Reads the owner's nonce and the token's `DOMAIN_SEPARATOR`.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - Read context of the current chain
+    /// * `token` - Address of the token
+    /// * `owner` - Holder of the tokens
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(U256, B256)>` - The nonce and domain separator, or an error
+    pub async fn read_domain(
+        read: &ReadContext,
+        token: Address,
+        owner: Address,
+    ) -> Result<(U256, B256)> {
+        let nonces = Function::parse("nonces(address) returns (uint256)")
+            .map_err(|e| Error::InvalidFunction(e.to_string()))?;
+        let separator = Function::parse("DOMAIN_SEPARATOR() returns (bytes32)")
+            .map_err(|e| Error::InvalidFunction(e.to_string()))?;
+        let nonce = read
+            .call(token, &nonces, &[DynSolValue::Address(owner)])
+            .await?;
+        let domain = read.call(token, &separator, &[]).await?;
+        match (nonce.values.first(), domain.values.first()) {
+            (Some(DynSolValue::Uint(nonce, _)), Some(DynSolValue::FixedBytes(domain, 32))) => {
+                Ok((*nonce, *domain))
+            }
+            _ => Err(Error::InvalidAbi(
+                "nonces or DOMAIN_SEPARATOR returned an unexpected value".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the EIP-712 struct hash of the permit.
+    pub fn struct_hash(&self) -> B256 {
+        keccak256(
+            (
+                PERMIT_TYPEHASH,
+                self.owner,
+                self.spender,
+                self.value,
+                self.nonce,
+                self.deadline,
+            )
+                .abi_encode(),
+        )
+    }
+
+    /// Returns the EIP-712 digest the owner signs.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain_separator` - The token's `DOMAIN_SEPARATOR`
+    ///
+    /// # Returns
+    ///
+    /// * `B256` - The digest of the permit under the token's domain
+    pub fn digest(&self, domain_separator: B256) -> B256 {
+        eip712_digest(domain_separator, self.struct_hash())
+    }
+
+    /// Signs the permit with the owner's signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - Signer of the owner
+    /// * `domain_separator` - The token's `DOMAIN_SEPARATOR`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SignedPermit>` - The signed permit or a signing error
+    pub async fn sign(self, signer: &Signer, domain_separator: B256) -> Result<SignedPermit> {
+        let digest = self.digest(domain_separator);
+        let signature = signer.sign_hash(&digest).await?;
+        Ok(SignedPermit {
+            permit: self,
+            digest,
+            signature,
+        })
+    }
+}
+
+/// A permit with the owner's signature, ready to be submitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedPermit {
+    /// The signed permit
+    pub permit: Permit,
+    /// The EIP-712 digest that was signed
+    pub digest: B256,
+    /// The owner's signature
+    pub signature: PrimitiveSignature,
+}
+
+impl SignedPermit {
+    /// Returns the `v` value expected by `permit`, 27 or 28.
+    pub fn v(&self) -> u8 {
+        27 + self.signature.v() as u8
+    }
+
+    /// Returns the arguments of the `permit` call, as typed in the method prompt.
+    pub fn args(&self) -> Vec<String> {
+        vec![
+            self.permit.owner.to_string(),
+            self.permit.spender.to_string(),
+            self.permit.value.to_string(),
+            self.permit.deadline.to_string(),
+            self.v().to_string(),
+            B256::from(self.signature.r()).to_string(),
+            B256::from(self.signature.s()).to_string(),
+        ]
+    }
+
+    /// Converts the signed permit into JSON.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "owner": self.permit.owner.to_string(),
+            "spender": self.permit.spender.to_string(),
+            "value": self.permit.value.to_string(),
+            "nonce": self.permit.nonce.to_string(),
+            "deadline": self.permit.deadline.to_string(),
+            "digest": self.digest.to_string(),
+            "signature": hex::encode_prefixed(self.signature.as_bytes()),
+            "v": self.v(),
+            "r": B256::from(self.signature.r()).to_string(),
+            "s": B256::from(self.signature.s()).to_string(),
+        })
+    }
+}
+
+impl fmt::Display for SignedPermit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Owner: {}", self.permit.owner)?;
+        writeln!(f, "Spender: {}", self.permit.spender)?;
+        writeln!(f, "Value: {}", self.permit.value)?;
+        writeln!(f, "Nonce: {}", self.permit.nonce)?;
+        writeln!(f, "Deadline: {}", self.permit.deadline)?;
+        writeln!(f, "v: {}", self.v())?;
+        writeln!(f, "r: {}", B256::from(self.signature.r()))?;
+        write!(f, "s: {}", B256::from(self.signature.s()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TEST_PRIVATE_KEY;
+    use alloy::signers::local::PrivateKeySigner;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_sign_permit() {
+        assert_eq!(
+            PERMIT_TYPEHASH,
+            keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)")
+        );
+        let key = PrivateKeySigner::from_str(TEST_PRIVATE_KEY).unwrap();
+        let permit = Permit {
+            owner: key.address(),
+            spender: Address::repeat_byte(0x22),
+            value: U256::from(1000),
+            nonce: U256::from(3),
+            deadline: U256::from(1_700_000_000u64),
+        };
+        let domain = B256::repeat_byte(0x11);
+        let signed = permit
+            .sign(&Signer::from(key.clone()), domain)
+            .await
+            .unwrap();
+
+        assert_eq!(signed.digest, permit.digest(domain));
+        assert_ne!(signed.digest, permit.digest(B256::ZERO));
+        assert_eq!(
+            signed
+                .signature
+                .recover_address_from_prehash(&signed.digest)
+                .unwrap(),
+            key.address()
+        );
+        assert!(matches!(signed.v(), 27 | 28));
+        let args = signed.args();
+        assert_eq!(args.len(), 7);
+        assert_eq!(args[3], "1700000000");
+
+        let abi = JsonAbi::parse([
+            "function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s)",
+        ])
+        .unwrap();
+        assert!(Permit::find(&abi).is_some());
+        assert!(Permit::find(&JsonAbi::new()).is_none());
+    }
+}
@@ -0,0 +1,249 @@
+/// src/policy.rs
+use crate::address_book::AddressBook;
+use crate::erc20;
+use crate::error::{Error, Result};
+use crate::hooks::{HookDecision, PendingSend, TransactionHook};
+#[cfg(feature = "interactive")]
+use crate::prompt;
+use crate::transaction::TransactionOptions;
+use crate::validation;
+use alloy::json_abi::Function;
+use alloy::primitives::{Address, Selector, U256};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// What happens to a transaction breaking a policy rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    /// The transaction is not sent
+    #[default]
+    Refuse,
+    /// The transaction is sent once the user types a confirmation, and
+    /// refused when nobody can be asked, e.g. in daemon or server mode
+    Confirm,
+}
+
+/// Rules every transaction is checked against before it is signed, the
+/// `[policy]` table of `config.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolicyConfig {
+    /// Largest native value a transaction may carry, e.g. `0.5 ether`
+    pub max_value: Option<String>,
+    /// Functions never called, by name (`transferOwnership`) or by signature
+    /// (`upgradeTo(address)`), which also catches them in raw calldata
+    pub denied_methods: Vec<String>,
+    /// Addresses or address book aliases transactions may be sent to, any when empty
+    pub allowed_targets: Vec<String>,
+    /// Refuse transactions whose simulation reverts, on every write path
+    pub require_simulation: bool,
+    /// What happens to a transaction breaking a rule
+    pub on_violation: PolicyAction,
+}
+
+impl PolicyConfig {
+    /// Parses the rules, resolving target aliases with the address book.
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The address book aliases in `allowed_targets` refer to
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Policy>` - The policy or an error naming the first invalid rule
+    pub fn policy(&self, book: &AddressBook) -> Result<Policy> {
+        let max_value = match self.max_value {
+            Some(ref max_value) => Some(validation::parse_value(max_value).map_err(|e| {
+                Error::InvalidConfig(format!("policy.max_value {:?}: {}", max_value, e))
+            })?),
+            None => None,
+        };
+        let denied_methods = self
+            .denied_methods
+            .iter()
+            .map(|method| match method.contains('(') {
+                true => Function::parse(method)
+                    .map(|function| (function.name.clone(), Some(function.selector())))
+                    .map_err(|e| {
+                        Error::InvalidConfig(format!("policy.denied_methods {:?}: {}", method, e))
+                    }),
+                false => Ok((method.trim().to_string(), None)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let allowed_targets = self
+            .allowed_targets
+            .iter()
+            .map(|target| {
+                book.resolve(target).parse::<Address>().map_err(|_| {
+                    Error::InvalidConfig(format!(
+                        "policy.allowed_targets {:?} is neither an address nor an alias",
+                        target
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Policy {
+            max_value,
+            denied_methods,
+            allowed_targets,
+            on_violation: self.on_violation,
+        })
+    }
+}
+
+/// The parsed rules of a [`PolicyConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Policy {
+    max_value: Option<U256>,
+    denied_methods: Vec<(String, Option<Selector>)>,
+    allowed_targets: Vec<Address>,
+    on_violation: PolicyAction,
+}
+
+impl Policy {
+    /// Checks a transaction against the rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction about to be sent
+    /// * `value` - The native value it carries, in wei
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - The rules the transaction breaks, empty if none
+    pub fn violations(&self, tx: &PendingSend, value: U256) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some(max_value) = self.max_value {
+            if value > max_value {
+                violations.push(format!(
+                    "value {} ETH exceeds the maximum of {} ETH",
+                    erc20::format_amount(value, 18),
+                    erc20::format_amount(max_value, 18)
+                ));
+            }
+        }
+        let name = tx.function.split('(').next().unwrap_or(&tx.function);
+        let selector = tx.calldata.get(..4);
+        let denied = self
+            .denied_methods
+            .iter()
+            .find(|(denied, signature)| match signature {
+                Some(signature) => selector == Some(signature.as_slice()),
+                None => denied == name,
+            });
+        if let Some((denied, _)) = denied {
+            violations.push(format!("{} is a denied method", denied));
+        }
+        if !self.allowed_targets.is_empty() && !self.allowed_targets.contains(&tx.to) {
+            violations.push(format!("{} is not an allowed target", tx.to));
+        }
+        violations
+    }
+}
+
+/// Hook enforcing a [`Policy`] before every transaction is sent.
+#[derive(Debug, Clone)]
+pub struct PolicyHook {
+    policy: Policy,
+    interactive: bool,
+}
+
+impl PolicyHook {
+    /// Creates a hook enforcing a policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy
+    /// * `interactive` - Whether the user can be asked to confirm violations
+    pub fn new(policy: Policy, interactive: bool) -> Self {
+        Self {
+            policy,
+            interactive,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionHook for PolicyHook {
+    fn name(&self) -> &str {
+        "policy"
+    }
+
+    async fn before_send(
+        &self,
+        tx: &PendingSend,
+        options: &mut TransactionOptions,
+    ) -> Result<HookDecision> {
+        let violations = self.policy.violations(tx, options.value());
+        if violations.is_empty() {
+            return Ok(HookDecision::Proceed);
+        }
+        let confirmed = match (self.policy.on_violation, self.interactive) {
+            #[cfg(feature = "interactive")]
+            (PolicyAction::Confirm, true) => prompt::confirm_policy_override(&violations)?,
+            _ => false,
+        };
+        Ok(match confirmed {
+            true => HookDecision::Proceed,
+            false => HookDecision::Veto(violations.join("; ")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Bytes;
+
+    #[test]
+    fn test_policy_violations() {
+        let vault: Address = "0xdAC17F958D2ee523a2206206994597C13D831ec7"
+            .parse()
+            .unwrap();
+        let mut book = AddressBook::new();
+        book.insert("vault", vault).unwrap();
+        let config = PolicyConfig {
+            max_value: Some("0.5 ether".to_string()),
+            denied_methods: vec![
+                "transferOwnership".to_string(),
+                "upgradeTo(address)".to_string(),
+            ],
+            allowed_targets: vec!["vault".to_string()],
+            ..Default::default()
+        };
+        let policy = config.policy(&book).unwrap();
+        let tx = |to: Address, function: &str| {
+            let selector = Function::parse(function).unwrap().selector();
+            PendingSend {
+                from: Address::ZERO,
+                to,
+                chain_id: 1,
+                function: function.to_string(),
+                calldata: Bytes::from(selector.to_vec()),
+            }
+        };
+
+        let deposit = tx(vault, "deposit()");
+        assert!(policy.violations(&deposit, U256::ZERO).is_empty());
+        let value = U256::from(10u64.pow(18));
+        assert_eq!(
+            policy.violations(&deposit, value),
+            vec!["value 1 ETH exceeds the maximum of 0.5 ETH"]
+        );
+        let handover = tx(Address::ZERO, "transferOwnership(address)");
+        assert_eq!(policy.violations(&handover, U256::ZERO).len(), 2);
+        let mut raw = tx(vault, "upgradeTo(address)");
+        raw.function = "raw calldata".to_string();
+        assert_eq!(
+            policy.violations(&raw, U256::ZERO),
+            vec!["upgradeTo is a denied method"]
+        );
+
+        let invalid = PolicyConfig {
+            allowed_targets: vec!["nobody".to_string()],
+            ..Default::default()
+        };
+        assert!(invalid.policy(&book).is_err());
+    }
+}
@@ -1,135 +1,2173 @@
 /// src/prompt.rs
-use alloy::json_abi::Function;
+use alloy::dyn_abi::DynSolType;
+use alloy::eips::BlockNumberOrTag;
+use alloy::json_abi::{Event, Function, JsonAbi, Param};
+use alloy::primitives::utils::{format_ether, Unit};
+use alloy::primitives::{Address, Bytes, PrimitiveSignature, TxHash, B256, U256};
+use chain_info::{ChainInfo, ChainRegistry, RpcProbe};
 use colored::Colorize;
-use inquire::{validator::Validation, Select, Text};
+use inquire::{validator::Validation, CustomUserError, Password, Select, Text};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
 use crate::{
-    abi::MethodType,
+    abi::{self, MethodType},
+    address_book::{self, AddressBook},
+    codec,
+    context::GlobalContext,
+    create2, editor, ens,
+    erc20::{self, TokenInfo},
     error::{Error, Result},
-    step::Step,
+    export,
+    fork::{DevNode, ForkSession, StateSnapshot},
+    journal::{JournalEntry, JournalStatus},
+    message,
+    nft::NftStandard,
+    nonce::{NonceStatus, Replacement},
+    offline::SignedTransaction,
+    output::{Output, OutputFormat},
+    params,
+    proxy::ProxyInfo,
+    relay::RelayConfig,
+    result::{CallResult, EventLog, ExecutionResult},
+    safe::SafeTx,
+    signer::Signer,
+    step::WorkflowStep,
+    transaction::{TransactionOptions, TransactionPreview, TxType},
+    usage::QuickAccess,
     validation,
+    wallet::WalletSummary,
 };
 
 /// Prompts the user to select an action from the available steps.
 ///
+/// # Arguments
+///
+/// * `steps` - The steps to offer
+///
+/// # Returns
+///
+/// * `Result<&dyn WorkflowStep>` - The selected step or an error
+pub fn select_step<'a>(steps: &[&'a dyn WorkflowStep]) -> Result<&'a dyn WorkflowStep> {
+    let names: Vec<&str> = steps.iter().map(|step| step.name()).collect();
+    let selected = Select::new("Select an action:", names).raw_prompt()?;
+    Ok(steps[selected.index])
+}
+
+/// Prompts the user to select a contract from a list of available contracts.
+///
+/// # Arguments
+///
+/// * `contracts` - Available contract names, each with the chains it is deployed on, if known
+///
+/// # Returns
+///
+/// * `Result<String>` - The selected contract name or an error
+pub fn select_contract_name(contracts: &[(String, Option<String>)]) -> Result<String> {
+    let labels = contracts
+        .iter()
+        .map(|(name, chains)| match chains {
+            Some(chains) => format!("{}  {}", name, chains.dimmed()),
+            None => name.clone(),
+        })
+        .collect();
+    let index = fuzzy_select("Select a contract:", labels)
+        .raw_prompt()?
+        .index;
+    Ok(contracts[index].0.clone())
+}
+
+/// Builds a selection list filtered with [`text::fuzzy_score`] as the user
+/// types, best matches first and equal matches in their listed order.
+///
+/// # Arguments
+///
+/// * `message` - The prompt message
+/// * `labels` - The options
+///
+/// # Returns
+///
+/// * `Select<String>` - The selection prompt
+fn fuzzy_select(message: &str, labels: Vec<String>) -> Select<'_, String> {
+    Select::new(message, labels)
+        .with_page_size(15)
+        .with_scorer(&|input, _, label, index| {
+            text::fuzzy_score(input, label).map(|score| score * 100_000 - index as i64)
+        })
+}
+
+/// Prompts the user to input a contract address with validation.
+///
+/// # Arguments
+///
+/// * `book` - Address book whose aliases are suggested and accepted
+///
+/// # Returns
+///
+/// * `Result<String>` - The validated contract address or an error
+pub fn input_contract_address(book: &AddressBook) -> Result<String> {
+    input_address("Enter contract address:", book)
+}
+
+/// Prompts for an address, accepting and autocompleting address book aliases.
+///
+/// # Arguments
+///
+/// * `message` - The prompt message
+/// * `book` - Address book whose aliases are suggested and accepted
+///
+/// # Returns
+///
+/// * `Result<String>` - The validated address, aliases resolved, or an error
+pub fn input_address(message: &str, book: &AddressBook) -> Result<String> {
+    let resolver = book.clone();
+    let address =
+        address_text(message, book)
+            .with_validator(
+                move |input: &str| -> std::result::Result<
+                    Validation,
+                    Box<dyn std::error::Error + Send + Sync>,
+                > {
+                    match validation::validate_contract_address(&resolver.resolve(input)) {
+                        Ok(_) => Ok(Validation::Valid),
+                        Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                    }
+                },
+            )
+            .prompt()?;
+    Ok(book.resolve(&address))
+}
+
+/// Prompts for an account, e.g. the recipient of a transfer: an address, an
+/// address book alias or an ENS name.
+///
+/// # Arguments
+///
+/// * `message` - The prompt message
+/// * `book` - Address book whose aliases are suggested and accepted
+///
+/// # Returns
+///
+/// * `Result<String>` - The address, aliases resolved, or the ENS name as entered
+pub fn input_account(message: &str, book: &AddressBook) -> Result<String> {
+    let resolver = book.clone();
+    let recipient =
+        address_text(message, book)
+            .with_help_message("Address, address book alias or ENS name")
+            .with_validator(
+                move |input: &str| -> std::result::Result<
+                    Validation,
+                    Box<dyn std::error::Error + Send + Sync>,
+                > {
+                    if ens::is_name(input) {
+                        return Ok(Validation::Valid);
+                    }
+                    match validation::validate_contract_address(&resolver.resolve(input)) {
+                        Ok(_) => Ok(Validation::Valid),
+                        Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                    }
+                },
+            )
+            .prompt()?;
+    match ens::is_name(&recipient) {
+        true => Ok(recipient.trim().to_string()),
+        false => Ok(book.resolve(&recipient)),
+    }
+}
+
+/// Creates a text prompt suggesting the address book aliases.
+fn address_text<'a>(message: &'a str, book: &AddressBook) -> Text<'a> {
+    let book = book.clone();
+    let text = Text::new(message).with_autocomplete(
+        move |input: &str| -> std::result::Result<Vec<String>, CustomUserError> {
+            Ok(book.suggest(input))
+        },
+    );
+    text.with_help_message("Address or address book alias")
+}
+
+/// Prompts the user to select a method type (Read, Write, or All).
+///
+/// # Returns
+///
+/// * `Result<MethodType>` - The selected method type or an error
+pub fn select_method_type() -> Result<MethodType> {
+    let method_types = vec![MethodType::Read, MethodType::Write, MethodType::All];
+    let method_type = Select::new("Select method type:", method_types).prompt()?;
+    Ok(method_type)
+}
+
+/// Prompts the user to select a method from a list of available methods.
+///
+/// Methods are listed by signature, so overloads appear once each, and shown
+/// with their outputs; the list is filtered on them as the user types. The
+/// favorite and recent methods of the contract come first.
+///
+/// # Arguments
+///
+/// * `methods` - A map of method signatures to Function objects
+/// * `quick` - Signatures to list first, with why, see [`crate::usage::Usage::quick_access`]
+///
+/// # Returns
+///
+/// * `Result<String>` - The selected method signature or an error
+pub fn select_method(
+    methods: &HashMap<String, Function>,
+    quick: &[(String, QuickAccess)],
+) -> Result<String> {
+    let mut method_names: Vec<&String> = methods.keys().collect();
+    method_names.sort();
+    let entries = method_names
+        .into_iter()
+        .map(|name| (abi::signature_label(&methods[name]), name.clone()))
+        .collect();
+    select_method_entry(entries, methods, quick)
+}
+
+/// Prompts the user to select a method, listing methods under a group label
+/// after the favorite and recent methods of the contract.
+///
+/// # Arguments
+///
+/// * `groups` - Group labels with the method signatures they contain, in display order
+/// * `methods` - The methods by signature, whose outputs are shown
+/// * `quick` - Signatures to list first, with why, see [`crate::usage::Usage::quick_access`]
+///
+/// # Returns
+///
+/// * `Result<String>` - The selected method signature or an error
+pub fn select_grouped_method(
+    groups: &[(String, Vec<String>)],
+    methods: &HashMap<String, Function>,
+    quick: &[(String, QuickAccess)],
+) -> Result<String> {
+    let entries = groups
+        .iter()
+        .flat_map(|(group, names)| {
+            names.iter().map(move |name| {
+                let signature = methods
+                    .get(name)
+                    .map(abi::signature_label)
+                    .unwrap_or_else(|| name.clone());
+                (format!("[{}] {}", group, signature), name.clone())
+            })
+        })
+        .collect();
+    select_method_entry(entries, methods, quick)
+}
+
+/// Lets the user pick one of labelled method signatures, the quick access
+/// ones among `methods` listed first under their own label.
+fn select_method_entry(
+    entries: Vec<(String, String)>,
+    methods: &HashMap<String, Function>,
+    quick: &[(String, QuickAccess)],
+) -> Result<String> {
+    let entries: Vec<(String, String)> = quick
+        .iter()
+        .filter_map(|(name, access)| {
+            let function = methods.get(name)?;
+            Some((
+                format!("[{}] {}", access, abi::signature_label(function)),
+                name.clone(),
+            ))
+        })
+        .chain(entries)
+        .collect();
+    let labels: Vec<String> = entries.iter().map(|(label, _)| label.clone()).collect();
+    let index = fuzzy_select("Select a method:", labels).raw_prompt()?.index;
+    Ok(entries[index].1.clone())
+}
+
+/// Prompts the user to input parameters for a function.
+///
+/// Each input is validated against its type as it is entered (see
+/// [`validation::validate_param`]); `bool` parameters are chosen as Yes/No,
+/// structs are entered one named field at a time or as a tuple, and arrays
+/// as a list or element by element. Any typed input may instead be
+/// `@file.json`, reading the value from a JSON file, and long values such
+/// as `bytes`, strings and arrays may be entered in `$EDITOR` with `:edit`.
+///
+/// # Arguments
+///
+/// * `function` - The function for which parameters are needed
+/// * `defaults` - Values to start each input from, e.g. those of a past call
+/// * `book` - Address book whose aliases are accepted for `address` parameters
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - A vector of parameter inputs, aliases resolved, or an error
+pub fn input_method_params(
+    function: &Function,
+    defaults: &[String],
+    book: &AddressBook,
+) -> Result<Vec<String>> {
+    function
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let label = if param.name.is_empty() {
+                "unnamed"
+            } else {
+                &param.name
+            };
+            input_param(param, label, defaults.get(i).map(String::as_str), book)
+        })
+        .collect()
+}
+
+/// Prompts for the input of one parameter; the fields of a struct are
+/// prompted for in turn, labelled with their path, e.g. `order.maker`.
+///
+/// # Arguments
+///
+/// * `param` - The parameter
+/// * `label` - Name the parameter is prompted for as
+/// * `default` - Value to start the input from
+/// * `book` - Address book whose aliases are accepted for `address` parameters
+///
+/// # Returns
+///
+/// * `Result<String>` - The input, aliases resolved, or an error
+fn input_param(
+    param: &Param,
+    label: &str,
+    default: Option<&str>,
+    book: &AddressBook,
+) -> Result<String> {
+    let param_type = &param.ty;
+    let ty = codec::param_type(param)?;
+    let modes = match &ty {
+        DynSolType::Tuple(_) => vec!["Field by field", "As a tuple", "In $EDITOR"],
+        DynSolType::Array(_) | DynSolType::FixedArray(..) => {
+            vec!["As a list", "Element by element", "In $EDITOR"]
+        }
+        _ => Vec::new(),
+    };
+    let mode = match modes.is_empty() {
+        true => 0,
+        false => Select::new(
+            &format!("Enter {} ({}):", label, param.selector_type()),
+            modes,
+        )
+        .with_help_message(
+            "A tuple is written like (1, 2), a list like [1, 2], either also as JSON or @file.json",
+        )
+        .raw_prompt()?
+        .index,
+    };
+    if mode == 2 {
+        return edit_param(label, param_type, &ty, default);
+    }
+    if let (DynSolType::Tuple(types), 0) = (&ty, mode) {
+        let defaults = default.and_then(abi::split_list_input).unwrap_or_default();
+        let components = param
+            .components
+            .iter()
+            .enumerate()
+            .map(|(i, component)| {
+                let name = match component.name.is_empty() {
+                    true => i.to_string(),
+                    false => component.name.clone(),
+                };
+                let default = defaults.get(i).map(String::as_str);
+                input_param(component, &format!("{}.{}", label, name), default, book)
+            })
+            .collect::<Result<Vec<String>>>()?;
+        return Ok(abi::join_tuple_input(types, &components));
+    }
+    if let (DynSolType::Array(element) | DynSolType::FixedArray(element, _), 1) = (&ty, mode) {
+        let size = match &ty {
+            DynSolType::FixedArray(_, size) => Some(*size),
+            _ => None,
+        };
+        let elements = input_array_elements(param, label, size, default, book)?;
+        return Ok(abi::join_array_input(element, &elements));
+    }
+    let prompt = format!("Enter {} ({}):", label, param_type);
+    if ty == DynSolType::Bool {
+        let cursor = match default {
+            Some("true") => 1,
+            _ => 0,
+        };
+        let value = Select::new(&prompt, vec!["No", "Yes"])
+            .with_starting_cursor(cursor)
+            .prompt()?;
+        return Ok((value == "Yes").to_string());
+    }
+    let editable = is_editable(&ty);
+    let text = match param_type.as_str() {
+        "address" => address_text(&prompt, book),
+        _ if editable => Text::new(&prompt).with_help_message(
+            "Or @file.json to read the value from a JSON file, or :edit to open $EDITOR",
+        ),
+        _ => {
+            Text::new(&prompt).with_help_message("Or @file.json to read the value from a JSON file")
+        }
+    };
+    let text = match default {
+        Some(default) => text.with_initial_value(default),
+        None => text,
+    };
+    let resolver = book.clone();
+    let file_param = param.clone();
+    let validated = ty.clone();
+    let text = text.with_validator(
+        move |input: &str| -> std::result::Result<
+            Validation,
+            Box<dyn std::error::Error + Send + Sync>,
+        > {
+            if editable && editor::is_edit_command(input) {
+                return Ok(Validation::Valid);
+            }
+            let input = match params::file_reference(input) {
+                Some(path) => match params::read_param_file(path, &file_param) {
+                    Ok(input) => input,
+                    Err(e) => return Ok(Validation::Invalid(e.to_string().into())),
+                },
+                None => input.to_string(),
+            };
+            let input = match validated {
+                DynSolType::Address => resolver.resolve(&input),
+                _ => input,
+            };
+            match validation::validate_param(&validated, &input) {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+            }
+        },
+    );
+    let input = text.prompt()?;
+    if editable && editor::is_edit_command(&input) {
+        return edit_param(label, param_type, &ty, default);
+    }
+    let input = match params::file_reference(&input) {
+        Some(path) => params::read_param_file(path, param)?,
+        None => input,
+    };
+    match param_type.as_str() {
+        "address" => Ok(book.resolve(&input)),
+        _ => Ok(input),
+    }
+}
+
+/// Whether a parameter type may be long enough to be entered in the editor.
+fn is_editable(ty: &DynSolType) -> bool {
+    matches!(
+        ty,
+        DynSolType::Bytes
+            | DynSolType::String
+            | DynSolType::Array(_)
+            | DynSolType::FixedArray(..)
+            | DynSolType::Tuple(_)
+    )
+}
+
+/// Enters the value of a parameter in the user's editor, reopening it with
+/// the error until the value is valid. Whitespace in `bytes` is ignored, so
+/// wrapped hex can be pasted.
+///
+/// # Arguments
+///
+/// * `label` - Name the parameter is entered as
+/// * `param_type` - The parameter type as declared
+/// * `ty` - The parameter type
+/// * `default` - Value to start from
+///
+/// # Returns
+///
+/// * `Result<String>` - The input, or an error if the editor cannot be run or
+///   nothing was entered
+fn edit_param(
+    label: &str,
+    param_type: &str,
+    ty: &DynSolType,
+    default: Option<&str>,
+) -> Result<String> {
+    let (format, extension) = match ty {
+        DynSolType::Bytes => ("0x-prefixed hex, line breaks and spaces are ignored", "txt"),
+        DynSolType::String => ("text, possibly over several lines", "txt"),
+        DynSolType::Tuple(_) => ("a tuple such as (1, \"a\"), or a JSON array", "json"),
+        _ => ("a list such as [1, 2], or a JSON array", "json"),
+    };
+    let heading = format!("Enter {} ({}): {}", label, param_type, format);
+    let mut content = default.unwrap_or_default().to_string();
+    let mut error: Option<String> = None;
+    loop {
+        let mut instructions = vec![heading.clone()];
+        instructions.extend(error.take().map(|e| format!("Error: {}", e)));
+        let instructions: Vec<&str> = instructions.iter().map(String::as_str).collect();
+        let edited = editor::edit(&editor::template(&instructions, &content), extension)?;
+        let edited = match ty {
+            DynSolType::Bytes => edited.split_whitespace().collect(),
+            _ => edited,
+        };
+        if edited.is_empty() && *ty != DynSolType::String {
+            return Err(Error::InvalidArguments(format!(
+                "No value entered for {}",
+                label
+            )));
+        }
+        match validation::validate_param(ty, &edited) {
+            Ok(_) => return Ok(edited),
+            Err(e) => {
+                eprintln!("{} {}", "Invalid value:".red(), e);
+                error = Some(e.to_string());
+                content = edited;
+            }
+        }
+    }
+}
+
+/// Prompts for the elements of an array parameter one at a time, asking
+/// before each element of a dynamic array whether to add another.
+///
+/// # Arguments
+///
+/// * `param` - The array parameter
+/// * `label` - Name the parameter is prompted for as
+/// * `size` - Number of elements of a fixed-size array
+/// * `default` - Array input to start the elements from
+/// * `book` - Address book whose aliases are accepted for `address` elements
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - One input per element, or an error
+fn input_array_elements(
+    param: &Param,
+    label: &str,
+    size: Option<usize>,
+    default: Option<&str>,
+    book: &AddressBook,
+) -> Result<Vec<String>> {
+    let element = abi::element_param(param)
+        .ok_or_else(|| Error::InvalidArguments(format!("{} is not an array", param.ty)))?;
+    let defaults = default.and_then(abi::split_list_input).unwrap_or_default();
+    let mut elements = Vec::new();
+    loop {
+        let i = elements.len();
+        match size {
+            Some(size) if i == size => break,
+            Some(_) => {}
+            None => {
+                let message = match i {
+                    0 => format!("Add an element to {}?", label),
+                    _ => format!("Add another element to {} ({} so far)?", label, i),
+                };
+                let cursor = usize::from(i < defaults.len());
+                let add = Select::new(&message, vec!["No", "Yes"])
+                    .with_starting_cursor(cursor)
+                    .prompt()?;
+                if add == "No" {
+                    break;
+                }
+            }
+        }
+        let default = defaults.get(i).map(String::as_str);
+        elements.push(input_param(
+            &element,
+            &format!("{}[{}]", label, i),
+            default,
+            book,
+        )?);
+    }
+    Ok(elements)
+}
+
+/// Prompts for human-readable signatures, one per line, until an empty line.
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - The entered signatures or an error
+pub fn input_signatures() -> Result<Vec<String>> {
+    eprintln!(
+        "Enter signatures such as {}, then an empty line to finish.",
+        "function transfer(address,uint256) returns (bool)".cyan()
+    );
+    let mut signatures = Vec::new();
+    loop {
+        let signature =
+            Text::new(&format!("Signature {}:", signatures.len() + 1))
+                .with_validator(
+                    |input: &str| -> std::result::Result<
+                        Validation,
+                        Box<dyn std::error::Error + Send + Sync>,
+                    > {
+                        if input.trim().is_empty() {
+                            return Ok(Validation::Valid);
+                        }
+                        match abi::parse_human_readable(input) {
+                            Ok(_) => Ok(Validation::Valid),
+                            Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                        }
+                    },
+                )
+                .prompt()?;
+        let signature = signature.trim();
+        if signature.is_empty() {
+            break;
+        }
+        signatures.push(signature.to_string());
+    }
+    if signatures.is_empty() {
+        return Err(Error::InvalidAbi("No signature entered".to_string()));
+    }
+    Ok(signatures)
+}
+
+/// Prompts the user to select an event of the contract.
+///
+/// # Arguments
+///
+/// * `events` - The events declared by the ABI
+///
+/// # Returns
+///
+/// * `Result<Event>` - The selected event or an error
+pub fn select_event(events: &[Event]) -> Result<Event> {
+    let labels: Vec<String> = events.iter().map(|e| e.signature()).collect();
+    let index = Select::new("Select an event:", labels).raw_prompt()?.index;
+    Ok(events[index].clone())
+}
+
+/// Prompts for a filter value per indexed event parameter.
+///
+/// # Arguments
+///
+/// * `event` - The event to filter
+/// * `book` - Address book whose aliases are accepted for `address` parameters
+///
+/// # Returns
+///
+/// * `Result<Vec<Option<String>>>` - One entry per indexed parameter, `None` to match any value
+pub fn input_topic_filters(event: &Event, book: &AddressBook) -> Result<Vec<Option<String>>> {
+    event
+        .inputs
+        .iter()
+        .filter(|input| input.indexed)
+        .map(|input| {
+            let name = if input.name.is_empty() {
+                "unnamed"
+            } else {
+                &input.name
+            };
+            let message = format!("Filter {} ({}):", name, input.ty);
+            let value = match input.ty.as_str() {
+                "address" => address_text(&message, book)
+                    .with_help_message("Address or alias, empty to match any value")
+                    .prompt()?,
+                _ => Text::new(&message)
+                    .with_help_message("Leave empty to match any value")
+                    .prompt()?,
+            };
+            let value = book.resolve(&value);
+            Ok((!value.is_empty()).then_some(value))
+        })
+        .collect()
+}
+
+/// Prompts for the block range to search.
+///
+/// Blocks are given as numbers or as `latest`, `earliest`, `safe` or `finalized`.
+///
+/// # Returns
+///
+/// * `Result<(BlockNumberOrTag, BlockNumberOrTag)>` - The first and last block, or an error
+pub fn input_block_range() -> Result<(BlockNumberOrTag, BlockNumberOrTag)> {
+    let block = |message: &str, default: &str| -> Result<BlockNumberOrTag> {
+        let input =
+            Text::new(message)
+                .with_default(default)
+                .with_validator(
+                    |input: &str| -> std::result::Result<
+                        Validation,
+                        Box<dyn std::error::Error + Send + Sync>,
+                    > {
+                        match validation::parse_block(input) {
+                            Ok(_) => Ok(Validation::Valid),
+                            Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                        }
+                    },
+                )
+                .prompt()?;
+        validation::parse_block(&input)
+    };
+    Ok((
+        block("From block:", "earliest")?,
+        block("To block:", "latest")?,
+    ))
+}
+
+/// Prompts for the block a read-only call is made at, or two blocks to
+/// compare its outputs at.
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - One block, or two to compare, each a number, hash or tag
+pub fn input_call_blocks() -> Result<Vec<String>> {
+    let input = Text::new("Block:")
+        .with_default("latest")
+        .with_help_message(
+            "Number, hash or tag (latest, finalized, safe); two separated by a space compare the outputs",
+        )
+        .with_validator(
+            |input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+                let blocks: Vec<&str> = input.split_whitespace().collect();
+                if blocks.is_empty() || blocks.len() > 2 {
+                    return Ok(Validation::Invalid("Enter one or two blocks".into()));
+                }
+                match blocks.iter().try_for_each(|block| validation::parse_block_id(block).map(|_| ())) {
+                    Ok(()) => Ok(Validation::Valid),
+                    Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                }
+            },
+        )
+        .prompt()?;
+    Ok(input.split_whitespace().map(str::to_string).collect())
+}
+
+/// Asks whether to merge the implementation ABI of a detected proxy.
+///
+/// # Arguments
+///
+/// * `info` - The detected proxy
+///
+/// # Returns
+///
+/// * `Result<bool>` - Whether the user wants the implementation ABI merged
+pub fn confirm_merge_implementation(info: &ProxyInfo) -> Result<bool> {
+    eprintln!(
+        "{} {} proxy delegating to {}",
+        "Detected".cyan(),
+        info.kind,
+        info.implementation
+    );
+    let merge = Select::new("Merge the implementation ABI?", vec!["Yes", "No"]).prompt()?;
+    Ok(merge == "Yes")
+}
+
+/// What to do with a previewed transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirmation {
+    /// Sign and broadcast it
+    Send,
+    /// Export it unsigned, to be signed offline
+    Export,
+    /// Drop it
+    Cancel,
+}
+
+/// Lists the no-op transactions about to take over stuck or missing nonces
+/// and asks the user to send them.
+///
+/// # Arguments
+///
+/// * `replacements` - The planned replacements
+///
+/// # Returns
+///
+/// * `Result<bool>` - Whether the user confirmed
+pub fn confirm_replacements(replacements: &[Replacement]) -> Result<bool> {
+    for replacement in replacements {
+        eprintln!(
+            "  nonce {}: max fee {} wei, priority fee {} wei",
+            replacement.nonce, replacement.max_fee_per_gas, replacement.max_priority_fee_per_gas
+        );
+    }
+    let prompt = format!(
+        "Send {} no-op transaction(s) to yourself to take these nonces over?",
+        replacements.len()
+    );
+    Ok(Select::new(&prompt, vec!["Yes", "No"]).prompt()? == "Yes")
+}
+
+/// Asks whether to send a transaction through the private relay, defaulting
+/// to the relay's `enabled` setting.
+///
+/// # Arguments
+///
+/// * `relay` - The configured relay
+///
+/// # Returns
+///
+/// * `Result<bool>` - Whether to use the relay
+pub fn confirm_private_relay(relay: &RelayConfig) -> Result<bool> {
+    let prompt = format!("Send privately through {}?", relay.url());
+    let choice = Select::new(&prompt, vec!["Yes", "No"])
+        .with_starting_cursor(if relay.enabled { 0 } else { 1 })
+        .prompt()?;
+    Ok(choice == "Yes")
+}
+
+/// Shows a transaction preview and asks the user to confirm it.
+///
+/// # Arguments
+///
+/// * `preview` - The transaction about to be signed
+///
+/// # Returns
+///
+/// * `Result<Confirmation>` - Whether to send, export or cancel the transaction
+pub fn confirm_transaction(preview: &TransactionPreview) -> Result<Confirmation> {
+    eprintln!(
+        "{}",
+        "Warning: This is a write operation that will modify the blockchain state.".yellow()
+    );
+    eprintln!("{}", preview);
+    let options = vec!["Yes", "Export unsigned for offline signing", "No"];
+    let confirm = Select::new("Do you want to proceed?", options)
+        .raw_prompt()?
+        .index;
+    Ok(match confirm {
+        0 => Confirmation::Send,
+        1 => Confirmation::Export,
+        _ => Confirmation::Cancel,
+    })
+}
+
+/// Word to type to send a transaction breaking the policy.
+const POLICY_OVERRIDE: &str = "override";
+
+/// Lists the policy rules a transaction breaks and asks the user to type
+/// [`POLICY_OVERRIDE`] to send it anyway.
+///
+/// # Arguments
+///
+/// * `violations` - The rules the transaction breaks
+///
+/// # Returns
+///
+/// * `Result<bool>` - Whether the user typed the confirmation, or an error
+pub fn confirm_policy_override(violations: &[String]) -> Result<bool> {
+    eprintln!(
+        "{}",
+        "This transaction breaks the configured policy:"
+            .red()
+            .bold()
+    );
+    for violation in violations {
+        eprintln!("  {} {}", "-".red(), violation);
+    }
+    let answer = Text::new(&format!("Type \"{}\" to send it anyway:", POLICY_OVERRIDE))
+        .with_help_message("Anything else cancels the transaction")
+        .prompt()?;
+    Ok(answer.trim() == POLICY_OVERRIDE)
+}
+
+/// Shows the gas and fee estimate and prompts the user for optional overrides.
+///
+/// Every value may be left empty to keep the default, or let the provider
+/// fill it in when there is none.
+///
+/// # Arguments
+///
+/// * `estimate` - The transaction as it would be sent with `defaults`
+/// * `nonces` - The sender's latest and pending nonces
+/// * `defaults` - The configured gas settings
+///
+/// # Returns
+///
+/// * `Result<TransactionOptions>` - The chosen overrides or an error
+pub fn input_transaction_options(
+    estimate: &TransactionPreview,
+    nonces: &NonceStatus,
+    defaults: TransactionOptions,
+) -> Result<TransactionOptions> {
+    eprintln!(
+        "{} gas limit {}, fees {}",
+        "Estimate:".bold(),
+        estimate.gas_limit,
+        estimate.fees()
+    );
+    match nonces.is_clean() {
+        true => eprintln!("{}", nonces),
+        false => eprintln!("{}", nonces.to_string().yellow()),
+    }
+    let customize =
+        Select::new("Customize transaction options?", vec!["No", "Yes"]).prompt()? == "Yes";
+    let mut options = defaults;
+    if !customize {
+        return Ok(options);
+    }
+
+    if let Some(gas_limit) = input_optional::<u64>("Gas limit:")? {
+        options = options.with_gas_limit(gas_limit);
+    }
+    let tx_type = Select::new(
+        "Transaction type:",
+        vec!["auto", "eip1559", "legacy", "eip2930"],
+    )
+    .prompt()?;
+    if tx_type != "auto" {
+        options = options.with_tx_type(TxType::from_str(tx_type)?);
+    }
+    if tx_type == "legacy" || tx_type == "eip2930" {
+        if let Some(FeePerGas(gas_price)) = input_optional("Gas price (wei, or e.g. 30 gwei):")? {
+            options = options.with_gas_price(gas_price);
+        }
+    } else {
+        if let Some(FeePerGas(max_fee)) = input_optional("Max fee per gas (wei, or e.g. 30 gwei):")?
+        {
+            options = options.with_max_fee_per_gas(max_fee);
+        }
+        if let Some(FeePerGas(priority_fee)) =
+            input_optional("Max priority fee per gas (wei, or e.g. 2 gwei):")?
+        {
+            options = options.with_max_priority_fee_per_gas(priority_fee);
+        }
+    }
+    let nonce_help = format!(
+        "Next is {}; a nonce from {} replaces the transaction pending at it",
+        nonces.pending, nonces.latest
+    );
+    let nonce = Text::new("Nonce:")
+        .with_help_message(&nonce_help)
+        .with_validator(|input: &str| match input.trim() {
+            "" => Ok(Validation::Valid),
+            input => match input.parse::<u64>() {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+            },
+        })
+        .prompt()?;
+    if let Ok(nonce) = nonce.trim().parse::<u64>() {
+        options = options.with_nonce(nonce);
+    }
+    if tx_type != "legacy" {
+        let access_list =
+            Text::new("Access list (JSON):")
+                .with_help_message(
+                    "auto to generate one with eth_createAccessList, attached if it saves gas; empty for none",
+                )
+                .with_validator(
+                    |input: &str| -> std::result::Result<
+                        Validation,
+                        Box<dyn std::error::Error + Send + Sync>,
+                    > {
+                        if matches!(input.trim(), "" | "auto") {
+                            return Ok(Validation::Valid);
+                        }
+                        match serde_json::from_str::<alloy::rpc::types::AccessList>(input) {
+                            Ok(_) => Ok(Validation::Valid),
+                            Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                        }
+                    },
+                )
+                .prompt()?;
+        options = match access_list.trim() {
+            "" => options,
+            "auto" => options.with_access_list_generation(true),
+            access_list => options.with_access_list(serde_json::from_str(access_list)?),
+        };
+    }
+    if let Some(confirmations) = input_optional::<u64>("Confirmations:")? {
+        options = options.with_confirmations(confirmations);
+    }
+
+    options.validate()?;
+    Ok(options)
+}
+
+/// Displays the outcome of a transaction simulation.
+///
+/// # Arguments
+///
+/// * `simulation` - The simulated outputs, or the error the transaction would revert with
+pub fn display_simulation(simulation: &Result<CallResult>) {
+    match simulation {
+        Ok(result) => {
+            eprintln!("{}", "Simulation succeeded:".green());
+            eprintln!("{}", result);
+        }
+        Err(e) => eprintln!("{} {}", "Simulation failed:".red(), e),
+    }
+}
+
+/// Asks whether to trace a reverting call with `debug_traceCall`.
+///
+/// # Returns
+///
+/// * `Result<bool>` - Whether the user wants the call tree
+pub fn confirm_trace() -> Result<bool> {
+    let trace = Select::new("Trace the call to find the revert?", vec!["No", "Yes"])
+        .with_help_message("Needs an RPC node exposing debug_traceCall")
+        .prompt()?;
+    Ok(trace == "Yes")
+}
+
+/// Prompts for a chain name or ID to search for.
+///
+/// # Returns
+///
+/// * `Result<String>` - The search query or an error
+pub fn input_chain_query() -> Result<String> {
+    Text::new("Search chains:")
+        .with_help_message("Chain name, short name or ID, e.g. \"base\" or 8453")
+        .with_validator(|input: &str| match input.trim().is_empty() {
+            true => Ok(Validation::Invalid("Enter a name or an ID".into())),
+            false => Ok(Validation::Valid),
+        })
+        .prompt()
+        .map_err(Error::from)
+}
+
+/// Prompts the user to select one of the chains matching a search, marking
+/// testnets, the chain L2s settle on and red flags.
+///
+/// # Arguments
+///
+/// * `chains` - The matching chains
+/// * `registry` - The chain list, naming the parents of L2s
+///
+/// # Returns
+///
+/// * `Result<usize>` - Index of the selected chain or an error
+pub fn select_chain(chains: &[&ChainInfo], registry: &ChainRegistry) -> Result<usize> {
+    let labels = chains
+        .iter()
+        .map(|chain| {
+            let mut details = format!("{}, {}", chain.chain_id, chain.native_currency.symbol);
+            if chain.is_testnet() {
+                details.push_str(", testnet");
+            }
+            if let Some(parent) = chain.parent_chain_id().filter(|_| chain.is_l2()) {
+                match registry.get(parent) {
+                    Ok(parent) => details.push_str(&format!(", L2 of {}", parent.name)),
+                    Err(_) => details.push_str(&format!(", L2 of chain {}", parent)),
+                }
+            }
+            let mut label = format!("{} {}", chain.name, format!("({})", details).dimmed());
+            if !chain.red_flags.is_empty() {
+                label.push_str(&format!(" {}", chain.red_flags.join(", ").yellow()));
+            }
+            label
+        })
+        .collect();
+    Ok(Select::new("Select a chain:", labels).raw_prompt()?.index)
+}
+
+/// Asks whether to register a chain ID missing from the chain list as a
+/// custom chain, e.g. an internal devnet.
+///
+/// # Arguments
+///
+/// * `chain_id` - The unknown chain ID
+///
+/// # Returns
+///
+/// * `Result<bool>` - Whether to register it
+pub fn confirm_register_chain(chain_id: u64) -> Result<bool> {
+    let register = Select::new(
+        &format!(
+            "Chain {} is unknown. Register it as a custom chain?",
+            chain_id
+        ),
+        vec!["No", "Yes"],
+    )
+    .with_help_message("Saved to custom_chains.json and listed with the known chains")
+    .prompt()?;
+    Ok(register == "Yes")
+}
+
+/// Prompts for the name of a custom chain.
+///
+/// # Returns
+///
+/// * `Result<String>` - The name or an error
+pub fn input_custom_chain_name() -> Result<String> {
+    Text::new("Chain name:")
+        .with_help_message("e.g. \"Acme Devnet\"")
+        .with_validator(|input: &str| match input.trim().is_empty() {
+            true => Ok(Validation::Invalid("Enter a name".into())),
+            false => Ok(Validation::Valid),
+        })
+        .prompt()
+        .map(|name| name.trim().to_string())
+        .map_err(Error::from)
+}
+
+/// Asks whether to make a chain the default in `config.toml`.
+///
+/// # Arguments
+///
+/// * `chain` - The chain switched to
+///
+/// # Returns
+///
+/// * `Result<bool>` - Whether to save the chain
+pub fn confirm_save_chain(chain: &ChainInfo) -> Result<bool> {
+    let save = Select::new(
+        &format!("Use {} by default on next start?", chain.name),
+        vec!["No", "Yes"],
+    )
+    .with_help_message("Writes chain_id and rpc_url to config.toml")
+    .prompt()?;
+    Ok(save == "Yes")
+}
+
+/// Prompts for the block a chain is forked at.
+///
+/// # Returns
+///
+/// * `Result<Option<u64>>` - The block number, `None` for the latest block, or an error
+pub fn input_fork_block() -> Result<Option<u64>> {
+    let input = Text::new("Fork at block:")
+        .with_default("latest")
+        .with_help_message("Block number, or latest")
+        .with_validator(|input: &str| match input.trim() {
+            "latest" => Ok(Validation::Valid),
+            block => match block.parse::<u64>() {
+                Ok(_) => Ok(Validation::Valid),
+                Err(_) => Ok(Validation::Invalid("Enter a block number or latest".into())),
+            },
+        })
+        .prompt()?;
+    match input.trim() {
+        "latest" => Ok(None),
+        block => block
+            .parse()
+            .map(Some)
+            .map_err(|_| Error::InvalidArguments(format!("Invalid block number: {}", block))),
+    }
+}
+
+/// What to do with the development node the session is connected to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevNodeAction {
+    /// Call and send from an impersonated account
+    Impersonate,
+    /// Call and send from the signer again
+    StopImpersonating,
+    /// Snapshot the node's state
+    Snapshot,
+    /// Revert the node's state to a snapshot
+    Revert,
+    /// Move the node's clock forward
+    IncreaseTime,
+    /// Stop the fork and reconnect to the forked chain
+    StopFork,
+    /// Leave the development node tools
+    Back,
+}
+
+/// Shows the development node the session is connected to and prompts for
+/// what to do with it.
+///
+/// # Arguments
+///
+/// * `node` - The node
+/// * `fork` - The fork the node runs, if Inachus started it
+/// * `impersonating` - The account impersonated, if any
+///
+/// # Returns
+///
+/// * `Result<DevNodeAction>` - The selected action or an error
+pub fn select_dev_node_action(
+    node: &DevNode,
+    fork: Option<&ForkSession>,
+    impersonating: Option<Address>,
+) -> Result<DevNodeAction> {
+    if let Some(fork) = fork {
+        let block = match fork.block {
+            Some(block) => format!("block {}", block),
+            None => "the latest block".to_string(),
+        };
+        eprintln!(
+            "{} {} at {} on {}",
+            "Forked".cyan(),
+            fork.origin(),
+            block,
+            fork.node.url()
+        );
+    }
+    if let Some(address) = impersonating {
+        eprintln!("{} {}", "Acting as".cyan(), address);
+    }
+    let mut actions = vec![("Act as an account", DevNodeAction::Impersonate)];
+    if impersonating.is_some() {
+        actions.push(("Act as your wallet again", DevNodeAction::StopImpersonating));
+    }
+    actions.push(("Take a snapshot", DevNodeAction::Snapshot));
+    if !node.snapshots.is_empty() {
+        actions.push(("Revert to a snapshot", DevNodeAction::Revert));
+    }
+    actions.push(("Move the clock forward", DevNodeAction::IncreaseTime));
+    if fork.is_some() {
+        actions.push(("Stop the fork", DevNodeAction::StopFork));
+    }
+    actions.push(("Back", DevNodeAction::Back));
+    let labels = actions.iter().map(|(label, _)| *label).collect();
+    let index = Select::new("Development node:", labels).raw_prompt()?.index;
+    Ok(actions[index].1)
+}
+
+/// Prompts the user to pick the snapshot to revert a development node to.
+///
+/// # Arguments
+///
+/// * `snapshots` - The snapshots taken, oldest first
+///
+/// # Returns
+///
+/// * `Result<usize>` - Index of the selected snapshot or an error
+pub fn select_snapshot(snapshots: &[StateSnapshot]) -> Result<usize> {
+    let labels = snapshots
+        .iter()
+        .map(|snapshot| {
+            format!(
+                "Block {} {}",
+                snapshot.block,
+                format!("(snapshot {})", snapshot.id).dimmed()
+            )
+        })
+        .collect();
+    Ok(Select::new("Revert to:", labels)
+        .with_help_message("Later snapshots are discarded")
+        .raw_prompt()?
+        .index)
+}
+
+/// Prompts for how far to move a development node's clock forward.
+///
+/// # Returns
+///
+/// * `Result<Duration>` - The duration or an error
+pub fn input_time_increase() -> Result<Duration> {
+    let input = Text::new("Move the clock forward by:")
+        .with_default("1day")
+        .with_help_message("e.g. 3600s, 12h or 7days")
+        .with_validator(
+            |input: &str| match validation::parse_duration("duration", input.trim()) {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+            },
+        )
+        .prompt()?;
+    validation::parse_duration("duration", input.trim())
+}
+
+/// Prompts for the passphrase of a keystore.
+///
+/// # Arguments
+///
+/// * `path` - The keystore being unlocked
+///
+/// # Returns
+///
+/// * `Result<String>` - The entered passphrase or an error
+pub fn input_passphrase(path: &Path) -> Result<String> {
+    Ok(
+        Password::new(&format!("Passphrase for {}:", path.display()))
+            .without_confirmation()
+            .prompt()?,
+    )
+}
+
+/// Prompts for the file name of a new keystore.
+///
+/// # Returns
+///
+/// * `Result<String>` - The keystore name or an error
+pub fn input_keystore_name() -> Result<String> {
+    Text::new("Keystore name:")
+        .with_default("default")
+        .with_validator(|input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+            if !input.is_empty() && input.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid("Use letters, digits, '_' or '-'".into()))
+            }
+        })
+        .prompt()
+        .map_err(Error::from)
+}
+
+/// Prompts the user to pick one of the accounts derived from a mnemonic.
+///
+/// # Arguments
+///
+/// * `accounts` - The derived signers by index, with their balance when it could be fetched
+///
+/// # Returns
+///
+/// * `Result<usize>` - Index of the selected account or an error
+pub fn select_account(accounts: &[(Signer, Option<U256>)]) -> Result<usize> {
+    let labels = accounts
+        .iter()
+        .enumerate()
+        .map(|(index, (signer, balance))| {
+            let balance = match balance {
+                Some(balance) => format!("{} ETH", format_ether(*balance)),
+                None => "balance unavailable".to_string(),
+            };
+            format!(
+                "#{:<3} {}  {}",
+                index,
+                signer.address().to_checksum(None),
+                balance.dimmed()
+            )
+        })
+        .collect();
+    Ok(Select::new("Select an account:", labels)
+        .raw_prompt()?
+        .index)
+}
+
+/// Prompts the user to pick the wallet transactions are sent from.
+///
+/// # Arguments
+///
+/// * `wallets` - The configured wallets with their address, balance and nonce
+///
+/// # Returns
+///
+/// * `Result<usize>` - Index of the selected wallet or an error
+pub fn select_wallet(wallets: &[WalletSummary]) -> Result<usize> {
+    let width = wallets.iter().map(|w| w.name.len()).max().unwrap_or(0);
+    let labels = wallets
+        .iter()
+        .map(|wallet| {
+            let address = match wallet.address {
+                Some(address) => address.to_checksum(None),
+                None => "(connects on selection)".to_string(),
+            };
+            let balance = match wallet.balance {
+                Some(balance) => format!("{} ETH", format_ether(balance)),
+                None => "balance unavailable".to_string(),
+            };
+            let nonce = match wallet.nonce {
+                Some(nonce) => format!("nonce {}", nonce),
+                None => "nonce unavailable".to_string(),
+            };
+            let label = format!(
+                "{:<width$}  {}  {}",
+                wallet.name,
+                address,
+                format!("{}, {}", balance, nonce).dimmed(),
+                width = width
+            );
+            match wallet.active {
+                true => format!("{} {}", label, "(active)".green()),
+                false => label,
+            }
+        })
+        .collect();
+    Ok(Select::new("Select a wallet:", labels).raw_prompt()?.index)
+}
+
+/// Prompts the user to pick the configuration profile to switch to.
+///
+/// # Arguments
+///
+/// * `profiles` - Names of the defined profiles
+/// * `active` - The profile of the current session, if any
+///
+/// # Returns
+///
+/// * `Result<usize>` - Index of the selected profile or an error
+pub fn select_profile(profiles: &[String], active: Option<&str>) -> Result<usize> {
+    let labels = profiles
+        .iter()
+        .map(|profile| match Some(profile.as_str()) == active {
+            true => format!("{} {}", profile, "(active)".green()),
+            false => profile.clone(),
+        })
+        .collect();
+    Ok(Select::new("Select a profile:", labels).raw_prompt()?.index)
+}
+
+/// Action picked in the address book manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressBookAction {
+    /// Add an alias or change its address
+    Add,
+    /// Remove an alias
+    Remove,
+    /// Leave the address book
+    Back,
+}
+
+/// Lists the address book and prompts for what to do with it.
+///
+/// # Arguments
+///
+/// * `book` - The address book
+///
+/// # Returns
+///
+/// * `Result<AddressBookAction>` - The selected action or an error
+pub fn select_address_book_action(book: &AddressBook) -> Result<AddressBookAction> {
+    if book.is_empty() {
+        eprintln!("{}", "The address book is empty.".yellow());
+    } else {
+        let rows: Vec<(&str, String)> = book
+            .entries()
+            .map(|(alias, address)| (alias, address.to_checksum(None)))
+            .collect();
+        eprintln!("{}", text::table(&rows));
+    }
+    let mut actions = vec![("Add alias", AddressBookAction::Add)];
+    if !book.is_empty() {
+        actions.push(("Remove alias", AddressBookAction::Remove));
+    }
+    actions.push(("Back", AddressBookAction::Back));
+    let labels = actions.iter().map(|(label, _)| *label).collect();
+    let index = Select::new("Address book:", labels).raw_prompt()?.index;
+    Ok(actions[index].1)
+}
+
+/// Prompts for a new address book alias.
+///
+/// # Returns
+///
+/// * `Result<String>` - The alias or an error
+pub fn input_alias() -> Result<String> {
+    Text::new("Alias:")
+        .with_validator(|input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+            match address_book::validate_alias(input) {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+            }
+        })
+        .prompt()
+        .map_err(Error::from)
+}
+
+/// Prompts the user to pick an alias of the address book.
+///
+/// # Arguments
+///
+/// * `book` - The address book
+///
+/// # Returns
+///
+/// * `Result<String>` - The selected alias or an error
+pub fn select_alias(book: &AddressBook) -> Result<String> {
+    let aliases = book.entries().map(|(alias, _)| alias.to_string()).collect();
+    Ok(Select::new("Select an alias:", aliases).prompt()?)
+}
+
+/// What to do with a transaction picked in the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryAction {
+    /// Send the same call again
+    Repeat,
+    /// Edit the arguments, then send the call
+    Edit,
+    /// Go back to the history
+    Back,
+}
+
+/// Prompts the user to pick a journaled transaction, most recent first.
+///
+/// # Arguments
+///
+/// * `entries` - The journal entries, oldest first
+///
+/// # Returns
+///
+/// * `Result<usize>` - Index of the selected entry in `entries`, or an error
+pub fn select_history_entry(entries: &[JournalEntry]) -> Result<usize> {
+    let labels: Vec<String> = entries
+        .iter()
+        .rev()
+        .map(|entry| {
+            let status = match entry.status {
+                JournalStatus::Confirmed => entry.status.to_string().green(),
+                JournalStatus::Reverted => entry.status.to_string().red(),
+                JournalStatus::Submitted => entry.status.to_string().yellow(),
+            };
+            format!("{}  {}  {}", entry.date().dimmed(), entry.call(), status)
+        })
+        .collect();
+    let index = Select::new("Select a transaction:", labels)
+        .raw_prompt()?
+        .index;
+    Ok(entries.len() - 1 - index)
+}
+
+/// Shows a journaled transaction and prompts for what to do with it.
+///
+/// # Arguments
+///
+/// * `entry` - The transaction
+///
+/// # Returns
+///
+/// * `Result<HistoryAction>` - The selected action or an error
+pub fn select_history_action(entry: &JournalEntry) -> Result<HistoryAction> {
+    let mut rows = vec![
+        ("Sent", entry.date()),
+        ("Chain", entry.chain_id.to_string()),
+        match entry.contract.is_empty() {
+            true => ("To", entry.address.to_string()),
+            false => (
+                "Contract",
+                format!("{} ({})", entry.contract, entry.address),
+            ),
+        },
+        ("Function", entry.function.clone()),
+    ];
+    for (i, arg) in entry.args.iter().enumerate() {
+        rows.push(("Argument", format!("{}: {}", i, arg)));
+    }
+    if !entry.value.is_zero() {
+        rows.push(("Value", format!("{} ETH", format_ether(entry.value))));
+    }
+    rows.push(("Hash", entry.hash.to_string()));
+    rows.push(("Status", entry.status.to_string()));
+    if let Some(gas_used) = entry.gas_used {
+        rows.push(("Gas used", gas_used.to_string()));
+    }
+    eprintln!("{}", text::table(&rows));
+    // A transaction recorded without a contract has no ABI to be sent again with.
+    let actions = match entry.contract.is_empty() {
+        true => vec![("Back", HistoryAction::Back)],
+        false => vec![
+            ("Send again", HistoryAction::Repeat),
+            ("Edit arguments and send", HistoryAction::Edit),
+            ("Back", HistoryAction::Back),
+        ],
+    };
+    let labels = actions.iter().map(|(label, _)| *label).collect();
+    let index = Select::new("Transaction:", labels).raw_prompt()?.index;
+    Ok(actions[index].1)
+}
+
+/// What to do in the CREATE2 step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Create2Action {
+    /// Compute an address from a deployer, salt and init code hash
+    Compute,
+    /// Deploy a build artifact through the CREATE2 factory
+    Deploy,
+}
+
+/// Prompts the user to compute a CREATE2 address or deploy through the factory.
+///
+/// # Returns
+///
+/// * `Result<Create2Action>` - The selected action or an error
+pub fn select_create2_action() -> Result<Create2Action> {
+    let actions = [
+        ("Compute an address", Create2Action::Compute),
+        (
+            "Deploy an artifact through the factory",
+            Create2Action::Deploy,
+        ),
+    ];
+    let labels = actions.iter().map(|(label, _)| *label).collect();
+    let index = Select::new("CREATE2:", labels).raw_prompt()?.index;
+    Ok(actions[index].1)
+}
+
+/// Prompts for a CREATE2 salt.
+///
+/// # Returns
+///
+/// * `Result<B256>` - The salt or an error
+pub fn input_salt() -> Result<B256> {
+    let input = Text::new("Salt:")
+        .with_default("0")
+        .with_help_message("Hex value of at most 32 bytes, or a decimal number")
+        .with_validator(
+            |input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+                match create2::parse_salt(input) {
+                    Ok(_) => Ok(Validation::Valid),
+                    Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                }
+            },
+        )
+        .prompt()?;
+    create2::parse_salt(&input)
+}
+
+/// Prompts for the keccak256 hash of some init code.
+///
+/// # Returns
+///
+/// * `Result<B256>` - The hash or an error
+pub fn input_init_code_hash() -> Result<B256> {
+    let input = Text::new("Init code hash:")
+        .with_validator(
+            |input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+                match B256::from_str(input.trim()) {
+                    Ok(_) => Ok(Validation::Valid),
+                    Err(_) => Ok(Validation::Invalid("Enter a 0x-prefixed 32-byte hash".into())),
+                }
+            },
+        )
+        .prompt()?;
+    B256::from_str(input.trim()).map_err(|e| Error::InvalidArguments(e.to_string()))
+}
+
+/// Prompts for the path of a Foundry or Hardhat build artifact.
+///
 /// # Returns
 ///
-/// * `Result<Step>` - The selected step or an error
-pub fn select_step() -> Result<Step> {
-    let steps = Step::all();
-    let step = Select::new("Select an action:", steps.to_vec())
+/// * `Result<PathBuf>` - The path of an existing file or an error
+pub fn input_artifact_path() -> Result<PathBuf> {
+    Text::new("Artifact path:")
+        .with_help_message("Foundry or Hardhat JSON artifact with linked creation bytecode")
+        .with_validator(
+            |input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+                match Path::new(input.trim()).is_file() {
+                    true => Ok(Validation::Valid),
+                    false => Ok(Validation::Invalid("No such file".into())),
+                }
+            },
+        )
         .prompt()
-        .map_err(|e| Error::Other(e.to_string()))?;
-    Ok(step)
+        .map(|input| PathBuf::from(input.trim()))
+        .map_err(Error::from)
 }
 
-/// Prompts the user to select a contract from a list of available contracts.
+/// What to do with an ERC-20 token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Erc20Action {
+    /// Read the balance of an account
+    Balance,
+    /// Read what a spender may transfer from an owner
+    Allowance,
+    /// Transfer tokens from the signer
+    Transfer,
+    /// Allow a spender to transfer the signer's tokens
+    Approve,
+    /// Go back to the step selector
+    Back,
+}
+
+/// Asks whether to treat a contract whose ABI does not look like ERC-20 as a token.
 ///
 /// # Arguments
 ///
-/// * `contract_names` - A slice of available contract names
+/// * `contract` - Name of the contract
 ///
 /// # Returns
 ///
-/// * `Result<String>` - The selected contract name or an error
-pub fn select_contract_name(contract_names: &[String]) -> Result<String> {
-    let contract_name = Select::new("Select a contract:", contract_names.to_vec())
+/// * `Result<bool>` - Whether to use the standard ERC-20 ABI for it, or an error
+pub fn confirm_assume_erc20(contract: &str) -> Result<bool> {
+    eprintln!(
+        "{} {}",
+        contract,
+        "does not declare the ERC-20 functions.".yellow()
+    );
+    let assume = Select::new("Treat it as an ERC-20 token anyway?", vec!["No", "Yes"]).prompt()?;
+    Ok(assume == "Yes")
+}
+
+/// Prompts the user to pick what to do with an ERC-20 token.
+///
+/// # Returns
+///
+/// * `Result<Erc20Action>` - The selected action or an error
+pub fn select_erc20_action() -> Result<Erc20Action> {
+    let actions = [
+        ("Balance of", Erc20Action::Balance),
+        ("Allowance", Erc20Action::Allowance),
+        ("Transfer", Erc20Action::Transfer),
+        ("Approve", Erc20Action::Approve),
+        ("Back", Erc20Action::Back),
+    ];
+    let labels = actions.iter().map(|(label, _)| *label).collect();
+    let index = Select::new("Token:", labels).raw_prompt()?.index;
+    Ok(actions[index].1)
+}
+
+/// Prompts for an amount of a token, in whole tokens.
+///
+/// # Arguments
+///
+/// * `token` - The token, whose decimals scale the amount
+/// * `allow_max` - Whether `max`, the largest amount, is accepted
+///
+/// # Returns
+///
+/// * `Result<U256>` - The amount in base units or an error
+pub fn input_token_amount(token: &TokenInfo, allow_max: bool) -> Result<U256> {
+    let decimals = token.decimals;
+    let message = format!("Amount ({}):", token.symbol.as_deref().unwrap_or("tokens"));
+    let help = match allow_max {
+        true => format!("e.g. 1.5, at most {} decimals; max for unlimited", decimals),
+        false => format!("e.g. 1.5, at most {} decimals", decimals),
+    };
+    let input =
+        Text::new(&message)
+            .with_help_message(&help)
+            .with_validator(
+                move |input: &str| -> std::result::Result<
+                    Validation,
+                    Box<dyn std::error::Error + Send + Sync>,
+                > {
+                    if !allow_max && input.trim().eq_ignore_ascii_case("max") {
+                        return Ok(Validation::Invalid("Enter an amount".into()));
+                    }
+                    match erc20::parse_amount(input, decimals) {
+                        Ok(_) => Ok(Validation::Valid),
+                        Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                    }
+                },
+            )
+            .prompt()?;
+    erc20::parse_amount(&input, decimals)
+}
+
+/// What to do with an NFT collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NftAction {
+    /// Read the owner of an ERC-721 token
+    OwnerOf,
+    /// Read the balance of an account
+    Balance,
+    /// Fetch and show the metadata of a token
+    Metadata,
+    /// Transfer a token from the signer with `safeTransferFrom`
+    Transfer,
+    /// Go back to the step selector
+    Back,
+}
+
+/// Asks which NFT standard a contract follows when it cannot be detected.
+///
+/// # Arguments
+///
+/// * `contract` - Name of the contract
+///
+/// # Returns
+///
+/// * `Result<Option<NftStandard>>` - The asserted standard, `None` if it is not an NFT contract
+pub fn select_nft_standard(contract: &str) -> Result<Option<NftStandard>> {
+    eprintln!(
+        "{} {}",
+        contract,
+        "was not recognized as an ERC-721 or ERC-1155 contract.".yellow()
+    );
+    let standards = [
+        ("Treat it as ERC-721", Some(NftStandard::Erc721)),
+        ("Treat it as ERC-1155", Some(NftStandard::Erc1155)),
+        ("Back", None),
+    ];
+    let labels = standards.iter().map(|(label, _)| *label).collect();
+    let index = Select::new("Standard:", labels).raw_prompt()?.index;
+    Ok(standards[index].1)
+}
+
+/// Prompts the user to pick what to do with an NFT collection.
+///
+/// # Arguments
+///
+/// * `standard` - The standard of the collection; `ownerOf` is only offered for ERC-721
+///
+/// # Returns
+///
+/// * `Result<NftAction>` - The selected action or an error
+pub fn select_nft_action(standard: NftStandard) -> Result<NftAction> {
+    let actions: Vec<(&str, NftAction)> = [
+        ("Owner of", NftAction::OwnerOf),
+        ("Balance of", NftAction::Balance),
+        ("Metadata", NftAction::Metadata),
+        ("Transfer", NftAction::Transfer),
+        ("Back", NftAction::Back),
+    ]
+    .into_iter()
+    .filter(|(_, action)| *action != NftAction::OwnerOf || standard == NftStandard::Erc721)
+    .collect();
+    let labels = actions.iter().map(|(label, _)| *label).collect();
+    let index = Select::new(&format!("{}:", standard), labels)
+        .raw_prompt()?
+        .index;
+    Ok(actions[index].1)
+}
+
+/// Prompts for a non-negative integer such as a token ID or a quantity.
+///
+/// # Arguments
+///
+/// * `message` - The prompt message
+///
+/// # Returns
+///
+/// * `Result<U256>` - The number or an error
+pub fn input_uint(message: &str) -> Result<U256> {
+    let input = Text::new(message)
+        .with_help_message("Decimal, or hex with 0x")
+        .with_validator(
+            |input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+                match U256::from_str(input.trim()) {
+                    Ok(_) => Ok(Validation::Valid),
+                    Err(_) => Ok(Validation::Invalid("Enter a non-negative integer".into())),
+                }
+            },
+        )
+        .prompt()?;
+    U256::from_str(input.trim()).map_err(|e| Error::InvalidArguments(e.to_string()))
+}
+
+/// Prompts for a message to sign or verify.
+///
+/// # Returns
+///
+/// * `Result<Bytes>` - The message bytes or an error
+pub fn input_message() -> Result<Bytes> {
+    let input = Text::new("Message:")
+        .with_help_message("Text, or 0x-prefixed hex for raw bytes; :edit to open $EDITOR")
+        .prompt()?;
+    let input = match editor::is_edit_command(&input) {
+        true => editor::edit(
+            &editor::template(
+                &["Message to sign: text, or 0x-prefixed hex for raw bytes"],
+                "",
+            ),
+            "txt",
+        )?,
+        false => input,
+    };
+    Ok(message::parse_message(&input))
+}
+
+/// Prompts for the hash of a transaction.
+///
+/// # Returns
+///
+/// * `Result<TxHash>` - The hash or an error
+pub fn input_transaction_hash() -> Result<TxHash> {
+    let input = Text::new("Transaction hash:")
+        .with_validator(
+            |input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+                match TxHash::from_str(input.trim()) {
+                    Ok(_) => Ok(Validation::Valid),
+                    Err(_) => Ok(Validation::Invalid("Enter a 0x-prefixed 32-byte hash".into())),
+                }
+            },
+        )
+        .prompt()?;
+    TxHash::from_str(input.trim()).map_err(|e| Error::InvalidArguments(e.to_string()))
+}
+
+/// Prompts for hex calldata to decode.
+///
+/// # Returns
+///
+/// * `Result<Bytes>` - The calldata, at least a selector long, or an error
+pub fn input_calldata() -> Result<Bytes> {
+    let input = Text::new("Calldata:")
+        .with_help_message("0x-prefixed hex, starting with the 4-byte selector; :edit to open $EDITOR")
+        .with_validator(
+            |input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+                if editor::is_edit_command(input) {
+                    return Ok(Validation::Valid);
+                }
+                match Bytes::from_str(input.trim()) {
+                    Ok(data) if data.len() >= 4 => Ok(Validation::Valid),
+                    Ok(_) => Ok(Validation::Invalid("Calldata starts with a 4-byte selector".into())),
+                    Err(e) => Ok(Validation::Invalid(format!("Invalid hex: {}", e).into())),
+                }
+            },
+        )
+        .prompt()?;
+    let input = match editor::is_edit_command(&input) {
+        true => editor::edit(
+            &editor::template(
+                &["Calldata: 0x-prefixed hex, line breaks and spaces are ignored"],
+                "",
+            ),
+            "txt",
+        )?
+        .split_whitespace()
+        .collect(),
+        false => input,
+    };
+    Bytes::from_str(input.trim()).map_err(|e| Error::InvalidArguments(e.to_string()))
+}
+
+/// Prompts the user to pick among functions sharing a selector.
+///
+/// # Arguments
+///
+/// * `matches` - The functions, with the names of the ABIs declaring them
+///
+/// # Returns
+///
+/// * `Result<usize>` - Index of the selected function, or an error
+pub fn select_selector_match(matches: &[(&Function, Vec<&str>)]) -> Result<usize> {
+    let labels: Vec<String> = matches
+        .iter()
+        .map(|(function, names)| format!("{}  {}", function.signature(), names.join(", ").dimmed()))
+        .collect();
+    Ok(
+        Select::new("Several functions share this selector:", labels)
+            .raw_prompt()?
+            .index,
+    )
+}
+
+/// Prompts for a 65-byte signature.
+///
+/// # Returns
+///
+/// * `Result<String>` - The signature as hex or an error
+pub fn input_signature() -> Result<String> {
+    Text::new("Signature:")
+        .with_help_message("0x-prefixed, 65 bytes")
+        .with_validator(
+            |input: &str| match PrimitiveSignature::from_str(input.trim()) {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+            },
+        )
         .prompt()
-        .map_err(|e| Error::Other(e.to_string()))?;
-    Ok(contract_name)
+        .map_err(Error::from)
 }
 
-/// Prompts the user to input a contract address with validation.
+/// Prompts for a signed raw transaction, typed as hex or read from a file.
 ///
 /// # Returns
 ///
-/// * `Result<String>` - The validated contract address or an error
-pub fn input_contract_address() -> Result<String> {
-    let address = Text::new("Enter contract address:")
-        .with_validator(|input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
-            match validation::validate_contract_address(input) {
+/// * `Result<SignedTransaction>` - The decoded transaction or an error
+pub fn input_raw_transaction() -> Result<SignedTransaction> {
+    let input = Text::new("Signed transaction:")
+        .with_help_message("0x-prefixed hex, or the path of a file containing it")
+        .with_validator(|input: &str| {
+            match SignedTransaction::decode(&read_raw_transaction(input)) {
                 Ok(_) => Ok(Validation::Valid),
-                Err(e) => Ok(Validation::Invalid(e.to_string().into()))
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
             }
         })
-        .prompt()
-        .map_err(|e| Error::Other(e.to_string()))?;
-    Ok(address)
+        .prompt()?;
+    SignedTransaction::decode(&read_raw_transaction(&input))
 }
 
-/// Prompts the user to select a method type (Read, Write, or All).
+/// Returns the contents of the file `input` names, or `input` itself.
+fn read_raw_transaction(input: &str) -> String {
+    let input = input.trim();
+    match input.starts_with("0x") {
+        true => input.to_string(),
+        false => std::fs::read_to_string(input).unwrap_or_else(|_| input.to_string()),
+    }
+}
+
+/// Shows a decoded signed transaction and asks the user to broadcast it.
+///
+/// # Arguments
+///
+/// * `tx` - The signed transaction
 ///
 /// # Returns
 ///
-/// * `Result<MethodType>` - The selected method type or an error
-pub fn select_method_type() -> Result<MethodType> {
-    let method_types = vec![MethodType::Read, MethodType::Write, MethodType::All];
-    let method_type = Select::new("Select method type:", method_types)
-        .prompt()
-        .map_err(|e| Error::Other(e.to_string()))?;
-    Ok(method_type)
+/// * `Result<bool>` - Whether the user confirmed the broadcast
+pub fn confirm_broadcast(tx: &SignedTransaction) -> Result<bool> {
+    eprintln!("{}", tx);
+    let confirm = Select::new("Broadcast this transaction?", vec!["Yes", "No"]).prompt()?;
+    Ok(confirm == "Yes")
 }
 
-/// Prompts the user to select a method from a list of available methods.
+/// Prompts for the address a signature is expected from, which may be left empty.
 ///
 /// # Arguments
 ///
-/// * `methods` - A map of method names to Function objects
+/// * `book` - Address book whose aliases are suggested and accepted
 ///
 /// # Returns
 ///
-/// * `Result<String>` - The selected method name or an error
-pub fn select_method(methods: &HashMap<String, Function>) -> Result<String> {
-    let method_names: Vec<String> = methods.keys().cloned().collect();
-    let method_name = Select::new("Select a method:", method_names)
-        .prompt()
-        .map_err(|e| Error::Other(e.to_string()))?;
-    Ok(method_name)
+/// * `Result<Option<Address>>` - The expected signer, `None` if left empty, or an error
+pub fn input_expected_signer(book: &AddressBook) -> Result<Option<Address>> {
+    let resolver = book.clone();
+    let input =
+        address_text("Expected signer:", book)
+            .with_help_message("Address or alias to compare with, empty to only recover the signer")
+            .with_validator(
+                move |input: &str| -> std::result::Result<
+                    Validation,
+                    Box<dyn std::error::Error + Send + Sync>,
+                > {
+                    if input.trim().is_empty() {
+                        return Ok(Validation::Valid);
+                    }
+                    match Address::from_str(&resolver.resolve(input)) {
+                        Ok(_) => Ok(Validation::Valid),
+                        Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+                    }
+                },
+            )
+            .prompt()?;
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+    Address::from_str(&book.resolve(&input))
+        .map(Some)
+        .map_err(|e| Error::InvalidAddress(e.to_string()))
 }
 
-/// Prompts the user to input parameters for a function.
+/// Asks whether to propose a transaction to a Safe.
 ///
 /// # Arguments
 ///
-/// * `function` - The function for which parameters are needed
+/// * `safe` - Address of the Safe
+/// * `tx` - The Safe transaction
+/// * `safe_tx_hash` - The hash the owners will sign
 ///
 /// # Returns
 ///
-/// * `Result<Vec<String>>` - A vector of parameter inputs or an error
-pub fn input_method_params(function: &Function) -> Result<Vec<String>> {
-    let mut params = Vec::new();
-    for param in function.inputs.iter() {
-        let param_name = if param.name.is_empty() {
-            "unnamed"
-        } else {
-            &param.name
-        };
-        let param_type = &param.ty;
-        let prompt = format!("Enter {} ({}):", param_name, param_type);
-        let value = Text::new(&prompt)
-            .prompt()
-            .map_err(|e| Error::Other(e.to_string()))?;
-        params.push(value);
+/// * `Result<bool>` - Whether to sign and propose the transaction
+pub fn confirm_safe_proposal(safe: Address, tx: &SafeTx, safe_tx_hash: B256) -> Result<bool> {
+    eprintln!("\n{} {}", "Safe:".cyan(), safe);
+    eprintln!("{} {}", "Transaction:".cyan(), tx);
+    eprintln!("{} {}", "safeTxHash:".cyan(), safe_tx_hash);
+    let propose = Select::new("Sign and propose to the Safe?", vec!["Yes", "No"])
+        .with_help_message("The other owners confirm and execute it in the Safe app")
+        .prompt()?;
+    Ok(propose == "Yes")
+}
+
+/// Prompts for the allowance granted by a permit.
+///
+/// # Returns
+///
+/// * `Result<U256>` - The value in token base units, `U256::MAX` for `max`, or an error
+pub fn input_permit_value() -> Result<U256> {
+    let parse = |input: &str| match input.trim() {
+        "max" => Ok(U256::MAX),
+        input => U256::from_str(input).map_err(|e| e.to_string()),
+    };
+    let input = Text::new("Value:")
+        .with_help_message("Allowance in token base units, or max")
+        .with_validator(move |input: &str| match parse(input) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(e) => Ok(Validation::Invalid(e.into())),
+        })
+        .prompt()?;
+    parse(&input).map_err(Error::InvalidArguments)
+}
+
+/// Prompts for the deadline of a permit.
+///
+/// # Returns
+///
+/// * `Result<U256>` - The deadline as a unix timestamp, or an error
+pub fn input_permit_deadline() -> Result<U256> {
+    let input = Text::new("Deadline:")
+        .with_default("1h")
+        .with_help_message("How long from now, e.g. 30m or 7d, or a unix timestamp")
+        .with_validator(|input: &str| match parse_deadline(input) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+        })
+        .prompt()?;
+    parse_deadline(&input)
+}
+
+/// Reads a deadline typed as a duration from now or as a unix timestamp.
+fn parse_deadline(input: &str) -> Result<U256> {
+    let input = input.trim();
+    if let Ok(timestamp) = input.parse::<u64>() {
+        return Ok(U256::from(timestamp));
     }
-    Ok(params)
+    let from_now = validation::parse_duration("deadline", input)?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok(U256::from((now + from_now).as_secs()))
 }
 
-/// Asks the user to confirm a transaction before proceeding.
+/// Asks whether to submit a signed permit as a transaction.
 ///
 /// # Returns
 ///
-/// * `Result<bool>` - Whether the user confirmed (true) or denied (false) the transaction
-pub fn confirm_transaction() -> Result<bool> {
-    println!(
-        "{}",
-        "Warning: This is a write operation that will modify the blockchain state.".yellow()
-    );
-    let confirm = Select::new("Do you want to proceed?", vec!["Yes", "No"])
-        .prompt()
-        .map_err(|e| Error::Other(e.to_string()))?;
-    Ok(confirm == "Yes")
+/// * `Result<bool>` - Whether to call `permit` now
+pub fn confirm_submit_permit() -> Result<bool> {
+    let submit = Select::new("Submit the permit transaction now?", vec!["No", "Yes"])
+        .with_help_message("Otherwise hand the signature to whoever submits it")
+        .prompt()?;
+    Ok(submit == "Yes")
+}
+
+/// Prompts for a new keystore passphrase, asking for it twice.
+///
+/// # Returns
+///
+/// * `Result<String>` - The confirmed passphrase or an error
+pub fn input_new_passphrase() -> Result<String> {
+    Ok(Password::new("New passphrase:")
+        .with_custom_confirmation_message("Confirm passphrase:")
+        .with_custom_confirmation_error_message("The passphrases don't match")
+        .prompt()?)
+}
+
+/// Prompts for the native value sent to a payable function.
+///
+/// # Returns
+///
+/// * `Result<U256>` - The value in wei, zero if left empty, or an error
+pub fn input_value() -> Result<U256> {
+    let input = Text::new("Value (ETH):")
+        .with_help_message("e.g. 1.5 ether or 2000 gwei; a bare number is in ether, empty for none")
+        .with_validator(|input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+            if input.trim().is_empty() {
+                return Ok(Validation::Valid);
+            }
+            match validation::parse_value(input) {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+            }
+        })
+        .prompt()?;
+    if input.trim().is_empty() {
+        return Ok(U256::ZERO);
+    }
+    validation::parse_value(&input)
+}
+
+/// A fee per gas typed with an optional unit, e.g. `30 gwei`; a bare number is in wei.
+struct FeePerGas(u128);
+
+impl FromStr for FeePerGas {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let wei = codec::parse_amount(input, Unit::WEI)?;
+        u128::try_from(wei)
+            .map(FeePerGas)
+            .map_err(|_| Error::InvalidArguments(format!("Fee {} is too large", input)))
+    }
+}
+
+/// Prompts for a value that may be left empty.
+///
+/// # Arguments
+///
+/// * `message` - The prompt message
+///
+/// # Returns
+///
+/// * `Result<Option<T>>` - The parsed value, `None` if left empty, or an error
+fn input_optional<T>(message: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let input = Text::new(message)
+        .with_help_message("Leave empty for default")
+        .with_validator(|input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+            if input.trim().is_empty() {
+                return Ok(Validation::Valid);
+            }
+            match input.trim().parse::<T>() {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+            }
+        })
+        .prompt()?;
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    input
+        .parse()
+        .map(Some)
+        .map_err(|e: T::Err| Error::InvalidArguments(e.to_string()))
 }
 
 /// Displays a result to the user.
@@ -137,9 +2175,148 @@ pub fn confirm_transaction() -> Result<bool> {
 /// # Arguments
 ///
 /// * `result` - The result to display
-pub fn display_result(result: &str) {
-    println!("\n{}", "Result:".green());
-    println!("{}", result);
+/// * `book` - Address book used to annotate the addresses of the result
+/// * `format` - Whether to print text or a structured [`Output`]
+pub fn display_result<'a, R>(result: &'a R, book: &AddressBook, format: OutputFormat)
+where
+    R: std::fmt::Display,
+    Output: From<&'a R>,
+{
+    match format {
+        OutputFormat::Json => println!("{}", Output::from(result)),
+        OutputFormat::Text => {
+            println!("\n{}", "Result:".green());
+            println!("{}", book.annotate(&result.to_string()));
+        }
+    }
+}
+
+/// Displays the result of executing a method or sending a transaction,
+/// followed by its explorer link and decoded logs once confirmed.
+///
+/// # Arguments
+///
+/// * `ctx` - The global context, for the address book, ABIs and explorers
+/// * `result` - The result to display
+pub fn display_execution(ctx: &GlobalContext, result: &ExecutionResult) {
+    display_result(result, &ctx.address_book, ctx.output);
+    display_receipt(ctx, result);
+}
+
+/// Displays the explorer link of a sent transaction and, once confirmed,
+/// the logs of its receipt, for results already printed.
+///
+/// # Arguments
+///
+/// * `ctx` - The global context, for the address book, ABIs and explorers
+/// * `result` - The result just displayed
+pub fn display_receipt(ctx: &GlobalContext, result: &ExecutionResult) {
+    display_explorer_link(ctx.explorer_url(result), ctx.output);
+    if let ExecutionResult::Confirmed(receipt) = result {
+        display_receipt_logs(
+            receipt.inner.logs(),
+            &ctx.abis,
+            &ctx.address_book,
+            ctx.output,
+        );
+    }
+}
+
+/// Offers to save a displayed result to a file or copy it to the clipboard,
+/// as JSON holding the raw data and the decoded values. Nothing is asked
+/// when results are printed as JSON.
+///
+/// # Arguments
+///
+/// * `result` - The result just displayed
+/// * `format` - Whether results are printed as text or JSON
+///
+/// # Returns
+///
+/// * `Result<()>` - Success, also when the user declines, or an error if
+///   the file cannot be written or no clipboard is available
+pub fn offer_export<'a, R>(result: &'a R, format: OutputFormat) -> Result<()>
+where
+    Output: From<&'a R>,
+{
+    const SAVE: &str = "Save to a file";
+    const COPY: &str = "Copy to the clipboard";
+    if format == OutputFormat::Json {
+        return Ok(());
+    }
+    let choice = Select::new("Export the result?", vec!["No", SAVE, COPY]).prompt()?;
+    let output = Output::from(result);
+    match choice {
+        SAVE => {
+            let path = Text::new("File to save the result to:")
+                .with_default("result.json")
+                .with_validator(|input: &str| match input.trim().is_empty() {
+                    true => Ok(Validation::Invalid("Enter a file path".into())),
+                    false => Ok(Validation::Valid),
+                })
+                .prompt()?;
+            let path = PathBuf::from(path.trim());
+            export::write_file(&path, &output)?;
+            eprintln!("{} {}", "Saved to".green(), path.display());
+        }
+        COPY => {
+            export::copy_to_clipboard(&output)?;
+            eprintln!("{}", "Copied to the clipboard".green());
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Displays a link to a transaction on the chain's block explorer, in text output only.
+///
+/// # Arguments
+///
+/// * `url` - The explorer URL, if the chain has an explorer
+/// * `format` - Whether results are printed as text or JSON
+pub fn display_explorer_link(url: Option<String>, format: OutputFormat) {
+    if let (OutputFormat::Text, Some(url)) = (format, url) {
+        println!("{} {}", "Explorer:".dimmed(), url);
+    }
+}
+
+/// Displays the logs emitted by a transaction, decoded against the loaded ABIs.
+///
+/// # Arguments
+///
+/// * `logs` - The logs of the receipt
+/// * `abis` - The loaded ABIs
+/// * `book` - Address book used to annotate the addresses of the logs
+/// * `format` - Whether to print text or one structured [`Output`] per decoded log
+pub fn display_receipt_logs(
+    logs: &[alloy::rpc::types::Log],
+    abis: &HashMap<String, JsonAbi>,
+    book: &AddressBook,
+    format: OutputFormat,
+) {
+    if logs.is_empty() {
+        return;
+    }
+    if format == OutputFormat::Text {
+        println!("\n{}", "Logs:".green());
+    }
+    for log in logs {
+        match EventLog::decode_any(abis.values(), log) {
+            Some(decoded) if format == OutputFormat::Json => {
+                println!("{}", Output::from(&decoded))
+            }
+            Some(decoded) => println!("{}", book.annotate(&decoded.to_string())),
+            None => eprintln!(
+                "{} {} (topic {})",
+                "Unknown log from".yellow(),
+                book.annotate(&log.address().to_string()),
+                log.topics()
+                    .first()
+                    .map(|topic| topic.to_string())
+                    .unwrap_or_else(|| "none".to_string())
+            ),
+        }
+    }
 }
 
 /// Prompts the user for the path to the ABI directory.
@@ -153,7 +2330,7 @@ pub fn prompt_abi_dir() -> Result<PathBuf> {
         .with_help_message("Directory containing ABI JSON files")
         .prompt()
         .map(PathBuf::from)
-        .map_err(|e| Error::Other(e.to_string()))
+        .map_err(Error::from)
 }
 
 /// Prompts the user for a contract name.
@@ -165,7 +2342,7 @@ pub fn prompt_contract_name() -> Result<String> {
     Text::new("Enter the contract name:")
         .with_help_message("Name of the contract to interact with")
         .prompt()
-        .map_err(|e| Error::Other(e.to_string()))
+        .map_err(Error::from)
 }
 
 /// Prompts the user for a contract address with validation.
@@ -183,7 +2360,7 @@ pub fn prompt_contract_address() -> Result<String> {
             }
         })
         .prompt()
-        .map_err(|e| Error::Other(e.to_string()))
+        .map_err(Error::from)
 }
 
 /// Prompts the user for an Ethereum RPC URL.
@@ -194,21 +2371,115 @@ pub fn prompt_contract_address() -> Result<String> {
 pub fn prompt_rpc_url() -> Result<String> {
     Text::new("Enter the Ethereum RPC URL:")
         .with_default("http://localhost:8545")
-        .with_help_message("URL of the Ethereum JSON-RPC endpoint")
+        .with_help_message(
+            "http(s):// or ws(s):// URL, or IPC socket path of the JSON-RPC endpoint",
+        )
         .prompt()
-        .map_err(|e| Error::Other(e.to_string()))
+        .map_err(Error::from)
 }
 
-/// Prompts the user for their private key.
+/// Prompts the user for their private key, without echoing it.
 ///
 /// # Returns
 ///
 /// * `Result<String>` - The private key or an error
 pub fn prompt_private_key() -> Result<String> {
-    Text::new("Enter your private key (without 0x prefix):")
+    Password::new("Enter your private key (without 0x prefix):")
+        .without_confirmation()
         .with_help_message("Private key for transaction signing")
+        .with_validator(|input: &str| -> std::result::Result<Validation, Box<dyn std::error::Error + Send + Sync>> {
+            match validation::validate_private_key(input) {
+                Ok(_) => Ok(Validation::Valid),
+                Err(e) => Ok(Validation::Invalid(e.to_string().into())),
+            }
+        })
+        .prompt()
+        .map_err(Error::from)
+}
+
+/// How the configuration written by the setup wizard signs transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupSigner {
+    /// No signer, only read-only calls
+    ReadOnly,
+    /// A private key stored in `config.toml`
+    PrivateKey,
+    /// A private key encrypted into a keystore
+    Keystore,
+    /// A Ledger device
+    Ledger,
+}
+
+/// Prompts for how transactions are signed, during the setup wizard.
+///
+/// # Returns
+///
+/// * `Result<SetupSigner>` - The selected signer or an error
+pub fn select_setup_signer() -> Result<SetupSigner> {
+    let signers = [
+        ("Encrypted keystore (recommended)", SetupSigner::Keystore),
+        ("Ledger device", SetupSigner::Ledger),
+        ("Private key in config.toml", SetupSigner::PrivateKey),
+        ("None, read-only for now", SetupSigner::ReadOnly),
+    ];
+    let labels = signers.iter().map(|(label, _)| *label).collect();
+    let index = Select::new("How should transactions be signed?", labels)
+        .raw_prompt()?
+        .index;
+    Ok(signers[index].1)
+}
+
+/// Prompts for the derivation path of a Ledger account.
+///
+/// # Returns
+///
+/// * `Result<String>` - The derivation path or an error
+pub fn input_derivation_path() -> Result<String> {
+    Text::new("Derivation path:")
+        .with_default("m/44'/60'/0'/0/0")
         .prompt()
-        .map_err(|e| Error::Other(e.to_string()))
+        .map_err(Error::from)
+}
+
+/// Prompts the user to pick one of the healthy RPC endpoints of a chain, or
+/// to enter another one.
+///
+/// # Arguments
+///
+/// * `probes` - The healthy endpoints, fastest first
+///
+/// # Returns
+///
+/// * `Result<Option<usize>>` - Index of the selected endpoint, `None` to enter
+///   another one, or an error
+pub fn select_rpc(probes: &[&RpcProbe]) -> Result<Option<usize>> {
+    let labels: Vec<String> = probes
+        .iter()
+        .map(|probe| {
+            format!(
+                "{} {}",
+                probe.listed_url,
+                format!("({:?})", probe.latency).dimmed()
+            )
+        })
+        .chain(std::iter::once("Enter another URL".to_string()))
+        .collect();
+    let index = Select::new("Select an RPC endpoint:", labels)
+        .raw_prompt()?
+        .index;
+    Ok((index < probes.len()).then_some(index))
+}
+
+/// Prompts for an optional Etherscan API key.
+///
+/// # Returns
+///
+/// * `Result<Option<String>>` - The key, `None` if left empty, or an error
+pub fn input_etherscan_api_key() -> Result<Option<String>> {
+    let key = Text::new("Etherscan API key (optional):")
+        .with_help_message("Used to import verified ABIs; empty to only use Sourcify")
+        .prompt()?;
+    Ok(Some(key.trim().to_string()).filter(|key| !key.is_empty()))
 }
 
 /// Prompts the user for a chain ID.
@@ -221,7 +2492,7 @@ pub fn prompt_chain_id() -> Result<String> {
         .with_default("1")
         .with_help_message("Chain ID for transaction signing (1 for Ethereum Mainnet)")
         .prompt()
-        .map_err(|e| Error::Other(e.to_string()))
+        .map_err(Error::from)
 }
 
 /// Prompts the user to select a method from a list of functions.
@@ -234,16 +2505,12 @@ pub fn prompt_chain_id() -> Result<String> {
 ///
 /// * `Result<Function>` - The selected Function or an error
 pub fn prompt_method(methods: &[Function]) -> Result<Function> {
-    let method_names: Vec<String> = methods.iter().map(|m| m.name.clone()).collect();
+    let labels: Vec<String> = methods.iter().map(abi::signature_label).collect();
 
-    let selected_name = Select::new("Select a method to call:", method_names)
-        .with_help_message("Choose a contract method to execute")
-        .prompt()
-        .map_err(|e| Error::Other(e.to_string()))?;
+    let index = fuzzy_select("Select a method to call:", labels)
+        .with_help_message("Choose a contract method to execute, type to filter")
+        .raw_prompt()?
+        .index;
 
-    methods
-        .iter()
-        .find(|m| m.name == selected_name)
-        .cloned()
-        .ok_or_else(|| Error::InvalidFunction(format!("Method {} not found", selected_name)))
+    Ok(methods[index].clone())
 }
@@ -0,0 +1,269 @@
+/// src/proxy.rs
+use crate::cancel;
+use crate::context::ReadContext;
+use crate::error::{Error, Result};
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::{b256, Address, Selector, B256, U256};
+use alloy::providers::Provider;
+use std::fmt;
+
+/// EIP-1967 implementation slot, `keccak256("eip1967.proxy.implementation") - 1`.
+pub const EIP1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// EIP-1967 beacon slot, `keccak256("eip1967.proxy.beacon") - 1`.
+pub const EIP1967_BEACON_SLOT: B256 =
+    b256!("a3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50");
+
+/// EIP-1822 (UUPS) slot, `keccak256("PROXIABLE")`.
+pub const EIP1822_PROXIABLE_SLOT: B256 =
+    b256!("c5f16f0fcc639fa48a6947836d9850f504798523bf8c9a3a87d5876cf622bcf7");
+
+/// Proxy pattern a contract follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProxyKind {
+    /// EIP-1967 transparent or UUPS proxy storing its implementation address
+    Eip1967,
+    /// EIP-1967 beacon proxy, whose beacon returns the implementation address
+    Eip1967Beacon,
+    /// EIP-1822 universal upgradeable proxy
+    Eip1822,
+}
+
+impl fmt::Display for ProxyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyKind::Eip1967 => write!(f, "EIP-1967"),
+            ProxyKind::Eip1967Beacon => write!(f, "EIP-1967 beacon"),
+            ProxyKind::Eip1822 => write!(f, "EIP-1822"),
+        }
+    }
+}
+
+/// A detected proxy and the implementation it delegates to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyInfo {
+    /// Proxy pattern
+    pub kind: ProxyKind,
+    /// Address of the implementation contract
+    pub implementation: Address,
+}
+
+/// Detects whether a contract is a proxy by reading the standard storage slots.
+///
+/// # Arguments
+///
+/// * `read` - The read context used to query storage
+/// * `address` - Address of the contract
+///
+/// # Returns
+///
+/// * `Result<Option<ProxyInfo>>` - The proxy information, `None` if no slot is set, or an error
+pub async fn detect(read: &ReadContext, address: Address) -> Result<Option<ProxyInfo>> {
+    if let Some(implementation) = slot_address(read, address, EIP1967_IMPLEMENTATION_SLOT).await? {
+        return Ok(Some(ProxyInfo {
+            kind: ProxyKind::Eip1967,
+            implementation,
+        }));
+    }
+    if let Some(beacon) = slot_address(read, address, EIP1967_BEACON_SLOT).await? {
+        let function = Function::parse("implementation() view returns (address)")
+            .map_err(|e| Error::InvalidFunction(e.to_string()))?;
+        let implementation = read
+            .call(beacon, &function, &[])
+            .await?
+            .single()
+            .and_then(|value| value.as_address())
+            .ok_or_else(|| {
+                Error::InvalidContract(format!("Beacon {} returned no implementation", beacon))
+            })?;
+        return Ok(Some(ProxyInfo {
+            kind: ProxyKind::Eip1967Beacon,
+            implementation,
+        }));
+    }
+    if let Some(implementation) = slot_address(read, address, EIP1822_PROXIABLE_SLOT).await? {
+        return Ok(Some(ProxyInfo {
+            kind: ProxyKind::Eip1822,
+            implementation,
+        }));
+    }
+    Ok(None)
+}
+
+/// Reads a storage slot holding an address, `None` when it is zero.
+async fn slot_address(read: &ReadContext, address: Address, slot: B256) -> Result<Option<Address>> {
+    let operation = format!("reading storage of {}", address);
+    let value = cancel::guard(
+        &operation,
+        read.cancellation(),
+        read.timeouts().call,
+        async {
+            read.provider()
+                .get_storage_at(address, U256::from_be_bytes(slot.0))
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        },
+    )
+    .await?;
+    let implementation = Address::from_word(B256::from(value));
+    Ok((!implementation.is_zero()).then_some(implementation))
+}
+
+/// A facet of an EIP-2535 diamond, as returned by the loupe's `facets()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Facet {
+    /// Address of the facet contract
+    pub address: Address,
+    /// Selectors the diamond routes to this facet
+    pub selectors: Vec<Selector>,
+}
+
+impl Facet {
+    /// Returns whether the diamond routes a function to this facet.
+    pub fn routes(&self, function: &Function) -> bool {
+        self.selectors.contains(&function.selector())
+    }
+
+    /// Keeps the functions of a facet ABI that the diamond routes to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `abi` - The full ABI of the facet contract
+    ///
+    /// # Returns
+    ///
+    /// * `JsonAbi` - The ABI restricted to the routed functions, with all events and errors
+    pub fn restrict(&self, abi: &JsonAbi) -> JsonAbi {
+        let mut restricted = abi.clone();
+        for overloads in restricted.functions.values_mut() {
+            overloads.retain(|function| self.routes(function));
+        }
+        restricted
+            .functions
+            .retain(|_, overloads| !overloads.is_empty());
+        restricted
+    }
+}
+
+/// Lists the facets of an EIP-2535 diamond through its loupe.
+///
+/// # Arguments
+///
+/// * `read` - The read context used to call the loupe
+/// * `address` - Address of the diamond
+///
+/// # Returns
+///
+/// * `Result<Vec<Facet>>` - The facets, or an error if the contract has no loupe
+pub async fn diamond_facets(read: &ReadContext, address: Address) -> Result<Vec<Facet>> {
+    let function = Function::parse(
+        "facets() view returns ((address facetAddress, bytes4[] functionSelectors)[])",
+    )
+    .map_err(|e| Error::InvalidFunction(e.to_string()))?;
+    let result = read.call(address, &function, &[]).await?;
+    let facets = result
+        .single()
+        .and_then(|value| value.as_array())
+        .ok_or_else(|| Error::InvalidContract(format!("{} is not a diamond", address)))?;
+    facets
+        .iter()
+        .map(|facet| {
+            let (address, selectors) = match facet.as_tuple() {
+                Some([address, selectors]) => (address.as_address(), selectors.as_array()),
+                _ => (None, None),
+            };
+            let invalid = || Error::InvalidContract("Malformed facets() result".to_string());
+            Ok(Facet {
+                address: address.ok_or_else(invalid)?,
+                selectors: selectors
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .map(|selector| {
+                        selector
+                            .as_fixed_bytes()
+                            .map(|(bytes, _)| Selector::from_slice(&bytes[..4]))
+                            .ok_or_else(invalid)
+                    })
+                    .collect::<Result<_>>()?,
+            })
+        })
+        .collect()
+}
+
+/// Merges an implementation ABI into a proxy ABI.
+///
+/// Functions, events and errors of the implementation are added unless the
+/// proxy already declares one with the same signature.
+///
+/// # Arguments
+///
+/// * `proxy` - The proxy's own ABI
+/// * `implementation` - The implementation ABI
+///
+/// # Returns
+///
+/// * `JsonAbi` - The merged ABI
+pub fn merge_abis(proxy: &JsonAbi, implementation: &JsonAbi) -> JsonAbi {
+    let mut merged = proxy.clone();
+    for function in implementation.functions() {
+        let overloads = merged.functions.entry(function.name.clone()).or_default();
+        if !overloads
+            .iter()
+            .any(|f| f.signature() == function.signature())
+        {
+            overloads.push(function.clone());
+        }
+    }
+    for event in implementation.events() {
+        let overloads = merged.events.entry(event.name.clone()).or_default();
+        if !overloads.iter().any(|e| e.signature() == event.signature()) {
+            overloads.push(event.clone());
+        }
+    }
+    for error in implementation.errors() {
+        let overloads = merged.errors.entry(error.name.clone()).or_default();
+        if !overloads.iter().any(|e| e.signature() == error.signature()) {
+            overloads.push(error.clone());
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixtures, MockRpc, TEST_CONTRACT};
+
+    #[tokio::test]
+    async fn test_detect_merge_and_restrict() {
+        let implementation = Address::repeat_byte(0x11);
+        let mock = MockRpc::new();
+        mock.respond("eth_getStorageAt", implementation.into_word());
+
+        let proxy = detect(&mock.read_context(), TEST_CONTRACT)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(proxy.kind, ProxyKind::Eip1967);
+        assert_eq!(proxy.implementation, implementation);
+
+        let proxy_abi = JsonAbi::parse(["function upgradeTo(address)"]).unwrap();
+        let merged = merge_abis(&proxy_abi, &fixtures::erc20_abi());
+        assert!(merged.function("upgradeTo").is_some());
+        assert!(merged.function("transfer").is_some());
+        assert_eq!(
+            merge_abis(&merged, &fixtures::erc20_abi()),
+            merged,
+            "merging twice adds nothing"
+        );
+
+        let transfer = merged.function("transfer").unwrap()[0].clone();
+        let facet = Facet {
+            address: implementation,
+            selectors: vec![transfer.selector()],
+        };
+        let restricted = facet.restrict(&fixtures::erc20_abi());
+        assert_eq!(restricted.functions().collect::<Vec<_>>(), vec![&transfer]);
+    }
+}
@@ -0,0 +1,434 @@
+/// src/relay.rs
+use crate::context::WriteContext;
+use crate::error::{Error, Result};
+use crate::events::Event;
+use crate::result::ExecutionResult;
+use crate::transaction::TransactionOptions;
+use alloy::dyn_abi::DynSolValue;
+use alloy::eips::eip2718::Encodable2718;
+use alloy::json_abi::Function;
+use alloy::primitives::{hex, keccak256, Address, TxHash};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use alloy::transports::http::reqwest;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Flashbots Protect RPC, keeping transactions out of the public mempool.
+pub const FLASHBOTS_PROTECT_URL: &str = "https://rpc.flashbots.net";
+
+/// Flashbots relay accepting `eth_sendBundle`.
+pub const FLASHBOTS_RELAY_URL: &str = "https://relay.flashbots.net";
+
+/// Flashbots Protect status API, followed by `/<transaction hash>`.
+pub const FLASHBOTS_STATUS_URL: &str = "https://protect.flashbots.net/tx";
+
+/// How often inclusion is checked while waiting.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How a private relay takes transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayKind {
+    /// An RPC taking `eth_sendRawTransaction` and keeping the transaction
+    /// private, e.g. Flashbots Protect or MEV Blocker
+    #[default]
+    Rpc,
+    /// A bundle relay taking `eth_sendBundle`, submitted for each target block
+    Bundle,
+}
+
+/// Private relay settings, the `[private_relay]` table of `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RelayConfig {
+    /// How the relay takes transactions
+    pub kind: RelayKind,
+    /// Relay endpoint, Flashbots for the kind when unset
+    pub url: Option<String>,
+    /// Status API followed by `/<hash>`, Flashbots Protect's for the default RPC
+    pub status_url: Option<String>,
+    /// Private key signing `X-Flashbots-Signature` headers of bundles,
+    /// a throwaway key when unset
+    pub auth_key: Option<String>,
+    /// Whether writes go through the relay unless declined
+    pub enabled: bool,
+    /// Blocks to wait for inclusion, and bundle target blocks
+    pub blocks: u64,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            kind: RelayKind::default(),
+            url: None,
+            status_url: None,
+            auth_key: None,
+            enabled: true,
+            blocks: 25,
+        }
+    }
+}
+
+impl RelayConfig {
+    /// Returns the relay endpoint.
+    pub fn url(&self) -> &str {
+        match (&self.url, self.kind) {
+            (Some(url), _) => url,
+            (None, RelayKind::Rpc) => FLASHBOTS_PROTECT_URL,
+            (None, RelayKind::Bundle) => FLASHBOTS_RELAY_URL,
+        }
+    }
+
+    /// Returns the status API, if the relay has one.
+    pub fn status_url(&self) -> Option<&str> {
+        match (&self.status_url, &self.url, self.kind) {
+            (Some(url), _, _) => Some(url.trim_end_matches('/')),
+            (None, None, RelayKind::Rpc) => Some(FLASHBOTS_STATUS_URL),
+            _ => None,
+        }
+    }
+
+    /// Returns the key signing bundle requests.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PrivateKeySigner>` - The configured key or a random one, or an error if malformed
+    pub fn auth_signer(&self) -> Result<PrivateKeySigner> {
+        match &self.auth_key {
+            Some(key) => PrivateKeySigner::from_str(key).map_err(|e| {
+                Error::InvalidConfig(format!("Invalid private_relay.auth_key: {}", e))
+            }),
+            None => Ok(PrivateKeySigner::random()),
+        }
+    }
+}
+
+/// Where a privately sent transaction stands, as reported by the relay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayStatus {
+    /// Still being offered to builders
+    Pending,
+    /// Included in a block
+    Included,
+    /// Dropped by the relay, with its reason
+    Failed(String),
+    /// The relay does not know the transaction or reports no status
+    Unknown,
+}
+
+impl RelayStatus {
+    /// Reads a Flashbots Protect status response.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The JSON response
+    ///
+    /// # Returns
+    ///
+    /// * `RelayStatus` - The status, `Unknown` when absent
+    pub fn from_json(body: &Value) -> Self {
+        match body["status"].as_str() {
+            Some("PENDING") => RelayStatus::Pending,
+            Some("INCLUDED") => RelayStatus::Included,
+            Some(status @ ("FAILED" | "CANCELLED")) => RelayStatus::Failed(
+                body["simError"]
+                    .as_str()
+                    .filter(|reason| !reason.is_empty())
+                    .unwrap_or(status)
+                    .to_lowercase(),
+            ),
+            _ => RelayStatus::Unknown,
+        }
+    }
+}
+
+/// A transaction handed to a private relay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Submission {
+    /// Hash of the transaction
+    pub hash: TxHash,
+    /// Latest block when it was submitted
+    pub block: u64,
+}
+
+/// Client of a private relay.
+#[derive(Debug, Clone)]
+pub struct PrivateRelay {
+    config: RelayConfig,
+    auth: PrivateKeySigner,
+    client: reqwest::Client,
+}
+
+impl PrivateRelay {
+    /// Creates a client for the configured relay.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The relay settings
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PrivateRelay>` - The client or an error if the auth key is malformed
+    pub fn new(config: &RelayConfig) -> Result<Self> {
+        Ok(Self {
+            config: config.clone(),
+            auth: config.auth_signer()?,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Returns the relay settings.
+    pub fn config(&self) -> &RelayConfig {
+        &self.config
+    }
+
+    /// Submits a signed transaction, as a bundle for each of the next
+    /// `blocks` blocks when the relay takes bundles.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - The EIP-2718 encoded signed transaction
+    /// * `block` - The latest block
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error from the relay
+    pub async fn submit(&self, raw: &[u8], block: u64) -> Result<()> {
+        let raw = hex::encode_prefixed(raw);
+        match self.config.kind {
+            RelayKind::Rpc => {
+                self.request("eth_sendRawTransaction", json!([raw])).await?;
+            }
+            RelayKind::Bundle => {
+                for target in block + 1..=block + self.config.blocks.max(1) {
+                    let bundle = json!([{ "txs": [raw], "blockNumber": format!("{:#x}", target) }]);
+                    self.request("eth_sendBundle", bundle).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Asks the relay's status API about a transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - Hash of the transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RelayStatus>` - The status, `Unknown` without a status API, or an error
+    pub async fn status(&self, hash: TxHash) -> Result<RelayStatus> {
+        let Some(status_url) = self.config.status_url() else {
+            return Ok(RelayStatus::Unknown);
+        };
+        let url = format!("{}/{}", status_url, hash);
+        let body: Value = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::Other(format!("Failed to query {}: {}", url, e)))?
+            .json()
+            .await
+            .map_err(|e| Error::Other(format!("Invalid response from {}: {}", url, e)))?;
+        Ok(RelayStatus::from_json(&body))
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let url = self.config.url();
+        let body =
+            json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+        let mut request = self
+            .client
+            .post(url)
+            .header("content-type", "application/json");
+        if self.config.kind == RelayKind::Bundle {
+            request = request.header("X-Flashbots-Signature", self.flashbots_signature(&body)?);
+        }
+        let response: Value = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to reach relay {}: {}", url, e)))?
+            .json()
+            .await
+            .map_err(|e| Error::Other(format!("Invalid response from relay {}: {}", url, e)))?;
+        match response.get("error") {
+            Some(error) => Err(Error::Other(format!(
+                "Relay {} rejected {}: {}",
+                url,
+                method,
+                error["message"].as_str().unwrap_or(&error.to_string())
+            ))),
+            None => Ok(response["result"].clone()),
+        }
+    }
+
+    /// Signs a request body the way Flashbots relays authenticate searchers:
+    /// `personal_sign` of the hex `keccak256` of the body.
+    fn flashbots_signature(&self, body: &str) -> Result<String> {
+        let digest = keccak256(body.as_bytes()).to_string();
+        let signature = self
+            .auth
+            .sign_message_sync(digest.as_bytes())
+            .map_err(|e| Error::Other(format!("Failed to sign relay request: {}", e)))?;
+        Ok(format!(
+            "{}:{}",
+            self.auth.address(),
+            hex::encode_prefixed(signature.as_bytes())
+        ))
+    }
+}
+
+/// Signs a contract call and hands it to a private relay instead of the
+/// public mempool, emitting `TxSubmitted` on the write context's bus.
+///
+/// # Arguments
+///
+/// * `write` - The write context signing the transaction
+/// * `relay` - The relay
+/// * `to` - Address of the contract
+/// * `function` - The function to call
+/// * `args` - One value per function input
+/// * `options` - Overrides for gas, fees, nonce and value
+///
+/// # Returns
+///
+/// * `Result<Submission>` - The submitted transaction or an error
+pub async fn send(
+    write: &WriteContext,
+    relay: &PrivateRelay,
+    to: Address,
+    function: &Function,
+    args: &[DynSolValue],
+    options: &TransactionOptions,
+) -> Result<Submission> {
+    let tx = write.sign(to, function, args, options).await?;
+    let block = write.reader().get_block_number().await?;
+    relay.submit(&tx.encoded_2718(), block).await?;
+    write.events().emit(Event::TxSubmitted {
+        hash: *tx.tx_hash(),
+        contract: to,
+        function: function.signature(),
+    });
+    Ok(Submission {
+        hash: *tx.tx_hash(),
+        block,
+    })
+}
+
+/// Polls for the receipt of a privately sent transaction and the relay's
+/// status until it is included, dropped, or `blocks` blocks have passed.
+///
+/// A `TxConfirmed` event is emitted and post-receipt hooks run once it is included.
+///
+/// # Arguments
+///
+/// * `write` - The write context that signed the transaction
+/// * `relay` - The relay it was submitted to
+/// * `submission` - The submitted transaction
+///
+/// # Returns
+///
+/// * `Result<ExecutionResult>` - `Confirmed` once included, `Submitted` if the
+///   relay may still include it, or an error if it was dropped
+pub async fn wait(
+    write: &WriteContext,
+    relay: &PrivateRelay,
+    submission: &Submission,
+) -> Result<ExecutionResult> {
+    let read = write.reader();
+    let hash = submission.hash;
+    let last_block = submission.block + relay.config().blocks.max(1);
+    loop {
+        if let Some(receipt) = read.get_transaction_receipt(hash).await? {
+            write.events().emit(Event::TxConfirmed {
+                hash,
+                block_number: receipt.block_number,
+                gas_used: receipt.gas_used,
+                success: receipt.status(),
+            });
+            write.hooks().after_receipt(&receipt).await;
+            return Ok(ExecutionResult::Confirmed(Box::new(receipt)));
+        }
+        match relay.status(hash).await {
+            Ok(RelayStatus::Failed(reason)) => {
+                return Err(Error::Other(format!(
+                    "Private relay dropped {}: {}",
+                    hash, reason
+                )))
+            }
+            Ok(status) => tracing::debug!(%hash, ?status, "private transaction not included yet"),
+            Err(e) => tracing::debug!(%hash, error = %e, "could not query relay status"),
+        }
+        if read.get_block_number().await? > last_block {
+            return match relay.config().kind {
+                RelayKind::Bundle => Err(Error::Other(format!(
+                    "Bundle with {} was not included in blocks {} to {}",
+                    hash,
+                    submission.block + 1,
+                    last_block
+                ))),
+                RelayKind::Rpc => {
+                    tracing::warn!(%hash, "private transaction not included yet");
+                    Ok(ExecutionResult::Submitted { hash })
+                }
+            };
+        }
+        tokio::select! {
+            _ = read.cancellation().cancelled() => {
+                return Err(Error::Cancelled(format!("waiting for inclusion of {}", hash)))
+            }
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_config_and_status() {
+        let config: RelayConfig = toml::from_str("").unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.url(), FLASHBOTS_PROTECT_URL);
+        assert_eq!(config.status_url(), Some(FLASHBOTS_STATUS_URL));
+
+        let config: RelayConfig = toml::from_str("kind = \"bundle\"\nauth_key = \"nope\"").unwrap();
+        assert_eq!(config.url(), FLASHBOTS_RELAY_URL);
+        assert_eq!(config.status_url(), None);
+        assert!(config.auth_signer().is_err());
+
+        let config: RelayConfig = toml::from_str("url = \"https://rpc.mevblocker.io\"").unwrap();
+        assert_eq!(config.status_url(), None);
+
+        assert_eq!(
+            RelayStatus::from_json(&json!({ "status": "INCLUDED" })),
+            RelayStatus::Included
+        );
+        assert_eq!(
+            RelayStatus::from_json(&json!({ "status": "FAILED", "simError": "" })),
+            RelayStatus::Failed("failed".to_string())
+        );
+        assert_eq!(RelayStatus::from_json(&json!({})), RelayStatus::Unknown);
+
+        let relay = PrivateRelay::new(&RelayConfig::default()).unwrap();
+        let header = relay.flashbots_signature("{}").unwrap();
+        let (address, signature) = header.split_once(':').unwrap();
+        let signature = alloy::primitives::PrimitiveSignature::from_str(signature).unwrap();
+        let digest = keccak256("{}").to_string();
+        assert_eq!(
+            signature
+                .recover_address_from_msg(digest.as_bytes())
+                .unwrap()
+                .to_string(),
+            address
+        );
+    }
+}
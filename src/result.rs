@@ -0,0 +1,582 @@
+/// src/result.rs
+use crate::address_book::AddressBook;
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format;
+use crate::offline::UnsignedTransaction;
+use crate::output::Output;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::{Event, Function, JsonAbi, Param};
+use alloy::primitives::utils::format_units;
+use alloy::primitives::{Address, Bytes, TxHash, B256, U256};
+use alloy::rpc::types::{Log, TransactionReceipt};
+use serde_json::{json, Value};
+use std::fmt;
+use text::Table;
+
+/// Display width event parameter columns are truncated to.
+const EVENT_COLUMN_WIDTH: usize = 60;
+
+/// Decoded outcome of a read-only function call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallResult {
+    /// Signature of the called function
+    pub function: String,
+    /// Names of the function outputs, empty strings for unnamed outputs
+    pub names: Vec<String>,
+    /// ABI parameters of the outputs, naming the fields of structs
+    pub params: Vec<Param>,
+    /// Decoded output values, one per function output
+    pub values: Vec<DynSolValue>,
+    /// Raw return data as returned by the node
+    pub raw: Bytes,
+}
+
+impl CallResult {
+    /// Creates a call result for a function from its decoded and raw outputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function that was called
+    /// * `values` - The decoded outputs
+    /// * `raw` - The raw return data
+    ///
+    /// # Returns
+    ///
+    /// * `CallResult` - A new call result
+    pub fn new(function: &Function, values: Vec<DynSolValue>, raw: Bytes) -> Self {
+        Self {
+            function: function.signature(),
+            names: function.outputs.iter().map(|o| o.name.clone()).collect(),
+            params: function.outputs.clone(),
+            values,
+            raw,
+        }
+    }
+
+    /// Creates a result holding the decoded arguments of a call to a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function the calldata is for
+    /// * `values` - The decoded arguments
+    /// * `raw` - The calldata
+    ///
+    /// # Returns
+    ///
+    /// * `CallResult` - A result naming its values after the function inputs
+    pub fn from_inputs(function: &Function, values: Vec<DynSolValue>, raw: Bytes) -> Self {
+        Self {
+            names: function.inputs.iter().map(|i| i.name.clone()).collect(),
+            params: function.inputs.clone(),
+            ..Self::new(function, values, raw)
+        }
+    }
+
+    /// Returns the single output of the call, if it has exactly one.
+    pub fn single(&self) -> Option<&DynSolValue> {
+        match self.values.as_slice() {
+            [value] => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Looks up an output by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The output name as declared in the ABI
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&DynSolValue>` - The output value, if an output has that name
+    pub fn get(&self, name: &str) -> Option<&DynSolValue> {
+        self.names
+            .iter()
+            .position(|n| !n.is_empty() && n == name)
+            .and_then(|i| self.values.get(i))
+    }
+
+    /// Converts the result into JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Value` - An object with the function, its outputs and the raw data
+    pub fn to_json(&self) -> Value {
+        let outputs: Vec<Value> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                json!({
+                    "name": self.names.get(i).cloned().unwrap_or_default(),
+                    "value": codec::to_json(value),
+                })
+            })
+            .collect();
+        json!({
+            "function": self.function,
+            "outputs": outputs,
+            "raw": self.raw.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for CallResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.values.is_empty() {
+            return write!(f, "(no return value)");
+        }
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let param = self.params.get(i);
+            match self.names.get(i).filter(|n| !n.is_empty()) {
+                Some(name) => write!(f, "{}", format::format_field(name, value, param))?,
+                None if self.values.len() > 1 => write!(
+                    f,
+                    "{}",
+                    format::format_field(&format!("[{}]", i), value, param)
+                )?,
+                None => write!(f, "{}", format::format_value(value, param, ""))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outputs of the same call at two blocks, compared output by output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallDiff {
+    /// The two blocks, as given by the user
+    pub blocks: [String; 2],
+    /// The call at the first block
+    pub before: CallResult,
+    /// The call at the second block
+    pub after: CallResult,
+}
+
+impl CallDiff {
+    /// Returns the indices of the outputs that differ between the two blocks.
+    pub fn changed(&self) -> Vec<usize> {
+        (0..self.before.values.len().max(self.after.values.len()))
+            .filter(|&i| self.before.values.get(i) != self.after.values.get(i))
+            .collect()
+    }
+
+    /// Converts the comparison into JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Value` - An object with the function, the blocks, and per output
+    ///   both values and whether it changed
+    pub fn to_json(&self) -> Value {
+        let changed = self.changed();
+        let outputs: Vec<Value> = (0..self.before.values.len().max(self.after.values.len()))
+            .map(|i| {
+                json!({
+                    "name": self.before.names.get(i).cloned().unwrap_or_default(),
+                    "before": self.before.values.get(i).map(codec::to_json),
+                    "after": self.after.values.get(i).map(codec::to_json),
+                    "changed": changed.contains(&i),
+                })
+            })
+            .collect();
+        json!({
+            "function": self.before.function,
+            "blocks": self.blocks,
+            "outputs": outputs,
+        })
+    }
+}
+
+impl fmt::Display for CallDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let render = |value: Option<&DynSolValue>, name: &str| match value {
+            Some(value) if format::is_tree(value) => codec::to_json(value).to_string(),
+            Some(value) => format::format_value(value, None, name),
+            None => "(none)".to_string(),
+        };
+        write!(
+            f,
+            "{} at {} -> {}",
+            self.before.function, self.blocks[0], self.blocks[1]
+        )?;
+        let changed = self.changed();
+        for i in 0..self.before.values.len().max(self.after.values.len()) {
+            let name = match self.before.names.get(i).filter(|n| !n.is_empty()) {
+                Some(name) => name.clone(),
+                None => format!("[{}]", i),
+            };
+            let before = render(self.before.values.get(i), &name);
+            match changed.contains(&i) {
+                true => write!(
+                    f,
+                    "\n  {}: {} -> {}",
+                    name,
+                    before,
+                    render(self.after.values.get(i), &name)
+                )?,
+                false => write!(f, "\n  {}: {} (unchanged)", name, before)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A decoded event log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventLog {
+    /// Signature of the event
+    pub event: String,
+    /// Block the log was emitted in, when known
+    pub block_number: Option<u64>,
+    /// Transaction that emitted the log, when known
+    pub transaction_hash: Option<TxHash>,
+    /// Names of the event parameters, in declaration order
+    pub names: Vec<String>,
+    /// ABI parameters of the event, naming the fields of structs
+    pub params: Vec<Param>,
+    /// Decoded parameter values, in declaration order
+    pub values: Vec<DynSolValue>,
+}
+
+impl EventLog {
+    /// Decodes a log emitted for an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event definition
+    /// * `log` - The log as returned by the node
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EventLog>` - The decoded log, or an error if it does not match the event
+    pub fn decode(event: &Event, log: &Log) -> Result<Self> {
+        let decoded = codec::decode_log(event, log.data())?;
+        let mut indexed = decoded.indexed.into_iter();
+        let mut body = decoded.body.into_iter();
+        let values = event
+            .inputs
+            .iter()
+            .map(|input| {
+                if input.indexed {
+                    indexed.next()
+                } else {
+                    body.next()
+                }
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::InvalidAbi(format!("Log does not match {}", event.name)))?;
+        Ok(Self {
+            event: event.signature(),
+            block_number: log.block_number,
+            transaction_hash: log.transaction_hash,
+            names: event.inputs.iter().map(|i| i.name.clone()).collect(),
+            params: event
+                .inputs
+                .iter()
+                .map(|input| Param {
+                    ty: input.ty.clone(),
+                    name: input.name.clone(),
+                    components: input.components.clone(),
+                    internal_type: input.internal_type.clone(),
+                })
+                .collect(),
+            values,
+        })
+    }
+
+    /// Decodes a log against every event of the given ABIs.
+    ///
+    /// # Arguments
+    ///
+    /// * `abis` - The ABIs to look the event up in
+    /// * `log` - The log as returned by the node
+    ///
+    /// # Returns
+    ///
+    /// * `Option<EventLog>` - The decoded log, or `None` if no event matches it
+    pub fn decode_any<'a>(abis: impl IntoIterator<Item = &'a JsonAbi>, log: &Log) -> Option<Self> {
+        let topic = log.topics().first()?;
+        abis.into_iter()
+            .flat_map(|abi| abi.events())
+            .filter(|event| !event.anonymous && event.selector() == *topic)
+            .find_map(|event| Self::decode(event, log).ok())
+    }
+
+    /// Converts the log into JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Value` - An object with the event, its location and its parameters
+    pub fn to_json(&self) -> Value {
+        let params: Vec<Value> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                json!({
+                    "name": self.names.get(i).cloned().unwrap_or_default(),
+                    "value": codec::to_json(value),
+                })
+            })
+            .collect();
+        json!({
+            "event": self.event,
+            "block_number": self.block_number,
+            "transaction_hash": self.transaction_hash.map(|h| h.to_string()),
+            "params": params,
+        })
+    }
+}
+
+/// Lays out logs of the same event as a table, one row per log with its
+/// block, transaction and parameters.
+///
+/// # Arguments
+///
+/// * `logs` - The decoded logs
+/// * `book` - The address book aliases are shown from
+///
+/// # Returns
+///
+/// * `Table` - The table, with the parameter names of the first log as headers
+pub fn event_table<'a>(logs: impl IntoIterator<Item = &'a EventLog>, book: &AddressBook) -> Table {
+    let mut logs = logs.into_iter().peekable();
+    let names: Vec<String> = logs
+        .peek()
+        .map(|log| {
+            (0..log.values.len())
+                .map(|i| match log.names.get(i).filter(|n| !n.is_empty()) {
+                    Some(name) => name.clone(),
+                    None => format!("[{}]", i),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut table = Table::new()
+        .headers(
+            ["Block".to_string(), "Tx".to_string()]
+                .into_iter()
+                .chain(names.clone()),
+        )
+        .align(0, text::Align::Right);
+    for column in 2..names.len() + 2 {
+        table = table.max_width(column, EVENT_COLUMN_WIDTH);
+    }
+    for log in logs {
+        let values = log.values.iter().enumerate().map(|(i, value)| {
+            let name = names.get(i).map(String::as_str).unwrap_or_default();
+            book.annotate(&format::format_value(value, log.params.get(i), name))
+        });
+        table.push_row(
+            [
+                log.block_number
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| "pending".to_string()),
+                log.transaction_hash
+                    .map(|h| h.to_string())
+                    .unwrap_or_else(|| "unknown".to_string()),
+            ]
+            .into_iter()
+            .chain(values),
+        );
+    }
+    table
+}
+
+impl fmt::Display for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (block {}, tx {})",
+            self.event,
+            self.block_number
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "pending".to_string()),
+            self.transaction_hash
+                .map(|h| h.to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        )?;
+        for (i, value) in self.values.iter().enumerate() {
+            let name = match self.names.get(i).filter(|n| !n.is_empty()) {
+                Some(name) => name.clone(),
+                None => format!("[{}]", i),
+            };
+            let field = format::format_field(&name, value, self.params.get(i));
+            write!(f, "\n{}", format::indent(&field, "  "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of executing a contract method from the workflow.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionResult {
+    /// A read-only call returned
+    Call(CallResult),
+    /// A transaction was broadcast but its receipt is not available yet
+    Submitted {
+        /// Hash of the transaction
+        hash: TxHash,
+    },
+    /// A transaction was included in a block
+    Confirmed(Box<TransactionReceipt>),
+    /// The transaction was proposed to a Safe, awaiting its owners' signatures
+    Proposed {
+        /// Address of the Safe
+        safe: Address,
+        /// Hash the owners sign
+        safe_tx_hash: B256,
+        /// Safe nonce the transaction is queued at
+        nonce: U256,
+        /// Safe app page of the queue, when the chain is known
+        queue_url: Option<String>,
+    },
+    /// The transaction was exported unsigned, to be signed offline
+    Exported(Box<UnsignedTransaction>),
+    /// The transaction was simulated and previewed in a dry run, and not sent
+    Simulated(Box<UnsignedTransaction>),
+    /// The user declined to send the transaction
+    Cancelled,
+}
+
+impl ExecutionResult {
+    /// Converts the result into versioned JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Value` - The result wrapped in an [`Output`] envelope
+    pub fn to_json(&self) -> Value {
+        Output::from(self).to_json()
+    }
+}
+
+impl fmt::Display for ExecutionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionResult::Call(call) => write!(f, "{}", call),
+            ExecutionResult::Submitted { hash } => write!(f, "Transaction sent: {}", hash),
+            ExecutionResult::Confirmed(receipt) => write!(
+                f,
+                "Transaction {} {} in block {} (gas used: {}, effective gas price: {})",
+                receipt.transaction_hash,
+                if receipt.status() {
+                    "confirmed"
+                } else {
+                    "reverted"
+                },
+                receipt
+                    .block_number
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| "pending".to_string()),
+                receipt.gas_used,
+                format_units(U256::from(receipt.effective_gas_price), "gwei")
+                    .map(|gwei| format!("{} gwei", gwei))
+                    .unwrap_or_else(|_| format!("{} wei", receipt.effective_gas_price))
+            ),
+            ExecutionResult::Proposed {
+                safe,
+                safe_tx_hash,
+                nonce,
+                queue_url,
+            } => {
+                write!(
+                    f,
+                    "Proposed to Safe {} at nonce {} (safeTxHash: {})",
+                    safe, nonce, safe_tx_hash
+                )?;
+                match queue_url {
+                    Some(url) => write!(f, "\nQueue: {}", url),
+                    None => Ok(()),
+                }
+            }
+            ExecutionResult::Exported(tx) => write!(f, "{}", tx),
+            ExecutionResult::Simulated(tx) => write!(f, "Dry run, not sent:\n{}", tx),
+            ExecutionResult::Cancelled => write!(f, "Transaction cancelled"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    #[test]
+    fn test_call_result_rendering() {
+        let function = Function::parse("balanceOf(address) returns (uint256 balance)").unwrap();
+        let result = CallResult::new(
+            &function,
+            vec![DynSolValue::Uint(U256::from(42), 256)],
+            Bytes::new(),
+        );
+
+        assert_eq!(result.to_string(), "balance: 42");
+        assert_eq!(result.get("balance"), result.single());
+        assert_eq!(result.to_json()["outputs"][0]["value"], "42");
+    }
+
+    #[test]
+    fn test_call_diff() {
+        let function =
+            Function::parse("getReserves() returns (uint112 reserve0, uint112 reserve1)").unwrap();
+        let reserves = |a: u64, b: u64| {
+            CallResult::new(
+                &function,
+                vec![
+                    DynSolValue::Uint(U256::from(a), 112),
+                    DynSolValue::Uint(U256::from(b), 112),
+                ],
+                Bytes::new(),
+            )
+        };
+        let diff = CallDiff {
+            blocks: ["100".to_string(), "latest".to_string()],
+            before: reserves(5, 7),
+            after: reserves(5, 9),
+        };
+
+        assert_eq!(diff.changed(), vec![1]);
+        assert_eq!(
+            diff.to_string(),
+            "getReserves() at 100 -> latest\n  reserve0: 5 (unchanged)\n  reserve1: 7 -> 9"
+        );
+        let json = diff.to_json();
+        assert_eq!(json["outputs"][1]["before"], "7");
+        assert_eq!(json["outputs"][0]["changed"], false);
+    }
+
+    #[test]
+    fn test_event_log_lookup() {
+        let abi = crate::testing::fixtures::erc20_abi();
+        let transfer = &abi.event("Transfer").unwrap()[0];
+        let from = alloy::primitives::Address::repeat_byte(0x01);
+        let to = alloy::primitives::Address::repeat_byte(0x02);
+        let log = Log {
+            inner: alloy::primitives::Log::new_unchecked(
+                crate::testing::TEST_CONTRACT,
+                vec![transfer.selector(), from.into_word(), to.into_word()],
+                U256::from(7).to_be_bytes_vec().into(),
+            ),
+            ..Default::default()
+        };
+
+        let decoded = EventLog::decode_any([&abi], &log).unwrap();
+        assert_eq!(decoded.event, "Transfer(address,address,uint256)");
+        assert_eq!(decoded.values[2], DynSolValue::Uint(U256::from(7), 256));
+        assert!(EventLog::decode_any(std::iter::empty(), &log).is_none());
+
+        let mut book = AddressBook::new();
+        book.insert("treasury", to).unwrap();
+        let table = event_table([&decoded, &decoded], &book).to_string();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("  Block  Tx"));
+        assert!(lines[0].ends_with("value"));
+        assert!(lines[2].starts_with("pending  unknown  0x0101"));
+        assert!(lines[2].contains(&format!("{} (treasury)  7", to)));
+    }
+}
@@ -0,0 +1,464 @@
+/// src/safe.rs
+use crate::codec;
+use crate::config::OperatingMode;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::message::eip712_digest;
+#[cfg(feature = "interactive")]
+use crate::prompt;
+use crate::result::ExecutionResult;
+use crate::transaction::TransactionOptions;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::Function;
+use alloy::primitives::{b256, hex, keccak256, Address, Bytes, B256, U256};
+use alloy::sol_types::SolValue;
+use alloy::transports::http::reqwest;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+
+/// `keccak256("EIP712Domain(uint256 chainId,address verifyingContract)")`.
+pub const DOMAIN_TYPEHASH: B256 =
+    b256!("47e79534a245952e8b16893a336b85a3d9ea9fa8c573f3d803afb92a79469218");
+
+/// `keccak256("SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256 nonce)")`.
+pub const SAFE_TX_TYPEHASH: B256 =
+    b256!("bb8310d486368db6bd6f849402fdd73ad53d316b5a4b2644ad6efe0f941286d8");
+
+/// Safe web app, where proposed transactions are confirmed and executed.
+pub const SAFE_APP_URL: &str = "https://app.safe.global";
+
+/// Network names of the hosted Safe Transaction Service, by chain ID.
+const SERVICE_NETWORKS: &[(u64, &str)] = &[
+    (1, "mainnet"),
+    (10, "optimism"),
+    (56, "bsc"),
+    (100, "gnosis-chain"),
+    (137, "polygon"),
+    (324, "zksync"),
+    (1101, "zkevm"),
+    (8453, "base"),
+    (42161, "arbitrum"),
+    (42220, "celo"),
+    (43114, "avalanche"),
+    (59144, "linea"),
+    (84532, "base-sepolia"),
+    (534352, "scroll"),
+    (11155111, "sepolia"),
+];
+
+/// Safe settings, the `[safe]` table of `config.toml`. When set, write
+/// transactions are proposed to the Safe instead of being sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafeConfig {
+    /// Address of the Safe; the configured signer must be one of its owners
+    pub address: String,
+    /// Transaction Service base URL, derived from the chain ID when unset
+    #[serde(default)]
+    pub service_url: Option<String>,
+    /// API key sent as a bearer token to the Transaction Service
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+impl SafeConfig {
+    /// Returns the address of the Safe.
+    pub fn address(&self) -> Result<Address> {
+        Address::from_str(&self.address)
+            .map_err(|e| Error::InvalidConfig(format!("Invalid safe.address: {}", e)))
+    }
+
+    /// Returns the Transaction Service URL for a chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - The current chain
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The base URL, or an error if the chain has no hosted service
+    pub fn service_url(&self, chain_id: u64) -> Result<String> {
+        if let Some(url) = &self.service_url {
+            return Ok(url.trim_end_matches('/').to_string());
+        }
+        SERVICE_NETWORKS
+            .iter()
+            .find(|(id, _)| *id == chain_id)
+            .map(|(_, network)| format!("https://safe-transaction-{}.safe.global", network))
+            .ok_or_else(|| {
+                Error::InvalidConfig(format!(
+                    "No Safe Transaction Service known for chain {}; set safe.service_url",
+                    chain_id
+                ))
+            })
+    }
+}
+
+/// A Safe transaction, executed by the Safe once enough owners have signed it.
+/// Gas refunds are not used, so the gas fields of the `SafeTx` are zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeTx {
+    /// Target of the call
+    pub to: Address,
+    /// Native value sent with the call
+    pub value: U256,
+    /// Calldata
+    pub data: Bytes,
+    /// The Safe nonce the transaction is queued at
+    pub nonce: U256,
+}
+
+impl SafeTx {
+    /// Returns the EIP-712 struct hash of the transaction.
+    pub fn struct_hash(&self) -> B256 {
+        keccak256(
+            (
+                SAFE_TX_TYPEHASH,
+                self.to,
+                self.value,
+                keccak256(&self.data),
+                // operation: CALL, padded to a word like any uint8
+                U256::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                Address::ZERO,
+                Address::ZERO,
+                self.nonce,
+            )
+                .abi_encode(),
+        )
+    }
+
+    /// Returns the `safeTxHash` owners sign.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - The chain the Safe is deployed on
+    /// * `safe` - Address of the Safe
+    ///
+    /// # Returns
+    ///
+    /// * `B256` - The EIP-712 digest of the transaction under the Safe's domain
+    pub fn hash(&self, chain_id: u64, safe: Address) -> B256 {
+        let domain = keccak256((DOMAIN_TYPEHASH, U256::from(chain_id), safe).abi_encode());
+        eip712_digest(domain, self.struct_hash())
+    }
+}
+
+impl fmt::Display for SafeTx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "to {} with {} wei at Safe nonce {} ({} bytes of calldata)",
+            self.to,
+            self.value,
+            self.nonce,
+            self.data.len()
+        )
+    }
+}
+
+/// Client of the Safe Transaction Service.
+#[derive(Debug, Clone)]
+pub struct SafeService {
+    base_url: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl SafeService {
+    /// Creates a client for a Transaction Service.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The service URL, without the `/api` path
+    /// * `api_key` - Optional bearer token
+    pub fn new(base_url: &str, api_key: Option<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    /// Returns the nonce a new proposal should use: the on-chain nonce, or
+    /// the one after the last transaction already queued.
+    ///
+    /// # Arguments
+    ///
+    /// * `safe` - Address of the Safe
+    /// * `onchain` - The Safe's current `nonce()`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<U256>` - The next free nonce or an error
+    pub async fn next_nonce(&self, safe: Address, onchain: U256) -> Result<U256> {
+        let url = format!(
+            "{}/api/v1/safes/{}/multisig-transactions/?executed=false&nonce__gte={}&ordering=-nonce&limit=1",
+            self.base_url,
+            safe.to_checksum(None),
+            onchain
+        );
+        let response = self
+            .request(self.client.get(&url))
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to query {}: {}", url, e)))?;
+        if !response.status().is_success() {
+            return Err(Error::Other(format!(
+                "Safe Transaction Service returned {} for {}",
+                response.status(),
+                url
+            )));
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Other(format!("Invalid response from {}: {}", url, e)))?;
+        let queued = body["results"][0]["nonce"]
+            .as_u64()
+            .or_else(|| body["results"][0]["nonce"].as_str()?.parse().ok());
+        Ok(match queued {
+            Some(last) => onchain.max(U256::from(last) + U256::from(1)),
+            None => onchain,
+        })
+    }
+
+    /// Proposes a signed transaction to the Safe's queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `safe` - Address of the Safe
+    /// * `tx` - The Safe transaction
+    /// * `safe_tx_hash` - Its `safeTxHash`
+    /// * `sender` - The owner that signed it
+    /// * `signature` - The owner's 65-byte signature of `safe_tx_hash`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error from the service
+    pub async fn propose(
+        &self,
+        safe: Address,
+        tx: &SafeTx,
+        safe_tx_hash: B256,
+        sender: Address,
+        signature: &[u8],
+    ) -> Result<()> {
+        let url = format!(
+            "{}/api/v1/safes/{}/multisig-transactions/",
+            self.base_url,
+            safe.to_checksum(None)
+        );
+        let body = json!({
+            "to": tx.to.to_checksum(None),
+            "value": tx.value.to_string(),
+            "data": (!tx.data.is_empty()).then(|| tx.data.to_string()),
+            "operation": 0,
+            "safeTxGas": "0",
+            "baseGas": "0",
+            "gasPrice": "0",
+            "gasToken": Address::ZERO.to_checksum(None),
+            "refundReceiver": Address::ZERO.to_checksum(None),
+            "nonce": tx.nonce.to_string(),
+            "contractTransactionHash": safe_tx_hash.to_string(),
+            "sender": sender.to_checksum(None),
+            "signature": hex::encode_prefixed(signature),
+            "origin": "inachus",
+        });
+        let response = self
+            .request(self.client.post(&url).json(&body))
+            .send()
+            .await
+            .map_err(|e| Error::Other(format!("Failed to propose to {}: {}", url, e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let reason = response.text().await.unwrap_or_default();
+            return Err(Error::Other(format!(
+                "Safe Transaction Service rejected the proposal ({}): {}",
+                status, reason
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Builds, signs and proposes a contract call as a Safe transaction,
+/// instead of sending it from the signer. The call goes through the pre-send
+/// hooks and the policy, and is simulated from the Safe when required, first.
+///
+/// # Arguments
+///
+/// * `ctx` - The global context, whose `safe` is set
+/// * `to` - Address of the contract
+/// * `function` - The function to call
+/// * `args` - One value per function input
+/// * `options` - Only the value, as the hooks leave it, is used; the Safe
+///   pays the gas when executing
+/// * `confirm` - Whether to ask the user before proposing
+///
+/// # Returns
+///
+/// * `Result<ExecutionResult>` - The proposal, `Cancelled` if declined, or an
+///   error, e.g. in a dry run, which proposes nothing
+pub async fn propose(
+    ctx: &GlobalContext,
+    to: Address,
+    function: &Function,
+    args: &[DynSolValue],
+    options: &TransactionOptions,
+    confirm: bool,
+) -> Result<ExecutionResult> {
+    if ctx.mode == OperatingMode::DryRun {
+        return Err(Error::ModeForbids {
+            mode: OperatingMode::DryRun,
+            reason: "nothing is proposed to the Safe".to_string(),
+        });
+    }
+    let config = ctx
+        .safe
+        .as_ref()
+        .ok_or_else(|| Error::InvalidConfig("No Safe configured".to_string()))?;
+    let safe = config.address()?;
+    let write = ctx.write_context()?;
+    let chain_id = write.chain_id();
+    let data = codec::encode_values(function, args)?;
+    let (_, options) = write
+        .check_before_send(safe, to, &function.signature(), data.clone(), options)
+        .await?;
+    let service = SafeService::new(&config.service_url(chain_id)?, config.api_key.clone());
+
+    let nonce_fn = Function::parse("nonce() returns (uint256)")
+        .map_err(|e| Error::InvalidFunction(e.to_string()))?;
+    let onchain = match ctx
+        .read_context()?
+        .call(safe, &nonce_fn, &[])
+        .await?
+        .values
+        .first()
+    {
+        Some(DynSolValue::Uint(nonce, _)) => *nonce,
+        _ => return Err(Error::InvalidContract(format!("{} is not a Safe", safe))),
+    };
+    let tx = SafeTx {
+        to,
+        value: options.value(),
+        data,
+        nonce: service.next_nonce(safe, onchain).await?,
+    };
+    let safe_tx_hash = tx.hash(chain_id, safe);
+    #[cfg(feature = "interactive")]
+    if confirm && !prompt::confirm_safe_proposal(safe, &tx, safe_tx_hash)? {
+        return Ok(ExecutionResult::Cancelled);
+    }
+    #[cfg(not(feature = "interactive"))]
+    if confirm {
+        return Err(Error::InvalidConfig(
+            "Confirming a Safe proposal needs the interactive feature".to_string(),
+        ));
+    }
+
+    let signer = write.signer();
+    let signature = signer.sign_hash(&safe_tx_hash).await?;
+    service
+        .propose(
+            safe,
+            &tx,
+            safe_tx_hash,
+            signer.address(),
+            &signature.as_bytes(),
+        )
+        .await?;
+
+    let queue_url = ctx
+        .chains
+        .get(chain_id)
+        .ok()
+        .map(|chain| queue_url(&chain.short_name, safe));
+    Ok(ExecutionResult::Proposed {
+        safe,
+        safe_tx_hash,
+        nonce: tx.nonce,
+        queue_url,
+    })
+}
+
+/// Returns the Safe app page listing the queued transactions of a Safe.
+///
+/// # Arguments
+///
+/// * `short_name` - EIP-3770 short name of the chain, e.g. `eth`
+/// * `safe` - Address of the Safe
+pub fn queue_url(short_name: &str, safe: Address) -> String {
+    format!(
+        "{}/transactions/queue?safe={}:{}",
+        SAFE_APP_URL,
+        short_name,
+        safe.to_checksum(None)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_tx_hash_and_service_urls() {
+        assert_eq!(
+            DOMAIN_TYPEHASH,
+            keccak256("EIP712Domain(uint256 chainId,address verifyingContract)")
+        );
+        assert_eq!(
+            SAFE_TX_TYPEHASH,
+            keccak256("SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256 nonce)")
+        );
+
+        let safe = Address::repeat_byte(0x5a);
+        let tx = SafeTx {
+            to: Address::repeat_byte(0x01),
+            value: U256::ZERO,
+            data: Bytes::from_static(&[0xde, 0xad]),
+            nonce: U256::from(7),
+        };
+        let hash = tx.hash(1, safe);
+        assert_ne!(hash, tx.hash(10, safe));
+        assert_ne!(
+            hash,
+            SafeTx {
+                nonce: U256::from(8),
+                ..tx.clone()
+            }
+            .hash(1, safe)
+        );
+
+        let config: SafeConfig =
+            toml::from_str("address = \"0x5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a\"").unwrap();
+        assert_eq!(config.address().unwrap(), safe);
+        assert_eq!(
+            config.service_url(8453).unwrap(),
+            "https://safe-transaction-base.safe.global"
+        );
+        assert!(config.service_url(31337).is_err());
+        let config = SafeConfig {
+            service_url: Some("http://localhost:8000/".to_string()),
+            ..config
+        };
+        assert_eq!(config.service_url(31337).unwrap(), "http://localhost:8000");
+        assert_eq!(
+            queue_url("eth", safe),
+            format!(
+                "https://app.safe.global/transactions/queue?safe=eth:{}",
+                safe.to_checksum(None)
+            )
+        );
+    }
+}
@@ -0,0 +1,449 @@
+/// src/script.rs
+use crate::address_book::AddressBook;
+use crate::codec;
+use crate::config::OperatingMode;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::result::{CallResult, ExecutionResult};
+use crate::safe;
+use crate::validation;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::Function;
+use serde::Deserialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::Path;
+
+/// A runbook of calls and transactions, read from a YAML file.
+///
+/// ```yaml
+/// name: Treasury top-up
+/// steps:
+///   - call: balanceOf
+///     contract: TetherToken
+///     args: [treasury]
+///     expect:
+///       - gte: 1000000
+///   - send: transfer
+///     args: [treasury, 1000000]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    /// Title printed before the steps run
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Steps, run in order
+    pub steps: Vec<ScriptStep>,
+}
+
+impl Script {
+    /// Parses a script from YAML and validates it.
+    ///
+    /// # Arguments
+    ///
+    /// * `yaml` - The script source
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Script>` - The script, or an error if it is malformed
+    pub fn parse(yaml: &str) -> Result<Self> {
+        let script: Script = serde_yaml::from_str(yaml)?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    /// Reads a script from a YAML file and validates it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the script
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Script>` - The script, or an error if it cannot be read or is malformed
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Validates every step of the script.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error naming the first invalid step
+    pub fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            return Err(Error::InvalidConfig("Script has no steps".to_string()));
+        }
+        self.steps.iter().enumerate().try_for_each(|(i, step)| {
+            step.validate().map_err(|e| match e {
+                Error::InvalidConfig(reason) => {
+                    Error::InvalidConfig(format!("Step {}: {}", i + 1, reason))
+                }
+                e => e,
+            })
+        })
+    }
+}
+
+/// One call or transaction of a [`Script`].
+///
+/// Exactly one of `call` and `send` must be set.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptStep {
+    /// Label printed while the step runs, derived from the method when unset
+    pub name: Option<String>,
+    /// Read-only method to call, by name or signature
+    pub call: Option<String>,
+    /// Method to send a transaction to, by name or signature
+    pub send: Option<String>,
+    /// Contract name, the configured contract when unset
+    pub contract: Option<String>,
+    /// Address or address book alias overriding the contract's known address
+    pub address: Option<String>,
+    /// Method arguments in declaration order
+    pub args: Vec<Value>,
+    /// Native value sent with a transaction, e.g. `0.1 ether`
+    pub value: Option<Value>,
+    /// Gas limit of a transaction, estimated when unset
+    pub gas_limit: Option<u64>,
+    /// Checks on the outputs of a call
+    pub expect: Vec<Assertion>,
+}
+
+impl ScriptStep {
+    /// Returns the method the step targets.
+    pub fn method(&self) -> &str {
+        self.call.as_deref().or(self.send.as_deref()).unwrap_or("")
+    }
+
+    /// Returns whether the step sends a transaction.
+    pub fn is_send(&self) -> bool {
+        self.send.is_some()
+    }
+
+    /// Returns the label printed while the step runs.
+    pub fn label(&self) -> String {
+        match (&self.name, self.is_send()) {
+            (Some(name), _) => name.clone(),
+            (None, true) => format!("send {}", self.method()),
+            (None, false) => format!("call {}", self.method()),
+        }
+    }
+
+    /// Validates that the step has one method and only the fields its kind uses.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success if the step is valid, or an error
+    pub fn validate(&self) -> Result<()> {
+        if self.call.is_some() == self.send.is_some() {
+            return Err(Error::InvalidConfig(
+                "needs exactly one of call and send".to_string(),
+            ));
+        }
+        if self.is_send() && !self.expect.is_empty() {
+            return Err(Error::InvalidConfig(
+                "expect only applies to call steps".to_string(),
+            ));
+        }
+        if !self.is_send() && (self.value.is_some() || self.gas_limit.is_some()) {
+            return Err(Error::InvalidConfig(
+                "value and gas_limit only apply to send steps".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Runs the step: calls the method and checks its outputs, or sends the
+    /// transaction and waits for a successful receipt.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context providing ABIs, providers, signer and address book
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ExecutionResult>` - The call result or receipt, or an error if the step failed
+    pub async fn execute(&self, ctx: &GlobalContext) -> Result<ExecutionResult> {
+        let (abi, function, address) = ctx.target(
+            self.contract.as_deref(),
+            self.address.as_deref(),
+            self.method(),
+        )?;
+        let args: Vec<String> = self.args.iter().map(scalar).collect();
+        let params = ctx.address_book.resolve_args(function, &args);
+        let args = codec::coerce_args(function, &params)?;
+
+        if !self.is_send() {
+            let result = ctx
+                .read_context()?
+                .call(address, function, &args)
+                .await
+                .map_err(|e| e.with_abi(abi))?;
+            for assertion in &self.expect {
+                assertion.check(function, &result, &ctx.address_book)?;
+            }
+            return Ok(ExecutionResult::Call(result));
+        }
+
+        let mut options = ctx.gas.options().with_confirmations(ctx.confirmations);
+        if let Some(value) = &self.value {
+            options = options.with_value(validation::parse_value(&scalar(value))?);
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            options = options.with_gas_limit(gas_limit);
+        }
+        options.validate()?;
+
+        if ctx.mode == OperatingMode::DryRun {
+            let tx = ctx
+                .write_context()?
+                .dry_run_send(address, function, &args, &options)
+                .await
+                .map_err(|e| e.with_abi(abi))?;
+            return Ok(ExecutionResult::Simulated(Box::new(tx)));
+        }
+        if ctx.safe.is_some() {
+            return safe::propose(ctx, address, function, &args, &options, false)
+                .await
+                .map_err(|e| e.with_abi(abi));
+        }
+        let write = ctx.write_context()?;
+        let pending = write
+            .send(address, function, &args, &options)
+            .await
+            .map_err(|e| e.with_abi(abi))?;
+        let receipt = write.confirm(pending).await?;
+        ctx.record(
+            self.contract.as_deref().unwrap_or(&ctx.contract_name),
+            address,
+            function,
+            &params,
+            &options,
+            &ExecutionResult::Confirmed(Box::new(receipt.clone())),
+        );
+        if !receipt.status() {
+            return Err(Error::AssertionFailed(format!(
+                "transaction {} reverted",
+                receipt.transaction_hash
+            )));
+        }
+        Ok(ExecutionResult::Confirmed(Box::new(receipt)))
+    }
+}
+
+/// A check on one output of a call, e.g. `{ output: balance, gte: 1000000 }`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Assertion {
+    /// Output name or index, the first output when unset
+    #[serde(default)]
+    pub output: Option<Value>,
+    /// Comparison with the expected value
+    #[serde(flatten)]
+    pub check: Check,
+}
+
+/// Comparison of an output with an expected value, coerced to the output's type.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// Equal to
+    Eq(Value),
+    /// Not equal to
+    Ne(Value),
+    /// Greater than, for integers
+    Gt(Value),
+    /// Greater than or equal to, for integers
+    Gte(Value),
+    /// Less than, for integers
+    Lt(Value),
+    /// Less than or equal to, for integers
+    Lte(Value),
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (op, value) = match self {
+            Check::Eq(value) => ("==", value),
+            Check::Ne(value) => ("!=", value),
+            Check::Gt(value) => (">", value),
+            Check::Gte(value) => (">=", value),
+            Check::Lt(value) => ("<", value),
+            Check::Lte(value) => ("<=", value),
+        };
+        write!(f, "{} {}", op, scalar(value))
+    }
+}
+
+impl Assertion {
+    /// Checks the assertion against the result of a call.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The called function, giving output names and types
+    /// * `result` - The call result
+    /// * `book` - Address book resolving aliases in expected addresses
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success if the output matches, or an error describing the mismatch
+    pub fn check(
+        &self,
+        function: &Function,
+        result: &CallResult,
+        book: &AddressBook,
+    ) -> Result<()> {
+        let index = match &self.output {
+            None => 0,
+            Some(Value::Number(n)) => n.as_u64().unwrap_or(u64::MAX) as usize,
+            Some(output) => {
+                let name = scalar(output);
+                result
+                    .names
+                    .iter()
+                    .position(|n| *n == name)
+                    .or_else(|| name.parse().ok())
+                    .ok_or_else(|| {
+                        Error::InvalidArguments(format!(
+                            "{} has no output named {}",
+                            result.function, name
+                        ))
+                    })?
+            }
+        };
+        let (param, actual) = function
+            .outputs
+            .get(index)
+            .zip(result.values.get(index))
+            .ok_or_else(|| {
+                Error::InvalidArguments(format!("{} has no output {}", result.function, index))
+            })?;
+
+        let expected = match &self.check {
+            Check::Eq(value)
+            | Check::Ne(value)
+            | Check::Gt(value)
+            | Check::Gte(value)
+            | Check::Lt(value)
+            | Check::Lte(value) => value,
+        };
+        let expected = codec::coerce(&codec::param_type(param)?, &book.resolve(&scalar(expected)))?;
+        let passed = match &self.check {
+            Check::Eq(_) => *actual == expected,
+            Check::Ne(_) => *actual != expected,
+            check => {
+                let ordering = compare(actual, &expected).ok_or_else(|| {
+                    Error::InvalidArguments(format!("{} only applies to integer outputs", check))
+                })?;
+                match check {
+                    Check::Gt(_) => ordering == Ordering::Greater,
+                    Check::Gte(_) => ordering != Ordering::Less,
+                    Check::Lt(_) => ordering == Ordering::Less,
+                    _ => ordering != Ordering::Greater,
+                }
+            }
+        };
+        if passed {
+            return Ok(());
+        }
+        let name = match param.name.as_str() {
+            "" => format!("output {}", index),
+            name => name.to_string(),
+        };
+        Err(Error::AssertionFailed(format!(
+            "{} is {}, expected {}",
+            name,
+            scalar(&codec::to_json(actual)),
+            self.check
+        )))
+    }
+}
+
+/// Orders two integers of the same signedness.
+fn compare(a: &DynSolValue, b: &DynSolValue) -> Option<Ordering> {
+    match (a, b) {
+        (DynSolValue::Uint(a, _), DynSolValue::Uint(b, _)) => Some(a.cmp(b)),
+        (DynSolValue::Int(a, _), DynSolValue::Int(b, _)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Renders a YAML scalar as the string the codec expects, e.g. `1000000` or `[1, 2]`.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, Bytes, U256};
+
+    #[test]
+    fn test_parse_and_check_assertions() {
+        let script = Script::parse(
+            "name: Top-up\nsteps:\n  - call: balanceOf\n    args: [treasury]\n    expect:\n      - output: balance\n        gte: 1000\n      - lt: \"2000\"\n  - send: transfer\n    args: [treasury, 5]\n    value: 0.1 ether\n",
+        )
+        .unwrap();
+        assert_eq!(script.steps.len(), 2);
+        assert_eq!(script.steps[0].label(), "call balanceOf");
+        assert_eq!(script.steps[1].label(), "send transfer");
+        assert_eq!(
+            script.steps[0].expect[0].check,
+            Check::Gte(Value::from(1000))
+        );
+        assert_eq!(
+            Script::parse("steps:\n  - call: a\n    send: b\n")
+                .unwrap_err()
+                .to_string(),
+            "Invalid config: Step 1: needs exactly one of call and send"
+        );
+        assert!(Script::parse("steps:\n  - send: a\n    expect: [{ eq: 1 }]\n").is_err());
+        assert!(Script::parse("steps: []\n").is_err());
+
+        let function =
+            Function::parse("balanceOf(address owner) returns (uint256 balance)").unwrap();
+        let result = CallResult::new(
+            &function,
+            vec![DynSolValue::Uint(U256::from(1500), 256)],
+            Bytes::new(),
+        );
+        let book = AddressBook::new();
+        for assertion in &script.steps[0].expect {
+            assertion.check(&function, &result, &book).unwrap();
+        }
+        let failing = Assertion {
+            output: Some(Value::from(0)),
+            check: Check::Eq(Value::from(1)),
+        };
+        assert_eq!(
+            failing
+                .check(&function, &result, &book)
+                .unwrap_err()
+                .to_string(),
+            "Assertion failed: balance is 1500, expected == 1"
+        );
+
+        let owner = Function::parse("owner() returns (address)").unwrap();
+        let treasury = Address::repeat_byte(0x11);
+        let mut book = AddressBook::new();
+        book.insert("treasury", treasury).unwrap();
+        let result = CallResult::new(&owner, vec![DynSolValue::Address(treasury)], Bytes::new());
+        let assertion = Assertion {
+            output: None,
+            check: Check::Eq(Value::from("treasury")),
+        };
+        assertion.check(&owner, &result, &book).unwrap();
+        assert!(Assertion {
+            output: None,
+            check: Check::Gt(Value::from("treasury")),
+        }
+        .check(&owner, &result, &book)
+        .is_err());
+    }
+}
@@ -0,0 +1,509 @@
+/// src/server.rs
+use crate::abi;
+use crate::cancel::CancellationToken;
+use crate::codec;
+use crate::context::{GlobalContext, ReadContext, WriteContext};
+use crate::error::{Error, ErrorKind, Result};
+use crate::hooks::{HookDecision, PendingSend, TransactionHook};
+use crate::output::Output;
+use crate::result::ExecutionResult;
+use crate::safe;
+use crate::transaction::TransactionOptions;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Environment variable holding the bearer token when none is configured.
+pub const TOKEN_ENV: &str = "INACHUS_SERVER_TOKEN";
+
+/// Server settings, the `[server]` table of `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Address the server listens on
+    pub bind: String,
+    /// Bearer token required on every request, falling back to `INACHUS_SERVER_TOKEN`
+    pub token: Option<String>,
+    /// Contracts and methods exposed by the server
+    pub endpoints: Vec<EndpointConfig>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:7545".to_string(),
+            token: None,
+            endpoints: Vec::new(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Returns the configured token, or the one from `INACHUS_SERVER_TOKEN`.
+    pub fn token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| std::env::var(TOKEN_ENV).ok())
+            .filter(|token| !token.is_empty())
+    }
+}
+
+/// A contract exposed over HTTP, with the methods allowed on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointConfig {
+    /// Name of the contract's ABI, also used in the URL
+    pub contract: String,
+    /// Read-only methods served on `GET`
+    #[serde(default)]
+    pub reads: Vec<String>,
+    /// State-changing methods served on `POST`
+    #[serde(default)]
+    pub writes: Vec<String>,
+    /// Largest native value a write may carry, in wei
+    #[serde(default)]
+    pub max_value: Option<U256>,
+}
+
+impl EndpointConfig {
+    /// Checks a write against the endpoint policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The requested method
+    /// * `options` - The options the transaction would be sent with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success, or `Error::Vetoed` if the policy forbids the write
+    pub fn check_write(&self, method: &str, options: &TransactionOptions) -> Result<()> {
+        let veto = |reason: String| Error::Vetoed {
+            hook: "server policy".to_string(),
+            reason,
+        };
+        if !self.writes.iter().any(|m| m == method) {
+            return Err(veto(format!(
+                "{} is not an allowed write on {}",
+                method, self.contract
+            )));
+        }
+        if let Some(max) = self.max_value {
+            if options.value() > max {
+                return Err(veto(format!(
+                    "value {} exceeds the limit of {} wei",
+                    options.value(),
+                    max
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Hook enforcing an endpoint policy, registered after every other hook so
+/// that it checks the options the transaction is actually sent with.
+#[derive(Debug, Clone)]
+struct EndpointHook {
+    endpoint: EndpointConfig,
+    method: String,
+}
+
+#[async_trait]
+impl TransactionHook for EndpointHook {
+    fn name(&self) -> &str {
+        "server policy"
+    }
+
+    async fn before_send(
+        &self,
+        _tx: &PendingSend,
+        options: &mut TransactionOptions,
+    ) -> Result<HookDecision> {
+        match self.endpoint.check_write(&self.method, options) {
+            Ok(()) => Ok(HookDecision::Proceed),
+            Err(Error::Vetoed { reason, .. }) => Ok(HookDecision::Veto(reason)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Body of a `POST` request.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct WriteRequest {
+    args: Vec<String>,
+    options: TransactionOptions,
+    wait: bool,
+}
+
+/// HTTP API mapping configured contract methods to endpoints.
+///
+/// `GET /contracts` lists the endpoints. `GET /contracts/{contract}/{method}`
+/// calls a read method, taking arguments as query parameters named after the
+/// inputs (or `arg0`, `arg1`, …). `POST /contracts/{contract}/{method}` with
+/// `{"args", "options", "wait"}` sends a write after checking the endpoint
+/// policy and simulating it. Every request needs `Authorization: Bearer <token>`.
+#[derive(Debug)]
+pub struct Server {
+    ctx: GlobalContext,
+    read: ReadContext,
+    write: Option<WriteContext>,
+    endpoints: HashMap<String, EndpointConfig>,
+    token: String,
+}
+
+impl Server {
+    /// Creates a server from a loaded context and the server settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context with configuration, ABIs and contracts loaded
+    /// * `config` - The server settings
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Server>` - The server, or an error if no token is set or an endpoint is invalid
+    pub fn new(ctx: GlobalContext, config: &ServerConfig) -> Result<Self> {
+        let token = config.token().ok_or_else(|| {
+            Error::InvalidConfig(format!(
+                "The server needs server.token or {} to be set",
+                TOKEN_ENV
+            ))
+        })?;
+        let mut contracts = HashSet::new();
+        for endpoint in &config.endpoints {
+            if !contracts.insert(endpoint.contract.as_str()) {
+                return Err(Error::InvalidConfig(format!(
+                    "Endpoints for {} are configured twice; list all its reads and writes in one",
+                    endpoint.contract
+                )));
+            }
+            let abi = ctx.abis.get(&endpoint.contract).ok_or_else(|| {
+                Error::InvalidContract(format!("ABI not found for contract: {}", endpoint.contract))
+            })?;
+            ctx.address_of(&endpoint.contract)?;
+            for method in endpoint.reads.iter().chain(&endpoint.writes) {
+                abi::find_function(abi, method)?;
+            }
+        }
+
+        let read = ctx.read_context()?;
+        let write = if ctx.capabilities().signer {
+            Some(ctx.write_context()?)
+        } else {
+            None
+        };
+        let endpoints = config
+            .endpoints
+            .iter()
+            .map(|endpoint| (endpoint.contract.clone(), endpoint.clone()))
+            .collect();
+        Ok(Self {
+            ctx,
+            read,
+            write,
+            endpoints,
+            token,
+        })
+    }
+
+    /// Builds the HTTP routes of the server.
+    pub fn router(self) -> Router {
+        let server = Arc::new(self);
+        Router::new()
+            .route("/contracts", get(list))
+            .route("/contracts/{contract}/{method}", get(read).post(write))
+            .layer(middleware::from_fn_with_state(server.clone(), authorize))
+            .with_state(server)
+    }
+
+    /// Serves the API until the token is cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `bind` - Address to listen on
+    /// * `shutdown` - Token stopping the server when cancelled
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success once shut down, or an error
+    pub async fn serve(self, bind: &str, shutdown: CancellationToken) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(bind).await?;
+        tracing::info!(bind, "server listening");
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(async move { shutdown.cancelled().await })
+            .await?;
+        Ok(())
+    }
+
+    /// Resolves an exposed method, rejecting anything not listed in the configuration.
+    fn target(
+        &self,
+        contract: &str,
+        method: &str,
+        write: bool,
+    ) -> Result<(&EndpointConfig, &JsonAbi, &Function, Address)> {
+        let endpoint = self
+            .endpoints
+            .get(contract)
+            .ok_or_else(|| Error::InvalidContract(format!("{} is not exposed", contract)))?;
+        let methods = if write {
+            &endpoint.writes
+        } else {
+            &endpoint.reads
+        };
+        if !methods.iter().any(|m| m == method) {
+            return Err(Error::InvalidFunction(format!(
+                "{} is not exposed on {}",
+                method, contract
+            )));
+        }
+        let abi = self.ctx.abis.get(contract).ok_or_else(|| {
+            Error::InvalidContract(format!("ABI not found for contract: {}", contract))
+        })?;
+        Ok((
+            endpoint,
+            abi,
+            abi::find_function(abi, method)?,
+            self.ctx.address_of(contract)?,
+        ))
+    }
+}
+
+/// Error rendered as a structured output with a matching status code.
+struct ApiError(Error);
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        Self(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self.0 {
+            Error::InvalidContract(_) | Error::InvalidFunction(_) => StatusCode::NOT_FOUND,
+            Error::Vetoed { .. } | Error::ModeForbids { .. } => StatusCode::FORBIDDEN,
+            e => match e.kind() {
+                ErrorKind::UserInput => StatusCode::BAD_REQUEST,
+                ErrorKind::Revert => StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorKind::Rpc => StatusCode::BAD_GATEWAY,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+        };
+        (status, Json(Output::from(&self.0).to_json())).into_response()
+    }
+}
+
+async fn authorize(State(server): State<Arc<Server>>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), server.token.as_bytes()));
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+/// Compares two byte strings without short-circuiting on the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn list(State(server): State<Arc<Server>>) -> Json<Value> {
+    let endpoints: Vec<Value> = server
+        .endpoints
+        .values()
+        .map(|endpoint| {
+            json!({
+                "contract": endpoint.contract,
+                "address": server.ctx.address_of(&endpoint.contract).ok().map(|a| a.to_checksum(None)),
+                "reads": endpoint.reads,
+                "writes": endpoint.writes,
+            })
+        })
+        .collect();
+    Json(json!(endpoints))
+}
+
+async fn read(
+    State(server): State<Arc<Server>>,
+    Path((contract, method)): Path<(String, String)>,
+    Query(query): Query<HashMap<String, String>>,
+) -> std::result::Result<Json<Value>, ApiError> {
+    let (_, abi, function, address) = server.target(&contract, &method, false)?;
+    let args = function
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            query
+                .get(&input.name)
+                .filter(|_| !input.name.is_empty())
+                .or_else(|| query.get(&format!("arg{}", i)))
+                .cloned()
+                .ok_or_else(|| Error::InvalidArguments(format!("Missing argument {}", i)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let args = codec::coerce_args(function, &args)?;
+    let result = server
+        .read
+        .call(address, function, &args)
+        .await
+        .map_err(|e| e.with_abi(abi))?;
+    Ok(Json(ExecutionResult::Call(result).to_json()))
+}
+
+async fn write(
+    State(server): State<Arc<Server>>,
+    Path((contract, method)): Path<(String, String)>,
+    Json(request): Json<WriteRequest>,
+) -> std::result::Result<Json<Value>, ApiError> {
+    let write = server
+        .write
+        .as_ref()
+        .ok_or_else(|| Error::InvalidPrivateKey("No private key configured".to_string()))?;
+    let (endpoint, abi, function, address) = server.target(&contract, &method, true)?;
+    endpoint.check_write(&method, &request.options)?;
+    let args = codec::coerce_args(function, &request.args)?;
+    // The pre-send hooks may change the value, so the endpoint policy is
+    // checked again after them, and the simulation runs on the final options.
+    let mut hooks = write.hooks().clone();
+    hooks.register(EndpointHook {
+        endpoint: endpoint.clone(),
+        method: method.clone(),
+    });
+    if server.ctx.safe.is_some() {
+        let mut ctx = server.ctx.clone();
+        ctx.hooks = hooks;
+        let result = safe::propose(&ctx, address, function, &args, &request.options, false)
+            .await
+            .map_err(|e| e.with_abi(abi))?;
+        return Ok(Json(result.to_json()));
+    }
+    let write = write
+        .clone()
+        .with_hooks(hooks)
+        .with_required_simulation(true);
+    let (pending, options) = write
+        .send_with_final_options(address, function, &args, &request.options)
+        .await
+        .map_err(|e| e.with_abi(abi))?;
+    let result = if request.wait {
+        ExecutionResult::Confirmed(Box::new(write.confirm(pending).await?))
+    } else {
+        ExecutionResult::Submitted {
+            hash: *pending.tx_hash(),
+        }
+    };
+    server.ctx.record(
+        &endpoint.contract,
+        address,
+        function,
+        &request.args,
+        &options,
+        &result,
+    );
+    Ok(Json(result.to_json()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::{CommandHook, HookStage, Hooks};
+    use crate::testing::{fixtures, MockRpc};
+
+    #[test]
+    fn test_write_policy() {
+        let endpoint = EndpointConfig {
+            contract: "Token".to_string(),
+            reads: vec!["balanceOf".to_string()],
+            writes: vec!["transfer".to_string()],
+            max_value: Some(U256::from(100)),
+        };
+        let options = TransactionOptions::new();
+
+        assert!(endpoint.check_write("transfer", &options).is_ok());
+        assert!(matches!(
+            endpoint.check_write("approve", &options),
+            Err(Error::Vetoed { .. })
+        ));
+        assert!(matches!(
+            endpoint.check_write("transfer", &options.with_value(U256::from(101))),
+            Err(Error::Vetoed { .. })
+        ));
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_endpoints_are_rejected() {
+        let endpoint = EndpointConfig {
+            contract: "Token".to_string(),
+            reads: vec!["balanceOf".to_string()],
+            writes: Vec::new(),
+            max_value: None,
+        };
+        let mut config = ServerConfig {
+            token: Some("secret".to_string()),
+            endpoints: vec![endpoint.clone()],
+            ..ServerConfig::default()
+        };
+        let mock = MockRpc::new();
+        assert!(Server::new(fixtures::context(&mock), &config).is_ok());
+
+        config.endpoints.push(EndpointConfig {
+            reads: Vec::new(),
+            writes: vec!["transfer".to_string()],
+            ..endpoint
+        });
+        let err = Server::new(fixtures::context(&mock), &config)
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::InvalidConfig(_)));
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_policy_checks_options_changed_by_hooks() {
+        let endpoint = EndpointConfig {
+            contract: "Token".to_string(),
+            reads: Vec::new(),
+            writes: vec!["transfer".to_string()],
+            max_value: Some(U256::from(100)),
+        };
+        let tx = PendingSend {
+            from: Address::ZERO,
+            to: Address::ZERO,
+            chain_id: 1,
+            function: "transfer(address,uint256)".to_string(),
+            calldata: Default::default(),
+        };
+        let mut hooks = Hooks::new();
+        hooks.register(CommandHook::new(
+            HookStage::PreSend,
+            r#"cat > /dev/null; echo '{"value": "0x1000"}'"#,
+        ));
+        hooks.register(EndpointHook {
+            endpoint,
+            method: "transfer".to_string(),
+        });
+
+        let err = hooks
+            .before_send(&tx, &mut TransactionOptions::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Vetoed { hook, .. } if hook == "server policy"));
+    }
+}
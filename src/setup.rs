@@ -0,0 +1,209 @@
+/// src/setup.rs
+use crate::error::{Error, Result};
+use crate::prompt::{self, SetupSigner};
+use crate::transport::RpcEndpoint;
+use crate::wallet::{self, KEYSTORE_DIR};
+use chain_info::{ChainRegistry, DEFAULT_TTL};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// Marks the dummy private key of the configuration template, removed with
+/// the line that follows it.
+const DUMMY_KEY_MARKER: &str = "# DUMMY PRIVATE KEY";
+
+/// How the configuration written by the wizard signs transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetupSignerConfig {
+    /// No signer
+    ReadOnly,
+    /// A private key stored in plain text
+    PrivateKey(String),
+    /// An encrypted keystore, relative to the data directory
+    Keystore(PathBuf),
+    /// A Ledger device with the derivation path of its account
+    Ledger(String),
+}
+
+/// Answers to the first-run setup wizard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetupAnswers {
+    /// Chain ID of the selected chain
+    pub chain_id: u64,
+    /// RPC endpoint of the chain
+    pub rpc_url: String,
+    /// How transactions are signed
+    pub signer: SetupSignerConfig,
+    /// Directory containing ABI files
+    pub abi_dir: PathBuf,
+    /// Etherscan API key, if any
+    pub etherscan_api_key: Option<String>,
+}
+
+/// Runs the first-run setup wizard: picks a chain from the chain list, an
+/// RPC endpoint among its healthy public ones, a signer, the ABI directory
+/// and an optional Etherscan key.
+///
+/// # Arguments
+///
+/// * `data_dir` - The Inachus data directory, where keystores and the chain list are written
+///
+/// # Returns
+///
+/// * `Result<SetupAnswers>` - The answers or an error, e.g. if the wizard is cancelled
+pub async fn run_wizard(data_dir: &Path) -> Result<SetupAnswers> {
+    eprintln!(
+        "{}",
+        "No configuration found, let's set up Inachus."
+            .green()
+            .bold()
+    );
+    let mut chains = ChainRegistry::refresh(data_dir.join(crate::CHAINS_FILE), DEFAULT_TTL)
+        .await
+        .map_err(|e| Error::InvalidConfig(format!("Invalid bundled chain list: {}", e)))?;
+    chains
+        .merge_custom(data_dir.join(crate::CUSTOM_CHAINS_FILE))
+        .map_err(|e| Error::InvalidConfig(format!("Invalid custom chain list: {}", e)))?;
+    let chain = loop {
+        let query = prompt::input_chain_query()?;
+        let matches = chains.search(&query);
+        match matches.as_slice() {
+            [] => eprintln!("{} {:?}", "No known chain matches".yellow(), query),
+            [chain] => break (*chain).clone(),
+            matches => break matches[prompt::select_chain(matches, &chains)?].clone(),
+        }
+    };
+
+    eprintln!("{}", "Probing public RPCs...".dimmed());
+    let probes = chain.probe_rpcs_with_keys(&Default::default()).await;
+    let healthy: Vec<_> = probes.iter().filter(|probe| probe.is_healthy()).collect();
+    // Saved with its API key placeholder, filled from the environment on load.
+    let rpc_url = match healthy.is_empty() {
+        true => None,
+        false => prompt::select_rpc(&healthy)?.map(|index| healthy[index].listed_url.clone()),
+    };
+    let rpc_url = match rpc_url {
+        Some(rpc_url) => rpc_url,
+        None => input_checked_rpc_url(chain.chain_id).await?,
+    };
+
+    let signer = match prompt::select_setup_signer()? {
+        SetupSigner::ReadOnly => SetupSignerConfig::ReadOnly,
+        SetupSigner::PrivateKey => SetupSignerConfig::PrivateKey(prompt::prompt_private_key()?),
+        SetupSigner::Keystore => {
+            let private_key = prompt::prompt_private_key()?;
+            let name = prompt::input_keystore_name()?;
+            let passphrase = prompt::input_new_passphrase()?;
+            let dir = data_dir.join(KEYSTORE_DIR);
+            let path = wallet::encrypt_private_key(&private_key, &dir, &name, &passphrase)?;
+            eprintln!("{} {}", "Keystore written to".green(), path.display());
+            SetupSignerConfig::Keystore(Path::new(KEYSTORE_DIR).join(name))
+        }
+        SetupSigner::Ledger => SetupSignerConfig::Ledger(prompt::input_derivation_path()?),
+    };
+
+    Ok(SetupAnswers {
+        chain_id: chain.chain_id,
+        rpc_url,
+        signer,
+        abi_dir: prompt::prompt_abi_dir()?,
+        etherscan_api_key: prompt::input_etherscan_api_key()?,
+    })
+}
+
+/// Prompts for an RPC URL until one answers with the expected chain ID.
+pub(crate) async fn input_checked_rpc_url(chain_id: u64) -> Result<String> {
+    loop {
+        let rpc_url = prompt::prompt_rpc_url()?;
+        match RpcEndpoint::parse(&rpc_url)?.chain_id().await {
+            Ok(id) if id == chain_id => return Ok(rpc_url),
+            Ok(id) => eprintln!("{} {} reports chain {}", "Skipped:".yellow(), rpc_url, id),
+            Err(e) => eprintln!("{} {}", "Skipped:".yellow(), e),
+        }
+    }
+}
+
+/// Fills the configuration template with the wizard's answers, keeping its
+/// comments. The template's dummy private key is replaced by the chosen signer.
+///
+/// # Arguments
+///
+/// * `template` - The default `config.toml`
+/// * `answers` - The answers to the wizard
+///
+/// # Returns
+///
+/// * `Result<String>` - The configuration or an error if the template is malformed
+pub fn render_config(template: &str, answers: &SetupAnswers) -> Result<String> {
+    let mut lines = template.lines();
+    let mut kept = Vec::new();
+    while let Some(line) = lines.next() {
+        match line.starts_with(DUMMY_KEY_MARKER) {
+            true => {
+                lines.next();
+            }
+            false => kept.push(line),
+        }
+    }
+    let mut doc = kept
+        .join("\n")
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+    doc.remove("private_key");
+
+    doc["rpc_url"] = toml_edit::value(answers.rpc_url.as_str());
+    doc["chain_id"] = toml_edit::value(answers.chain_id as i64);
+    match &answers.signer {
+        SetupSignerConfig::ReadOnly => {}
+        SetupSignerConfig::PrivateKey(key) => doc["private_key"] = toml_edit::value(key.as_str()),
+        SetupSignerConfig::Keystore(path) => {
+            doc["keystore"] = toml_edit::value(path.to_string_lossy().as_ref())
+        }
+        SetupSignerConfig::Ledger(derivation_path) => {
+            let mut ledger = toml_edit::InlineTable::new();
+            ledger.insert("derivation_path", derivation_path.as_str().into());
+            doc["ledger"] = toml_edit::value(ledger);
+        }
+    }
+    doc["abi_dir"] = toml_edit::value(answers.abi_dir.to_string_lossy().as_ref());
+    if let Some(ref key) = answers.etherscan_api_key {
+        doc["etherscan_api_key"] = toml_edit::value(key.as_str());
+    }
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_render_config() {
+        let template = "rpc_url = \"https://eth.llamarpc.com\"\n# Or a list\n# DUMMY PRIVATE KEY. DON'T USE IT.\nprivate_key = \"395fa17a9c24b21e34e9cf94c5a3a271a651b3a7c83a9abb71c1c0508a45abda\"\nchain_id = 1\n\n# [ledger]\n";
+        let mut answers = SetupAnswers {
+            chain_id: 10,
+            rpc_url: "https://mainnet.optimism.io".to_string(),
+            signer: SetupSignerConfig::Ledger("m/44'/60'/0'/0/1".to_string()),
+            abi_dir: PathBuf::from("./abis"),
+            etherscan_api_key: Some("KEY".to_string()),
+        };
+        let rendered = render_config(template, &answers).unwrap();
+        assert!(rendered.contains("# Or a list"));
+        assert!(!rendered.contains("DUMMY"));
+        let config: Config = toml::from_str(&rendered).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.chain_id, 10);
+        assert_eq!(config.rpc_url.primary(), "https://mainnet.optimism.io");
+        assert!(config.private_key.is_none());
+        assert_eq!(
+            config.ledger.map(|ledger| ledger.derivation_path),
+            Some("m/44'/60'/0'/0/1".to_string())
+        );
+        assert_eq!(config.etherscan_api_key.as_deref(), Some("KEY"));
+
+        answers.signer = SetupSignerConfig::Keystore(PathBuf::from("keystores/default"));
+        answers.etherscan_api_key = None;
+        let config: Config = toml::from_str(&render_config(template, &answers).unwrap()).unwrap();
+        assert_eq!(config.keystore, Some(PathBuf::from("keystores/default")));
+        assert!(config.etherscan_api_key.is_none());
+    }
+}
@@ -0,0 +1,604 @@
+/// src/signer.rs
+use crate::error::{Error, Result};
+use alloy::consensus::SignableTransaction;
+use alloy::network::{EthereumWallet, TxSigner};
+use alloy::primitives::{Address, PrimitiveSignature, B256};
+use alloy::signers::ledger::{HDPath, LedgerSigner};
+use alloy::signers::local::coins_bip39::English;
+use alloy::signers::local::MnemonicBuilder;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::UnsupportedSignerOperation;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Ledger derivation path of the first Ethereum account.
+pub const DEFAULT_LEDGER_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Ledger settings, the `[ledger]` table of `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LedgerConfig {
+    /// BIP-32 derivation path of the account to sign with
+    pub derivation_path: String,
+}
+
+impl Default for LedgerConfig {
+    fn default() -> Self {
+        Self {
+            derivation_path: DEFAULT_LEDGER_PATH.to_string(),
+        }
+    }
+}
+
+/// Derivation path prefix of Ethereum accounts; the account index is appended.
+pub const DEFAULT_MNEMONIC_PATH: &str = "m/44'/60'/0'/0";
+
+/// Environment variable holding the mnemonic, when `config.toml` does not.
+pub const MNEMONIC_ENV: &str = "INACHUS_MNEMONIC";
+
+/// HD wallet settings, the `[mnemonic]` table of `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MnemonicConfig {
+    /// BIP-39 phrase, falling back to `INACHUS_MNEMONIC`
+    pub phrase: Option<String>,
+    /// Derivation path prefix, to which the account index is appended
+    pub derivation_path: String,
+    /// Number of derived accounts offered for selection
+    pub accounts: u32,
+    /// Account index used without prompting
+    pub index: Option<u32>,
+}
+
+impl Default for MnemonicConfig {
+    fn default() -> Self {
+        Self {
+            phrase: None,
+            derivation_path: DEFAULT_MNEMONIC_PATH.to_string(),
+            accounts: 5,
+            index: None,
+        }
+    }
+}
+
+impl MnemonicConfig {
+    /// Returns the configured phrase, or the one from `INACHUS_MNEMONIC`.
+    pub fn phrase(&self) -> Option<String> {
+        self.phrase
+            .clone()
+            .or_else(|| std::env::var(MNEMONIC_ENV).ok())
+            .filter(|phrase| !phrase.trim().is_empty())
+    }
+
+    /// Returns the full derivation path of an account.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the account
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The path, e.g. `m/44'/60'/0'/0/3`
+    pub fn path(&self, index: u32) -> String {
+        format!("{}/{}", self.derivation_path.trim_end_matches('/'), index)
+    }
+}
+
+/// Where a signer keeps its key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerKind {
+    /// A private key held in memory
+    Local,
+    /// A private key decrypted from the keystore at the given path
+    Keystore(PathBuf),
+    /// A key derived from a mnemonic with the given derivation path
+    Mnemonic(String),
+    /// A Ledger device, signing on-device with the given derivation path
+    Ledger(String),
+    /// A key held by a cloud key management service, e.g. AWS or GCP KMS
+    Kms(String),
+    /// An account a development node impersonates, sending its transactions
+    /// unsigned; it cannot sign anything
+    Impersonated,
+}
+
+impl fmt::Display for SignerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerKind::Local => write!(f, "local key"),
+            SignerKind::Keystore(path) => write!(f, "keystore ({})", path.display()),
+            SignerKind::Mnemonic(path) => write!(f, "mnemonic ({})", path),
+            SignerKind::Ledger(path) => write!(f, "Ledger ({})", path),
+            SignerKind::Kms(service) => write!(f, "{} KMS", service),
+            SignerKind::Impersonated => write!(f, "impersonated"),
+        }
+    }
+}
+
+/// A source of signatures: a key in memory, a hardware wallet, a KMS or
+/// anything else able to sign for an address.
+///
+/// Sessions only ever hold a [`Signer`], a handle to one of these, so a new
+/// backend is added by implementing this trait and wrapping it with
+/// [`Signer::new`].
+#[async_trait]
+pub trait InachusSigner: fmt::Debug + Send + Sync {
+    /// Returns the address transactions are sent from.
+    fn address(&self) -> Address;
+
+    /// Returns where the signer keeps its key.
+    fn kind(&self) -> &SignerKind;
+
+    /// Returns the wallet used by providers to sign transactions.
+    fn wallet(&self) -> EthereumWallet;
+
+    /// Whether transactions are signed before being sent; when not, the node
+    /// sends them itself with `eth_sendTransaction`.
+    fn signs_transactions(&self) -> bool {
+        true
+    }
+
+    /// Returns what to tell the user before a transaction is signed, e.g. to
+    /// confirm it on a device.
+    fn confirmation_prompt(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the private key when it is held in memory, so that it can be
+    /// encrypted into a keystore.
+    fn local_key(&self) -> Option<&PrivateKeySigner> {
+        None
+    }
+
+    /// Signs a message with the EIP-191 `personal_sign` prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The raw message bytes
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PrimitiveSignature>` - The signature, or an error if the signer refused
+    async fn sign_message(&self, message: &[u8]) -> Result<PrimitiveSignature>;
+
+    /// Signs a 32-byte hash as is, without any prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash to sign
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PrimitiveSignature>` - The signature, or an error if the signer refused
+    async fn sign_hash(&self, hash: &B256) -> Result<PrimitiveSignature>;
+}
+
+/// A transaction signer, independent of where its key lives; cloning it
+/// shares the backend.
+#[derive(Clone)]
+pub struct Signer(Arc<dyn InachusSigner>);
+
+impl fmt::Debug for Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signer")
+            .field("address", &self.address())
+            .field("kind", self.kind())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Signer {
+    /// Wraps a signing backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend, e.g. a custom [`InachusSigner`]
+    ///
+    /// # Returns
+    ///
+    /// * `Signer` - A signer using the backend
+    pub fn new(backend: impl InachusSigner + 'static) -> Self {
+        Self(Arc::new(backend))
+    }
+
+    /// Creates a signer from a private key held in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - The local signer
+    ///
+    /// # Returns
+    ///
+    /// * `Signer` - A signer using the key
+    pub fn local(signer: PrivateKeySigner) -> Self {
+        Self::new(LocalKey {
+            signer,
+            kind: SignerKind::Local,
+        })
+    }
+
+    /// Decrypts a Web3 Secret Storage (geth keystore) file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the keystore JSON file
+    /// * `passphrase` - The passphrase the keystore was encrypted with
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Signer>` - A signer using the decrypted key, or an error if the file or passphrase is wrong
+    pub fn keystore(path: &Path, passphrase: &str) -> Result<Self> {
+        Ok(Self::new(LocalKey {
+            signer: crate::wallet::decrypt_keystore(path, passphrase)?,
+            kind: SignerKind::Keystore(path.to_path_buf()),
+        }))
+    }
+
+    /// Derives a signer from a BIP-39 mnemonic.
+    ///
+    /// # Arguments
+    ///
+    /// * `phrase` - The English mnemonic phrase
+    /// * `derivation_path` - Full derivation path of the account, e.g. `m/44'/60'/0'/0/0`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Signer>` - The derived signer, or an error if the phrase or path is invalid
+    pub fn mnemonic(phrase: &str, derivation_path: &str) -> Result<Self> {
+        let signer = MnemonicBuilder::<English>::default()
+            .phrase(phrase.trim())
+            .derivation_path(derivation_path)
+            .and_then(|builder| builder.build())
+            .map_err(|e| Error::signing("deriving account from mnemonic", e))?;
+        Ok(Self::new(LocalKey {
+            signer,
+            kind: SignerKind::Mnemonic(derivation_path.to_string()),
+        }))
+    }
+
+    /// Connects to a Ledger device; transactions are then confirmed on the device.
+    ///
+    /// # Arguments
+    ///
+    /// * `derivation_path` - BIP-32 derivation path of the account, e.g. `m/44'/60'/0'/0/0`
+    /// * `chain_id` - Chain ID transactions are signed for
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Signer>` - A signer backed by the device, or an error if it is not reachable
+    pub async fn ledger(derivation_path: &str, chain_id: u64) -> Result<Self> {
+        let ledger = LedgerSigner::new(HDPath::Other(derivation_path.to_string()), Some(chain_id))
+            .await
+            .map_err(|e| Error::signing("connecting to Ledger", e))?;
+        Ok(Self::new(RemoteKey {
+            signer: Arc::new(ledger),
+            kind: SignerKind::Ledger(derivation_path.to_string()),
+            prompt: Some("Confirm the transaction on your Ledger..."),
+        }))
+    }
+
+    /// Creates a signer whose key never leaves a cloud key management
+    /// service, such as alloy's `AwsSigner` or `GcpSigner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - Name of the service, e.g. `AWS`, shown with the wallet
+    /// * `signer` - The connected KMS signer
+    ///
+    /// # Returns
+    ///
+    /// * `Signer` - A signer backed by the service
+    pub fn kms<S>(service: &str, signer: S) -> Self
+    where
+        S: alloy::signers::Signer + TxSigner<PrimitiveSignature> + Send + Sync + 'static,
+    {
+        Self::new(RemoteKey {
+            signer: Arc::new(signer),
+            kind: SignerKind::Kms(service.to_string()),
+            prompt: None,
+        })
+    }
+
+    /// Creates a signer for an account impersonated by a development node.
+    /// Transactions are sent from it unsigned with `eth_sendTransaction`;
+    /// signing messages, permits or raw transactions fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The impersonated account
+    ///
+    /// # Returns
+    ///
+    /// * `Signer` - A signer sending from the account
+    pub fn impersonated(address: Address) -> Self {
+        Self::new(Impersonated {
+            address,
+            kind: SignerKind::Impersonated,
+        })
+    }
+
+    /// Returns the address transactions are sent from.
+    pub fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    /// Returns where the signer keeps its key.
+    pub fn kind(&self) -> &SignerKind {
+        self.0.kind()
+    }
+
+    /// Returns the wallet used by providers to sign transactions.
+    pub fn wallet(&self) -> EthereumWallet {
+        self.0.wallet()
+    }
+
+    /// Whether transactions are signed before being sent; see
+    /// [`InachusSigner::signs_transactions`].
+    pub fn signs_transactions(&self) -> bool {
+        self.0.signs_transactions()
+    }
+
+    /// Returns what to tell the user before a transaction is signed, if anything.
+    pub fn confirmation_prompt(&self) -> Option<&str> {
+        self.0.confirmation_prompt()
+    }
+
+    /// Returns the private key when it is held in memory.
+    pub fn local_key(&self) -> Option<&PrivateKeySigner> {
+        self.0.local_key()
+    }
+
+    /// Signs a message with the EIP-191 `personal_sign` prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The raw message bytes
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PrimitiveSignature>` - The signature, or an error if the signer refused
+    pub async fn sign_message(&self, message: &[u8]) -> Result<PrimitiveSignature> {
+        self.0.sign_message(message).await
+    }
+
+    /// Signs a 32-byte hash as is, without any prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash to sign
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PrimitiveSignature>` - The signature, or an error if the signer refused
+    pub async fn sign_hash(&self, hash: &B256) -> Result<PrimitiveSignature> {
+        self.0.sign_hash(hash).await
+    }
+}
+
+/// A private key held in memory, whether given as is, decrypted from a
+/// keystore or derived from a mnemonic.
+#[derive(Debug, Clone)]
+struct LocalKey {
+    signer: PrivateKeySigner,
+    kind: SignerKind,
+}
+
+#[async_trait]
+impl InachusSigner for LocalKey {
+    fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    fn kind(&self) -> &SignerKind {
+        &self.kind
+    }
+
+    fn wallet(&self) -> EthereumWallet {
+        EthereumWallet::from(self.signer.clone())
+    }
+
+    fn local_key(&self) -> Option<&PrivateKeySigner> {
+        Some(&self.signer)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<PrimitiveSignature> {
+        alloy::signers::Signer::sign_message(&self.signer, message)
+            .await
+            .map_err(|e| Error::signing("signing message", e))
+    }
+
+    async fn sign_hash(&self, hash: &B256) -> Result<PrimitiveSignature> {
+        alloy::signers::Signer::sign_hash(&self.signer, hash)
+            .await
+            .map_err(|e| Error::signing("signing hash", e))
+    }
+}
+
+/// A key held outside the process, on a hardware wallet or in a KMS.
+struct RemoteKey<S> {
+    signer: Arc<S>,
+    kind: SignerKind,
+    prompt: Option<&'static str>,
+}
+
+impl<S> fmt::Debug for RemoteKey<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteKey")
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<S> InachusSigner for RemoteKey<S>
+where
+    S: alloy::signers::Signer + TxSigner<PrimitiveSignature> + Send + Sync + 'static,
+{
+    fn address(&self) -> Address {
+        alloy::signers::Signer::address(self.signer.as_ref())
+    }
+
+    fn kind(&self) -> &SignerKind {
+        &self.kind
+    }
+
+    fn wallet(&self) -> EthereumWallet {
+        EthereumWallet::from(Shared(self.signer.clone()))
+    }
+
+    fn confirmation_prompt(&self) -> Option<&str> {
+        self.prompt
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<PrimitiveSignature> {
+        self.signer
+            .sign_message(message)
+            .await
+            .map_err(|e| Error::signing("signing message", e))
+    }
+
+    async fn sign_hash(&self, hash: &B256) -> Result<PrimitiveSignature> {
+        self.signer
+            .sign_hash(hash)
+            .await
+            .map_err(|e| Error::signing("signing hash", e))
+    }
+}
+
+/// Lets a device that cannot be cloned both sign transactions for the
+/// wallet and sign messages.
+struct Shared<S>(Arc<S>);
+
+#[async_trait]
+impl<S> TxSigner<PrimitiveSignature> for Shared<S>
+where
+    S: TxSigner<PrimitiveSignature> + Send + Sync,
+{
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<PrimitiveSignature>,
+    ) -> alloy::signers::Result<PrimitiveSignature> {
+        self.0.sign_transaction(tx).await
+    }
+}
+
+/// Stands in for the key of an impersonated account, refusing to sign.
+#[derive(Debug, Clone)]
+struct Impersonated {
+    address: Address,
+    kind: SignerKind,
+}
+
+#[async_trait]
+impl InachusSigner for Impersonated {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn kind(&self) -> &SignerKind {
+        &self.kind
+    }
+
+    fn wallet(&self) -> EthereumWallet {
+        EthereumWallet::from(Unsigned(self.address))
+    }
+
+    fn signs_transactions(&self) -> bool {
+        false
+    }
+
+    async fn sign_message(&self, _message: &[u8]) -> Result<PrimitiveSignature> {
+        Err(Error::signing(
+            "signing message",
+            alloy::signers::Error::UnsupportedOperation(UnsupportedSignerOperation::SignMessage),
+        ))
+    }
+
+    async fn sign_hash(&self, _hash: &B256) -> Result<PrimitiveSignature> {
+        Err(Error::signing(
+            "signing hash",
+            alloy::signers::Error::UnsupportedOperation(UnsupportedSignerOperation::SignHash),
+        ))
+    }
+}
+
+/// Transaction signer of an impersonated account, refusing to sign.
+#[derive(Debug, Clone, Copy)]
+struct Unsigned(Address);
+
+#[async_trait]
+impl TxSigner<PrimitiveSignature> for Unsigned {
+    fn address(&self) -> Address {
+        self.0
+    }
+
+    async fn sign_transaction(
+        &self,
+        _tx: &mut dyn SignableTransaction<PrimitiveSignature>,
+    ) -> alloy::signers::Result<PrimitiveSignature> {
+        Err(alloy::signers::Error::UnsupportedOperation(
+            UnsupportedSignerOperation::SignTransaction,
+        ))
+    }
+}
+
+impl From<PrivateKeySigner> for Signer {
+    fn from(signer: PrivateKeySigner) -> Self {
+        Self::local(signer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TEST_PRIVATE_KEY;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_local_signer_and_ledger_config() {
+        let key = PrivateKeySigner::from_str(TEST_PRIVATE_KEY).unwrap();
+        let signer = Signer::from(key.clone());
+        assert_eq!(signer.address(), key.address());
+        assert_eq!(signer.kind(), &SignerKind::Local);
+        assert!(signer.signs_transactions());
+        assert_eq!(signer.local_key().map(|k| k.address()), Some(key.address()));
+        assert_eq!(signer.confirmation_prompt(), None);
+
+        let impersonated = Signer::impersonated(key.address());
+        assert!(!impersonated.signs_transactions());
+        assert!(impersonated.local_key().is_none());
+        assert!(futures::executor::block_on(impersonated.sign_hash(&B256::ZERO)).is_err());
+
+        let kms = Signer::kms("AWS", key.clone());
+        assert_eq!(kms.address(), key.address());
+        assert_eq!(kms.kind().to_string(), "AWS KMS");
+        assert!(kms.local_key().is_none());
+        assert_eq!(
+            SignerKind::Keystore(PathBuf::from("keystores/ops")).to_string(),
+            "keystore (keystores/ops)"
+        );
+
+        let config: LedgerConfig = toml::from_str("").unwrap();
+        assert_eq!(config.derivation_path, DEFAULT_LEDGER_PATH);
+        assert_eq!(
+            SignerKind::Ledger(config.derivation_path).to_string(),
+            "Ledger (m/44'/60'/0'/0/0)"
+        );
+
+        let mnemonic: MnemonicConfig =
+            toml::from_str("derivation_path = \"m/44'/60'/1'/0/\"").unwrap();
+        assert_eq!(mnemonic.path(3), "m/44'/60'/1'/0/3");
+        assert_eq!(mnemonic.accounts, 5);
+        assert_eq!(
+            SignerKind::Mnemonic(mnemonic.path(3)).to_string(),
+            "mnemonic (m/44'/60'/1'/0/3)"
+        );
+    }
+}
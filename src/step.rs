@@ -1,42 +1,142 @@
 /// src/step.rs
-use serde::{Deserialize, Serialize};
-
-/// Represents the various steps or actions that can be taken in the application workflow.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum Step {
-    /// Change the current contract being interacted with
-    ChangeContract,
-    /// Change the address of the current contract
-    ChangeContractAddress,
-    /// Select a method to call on the current contract
-    SelectMethod,
-    /// Exit the application
+use crate::context::GlobalContext;
+use crate::error::Result;
+use async_trait::async_trait;
+
+/// Capabilities of the current session, used to decide which steps are offered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// A signer is configured, so write operations are possible
+    pub signer: bool,
+    /// Transactions may be broadcast: the session is neither read-only nor a dry run
+    pub broadcast: bool,
+    /// Named wallets are configured, so the sender can be switched
+    pub wallets: bool,
+    /// Configuration profiles are defined, so the session can switch between them
+    pub profiles: bool,
+    /// The RPC endpoint is a local development node (anvil, hardhat)
+    pub dev_node: bool,
+    /// The RPC transport supports subscriptions (WebSocket or IPC)
+    pub pubsub: bool,
+}
+
+/// What the workflow loop should do after a step completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// Go back to the step selector
+    Continue,
+    /// Reload the session from the configuration, e.g. after `profile` changed
+    Reload,
+    /// Leave the application
     Exit,
 }
 
-impl std::fmt::Display for Step {
+/// A single action the user can pick in the workflow loop.
+#[async_trait]
+pub trait WorkflowStep: Send + Sync {
+    /// Returns the label shown in the step selector.
+    fn name(&self) -> &str;
+
+    /// Returns whether the step should be offered for the given capabilities.
+    ///
+    /// # Arguments
+    ///
+    /// * `caps` - Capabilities of the current session
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        let _ = caps;
+        true
+    }
+
+    /// Runs the step against the session context.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The global context, which the step may modify
+    ///
+    /// # Returns
+    ///
+    /// * `Result<StepOutcome>` - What the loop should do next, or an error
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome>;
+}
+
+impl std::fmt::Debug for dyn WorkflowStep {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Step::ChangeContract => write!(f, "Change contract"),
-            Step::ChangeContractAddress => write!(f, "Change contract address"),
-            Step::SelectMethod => write!(f, "Select method"),
-            Step::Exit => write!(f, "Exit"),
-        }
+        f.debug_tuple("WorkflowStep").field(&self.name()).finish()
     }
 }
 
-impl Step {
-    /// Returns a slice containing all available steps in the application.
+/// Ordered collection of the steps offered in the workflow loop.
+#[derive(Debug, Default)]
+pub struct StepRegistry {
+    steps: Vec<Box<dyn WorkflowStep>>,
+}
+
+impl StepRegistry {
+    /// Creates an empty registry.
+    ///
+    /// # Returns
+    ///
+    /// * `StepRegistry` - A registry without any step
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry holding all built-in steps.
+    ///
+    /// # Returns
+    ///
+    /// * `StepRegistry` - A registry with the built-in steps registered
+    #[cfg(feature = "interactive")]
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        crate::steps::register_defaults(&mut registry);
+        registry
+    }
+
+    /// Appends a step to the registry. A step registered under an existing
+    /// name replaces the previous one in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - The step to register
+    pub fn register<S: WorkflowStep + 'static>(&mut self, step: S) {
+        let step: Box<dyn WorkflowStep> = Box::new(step);
+        match self.steps.iter().position(|s| s.name() == step.name()) {
+            Some(index) => self.steps[index] = step,
+            None => self.steps.push(step),
+        }
+    }
+
+    /// Returns the steps available for the given capabilities, in registration order.
+    ///
+    /// # Arguments
+    ///
+    /// * `caps` - Capabilities of the current session
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<&dyn WorkflowStep>` - The steps to offer
+    pub fn available(&self, caps: &Capabilities) -> Vec<&dyn WorkflowStep> {
+        self.steps
+            .iter()
+            .filter(|s| s.is_available(caps))
+            .map(|s| s.as_ref())
+            .collect()
+    }
+
+    /// Looks up a step by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The step's label
     ///
     /// # Returns
     ///
-    /// * A static slice containing all Step variants
-    pub fn all() -> &'static [Step] {
-        &[
-            Step::ChangeContract,
-            Step::ChangeContractAddress,
-            Step::SelectMethod,
-            Step::Exit,
-        ]
+    /// * `Option<&dyn WorkflowStep>` - The step, if registered
+    pub fn get(&self, name: &str) -> Option<&dyn WorkflowStep> {
+        self.steps
+            .iter()
+            .find(|s| s.name() == name)
+            .map(|s| s.as_ref())
     }
 }
@@ -0,0 +1,165 @@
+/// src/steps.rs
+mod address_book;
+mod bytecode;
+mod calldata;
+mod chain;
+mod contract;
+mod create2;
+mod erc20;
+mod events;
+mod fork;
+mod history;
+mod message;
+mod method;
+mod nft;
+mod nonce;
+mod offline;
+mod permit;
+mod profile;
+mod snapshot;
+mod transaction;
+mod transfer;
+mod wallet;
+
+pub use address_book::ManageAddressBookStep;
+pub use bytecode::InspectBytecodeStep;
+pub use calldata::{DecodeCalldataStep, EncodeCalldataStep};
+pub use chain::SwitchChainStep;
+pub use contract::{
+    ChangeContractAddressStep, ChangeContractStep, DefineAbiStep, ImportAbiStep, LoadDiamondStep,
+};
+pub use create2::Create2Step;
+pub use erc20::Erc20Step;
+pub use events::{event_filter, QueryEventsStep};
+pub use fork::{DevNodeToolsStep, ForkChainStep};
+pub use history::{HistoryStep, RepeatLastCallStep};
+pub use message::{SignMessageStep, VerifySignatureStep};
+pub use method::{checked_call, execute_method, with_access_list, SelectMethodStep};
+pub use nft::NftStep;
+pub use nonce::RepairNoncesStep;
+pub use offline::BroadcastTransactionStep;
+pub use permit::SignPermitStep;
+pub use profile::SwitchProfileStep;
+pub use snapshot::SnapshotStep;
+pub use transaction::DecodeTransactionStep;
+pub use transfer::TransferNativeStep;
+pub use wallet::{EncryptKeyStep, SwitchWalletStep};
+
+use crate::config::OperatingMode;
+use crate::context::{GlobalContext, WriteContext};
+use crate::error::{Error, Result};
+use crate::offline::UnsignedTransaction;
+use crate::prompt::{self, Confirmation};
+use crate::result::ExecutionResult;
+use crate::step::{StepOutcome, StepRegistry, WorkflowStep};
+use crate::transaction::{TransactionOptions, TransactionPreview};
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Registers all built-in steps, in the order they are offered to the user.
+///
+/// # Arguments
+///
+/// * `registry` - The registry to populate
+pub fn register_defaults(registry: &mut StepRegistry) {
+    registry.register(ChangeContractStep);
+    registry.register(ChangeContractAddressStep);
+    registry.register(ImportAbiStep);
+    registry.register(DefineAbiStep);
+    registry.register(LoadDiamondStep);
+    registry.register(SelectMethodStep);
+    registry.register(RepeatLastCallStep);
+    registry.register(Erc20Step);
+    registry.register(NftStep);
+    registry.register(HistoryStep);
+    registry.register(TransferNativeStep);
+    registry.register(EncodeCalldataStep);
+    registry.register(DecodeCalldataStep);
+    registry.register(DecodeTransactionStep);
+    registry.register(SnapshotStep);
+    registry.register(InspectBytecodeStep);
+    registry.register(Create2Step);
+    registry.register(QueryEventsStep);
+    registry.register(ManageAddressBookStep);
+    registry.register(SignMessageStep);
+    registry.register(SignPermitStep);
+    registry.register(VerifySignatureStep);
+    registry.register(BroadcastTransactionStep);
+    registry.register(SwitchChainStep);
+    registry.register(ForkChainStep);
+    registry.register(DevNodeToolsStep);
+    registry.register(SwitchWalletStep);
+    registry.register(SwitchProfileStep);
+    registry.register(RepairNoncesStep);
+    registry.register(EncryptKeyStep);
+    registry.register(ExitStep);
+}
+
+/// Leaves the application.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExitStep;
+
+#[async_trait]
+impl WorkflowStep for ExitStep {
+    fn name(&self) -> &str {
+        "Exit"
+    }
+
+    async fn run(&self, _ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        Ok(StepOutcome::Exit)
+    }
+}
+
+/// Asks for confirmation of a previewed transaction carrying raw calldata,
+/// then sends it and waits for its receipt, or exports it unsigned. A dry
+/// run stops at the preview.
+///
+/// # Arguments
+///
+/// * `ctx` - The context whose events are published
+/// * `write` - The write context to send through
+/// * `label` - What the transaction does, shown in place of a function signature
+/// * `preview` - The previewed transaction
+/// * `options` - The options it was previewed with
+///
+/// # Returns
+///
+/// * `Result<ExecutionResult>` - The receipt, the hash if the receipt is not
+///   available in time, the exported transaction, or `Cancelled`
+async fn confirm_and_send(
+    ctx: &GlobalContext,
+    write: &WriteContext,
+    label: &str,
+    preview: &TransactionPreview,
+    options: &TransactionOptions,
+) -> Result<ExecutionResult> {
+    if ctx.mode == OperatingMode::DryRun {
+        let tx = UnsignedTransaction::from_preview(preview);
+        return Ok(ExecutionResult::Simulated(Box::new(tx)));
+    }
+    match prompt::confirm_transaction(preview)? {
+        Confirmation::Send => {}
+        Confirmation::Export => {
+            let tx = UnsignedTransaction::from_preview(preview);
+            return Ok(ExecutionResult::Exported(Box::new(tx)));
+        }
+        Confirmation::Cancel => return Ok(ExecutionResult::Cancelled),
+    }
+    if let Some(prompt) = write.signer().confirmation_prompt() {
+        eprintln!("{}", prompt.yellow());
+    }
+    let pending = write
+        .send_calldata(preview.to, label, preview.calldata.clone(), options)
+        .await?;
+    let hash = *pending.tx_hash();
+    let result = match write.confirm(pending).await {
+        Ok(receipt) => ExecutionResult::Confirmed(Box::new(receipt)),
+        Err(e @ Error::Timeout { .. }) => {
+            tracing::warn!(%hash, error = %e, "receipt not available yet");
+            ExecutionResult::Submitted { hash }
+        }
+        Err(e) => return Err(e),
+    };
+    ctx.record_calldata(preview.to, label, options, &result);
+    Ok(result)
+}
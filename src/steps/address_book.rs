@@ -0,0 +1,42 @@
+/// src/steps/address_book.rs
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::prompt::{self, AddressBookAction};
+use crate::step::{StepOutcome, WorkflowStep};
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use colored::Colorize;
+use std::str::FromStr;
+
+/// Adds and removes address book aliases, saving them to `config.toml`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ManageAddressBookStep;
+
+#[async_trait]
+impl WorkflowStep for ManageAddressBookStep {
+    fn name(&self) -> &str {
+        "Manage address book"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        loop {
+            match prompt::select_address_book_action(&ctx.address_book)? {
+                AddressBookAction::Add => {
+                    let alias = prompt::input_alias()?;
+                    let address = prompt::input_address("Address:", &ctx.address_book)?;
+                    let address = Address::from_str(&address)
+                        .map_err(|_| Error::InvalidAddress(address.clone()))?;
+                    ctx.address_book.insert(&alias, address)?;
+                    eprintln!("{} {} = {}", "Saved".green(), alias, address);
+                }
+                AddressBookAction::Remove => {
+                    let alias = prompt::select_alias(&ctx.address_book)?;
+                    ctx.address_book.remove(&alias);
+                    eprintln!("{} {}", "Removed".green(), alias);
+                }
+                AddressBookAction::Back => return Ok(StepOutcome::Continue),
+            }
+            ctx.address_book.save(&ctx.config_path)?;
+        }
+    }
+}
@@ -0,0 +1,47 @@
+/// src/steps/bytecode.rs
+use crate::bytecode::BytecodeReport;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::step::{StepOutcome, WorkflowStep};
+use alloy::primitives::Selector;
+use async_trait::async_trait;
+use colored::Colorize;
+use std::collections::HashMap;
+
+/// Fetches the runtime bytecode of the active contract and compares the
+/// selectors its dispatcher handles with the loaded ABI.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InspectBytecodeStep;
+
+#[async_trait]
+impl WorkflowStep for InspectBytecodeStep {
+    fn name(&self) -> &str {
+        "Inspect bytecode"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let code = ctx.read_context()?.get_code(ctx.contract_address).await?;
+        if code.is_empty() {
+            return Err(Error::InvalidContract(format!(
+                "No code at {}",
+                ctx.contract_address
+            )));
+        }
+        let known: HashMap<Selector, String> = ctx
+            .abis
+            .values()
+            .flat_map(|abi| abi.functions())
+            .map(|function| (function.selector(), function.signature()))
+            .collect();
+        let report = BytecodeReport::new(&code, ctx.abis.get(&ctx.contract_name), &known);
+        eprintln!("{}", report);
+        if report.selectors.is_empty() || report.minimal_proxy.is_some() {
+            eprintln!(
+                "{}",
+                "No dispatcher found: the contract may be a proxy, whose functions live in its implementation."
+                    .yellow()
+            );
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
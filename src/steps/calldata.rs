@@ -0,0 +1,87 @@
+/// src/steps/calldata.rs
+use crate::abi::{self, MethodType};
+use crate::codec;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::output::{Output, OutputFormat, OutputKind};
+use crate::prompt;
+use crate::result::CallResult;
+use crate::step::{StepOutcome, WorkflowStep};
+use alloy::primitives::hex;
+use async_trait::async_trait;
+use colored::Colorize;
+use serde_json::json;
+
+/// Encodes a call to a method of the active contract and prints the calldata
+/// without sending anything, e.g. to paste into a multisig.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EncodeCalldataStep;
+
+#[async_trait]
+impl WorkflowStep for EncodeCalldataStep {
+    fn name(&self) -> &str {
+        "Encode calldata"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let methods = abi::get_methods_by_type(ctx.get_abi()?, MethodType::All);
+        if methods.is_empty() {
+            return Err(Error::InvalidFunction(format!(
+                "{} has no methods",
+                ctx.contract_name
+            )));
+        }
+        let quick = ctx.usage().quick_access(&ctx.contract_name);
+        let method_name = prompt::select_method(&methods, &quick)?;
+        let function = &methods[&method_name];
+        let params = prompt::input_method_params(function, &[], &ctx.address_book)?;
+        let calldata = codec::encode_calldata(function, &params)?;
+        match ctx.output {
+            OutputFormat::Json => println!(
+                "{}",
+                Output::new(
+                    OutputKind::Calldata,
+                    json!({ "function": function.signature(), "calldata": calldata }),
+                )
+            ),
+            OutputFormat::Text => {
+                eprintln!("{} {}", "To:".green(), ctx.contract_address);
+                eprintln!("{} {}", "Function:".green(), function.signature());
+                println!("{}", calldata);
+            }
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Decodes pasted calldata against every loaded ABI, matching its selector.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecodeCalldataStep;
+
+#[async_trait]
+impl WorkflowStep for DecodeCalldataStep {
+    fn name(&self) -> &str {
+        "Decode calldata"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let data = prompt::input_calldata()?;
+        let matches = abi::find_by_selector(&ctx.abis, &data);
+        let (function, names) = match matches.len() {
+            0 => {
+                return Err(Error::InvalidFunction(format!(
+                    "No loaded ABI has a function with selector {}",
+                    hex::encode_prefixed(&data[..4])
+                )))
+            }
+            1 => &matches[0],
+            _ => &matches[prompt::select_selector_match(&matches)?],
+        };
+        let values = codec::decode_input(function, &data)?;
+        let result = CallResult::from_inputs(function, values, data.clone());
+        eprintln!("{} {}", result.function.cyan(), names.join(", ").dimmed());
+        prompt::display_result(&result, &ctx.address_book, ctx.output);
+        prompt::offer_export(&result, ctx.output)?;
+        Ok(StepOutcome::Continue)
+    }
+}
@@ -0,0 +1,136 @@
+/// src/steps/chain.rs
+use crate::config::Config;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::prompt;
+use crate::setup;
+use crate::step::{StepOutcome, WorkflowStep};
+use crate::transport::RpcEndpoint;
+use async_trait::async_trait;
+use chain_info::{ChainInfo, ChainRegistry, RpcHealth, DEFAULT_TTL};
+use colored::Colorize;
+
+/// Switches to another chain found by name or ID, connecting to the fastest
+/// of its public RPC endpoints that answers with the right chain ID. An
+/// unknown chain ID can be registered as a custom chain on the way.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwitchChainStep;
+
+#[async_trait]
+impl WorkflowStep for SwitchChainStep {
+    fn name(&self) -> &str {
+        "Switch chain"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let query = prompt::input_chain_query()?;
+        // Only hits chainid.network once the cached list is a day old.
+        let custom_path = ctx.data_dir.join(crate::CUSTOM_CHAINS_FILE);
+        ctx.chains = ChainRegistry::refresh(ctx.data_dir.join(crate::CHAINS_FILE), DEFAULT_TTL)
+            .await
+            .map_err(|e| Error::InvalidConfig(format!("Invalid bundled chain list: {}", e)))?;
+        ctx.chains
+            .merge_custom(&custom_path)
+            .map_err(|e| Error::InvalidConfig(format!("Invalid custom chain list: {}", e)))?;
+        let matches = ctx.chains.search(&query);
+        let chain = match (matches.as_slice(), query.trim().parse::<u64>()) {
+            ([], Ok(chain_id)) if prompt::confirm_register_chain(chain_id)? => {
+                let name = prompt::input_custom_chain_name()?;
+                let rpc_url = setup::input_checked_rpc_url(chain_id).await?;
+                let chain = ChainInfo::custom(chain_id, &name, &rpc_url);
+                ctx.chains.register(chain.clone());
+                ctx.chains.save_custom(&custom_path).map_err(|e| {
+                    Error::Other(format!("Cannot save {}: {}", custom_path.display(), e))
+                })?;
+                eprintln!(
+                    "{} {} to {}",
+                    "Registered".green(),
+                    name,
+                    custom_path.display()
+                );
+                chain
+            }
+            ([], _) => {
+                return Err(Error::InvalidChainId(format!(
+                    "No known chain matches {:?}",
+                    query
+                )))
+            }
+            ([chain], _) => (*chain).clone(),
+            (chains, _) => chains[prompt::select_chain(chains, &ctx.chains)?].clone(),
+        };
+
+        // URLs are shown and saved as listed, with their API key placeholders;
+        // the keys are only filled in to connect.
+        eprintln!("{}", "Probing public RPCs...".dimmed());
+        let probes = chain.probe_rpcs_with_keys(&ctx.rpc_api_keys).await;
+        for probe in &probes {
+            let url = &probe.listed_url;
+            match &probe.health {
+                RpcHealth::Healthy => {
+                    eprintln!("{} {} ({:?})", "OK".green(), url, probe.latency)
+                }
+                RpcHealth::WrongChain(id) => {
+                    eprintln!("{} {} reports chain {}", "Skipped:".yellow(), url, id)
+                }
+                RpcHealth::Failed(e) => eprintln!("{} {}: {}", "Skipped:".yellow(), url, e),
+            }
+        }
+        let mut rpc_url = probes
+            .iter()
+            .find(|probe| probe.is_healthy())
+            .map(|probe| (probe.listed_url.clone(), probe.url.clone()));
+
+        // WebSocket endpoints are not probed; try them in order as a last resort.
+        let urls = chain.keyed_rpcs(&ctx.rpc_api_keys);
+        let sockets = urls.iter().filter(|(_, url)| url.starts_with("ws"));
+        for (listed_url, url) in sockets {
+            if rpc_url.is_some() {
+                break;
+            }
+            eprintln!("{} {}", "Trying".dimmed(), listed_url);
+            match RpcEndpoint::parse(url)?.chain_id().await {
+                Ok(id) if id == chain.chain_id => rpc_url = Some((listed_url.clone(), url.clone())),
+                Ok(id) => eprintln!(
+                    "{} {} reports chain {}",
+                    "Skipped:".yellow(),
+                    listed_url,
+                    id
+                ),
+                Err(_) => eprintln!("{} {} did not answer", "Skipped:".yellow(), listed_url),
+            }
+        }
+        let (listed_url, rpc_url) = rpc_url.ok_or_else(|| {
+            Error::InvalidConfig(format!("No public RPC of {} answered", chain.name))
+        })?;
+
+        ctx.switch_chain(chain.chain_id, &rpc_url).await?;
+        eprintln!(
+            "{} {} ({}) via {}",
+            "Switched to".green(),
+            chain.name,
+            chain.chain_id,
+            listed_url
+        );
+        if !ctx.contract_name.is_empty() {
+            match ctx.deployment(&ctx.contract_name, chain.chain_id) {
+                Some(address) => eprintln!(
+                    "{} {} at {}",
+                    "Using".green(),
+                    ctx.contract_name,
+                    address.to_checksum(None)
+                ),
+                None => eprintln!(
+                    "{} {} has no known deployment on {}, its address is unchanged",
+                    "Warning:".yellow(),
+                    ctx.contract_name,
+                    chain.name
+                ),
+            }
+        }
+        if prompt::confirm_save_chain(&chain)? {
+            Config::save_chain(&ctx.config_path, chain.chain_id, &listed_url)?;
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
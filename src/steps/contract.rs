@@ -0,0 +1,299 @@
+/// src/steps/contract.rs
+use crate::abi::{self, source::AbiQuery};
+use crate::config::ContractInfo;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::prompt;
+use crate::proxy;
+use crate::step::{StepOutcome, WorkflowStep};
+use alloy::json_abi::JsonAbi;
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use colored::Colorize;
+use std::str::FromStr;
+
+/// Switches the active contract to another loaded ABI.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChangeContractStep;
+
+#[async_trait]
+impl WorkflowStep for ChangeContractStep {
+    fn name(&self) -> &str {
+        "Change contract"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let mut contract_names: Vec<String> = ctx.abis.keys().cloned().collect();
+        contract_names.sort();
+        if contract_names.is_empty() {
+            return Err(Error::InvalidContract(format!(
+                "No ABI found in {}",
+                ctx.abis_dir.display()
+            )));
+        }
+        let chain_id = chain_id(ctx)?;
+        let contracts: Vec<(String, Option<String>)> = contract_names
+            .into_iter()
+            .map(|name| {
+                let chains = deployed_on(ctx, &name);
+                (name, chains)
+            })
+            .collect();
+        let contract_name = prompt::select_contract_name(&contracts)?;
+
+        let address = match ctx.deployment(&contract_name, chain_id) {
+            Some(address) => address,
+            None => {
+                let address = prompt::input_contract_address(&ctx.address_book)?;
+                remember_contract(ctx, &contract_name, &address)?;
+                parse_address(&address)?
+            }
+        };
+
+        ctx.set_contract(&contract_name, address);
+        offer_implementation_abi(ctx).await;
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Changes the address of the active contract and persists it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChangeContractAddressStep;
+
+#[async_trait]
+impl WorkflowStep for ChangeContractAddressStep {
+    fn name(&self) -> &str {
+        "Change contract address"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        if ctx.contract_name.is_empty() {
+            return Err(Error::InvalidContract(
+                "Select a contract first".to_string(),
+            ));
+        }
+        let address = prompt::input_contract_address(&ctx.address_book)?;
+        let contract_name = ctx.contract_name.clone();
+        remember_contract(ctx, &contract_name, &address)?;
+
+        ctx.set_contract(&contract_name, parse_address(&address)?);
+        offer_implementation_abi(ctx).await;
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Imports a verified contract's ABI from Etherscan or Sourcify and selects
+/// the contract.
+///
+/// The ABI is stored in the ABI directory along with where it came from, and
+/// the deployment is added to `contracts.json`, so the contract is available
+/// in later sessions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportAbiStep;
+
+#[async_trait]
+impl WorkflowStep for ImportAbiStep {
+    fn name(&self) -> &str {
+        "Import ABI from explorer"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let chain_id = ctx
+            .chain_id
+            .parse::<u64>()
+            .map_err(|_| Error::InvalidChainId(ctx.chain_id.clone()))?;
+        let address = prompt::input_contract_address(&ctx.address_book)?;
+        let contract_address = parse_address(&address)?;
+        let contract_name = prompt::prompt_contract_name()?;
+
+        let (abi, origin) = abi::import_from_explorers(
+            chain_id,
+            contract_address,
+            ctx.etherscan_api_key.as_deref(),
+            Some(&ctx.abi_cache_dir()),
+        )
+        .await?;
+        let path = abi::save_abi(&ctx.abis_dir, &contract_name, &abi)?;
+        origin.save(&ctx.abis_dir, &contract_name)?;
+        eprintln!(
+            "{} {} (from {})",
+            "ABI saved to".green(),
+            path.display(),
+            origin.source
+        );
+        ctx.register_abi(&contract_name, abi);
+        remember_contract(ctx, &contract_name, &address)?;
+
+        ctx.set_contract(&contract_name, contract_address);
+        offer_implementation_abi(ctx).await;
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Defines a contract from pasted human-readable signatures and selects it.
+///
+/// The signatures are saved as a `.habi` file in the ABI directory.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefineAbiStep;
+
+#[async_trait]
+impl WorkflowStep for DefineAbiStep {
+    fn name(&self) -> &str {
+        "Define contract from signatures"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let signatures = prompt::input_signatures()?;
+        let abi = abi::parse_human_readable(&signatures.join("\n"))?;
+        let contract_name = prompt::prompt_contract_name()?;
+        let address = prompt::input_contract_address(&ctx.address_book)?;
+        let contract_address = parse_address(&address)?;
+
+        let path = abi::save_human_readable(&ctx.abis_dir, &contract_name, &signatures)?;
+        eprintln!("{} {}", "ABI saved to".green(), path.display());
+        ctx.register_abi(&contract_name, abi);
+        remember_contract(ctx, &contract_name, &address)?;
+
+        ctx.set_contract(&contract_name, contract_address);
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Checks whether the active contract is a proxy and, if so, offers to merge
+/// its implementation ABI so the implementation's methods can be selected.
+///
+/// Failures are reported but never abort the step that selected the contract.
+async fn offer_implementation_abi(ctx: &mut GlobalContext) {
+    if let Err(e) = merge_implementation_abi(ctx).await {
+        eprintln!("{} {}", "Proxy check skipped:".yellow(), e);
+    }
+}
+
+async fn merge_implementation_abi(ctx: &mut GlobalContext) -> Result<()> {
+    let read = ctx.read_context()?;
+    let Some(info) = proxy::detect(&read, ctx.contract_address).await? else {
+        return Ok(());
+    };
+    if !prompt::confirm_merge_implementation(&info)? {
+        return Ok(());
+    }
+
+    let implementation = resolve_by_address(ctx, info.implementation).await?;
+    let contract_name = ctx.contract_name.clone();
+    let merged = proxy::merge_abis(ctx.get_abi()?, &implementation);
+    ctx.register_abi(&contract_name, merged);
+    eprintln!(
+        "{} {}",
+        "Merged implementation ABI from".green(),
+        info.implementation
+    );
+    Ok(())
+}
+
+/// Loads the facets of the active contract as an EIP-2535 diamond and merges
+/// the functions each facet serves into its ABI.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadDiamondStep;
+
+#[async_trait]
+impl WorkflowStep for LoadDiamondStep {
+    fn name(&self) -> &str {
+        "Load diamond facets"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let mut merged = ctx.get_abi()?.clone();
+        let read = ctx.read_context()?;
+        let facets = proxy::diamond_facets(&read, ctx.contract_address).await?;
+
+        for facet in &facets {
+            match resolve_by_address(ctx, facet.address).await {
+                Ok(abi) => {
+                    let served = facet.restrict(&abi);
+                    eprintln!(
+                        "{} {} ({} functions)",
+                        "Facet".cyan(),
+                        facet.address,
+                        served.functions().count()
+                    );
+                    merged = proxy::merge_abis(&merged, &served);
+                }
+                Err(e) => eprintln!(
+                    "{} {} ({} selectors): {}",
+                    "Facet ABI unavailable".yellow(),
+                    facet.address,
+                    facet.selectors.len(),
+                    e
+                ),
+            }
+        }
+
+        let contract_name = ctx.contract_name.clone();
+        ctx.register_abi(&contract_name, merged);
+        ctx.facets.insert(contract_name, facets);
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Finds the ABI of a deployed contract through the configured sources,
+/// falling back to the block explorers.
+async fn resolve_by_address(ctx: &GlobalContext, address: Address) -> Result<JsonAbi> {
+    let chain_id = ctx
+        .chain_id
+        .parse::<u64>()
+        .map_err(|_| Error::InvalidChainId(ctx.chain_id.clone()))?;
+    if let Some(abi) = ctx
+        .abi_source
+        .resolve(&AbiQuery::by_address(address, chain_id))
+        .await?
+    {
+        return Ok(abi);
+    }
+    let (abi, _) = abi::import_from_explorers(
+        chain_id,
+        address,
+        ctx.etherscan_api_key.as_deref(),
+        Some(&ctx.abi_cache_dir()),
+    )
+    .await?;
+    Ok(abi)
+}
+
+/// Records the address of a contract on the current chain in `contracts.json`.
+pub(super) fn remember_contract(ctx: &mut GlobalContext, name: &str, address: &str) -> Result<()> {
+    let chain_id = chain_id(ctx)?;
+    match ctx.contracts.iter_mut().find(|info| info.name == name) {
+        Some(info) => info.set_address(chain_id, address),
+        None => ctx
+            .contracts
+            .push(ContractInfo::new(name, chain_id, address)),
+    }
+    ctx.save_contracts()
+}
+
+/// Lists the chains a contract has a known deployment on, by name, e.g.
+/// `Ethereum Mainnet, OP Mainnet`, or `None` if there are none.
+fn deployed_on(ctx: &GlobalContext, name: &str) -> Option<String> {
+    let info = ctx.contracts.iter().find(|info| info.name == name)?;
+    let chains: Vec<String> = info
+        .chains()
+        .into_iter()
+        .map(|id| match ctx.chains.get(id) {
+            Ok(chain) => chain.name.clone(),
+            Err(_) => format!("chain {}", id),
+        })
+        .collect();
+    (!chains.is_empty()).then(|| chains.join(", "))
+}
+
+/// Parses the chain ID of the session.
+fn chain_id(ctx: &GlobalContext) -> Result<u64> {
+    ctx.chain_id
+        .parse::<u64>()
+        .map_err(|_| Error::InvalidChainId(ctx.chain_id.clone()))
+}
+
+/// Parses an address without enforcing its checksum.
+fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address).map_err(|_| Error::InvalidAddress(address.to_string()))
+}
@@ -0,0 +1,145 @@
+/// src/steps/create2.rs
+use super::{confirm_and_send, contract::remember_contract};
+use crate::abi;
+use crate::context::GlobalContext;
+use crate::create2::{self, Create2Deployment};
+use crate::error::{Error, Result};
+use crate::prompt::{self, Create2Action};
+use crate::result::ExecutionResult;
+use crate::step::{StepOutcome, WorkflowStep};
+use alloy::json_abi::{JsonAbi, StateMutability};
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Label of factory deployments in previews and pre-send hooks, which have
+/// no function signature to show.
+const DEPLOY_LABEL: &str = "CREATE2 deployment";
+
+/// Computes CREATE2 addresses, and deploys build artifacts through the
+/// configured CREATE2 factory at the address computed beforehand.
+///
+/// The factory is asked with `eth_call` where it would create the contract
+/// before anything is sent, and the deployment is refused if it disagrees
+/// with the computed address. Deployments are sent directly: a configured
+/// Safe or private relay is not used.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Create2Step;
+
+#[async_trait]
+impl WorkflowStep for Create2Step {
+    fn name(&self) -> &str {
+        "Deterministic deployment (CREATE2)"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        match prompt::select_create2_action()? {
+            Create2Action::Compute => {
+                let deployer = prompt::input_address("Deployer:", &ctx.address_book)?;
+                let deployer: Address = deployer
+                    .parse()
+                    .map_err(|_| Error::InvalidAddress(deployer.clone()))?;
+                let salt = prompt::input_salt()?;
+                let init_code_hash = prompt::input_init_code_hash()?;
+                println!(
+                    "{}",
+                    create2::compute_address(deployer, salt, init_code_hash)
+                );
+            }
+            Create2Action::Deploy => deploy(ctx).await?,
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
+
+async fn deploy(ctx: &mut GlobalContext) -> Result<()> {
+    let path = prompt::input_artifact_path()?;
+    let artifact = abi::parse_artifact(&std::fs::read_to_string(&path)?)?
+        .ok_or_else(|| Error::InvalidAbi(format!("{} is not a build artifact", path.display())))?;
+    let bytecode = artifact.bytecode.as_ref().ok_or_else(|| {
+        Error::InvalidAbi(format!(
+            "{} has no creation bytecode, or its libraries are not linked",
+            path.display()
+        ))
+    })?;
+    let contract_name = prompt::prompt_contract_name()?;
+    let constructor = create2::constructor_function(&artifact.abi);
+    let params = prompt::input_method_params(&constructor, &[], &ctx.address_book)?;
+    let deployment = Create2Deployment {
+        factory: ctx.create2_factory,
+        salt: prompt::input_salt()?,
+        init_code: create2::init_code(bytecode, &artifact.abi, &params)?,
+    };
+    let address = deployment.address();
+    eprintln!(
+        "{}",
+        text::table(&[
+            ("Factory", deployment.factory.to_string()),
+            ("Salt", deployment.salt.to_string()),
+            ("Init code hash", deployment.init_code_hash().to_string()),
+            ("Address", address.to_string()),
+        ])
+    );
+
+    let write = ctx.write_context()?;
+    let read = write.reader();
+    if read.get_code(deployment.factory).await?.is_empty() {
+        return Err(Error::InvalidConfig(format!(
+            "No CREATE2 factory is deployed at {} on this chain; set create2_factory",
+            deployment.factory
+        )));
+    }
+    if !read.get_code(address).await?.is_empty() {
+        eprintln!("{} {}", "Already deployed at".green(), address);
+        return register(ctx, &contract_name, artifact.abi, address);
+    }
+    let simulated = deployment.simulate(read, write.address()).await?;
+    if simulated != address {
+        return Err(Error::Other(format!(
+            "Factory {} would deploy to {}, not the computed {}",
+            deployment.factory, simulated, address
+        )));
+    }
+
+    let mut options = ctx.gas.options().with_confirmations(ctx.confirmations);
+    if constructor.state_mutability == StateMutability::Payable {
+        options = options.with_value(prompt::input_value()?);
+    }
+    let preview = write
+        .preview_calldata(
+            deployment.factory,
+            DEPLOY_LABEL,
+            deployment.calldata(),
+            &options,
+        )
+        .await?;
+    let result = confirm_and_send(ctx, &write, DEPLOY_LABEL, &preview, &options).await?;
+    prompt::display_execution(ctx, &result);
+
+    if let ExecutionResult::Confirmed(receipt) = &result {
+        if !receipt.status() || read.get_code(address).await?.is_empty() {
+            return Err(Error::Other(format!(
+                "Deployment transaction {} did not create a contract at {}",
+                receipt.transaction_hash, address
+            )));
+        }
+        eprintln!("{} {}", "Deployed at".green(), address);
+        register(ctx, &contract_name, artifact.abi, address)?;
+    }
+    Ok(())
+}
+
+/// Saves the ABI of a deployed artifact and selects the contract.
+fn register(
+    ctx: &mut GlobalContext,
+    contract_name: &str,
+    abi: JsonAbi,
+    address: Address,
+) -> Result<()> {
+    let path = abi::save_abi(&ctx.abis_dir, contract_name, &abi)?;
+    eprintln!("{} {}", "ABI saved to".green(), path.display());
+    ctx.register_abi(contract_name, abi);
+    remember_contract(ctx, contract_name, &address.to_string())?;
+    ctx.set_contract(contract_name, address);
+    Ok(())
+}
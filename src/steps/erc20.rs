@@ -0,0 +1,105 @@
+/// src/steps/erc20.rs
+use crate::context::GlobalContext;
+use crate::ens;
+use crate::erc20::{self, TokenInfo};
+use crate::error::{Error, Result};
+use crate::prompt::{self, Erc20Action};
+use crate::step::{StepOutcome, WorkflowStep};
+use crate::steps::execute_method;
+use alloy::dyn_abi::DynSolValue;
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use colored::Colorize;
+use std::str::FromStr;
+
+/// Shows the metadata of the current contract as an ERC-20 token, then reads
+/// balances and allowances and guides transfers and approvals, converting
+/// amounts with the token's decimals.
+///
+/// A contract whose ABI lacks the ERC-20 functions can still be asserted to
+/// be a token, in which case the standard ERC-20 ABI is used for it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Erc20Step;
+
+#[async_trait]
+impl WorkflowStep for Erc20Step {
+    fn name(&self) -> &str {
+        "ERC-20 token"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        if !erc20::is_erc20(ctx.get_abi()?) && !prompt::confirm_assume_erc20(&ctx.contract_name)? {
+            return Ok(StepOutcome::Continue);
+        }
+        let read = ctx.read_context()?;
+        let token = TokenInfo::fetch(&read, ctx.contract_address).await?;
+        eprintln!("{}", ctx.address_book.annotate(&token.to_string()));
+
+        loop {
+            match prompt::select_erc20_action()? {
+                Erc20Action::Balance => {
+                    let account = input_account(ctx, "Account:").await?;
+                    let balance = read_uint(ctx, "balanceOf", vec![account]).await?;
+                    println!("{}", token.format(balance));
+                }
+                Erc20Action::Allowance => {
+                    let owner = input_account(ctx, "Owner:").await?;
+                    let spender = input_account(ctx, "Spender:").await?;
+                    let allowance = read_uint(ctx, "allowance", vec![owner, spender]).await?;
+                    match allowance == U256::MAX {
+                        true => println!("unlimited"),
+                        false => println!("{}", token.format(allowance)),
+                    }
+                }
+                Erc20Action::Transfer => {
+                    let to = input_account(ctx, "Recipient:").await?;
+                    let amount = prompt::input_token_amount(&token, false)?;
+                    eprintln!("{} {}", "Transferring".cyan(), token.format(amount));
+                    send(ctx, "transfer", to, amount).await?;
+                }
+                Erc20Action::Approve => {
+                    let spender = input_account(ctx, "Spender:").await?;
+                    let amount = prompt::input_token_amount(&token, true)?;
+                    match amount == U256::MAX {
+                        true => eprintln!("{}", "Approving an unlimited amount".yellow()),
+                        false => eprintln!("{} {}", "Approving".cyan(), token.format(amount)),
+                    }
+                    send(ctx, "approve", spender, amount).await?;
+                }
+                Erc20Action::Back => return Ok(StepOutcome::Continue),
+            }
+        }
+    }
+}
+
+/// Prompts for an account as an address, alias or ENS name.
+async fn input_account(ctx: &GlobalContext, message: &str) -> Result<Address> {
+    let account = prompt::input_account(message, &ctx.address_book)?;
+    match ens::is_name(&account) {
+        true => ens::resolve(&ctx.read_context()?, &account).await,
+        false => Address::from_str(&account).map_err(|_| Error::InvalidAddress(account)),
+    }
+}
+
+/// Calls an ERC-20 view function returning an amount.
+async fn read_uint(ctx: &GlobalContext, name: &str, accounts: Vec<Address>) -> Result<U256> {
+    let args: Vec<DynSolValue> = accounts.into_iter().map(DynSolValue::Address).collect();
+    let result = ctx
+        .read_context()?
+        .call(ctx.contract_address, &erc20::function(name)?, &args)
+        .await?;
+    match result.values.first() {
+        Some(DynSolValue::Uint(amount, _)) => Ok(*amount),
+        _ => Err(Error::Other(format!("{} returned no amount", name))),
+    }
+}
+
+/// Sends `transfer` or `approve` with an amount in base units.
+async fn send(ctx: &GlobalContext, name: &str, account: Address, amount: U256) -> Result<()> {
+    let function = erc20::function(name)?;
+    let params = [account.to_string(), amount.to_string()];
+    let options = ctx.gas.options().with_confirmations(ctx.confirmations);
+    let result = execute_method(ctx, &function, &params, &options).await?;
+    prompt::display_execution(ctx, &result);
+    Ok(())
+}
@@ -0,0 +1,126 @@
+/// src/steps/events.rs
+use crate::codec;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::output::OutputFormat;
+use crate::prompt;
+use crate::result::{event_table, EventLog};
+use crate::step::{StepOutcome, WorkflowStep};
+use alloy::json_abi::Event;
+use alloy::rpc::types::Filter;
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Fetches and decodes the logs of an event emitted by the active contract.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryEventsStep;
+
+#[async_trait]
+impl WorkflowStep for QueryEventsStep {
+    fn name(&self) -> &str {
+        "Query events"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let events: Vec<Event> = ctx.get_abi()?.events().cloned().collect();
+        if events.is_empty() {
+            return Err(Error::InvalidAbi(format!(
+                "{} declares no events",
+                ctx.contract_name
+            )));
+        }
+        let event = prompt::select_event(&events)?;
+        let topics = prompt::input_topic_filters(&event, &ctx.address_book)?;
+        let (from, to) = prompt::input_block_range()?;
+
+        let filter = event_filter(&event, &topics)?
+            .address(ctx.contract_address)
+            .from_block(from)
+            .to_block(to);
+        let logs = ctx.read_context()?.get_logs(&filter).await?;
+        let decoded: Vec<_> = logs
+            .iter()
+            .map(|log| EventLog::decode(&event, log))
+            .collect();
+        let book = ctx
+            .labelled_address_book(decoded.iter().flatten().flat_map(|log| &log.values))
+            .await;
+        for log in &decoded {
+            match log {
+                Ok(decoded) if ctx.output == OutputFormat::Json => {
+                    prompt::display_result(decoded, &book, ctx.output)
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("{} {}", "Undecodable log:".yellow(), e),
+            }
+        }
+        let table = event_table(decoded.iter().flatten(), &book);
+        if ctx.output == OutputFormat::Text && !table.is_empty() {
+            println!("\n{}", table);
+        }
+        eprintln!("{} {} logs", "Found".green(), logs.len());
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Builds a filter matching an event, with optional values for its indexed
+/// parameters in declaration order.
+///
+/// # Arguments
+///
+/// * `event` - The event to match
+/// * `topics` - One optional value per indexed parameter
+///
+/// # Returns
+///
+/// * `Result<Filter>` - The filter, or an error if a value does not match its parameter type
+pub fn event_filter(event: &Event, topics: &[Option<String>]) -> Result<Filter> {
+    let mut filter = Filter::new();
+    let offset = if event.anonymous {
+        0
+    } else {
+        filter = filter.event_signature(event.selector());
+        1
+    };
+    let indexed = event.inputs.iter().filter(|input| input.indexed);
+    for (i, (input, value)) in indexed.zip(topics).enumerate() {
+        let Some(value) = value else {
+            continue;
+        };
+        let topic = codec::encode_topic(&codec::coerce(&codec::event_param_type(input)?, value)?);
+        filter = match i + offset {
+            0 => filter.event_signature(topic),
+            1 => filter.topic1(topic),
+            2 => filter.topic2(topic),
+            3 => filter.topic3(topic),
+            _ => {
+                return Err(Error::InvalidAbi(format!(
+                    "{} has too many indexed parameters",
+                    event.name
+                )))
+            }
+        };
+    }
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures;
+    use alloy::primitives::{Address, B256};
+
+    #[test]
+    fn test_event_filter_topics() {
+        let abi = fixtures::erc20_abi();
+        let transfer = &abi.event("Transfer").unwrap()[0];
+        let to = Address::repeat_byte(0x22);
+
+        let filter = event_filter(transfer, &[None, Some(to.to_string())]).unwrap();
+        assert!(filter.topics[0].matches(&transfer.selector()));
+        assert!(filter.topics[1].is_empty());
+        assert!(filter.topics[2].matches(&to.into_word()));
+        assert!(!filter.topics[2].matches(&B256::ZERO));
+        assert!(event_filter(transfer, &[Some("nope".to_string())]).is_err());
+    }
+}
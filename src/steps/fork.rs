@@ -0,0 +1,157 @@
+/// src/steps/fork.rs
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::fork;
+use crate::prompt::{self, DevNodeAction};
+use crate::step::{Capabilities, StepOutcome, WorkflowStep};
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Forks the current chain with a local anvil node and repoints the session
+/// at it, to rehearse transactions against the chain's real state.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForkChainStep;
+
+#[async_trait]
+impl WorkflowStep for ForkChainStep {
+    fn name(&self) -> &str {
+        "Fork chain with anvil"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        !caps.dev_node
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let block = prompt::input_fork_block()?;
+        eprintln!("{}", "Starting anvil...".dimmed());
+        let origin = ctx.rpc_url.clone();
+        fork::start(ctx, block).await?;
+        let url = ctx.rpc_url.clone();
+        let block = ctx.read_context()?.get_block_number().await?;
+        eprintln!(
+            "{} {} at block {}, now connected to {}",
+            "Forked".green(),
+            crate::status::endpoint_label(&origin),
+            block,
+            url
+        );
+        eprintln!(
+            "{}",
+            "Transactions are sent to the fork only; the Safe and private relay are set aside until it stops."
+                .dimmed()
+        );
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Acts as any account, snapshots and reverts state and moves the clock
+/// forward on the anvil or Hardhat node the session is connected to, or
+/// stops the fork Inachus started.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DevNodeToolsStep;
+
+#[async_trait]
+impl WorkflowStep for DevNodeToolsStep {
+    fn name(&self) -> &str {
+        "Dev node tools"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.dev_node
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let (kind, action) = match ctx.dev_node {
+            Some(ref node) => (
+                node.kind,
+                prompt::select_dev_node_action(node, ctx.fork.as_ref(), ctx.impersonating)?,
+            ),
+            None => {
+                return Err(Error::InvalidConfig(
+                    "The session is not connected to a development node".to_string(),
+                ))
+            }
+        };
+        let read = ctx.read_context()?;
+        match action {
+            DevNodeAction::Impersonate => {
+                let input = prompt::input_address("Account to act as:", &ctx.address_book)?;
+                let address: Address = input
+                    .parse()
+                    .map_err(|_| Error::InvalidAddress(input.clone()))?;
+                if let Some(previous) = ctx.impersonating.take() {
+                    fork::stop_impersonating(&read, kind, previous).await?;
+                }
+                fork::impersonate(&read, kind, address).await?;
+                ctx.impersonating = Some(address);
+                eprintln!(
+                    "{} {}: calls and transactions are sent from it, unsigned",
+                    "Acting as".green(),
+                    address
+                );
+                if read.get_balance(address).await?.is_zero() {
+                    eprintln!(
+                        "{} {} has no native currency to pay for gas",
+                        "Warning:".yellow(),
+                        address
+                    );
+                }
+            }
+            DevNodeAction::StopImpersonating => {
+                if let Some(address) = ctx.impersonating.take() {
+                    fork::stop_impersonating(&read, kind, address).await?;
+                    eprintln!("{} {}", "Stopped acting as".green(), address);
+                }
+            }
+            DevNodeAction::Snapshot => {
+                let snapshot = fork::snapshot(&read).await?;
+                eprintln!(
+                    "{} {} at block {}",
+                    "Took snapshot".green(),
+                    snapshot.id,
+                    snapshot.block
+                );
+                if let Some(ref mut node) = ctx.dev_node {
+                    node.snapshots.push(snapshot);
+                }
+            }
+            DevNodeAction::Revert => {
+                let Some(ref mut node) = ctx.dev_node else {
+                    return Ok(StepOutcome::Continue);
+                };
+                let index = prompt::select_snapshot(&node.snapshots)?;
+                let snapshot = node.snapshots[index].clone();
+                // Reverting consumes the snapshot and invalidates later ones.
+                node.snapshots.truncate(index);
+                fork::revert(&read, snapshot.id).await?;
+                eprintln!(
+                    "{} block {}",
+                    "Reverted to".green(),
+                    read.get_block_number().await?
+                );
+            }
+            DevNodeAction::IncreaseTime => {
+                let duration = prompt::input_time_increase()?;
+                fork::increase_time(&read, duration).await?;
+                eprintln!(
+                    "{} {}, mined block {}",
+                    "Moved the clock forward by".green(),
+                    humantime::format_duration(duration),
+                    read.get_block_number().await?
+                );
+            }
+            DevNodeAction::StopFork => {
+                fork::stop(ctx).await?;
+                eprintln!(
+                    "{} {}",
+                    "Fork stopped, reconnected to".green(),
+                    crate::status::endpoint_label(&ctx.rpc_url)
+                );
+            }
+            DevNodeAction::Back => {}
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
@@ -0,0 +1,137 @@
+/// src/steps/history.rs
+use crate::abi;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::journal::JournalStatus;
+use crate::prompt::{self, HistoryAction};
+use crate::result::ExecutionResult;
+use crate::step::{StepOutcome, WorkflowStep};
+use crate::steps::execute_method;
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Browses the journal of sent transactions and sends a past call again,
+/// with the same or edited arguments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HistoryStep;
+
+#[async_trait]
+impl WorkflowStep for HistoryStep {
+    fn name(&self) -> &str {
+        "History"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let entries = ctx.journal().load()?;
+        if entries.is_empty() {
+            eprintln!("{}", "No transaction sent yet.".yellow());
+            return Ok(StepOutcome::Continue);
+        }
+        let mut entry = entries[prompt::select_history_entry(&entries)?].clone();
+        // The journal keeps the status at send time; a pending one may have landed since.
+        if entry.status == JournalStatus::Submitted && entry.chain_id.to_string() == ctx.chain_id {
+            match ctx
+                .read_context()?
+                .get_transaction_receipt(entry.hash)
+                .await
+            {
+                Ok(Some(receipt)) => {
+                    entry.status = match receipt.status() {
+                        true => JournalStatus::Confirmed,
+                        false => JournalStatus::Reverted,
+                    };
+                    entry.gas_used = Some(receipt.gas_used);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(hash = %entry.hash, error = %e, "could not fetch the receipt")
+                }
+            }
+        }
+        let action = prompt::select_history_action(&entry)?;
+        if action == HistoryAction::Back {
+            return Ok(StepOutcome::Continue);
+        }
+
+        if entry.chain_id.to_string() != ctx.chain_id {
+            return Err(Error::InvalidArguments(format!(
+                "Transaction was sent on chain {}, connected to chain {}",
+                entry.chain_id, ctx.chain_id
+            )));
+        }
+        let abi = ctx.abis.get(&entry.contract).ok_or_else(|| {
+            Error::InvalidContract(format!("ABI not found for contract: {}", entry.contract))
+        })?;
+        let function = abi::find_function(abi, &entry.function)?.clone();
+        let params = match action {
+            HistoryAction::Edit => {
+                prompt::input_method_params(&function, &entry.args, &ctx.address_book)?
+            }
+            _ => entry.args.clone(),
+        };
+        let options = ctx
+            .gas
+            .options()
+            .with_confirmations(ctx.confirmations)
+            .with_value(entry.value);
+
+        ctx.set_contract(&entry.contract, entry.address);
+        let result = execute_method(ctx, &function, &params, &options).await?;
+        prompt::display_execution(ctx, &result);
+        if !matches!(result, ExecutionResult::Cancelled) {
+            prompt::offer_export(&result, ctx.output)?;
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Executes the last executed method again, with the same arguments and
+/// value, on the contract it was executed on.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RepeatLastCallStep;
+
+#[async_trait]
+impl WorkflowStep for RepeatLastCallStep {
+    fn name(&self) -> &str {
+        "Repeat last call"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let last = match ctx.usage().last_call {
+            Some(last) => last,
+            None => {
+                eprintln!("{}", "No method executed yet.".yellow());
+                return Ok(StepOutcome::Continue);
+            }
+        };
+        if last.chain_id.to_string() != ctx.chain_id {
+            return Err(Error::InvalidArguments(format!(
+                "{} was executed on chain {}, connected to chain {}",
+                last.call(),
+                last.chain_id,
+                ctx.chain_id
+            )));
+        }
+        let abi = ctx.abis.get(&last.contract).ok_or_else(|| {
+            Error::InvalidContract(format!("ABI not found for contract: {}", last.contract))
+        })?;
+        let function = abi::find_function(abi, &last.function)?.clone();
+        let options = ctx
+            .gas
+            .options()
+            .with_confirmations(ctx.confirmations)
+            .with_value(last.value);
+
+        eprintln!("{} {}", "Repeating".green(), last.call());
+        ctx.set_contract(&last.contract, last.address);
+        let result = execute_method(ctx, &function, &last.args, &options).await?;
+        match &result {
+            ExecutionResult::Call(call) => {
+                let book = ctx.labelled_address_book(&call.values).await;
+                prompt::display_result(&result, &book, ctx.output);
+            }
+            _ => prompt::display_execution(ctx, &result),
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
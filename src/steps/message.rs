@@ -0,0 +1,65 @@
+/// src/steps/message.rs
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::message::SignedMessage;
+use crate::prompt;
+use crate::step::{Capabilities, StepOutcome, WorkflowStep};
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Signs a message with the active wallet using `personal_sign` (EIP-191),
+/// e.g. to prove ownership of an address.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SignMessageStep;
+
+#[async_trait]
+impl WorkflowStep for SignMessageStep {
+    fn name(&self) -> &str {
+        "Sign message"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.signer
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let signer = ctx
+            .active_signer()
+            .ok_or_else(|| Error::InvalidPrivateKey("No private key configured".to_string()))?;
+        let message = prompt::input_message()?;
+        let signed = SignedMessage::sign(&signer, message).await?;
+        prompt::display_result(&signed, &ctx.address_book, ctx.output);
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Recovers the address that signed a `personal_sign` message, optionally
+/// checking it against the expected signer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VerifySignatureStep;
+
+#[async_trait]
+impl WorkflowStep for VerifySignatureStep {
+    fn name(&self) -> &str {
+        "Verify signature"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let message = prompt::input_message()?;
+        let signature = prompt::input_signature()?;
+        let expected = prompt::input_expected_signer(&ctx.address_book)?;
+        let recovered = SignedMessage::recover(message, &signature)?;
+        prompt::display_result(&recovered, &ctx.address_book, ctx.output);
+        match expected {
+            Some(expected) if expected != recovered.signer => {
+                return Err(Error::InvalidArguments(format!(
+                    "Signature was made by {}, not {}",
+                    recovered.signer, expected
+                )))
+            }
+            Some(expected) => eprintln!("{} {}", "Signed by".green(), expected),
+            None => {}
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
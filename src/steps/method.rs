@@ -0,0 +1,403 @@
+/// src/steps/method.rs
+use crate::abi::{self, MethodType};
+use crate::approval;
+use crate::codec;
+use crate::config::OperatingMode;
+use crate::context::{GlobalContext, WriteContext};
+use crate::error::{Error, Result};
+use crate::events::Event;
+use crate::nonce::NonceStatus;
+use crate::offline::UnsignedTransaction;
+use crate::prompt::{self, Confirmation};
+use crate::proxy::Facet;
+use crate::relay::{self, PrivateRelay};
+use crate::result::{CallResult, ExecutionResult};
+use crate::safe;
+use crate::step::{StepOutcome, WorkflowStep};
+use crate::trace::TraceDecoder;
+use crate::transaction::TransactionOptions;
+use crate::validation;
+use alloy::dyn_abi::DynSolValue;
+use alloy::eips::BlockId;
+use alloy::json_abi::{Function, StateMutability};
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use colored::Colorize;
+use std::collections::HashMap;
+
+/// Picks a method of the active contract, prompts for its arguments and executes it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SelectMethodStep;
+
+#[async_trait]
+impl WorkflowStep for SelectMethodStep {
+    fn name(&self) -> &str {
+        "Select method"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let abi = ctx.get_abi()?;
+        let method_type = match ctx.mode {
+            OperatingMode::ReadOnly => MethodType::Read,
+            _ => prompt::select_method_type()?,
+        };
+        let methods = abi::get_methods_by_type(abi, method_type);
+        if methods.is_empty() {
+            return Err(Error::InvalidFunction(format!(
+                "{} has no {} methods",
+                ctx.contract_name, method_type
+            )));
+        }
+        let quick = ctx.usage().quick_access(&ctx.contract_name);
+        let method_name = match ctx.facets.get(&ctx.contract_name) {
+            Some(facets) => {
+                prompt::select_grouped_method(&group_by_facet(&methods, facets), &methods, &quick)?
+            }
+            None => prompt::select_method(&methods, &quick)?,
+        };
+        let function = methods
+            .get(&method_name)
+            .ok_or_else(|| Error::InvalidFunction(method_name.clone()))?;
+
+        let params = prompt::input_method_params(function, &[], &ctx.address_book)?;
+        if is_read_only(function) {
+            let blocks = prompt::input_call_blocks()?;
+            if blocks != ["latest"] {
+                let args = codec::coerce_args(function, &params)?;
+                let read = ctx.read_context()?;
+                if let [before, after] = blocks.as_slice() {
+                    let diff = read
+                        .compare(ctx.contract_address, function, &args, [before, after])
+                        .await
+                        .map_err(|e| e.with_abi(abi))?;
+                    let book = ctx
+                        .labelled_address_book(diff.before.values.iter().chain(&diff.after.values))
+                        .await;
+                    prompt::display_result(&diff, &book, ctx.output);
+                    prompt::offer_export(&diff, ctx.output)?;
+                } else {
+                    let block = validation::parse_block_id(&blocks[0])?;
+                    let result = checked_call(ctx, ctx.contract_address, function, &args, block)
+                        .await
+                        .map_err(|e| e.with_abi(abi))?;
+                    let book = ctx.labelled_address_book(&result.values).await;
+                    let result = ExecutionResult::Call(result);
+                    prompt::display_result(&result, &book, ctx.output);
+                    prompt::offer_export(&result, ctx.output)?;
+                }
+                return Ok(StepOutcome::Continue);
+            }
+        }
+        let options = if is_read_only(function) {
+            TransactionOptions::default()
+        } else {
+            let mut defaults = ctx.gas.options().with_confirmations(ctx.confirmations);
+            if function.state_mutability == StateMutability::Payable {
+                defaults = defaults.with_value(prompt::input_value()?);
+            }
+            if ctx.safe.is_some() {
+                defaults
+            } else {
+                let args = codec::coerce_args(function, &params)?;
+                let write = ctx.write_context()?;
+                let estimate = write
+                    .preview(ctx.contract_address, function, &args, &defaults)
+                    .await
+                    .map_err(|e| e.with_abi(abi))?;
+                let nonces = NonceStatus::fetch(write.reader(), write.address()).await?;
+                prompt::input_transaction_options(&estimate, &nonces, defaults)?
+            }
+        };
+        let result = execute_method(ctx, function, &params, &options).await?;
+        match &result {
+            ExecutionResult::Call(call) => {
+                let book = ctx.labelled_address_book(&call.values).await;
+                prompt::display_result(&result, &book, ctx.output);
+            }
+            _ => prompt::display_execution(ctx, &result),
+        }
+        if !matches!(result, ExecutionResult::Cancelled) {
+            prompt::offer_export(&result, ctx.output)?;
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Groups method signatures by the diamond facet serving them, sorted,
+/// with methods no facet serves (e.g. the loupe itself) under `diamond`.
+fn group_by_facet(
+    methods: &HashMap<String, Function>,
+    facets: &[Facet],
+) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = facets
+        .iter()
+        .map(|facet| (facet.address.to_checksum(None), Vec::new()))
+        .collect();
+    let mut unrouted = Vec::new();
+    let mut names: Vec<&String> = methods.keys().collect();
+    names.sort();
+    for name in names {
+        match facets.iter().position(|facet| facet.routes(&methods[name])) {
+            Some(i) => groups[i].1.push(name.clone()),
+            None => unrouted.push(name.clone()),
+        }
+    }
+    groups.push(("diamond".to_string(), unrouted));
+    groups.retain(|(_, names)| !names.is_empty());
+    groups
+}
+
+/// Executes a contract method with the given parameters.
+///
+/// Read-only methods are executed with `eth_call`; other methods are first
+/// simulated from the signer's address, then sent as a signed transaction after
+/// the user confirms, and awaited until their receipt is available or the
+/// receipt timeout elapses. When the simulation reverts, the user may trace the
+/// call to see which internal call failed; the revert only warns unless
+/// `require_simulation` is set, in which case it aborts the transaction.
+/// Instead of sending it, the user may export the transaction unsigned, or
+/// send it through the configured private relay.
+/// When a Safe is configured, the transaction is proposed to the Safe instead
+/// of being sent. Sent transactions are recorded in the journal, and every
+/// execution but a cancelled one counts towards the method's usage.
+///
+/// # Arguments
+///
+/// * `ctx` - The global context
+/// * `function` - The contract function to execute
+/// * `params` - String representations of the function parameters
+/// * `options` - Overrides applied when the method is sent as a transaction
+///
+/// # Returns
+///
+/// * `Result<ExecutionResult>` - The decoded call outputs or the sent transaction, or an error
+pub async fn execute_method(
+    ctx: &GlobalContext,
+    function: &Function,
+    params: &[String],
+    options: &TransactionOptions,
+) -> Result<ExecutionResult> {
+    let result = execute(ctx, function, params, options).await?;
+    ctx.record(
+        &ctx.contract_name,
+        ctx.contract_address,
+        function,
+        params,
+        options,
+        &result,
+    );
+    ctx.record_usage(function, params, options, &result);
+    Ok(result)
+}
+
+async fn execute(
+    ctx: &GlobalContext,
+    function: &Function,
+    params: &[String],
+    options: &TransactionOptions,
+) -> Result<ExecutionResult> {
+    let args = codec::coerce_args(function, params)?;
+    let signature = function.signature();
+    let decode_revert = |e: Error| match ctx.get_abi() {
+        Ok(abi) => e.with_abi(abi),
+        Err(_) => e,
+    };
+
+    if !is_read_only(function) {
+        if let Some(warning) = approval::check_call(ctx, function, &args).await {
+            eprintln!("{}", warning);
+        }
+    }
+
+    if is_read_only(function) {
+        ctx.events.emit(Event::CallStarted {
+            contract: ctx.contract_address,
+            function: signature.clone(),
+        });
+        let result = checked_call(
+            ctx,
+            ctx.contract_address,
+            function,
+            &args,
+            BlockId::latest(),
+        )
+        .await
+        .map_err(decode_revert)?;
+        ctx.events.emit(Event::CallCompleted {
+            contract: ctx.contract_address,
+            function: signature,
+        });
+        Ok(ExecutionResult::Call(result))
+    } else if ctx.safe.is_some() && ctx.mode != OperatingMode::DryRun {
+        safe::propose(ctx, ctx.contract_address, function, &args, options, true)
+            .await
+            .map_err(decode_revert)
+    } else {
+        let write = ctx.write_context()?;
+        let simulation = write
+            .simulate(ctx.contract_address, function, &args, options)
+            .await
+            .map_err(decode_revert);
+        match simulation {
+            Ok(_) => prompt::display_simulation(&simulation),
+            Err(ref e @ Error::Revert { .. }) => {
+                ctx.events.emit(Event::SimulationFailed {
+                    function: signature,
+                    reason: e.to_string(),
+                });
+                prompt::display_simulation(&simulation);
+                if prompt::confirm_trace()? {
+                    match write
+                        .trace(ctx.contract_address, function, &args, options)
+                        .await
+                    {
+                        Ok(frame) => eprintln!("{}", TraceDecoder::new(ctx).render(&frame)),
+                        Err(e) => tracing::warn!(error = %e, "could not trace the call"),
+                    }
+                }
+                if ctx.require_simulation {
+                    simulation?;
+                }
+            }
+            Err(e) => return Err(e),
+        }
+        let options = &match options.generates_access_list() {
+            true => with_access_list(&write, ctx.contract_address, function, &args, options).await,
+            false => options.clone(),
+        };
+        let preview = write
+            .preview(ctx.contract_address, function, &args, options)
+            .await
+            .map_err(decode_revert)?;
+        if ctx.mode == OperatingMode::DryRun {
+            let tx = UnsignedTransaction::from_preview(&preview);
+            return Ok(ExecutionResult::Simulated(Box::new(tx)));
+        }
+        match prompt::confirm_transaction(&preview)? {
+            Confirmation::Send => {}
+            Confirmation::Export => {
+                let tx = UnsignedTransaction::from_preview(&preview);
+                return Ok(ExecutionResult::Exported(Box::new(tx)));
+            }
+            Confirmation::Cancel => return Ok(ExecutionResult::Cancelled),
+        }
+        let relay = match &ctx.private_relay {
+            Some(relay) if prompt::confirm_private_relay(relay)? => Some(PrivateRelay::new(relay)?),
+            _ => None,
+        };
+        if let Some(prompt) = write.signer().confirmation_prompt() {
+            eprintln!("{}", prompt.yellow());
+        }
+
+        if let Some(relay) = relay {
+            let submission = relay::send(
+                &write,
+                &relay,
+                ctx.contract_address,
+                function,
+                &args,
+                options,
+            )
+            .await
+            .map_err(decode_revert)?;
+            eprintln!(
+                "{} {}, waiting for inclusion...",
+                "Sent privately through".cyan(),
+                relay.config().url()
+            );
+            return relay::wait(&write, &relay, &submission).await;
+        }
+
+        let pending = write
+            .send(ctx.contract_address, function, &args, options)
+            .await
+            .map_err(decode_revert)?;
+        let hash = *pending.tx_hash();
+        match write.confirm(pending).await {
+            Ok(receipt) => Ok(ExecutionResult::Confirmed(Box::new(receipt))),
+            // The transaction is out; a slow block must not be reported as a failure.
+            Err(e @ Error::Timeout { .. }) => {
+                tracing::warn!(%hash, error = %e, "receipt not available yet");
+                Ok(ExecutionResult::Submitted { hash })
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Generates an access list for a transaction and shows it with the gas it
+/// saves, attaching it when it saves gas. A node failing to generate one
+/// only warns: the transaction is sent without.
+///
+/// # Arguments
+///
+/// * `write` - The write context the transaction is sent through
+/// * `to` - Address of the contract
+/// * `function` - The function to call
+/// * `args` - One value per function input
+/// * `options` - The options the transaction is sent with
+///
+/// # Returns
+///
+/// * `TransactionOptions` - The options, with the list attached if it saves gas
+pub async fn with_access_list(
+    write: &WriteContext,
+    to: Address,
+    function: &Function,
+    args: &[DynSolValue],
+    options: &TransactionOptions,
+) -> TransactionOptions {
+    match write.create_access_list(to, function, args, options).await {
+        Ok(estimate) => {
+            eprintln!("{}", estimate);
+            estimate.attach(options.clone())
+        }
+        Err(e) => {
+            eprintln!(
+                "{} no access list, the node could not generate one: {}",
+                "Warning:".yellow(),
+                e
+            );
+            options.clone()
+        }
+    }
+}
+
+/// Calls a read-only function, cross-checking the result on a second RPC
+/// endpoint in paranoid mode and printing how the endpoints compare.
+///
+/// # Arguments
+///
+/// * `ctx` - The session
+/// * `to` - Address of the contract
+/// * `function` - The function to call
+/// * `args` - One value per function input
+/// * `block` - Block number, hash or tag to read the state at
+///
+/// # Returns
+///
+/// * `Result<CallResult>` - The decoded and raw outputs on the session's endpoint, or an error
+pub async fn checked_call(
+    ctx: &GlobalContext,
+    to: Address,
+    function: &Function,
+    args: &[DynSolValue],
+    block: BlockId,
+) -> Result<CallResult> {
+    match ctx.cross_checker()? {
+        Some(checker) => {
+            let check = checker.call_at(to, function, args, block).await?;
+            eprintln!("{}", check);
+            Ok(check.into_result())
+        }
+        None => ctx.read_context()?.call_at(to, function, args, block).await,
+    }
+}
+
+/// Returns whether a function can be executed with `eth_call` alone.
+fn is_read_only(function: &Function) -> bool {
+    matches!(
+        function.state_mutability,
+        StateMutability::View | StateMutability::Pure
+    )
+}
@@ -0,0 +1,183 @@
+/// src/steps/nft.rs
+use crate::context::GlobalContext;
+use crate::ens;
+use crate::error::{Error, Result};
+use crate::nft::{self, NftMetadata, NftStandard};
+use crate::output::OutputFormat;
+use crate::prompt::{self, NftAction};
+use crate::step::{StepOutcome, WorkflowStep};
+use crate::steps::execute_method;
+use alloy::dyn_abi::DynSolValue;
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use colored::Colorize;
+use std::str::FromStr;
+
+/// Reads owners, balances and metadata of an ERC-721 or ERC-1155 collection
+/// at the current contract, and guides `safeTransferFrom` transfers.
+///
+/// The standard is recognized from the ABI, then through ERC-165, and can
+/// otherwise be asserted; its standard ABI is then used for the calls.
+/// Metadata URIs are fetched over HTTP, through a gateway for IPFS and
+/// Arweave, or decoded from `data:` URIs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NftStep;
+
+#[async_trait]
+impl WorkflowStep for NftStep {
+    fn name(&self) -> &str {
+        "NFT (ERC-721/1155)"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let read = ctx.read_context()?;
+        let detected = match nft::detect(ctx.get_abi()?) {
+            Some(standard) => Some(standard),
+            None => nft::detect_on_chain(&read, ctx.contract_address).await,
+        };
+        let standard = match detected {
+            Some(standard) => standard,
+            None => match prompt::select_nft_standard(&ctx.contract_name)? {
+                Some(standard) => standard,
+                None => return Ok(StepOutcome::Continue),
+            },
+        };
+
+        loop {
+            match prompt::select_nft_action(standard)? {
+                NftAction::OwnerOf => {
+                    let id = prompt::input_uint("Token ID:")?;
+                    let owner =
+                        call(ctx, standard, "ownerOf", vec![DynSolValue::Uint(id, 256)]).await?;
+                    let owner = owner
+                        .as_address()
+                        .ok_or_else(|| Error::Other("ownerOf returned no address".to_string()))?;
+                    println!("{}", ctx.address_book.annotate(&owner.to_string()));
+                }
+                NftAction::Balance => {
+                    let account = input_account(ctx, "Account:").await?;
+                    let mut args = vec![DynSolValue::Address(account)];
+                    if standard == NftStandard::Erc1155 {
+                        args.push(DynSolValue::Uint(prompt::input_uint("Token ID:")?, 256));
+                    }
+                    let balance = call(ctx, standard, "balanceOf", args).await?;
+                    let (balance, _) = balance
+                        .as_uint()
+                        .ok_or_else(|| Error::Other("balanceOf returned no amount".to_string()))?;
+                    println!("{}", balance);
+                }
+                NftAction::Metadata => {
+                    let id = prompt::input_uint("Token ID:")?;
+                    let uri = call(
+                        ctx,
+                        standard,
+                        standard.uri_function(),
+                        vec![DynSolValue::Uint(id, 256)],
+                    )
+                    .await?;
+                    let DynSolValue::String(uri) = uri else {
+                        return Err(Error::Other(format!(
+                            "{} returned no URI",
+                            standard.uri_function()
+                        )));
+                    };
+                    let url = nft::resolve_uri(&uri, id);
+                    if !url.starts_with("data:") {
+                        eprintln!("{} {}", "Metadata:".green(), url);
+                    }
+                    match nft::fetch_metadata(&url).await {
+                        Ok(json) if ctx.output == OutputFormat::Json => println!("{}", json),
+                        Ok(json) => println!("{}", NftMetadata::from_json(&json)),
+                        Err(e) => eprintln!("{} {}", "Metadata unavailable:".yellow(), e),
+                    }
+                }
+                NftAction::Transfer => transfer(ctx, standard).await?,
+                NftAction::Back => return Ok(StepOutcome::Continue),
+            }
+        }
+    }
+}
+
+/// Prompts for an account as an address, alias or ENS name.
+async fn input_account(ctx: &GlobalContext, message: &str) -> Result<Address> {
+    let account = prompt::input_account(message, &ctx.address_book)?;
+    match ens::is_name(&account) {
+        true => ens::resolve(&ctx.read_context()?, &account).await,
+        false => Address::from_str(&account).map_err(|_| Error::InvalidAddress(account)),
+    }
+}
+
+/// Calls a view function of the standard ABI and returns its first output.
+async fn call(
+    ctx: &GlobalContext,
+    standard: NftStandard,
+    name: &str,
+    args: Vec<DynSolValue>,
+) -> Result<DynSolValue> {
+    let result = ctx
+        .read_context()?
+        .call(ctx.contract_address, &standard.function(name)?, &args)
+        .await?;
+    result
+        .values
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Other(format!("{} returned nothing", name)))
+}
+
+/// Guides a `safeTransferFrom` of a token held by the signer.
+async fn transfer(ctx: &GlobalContext, standard: NftStandard) -> Result<()> {
+    let from = ctx
+        .active_signer()
+        .ok_or_else(|| Error::InvalidPrivateKey("No private key configured".to_string()))?
+        .address();
+    let to = input_account(ctx, "Recipient:").await?;
+    let id = prompt::input_uint("Token ID:")?;
+    let params = match standard {
+        NftStandard::Erc721 => {
+            let owner = call(ctx, standard, "ownerOf", vec![DynSolValue::Uint(id, 256)]).await?;
+            if owner != DynSolValue::Address(from) {
+                return Err(Error::InvalidArguments(format!(
+                    "Token {} is not owned by {}",
+                    id, from
+                )));
+            }
+            eprintln!("{} token {} to {}", "Transferring".cyan(), id, to);
+            vec![from.to_string(), to.to_string(), id.to_string()]
+        }
+        NftStandard::Erc1155 => {
+            let args = vec![DynSolValue::Address(from), DynSolValue::Uint(id, 256)];
+            let balance = call(ctx, standard, "balanceOf", args)
+                .await?
+                .as_uint()
+                .map(|(balance, _)| balance)
+                .unwrap_or(U256::ZERO);
+            let value = prompt::input_uint(&format!("Quantity (holding {}):", balance))?;
+            if value > balance {
+                return Err(Error::InvalidArguments(format!(
+                    "{} holds {} of token {}",
+                    from, balance, id
+                )));
+            }
+            eprintln!(
+                "{} {} of token {} to {}",
+                "Transferring".cyan(),
+                value,
+                id,
+                to
+            );
+            vec![
+                from.to_string(),
+                to.to_string(),
+                id.to_string(),
+                value.to_string(),
+                "0x".to_string(),
+            ]
+        }
+    };
+    let function = standard.function("safeTransferFrom")?;
+    let options = ctx.gas.options().with_confirmations(ctx.confirmations);
+    let result = execute_method(ctx, &function, &params, &options).await?;
+    prompt::display_execution(ctx, &result);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+/// src/steps/nonce.rs
+use crate::context::GlobalContext;
+use crate::error::Result;
+use crate::nonce::NonceStatus;
+use crate::prompt;
+use crate::result::ExecutionResult;
+use crate::step::{Capabilities, StepOutcome, WorkflowStep};
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Shows the active account's latest and pending nonces and clears stuck
+/// transactions and nonce gaps with no-op replacements.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RepairNoncesStep;
+
+#[async_trait]
+impl WorkflowStep for RepairNoncesStep {
+    fn name(&self) -> &str {
+        "Repair nonces"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.signer
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let write = ctx.write_context()?;
+        let read = write.reader();
+        let status = NonceStatus::fetch(read, write.address()).await?;
+        eprintln!("{}", status);
+        if status.is_clean() {
+            eprintln!("{}", "No stuck transactions or nonce gaps.".green());
+            return Ok(StepOutcome::Continue);
+        }
+        if status.pool.is_empty() {
+            eprintln!(
+                "{}",
+                "The node does not expose its pool: gaps cannot be detected and stuck fees are unknown."
+                    .yellow()
+            );
+        }
+
+        let (max_fee, priority_fee) = read.estimate_fees().await?;
+        let replacements = status.replacements(max_fee, priority_fee);
+        if !prompt::confirm_replacements(&replacements)? {
+            return Ok(StepOutcome::Continue);
+        }
+        let mut last = None;
+        for replacement in &replacements {
+            let pending = write.send_replacement(replacement).await?;
+            eprintln!(
+                "{} nonce {}: {}",
+                "Replaced".cyan(),
+                replacement.nonce,
+                pending.tx_hash()
+            );
+            last = Some(pending);
+        }
+        if let Some(pending) = last {
+            let receipt = write.confirm(pending).await?;
+            let result = ExecutionResult::Confirmed(Box::new(receipt));
+            prompt::display_result(&result, &ctx.address_book, ctx.output);
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
@@ -0,0 +1,60 @@
+/// src/steps/offline.rs
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::events::Event;
+use crate::prompt;
+use crate::result::ExecutionResult;
+use crate::step::{Capabilities, StepOutcome, WorkflowStep};
+use async_trait::async_trait;
+
+/// Broadcasts a transaction signed on another machine with `eth_sendRawTransaction`,
+/// e.g. one exported unsigned from the method step and signed offline.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BroadcastTransactionStep;
+
+#[async_trait]
+impl WorkflowStep for BroadcastTransactionStep {
+    fn name(&self) -> &str {
+        "Broadcast signed transaction"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.broadcast
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let tx = prompt::input_raw_transaction()?;
+        if let Some(chain_id) = tx.chain_id() {
+            if chain_id.to_string() != ctx.chain_id {
+                return Err(Error::InvalidArguments(format!(
+                    "Transaction is signed for chain {}, connected to chain {}",
+                    chain_id, ctx.chain_id
+                )));
+            }
+        }
+        if !prompt::confirm_broadcast(&tx)? {
+            return Ok(StepOutcome::Continue);
+        }
+
+        let pending = ctx.send_raw_transaction(&tx).await?;
+        let hash = *pending.tx_hash();
+        let result = match ctx.read_context()?.wait_for_receipt(pending).await {
+            Ok(receipt) => {
+                ctx.events.emit(Event::TxConfirmed {
+                    hash,
+                    block_number: receipt.block_number,
+                    gas_used: receipt.gas_used,
+                    success: receipt.status(),
+                });
+                ExecutionResult::Confirmed(Box::new(receipt))
+            }
+            Err(e @ Error::Timeout { .. }) => {
+                tracing::warn!(%hash, error = %e, "receipt not available yet");
+                ExecutionResult::Submitted { hash }
+            }
+            Err(e) => return Err(e),
+        };
+        prompt::display_execution(ctx, &result);
+        Ok(StepOutcome::Continue)
+    }
+}
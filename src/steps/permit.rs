@@ -0,0 +1,66 @@
+/// src/steps/permit.rs
+use crate::approval::{Approval, ApprovalWarning};
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::permit::Permit;
+use crate::prompt;
+use crate::step::{Capabilities, StepOutcome, WorkflowStep};
+use crate::steps::execute_method;
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use std::str::FromStr;
+
+/// Signs an ERC-2612 permit for the current token, reading its nonce and
+/// domain separator, and optionally submits it with `permit`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SignPermitStep;
+
+#[async_trait]
+impl WorkflowStep for SignPermitStep {
+    fn name(&self) -> &str {
+        "Sign ERC-2612 permit"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.signer
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let function = Permit::find(ctx.get_abi()?).cloned().ok_or_else(|| {
+            Error::InvalidFunction(format!("{} has no ERC-2612 permit", ctx.contract_name))
+        })?;
+        let signer = ctx
+            .active_signer()
+            .ok_or_else(|| Error::InvalidPrivateKey("No private key configured".to_string()))?;
+        let (nonce, domain_separator) =
+            Permit::read_domain(&ctx.read_context()?, ctx.contract_address, signer.address())
+                .await?;
+
+        let spender = prompt::input_address("Spender:", &ctx.address_book)?;
+        let permit = Permit {
+            owner: signer.address(),
+            spender: Address::from_str(&spender)
+                .map_err(|e| Error::InvalidAddress(e.to_string()))?,
+            value: prompt::input_permit_value()?,
+            nonce,
+            deadline: prompt::input_permit_deadline()?,
+        };
+        let approval = Approval {
+            function: function.signature(),
+            spender: permit.spender,
+            amount: Some(permit.value),
+        };
+        if let Some(warning) = ApprovalWarning::check(ctx, approval).await {
+            eprintln!("{}", warning);
+        }
+        let signed = permit.sign(&signer, domain_separator).await?;
+        prompt::display_result(&signed, &ctx.address_book, ctx.output);
+
+        if prompt::confirm_submit_permit()? {
+            let options = ctx.gas.options().with_confirmations(ctx.confirmations);
+            let result = execute_method(ctx, &function, &signed.args(), &options).await?;
+            prompt::display_execution(ctx, &result);
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
@@ -0,0 +1,34 @@
+/// src/steps/profile.rs
+use crate::context::GlobalContext;
+use crate::error::Result;
+use crate::prompt;
+use crate::step::{Capabilities, StepOutcome, WorkflowStep};
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Switches to another configuration profile, reloading the session with its
+/// chain, signer and contracts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwitchProfileStep;
+
+#[async_trait]
+impl WorkflowStep for SwitchProfileStep {
+    fn name(&self) -> &str {
+        "Switch profile"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.profiles
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let index = prompt::select_profile(&ctx.profiles, ctx.profile.as_deref())?;
+        let profile = &ctx.profiles[index];
+        if ctx.profile.as_ref() == Some(profile) {
+            return Ok(StepOutcome::Continue);
+        }
+        eprintln!("{} {}", "Switching to profile".green(), profile);
+        ctx.profile = Some(profile.clone());
+        Ok(StepOutcome::Reload)
+    }
+}
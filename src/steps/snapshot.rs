@@ -0,0 +1,75 @@
+/// src/steps/snapshot.rs
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::multicall::{self, Call};
+use crate::output::{Output, OutputFormat};
+use crate::step::{StepOutcome, WorkflowStep};
+use alloy::json_abi::{Function, StateMutability};
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Reads every parameterless view function of the active contract in one
+/// Multicall3 round trip and prints the results as a table.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SnapshotStep;
+
+#[async_trait]
+impl WorkflowStep for SnapshotStep {
+    fn name(&self) -> &str {
+        "Contract snapshot"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let mut functions: Vec<&Function> = ctx
+            .get_abi()?
+            .functions()
+            .filter(|function| {
+                function.inputs.is_empty()
+                    && matches!(
+                        function.state_mutability,
+                        StateMutability::View | StateMutability::Pure
+                    )
+            })
+            .collect();
+        if functions.is_empty() {
+            return Err(Error::InvalidFunction(format!(
+                "{} has no parameterless view functions",
+                ctx.contract_name
+            )));
+        }
+        functions.sort_by_key(|function| function.signature());
+
+        let calls: Vec<Call> = functions
+            .iter()
+            .map(|function| Call {
+                target: ctx.contract_address,
+                function,
+                args: Vec::new(),
+            })
+            .collect();
+        let results = multicall::aggregate(&ctx.read_context()?, &calls).await?;
+        let abi = ctx.get_abi()?;
+        if ctx.output == OutputFormat::Json {
+            for result in results {
+                match result {
+                    Ok(result) => println!("{}", Output::from(&result)),
+                    Err(e) => println!("{}", Output::from(&e.with_abi(abi))),
+                }
+            }
+            return Ok(StepOutcome::Continue);
+        }
+        let rows: Vec<(String, String)> = functions
+            .iter()
+            .zip(results)
+            .map(|(function, result)| {
+                let value = match result {
+                    Ok(result) => result.to_string(),
+                    Err(e) => e.with_abi(abi).to_string().red().to_string(),
+                };
+                (function.name.bold().to_string(), value)
+            })
+            .collect();
+        println!("{}", text::table(&rows));
+        Ok(StepOutcome::Continue)
+    }
+}
@@ -0,0 +1,133 @@
+/// src/steps/transaction.rs
+use crate::abi;
+use crate::codec;
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::output::{Output, OutputFormat};
+use crate::prompt;
+use crate::result::{CallResult, ExecutionResult};
+use crate::step::{StepOutcome, WorkflowStep};
+use alloy::consensus::Transaction;
+use alloy::network::TransactionResponse;
+use alloy::primitives::utils::{format_ether, format_units};
+use alloy::primitives::{hex, U256};
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Fetches a mined or pending transaction by hash and shows what it did:
+/// sender, value, gas and status, the decoded call and the decoded logs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecodeTransactionStep;
+
+#[async_trait]
+impl WorkflowStep for DecodeTransactionStep {
+    fn name(&self) -> &str {
+        "Decode transaction"
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let hash = prompt::input_transaction_hash()?;
+        let read = ctx.read_context()?;
+        let (tx, receipt) = futures::try_join!(
+            read.get_transaction(hash),
+            read.get_transaction_receipt(hash)
+        )?;
+        let tx =
+            tx.ok_or_else(|| Error::InvalidArguments(format!("Transaction {} not found", hash)))?;
+
+        let call = match tx.to() {
+            Some(_) if tx.input().len() >= 4 => abi::find_by_selector(&ctx.abis, tx.input())
+                .into_iter()
+                .find_map(|(function, _)| {
+                    let values = codec::decode_input(function, tx.input()).ok()?;
+                    Some(CallResult::from_inputs(
+                        function,
+                        values,
+                        tx.input().clone(),
+                    ))
+                }),
+            _ => None,
+        };
+
+        if ctx.output == OutputFormat::Json {
+            let result = match &receipt {
+                Some(receipt) => ExecutionResult::Confirmed(Box::new(receipt.clone())),
+                None => ExecutionResult::Submitted { hash },
+            };
+            println!("{}", Output::from(&result));
+            if let Some(call) = &call {
+                println!("{}", Output::from(call));
+            }
+        } else {
+            let gwei = |wei: u128| {
+                format_units(U256::from(wei), "gwei")
+                    .map(|gwei| format!("{} gwei", gwei))
+                    .unwrap_or_else(|_| format!("{} wei", wei))
+            };
+            let mut rows = vec![
+                ("Hash", hash.to_string()),
+                (
+                    "Block",
+                    tx.block_number()
+                        .map(|n| n.to_string())
+                        .unwrap_or_else(|| "pending".to_string()),
+                ),
+                ("From", tx.from().to_string()),
+                (
+                    "To",
+                    match (tx.to(), receipt.as_ref().and_then(|r| r.contract_address)) {
+                        (Some(to), _) => to.to_string(),
+                        (None, Some(created)) => format!("{} (created)", created),
+                        (None, None) => "(contract creation)".to_string(),
+                    },
+                ),
+                ("Value", format!("{} ETH", format_ether(tx.value()))),
+                ("Nonce", tx.nonce().to_string()),
+                ("Gas limit", tx.gas_limit().to_string()),
+            ];
+            match &receipt {
+                Some(receipt) => {
+                    let fee =
+                        U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price);
+                    rows.push(("Gas used", receipt.gas_used.to_string()));
+                    rows.push(("Gas price", gwei(receipt.effective_gas_price)));
+                    rows.push(("Fee", format!("{} ETH", format_ether(fee))));
+                    rows.push((
+                        "Status",
+                        match receipt.status() {
+                            true => "success".green().to_string(),
+                            false => "reverted".red().to_string(),
+                        },
+                    ));
+                }
+                None => rows.push(("Status", "pending".yellow().to_string())),
+            }
+            println!("{}", ctx.address_book.annotate(&text::table(&rows)));
+
+            match &call {
+                Some(call) => {
+                    println!("\n{} {}", "Call:".green(), call.function);
+                    println!("{}", ctx.address_book.annotate(&call.to_string()));
+                }
+                None if tx.input().is_empty() => {}
+                None => eprintln!(
+                    "\n{} {}",
+                    "No loaded ABI matches the input".yellow(),
+                    tx.input()
+                        .get(..4)
+                        .map(hex::encode_prefixed)
+                        .unwrap_or_default()
+                ),
+            }
+        }
+        if let Some(receipt) = &receipt {
+            prompt::display_receipt_logs(
+                receipt.inner.logs(),
+                &ctx.abis,
+                &ctx.address_book,
+                ctx.output,
+            );
+        }
+        Ok(StepOutcome::Continue)
+    }
+}
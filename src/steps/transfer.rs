@@ -0,0 +1,75 @@
+/// src/steps/transfer.rs
+use super::confirm_and_send;
+use crate::context::GlobalContext;
+use crate::ens;
+use crate::error::{Error, Result};
+use crate::prompt;
+use crate::step::{Capabilities, StepOutcome, WorkflowStep};
+use alloy::primitives::utils::format_ether;
+use alloy::primitives::{Address, Bytes};
+use async_trait::async_trait;
+use colored::Colorize;
+use std::str::FromStr;
+
+/// Label of native transfers in previews and pre-send hooks, which call no function.
+const TRANSFER_LABEL: &str = "Native transfer";
+
+/// Sends native currency to an address, alias or ENS name, with no calldata.
+///
+/// The fees are previewed and the balance checked against the most the
+/// transfer can cost before asking for confirmation. Transfers are sent
+/// directly: a configured Safe or private relay is not used.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransferNativeStep;
+
+#[async_trait]
+impl WorkflowStep for TransferNativeStep {
+    fn name(&self) -> &str {
+        "Transfer native currency"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.signer
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let write = ctx.write_context()?;
+        let read = write.reader();
+        let recipient = prompt::input_account("Recipient:", &ctx.address_book)?;
+        let to = match ens::is_name(&recipient) {
+            true => {
+                let address = ens::resolve(read, &recipient).await?;
+                eprintln!("{} {} {}", recipient, "resolves to".green(), address);
+                address
+            }
+            false => Address::from_str(&recipient)
+                .map_err(|_| Error::InvalidAddress(recipient.clone()))?,
+        };
+        let value = prompt::input_value()?;
+        if value.is_zero() {
+            return Err(Error::InvalidArguments(
+                "Enter an amount to transfer".to_string(),
+            ));
+        }
+
+        let options = ctx
+            .gas
+            .options()
+            .with_confirmations(ctx.confirmations)
+            .with_value(value);
+        let preview = write
+            .preview_calldata(to, TRANSFER_LABEL, Bytes::new(), &options)
+            .await?;
+        let balance = read.get_balance(write.address()).await?;
+        if balance < preview.max_cost() {
+            return Err(Error::InvalidArguments(format!(
+                "Balance of {} ETH does not cover the {} ETH the transfer can cost",
+                format_ether(balance),
+                format_ether(preview.max_cost())
+            )));
+        }
+        let result = confirm_and_send(ctx, &write, TRANSFER_LABEL, &preview, &options).await?;
+        prompt::display_execution(ctx, &result);
+        Ok(StepOutcome::Continue)
+    }
+}
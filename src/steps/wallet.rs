@@ -0,0 +1,137 @@
+/// src/steps/wallet.rs
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::prompt;
+use crate::step::{Capabilities, StepOutcome, WorkflowStep};
+use crate::wallet::{self, WalletSummary, DEFAULT_WALLET, KEYSTORE_DIR};
+use async_trait::async_trait;
+use colored::Colorize;
+
+/// Encrypts the private key of the signer into a keystore so it no longer has
+/// to be stored in plain text in `config.toml`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EncryptKeyStep;
+
+#[async_trait]
+impl WorkflowStep for EncryptKeyStep {
+    fn name(&self) -> &str {
+        "Encrypt private key into keystore"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.signer
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let private_key = match ctx.signer.as_ref() {
+            Some(signer) => match signer.local_key() {
+                Some(key) => wallet::private_key_hex(key),
+                None => {
+                    return Err(Error::InvalidConfig(format!(
+                        "The key of a {} signer cannot be exported",
+                        signer.kind()
+                    )))
+                }
+            },
+            None => {
+                return Err(Error::InvalidConfig(
+                    "No private key configured".to_string(),
+                ))
+            }
+        };
+        let name = prompt::input_keystore_name()?;
+        let passphrase = prompt::input_new_passphrase()?;
+        let dir = ctx.data_dir.join(KEYSTORE_DIR);
+        let path = wallet::encrypt_private_key(&private_key, &dir, &name, &passphrase)?;
+        eprintln!("{} {}", "Keystore written to".green(), path.display());
+        eprintln!(
+            "Set {} in config.toml and remove {} to use it.",
+            format!("keystore = \"{}/{}\"", KEYSTORE_DIR, name).cyan(),
+            "private_key".cyan()
+        );
+        Ok(StepOutcome::Continue)
+    }
+}
+
+/// Changes the wallet transactions are sent from, listing each configured
+/// wallet with its address, balance and nonce.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwitchWalletStep;
+
+#[async_trait]
+impl WorkflowStep for SwitchWalletStep {
+    fn name(&self) -> &str {
+        "Switch wallet"
+    }
+
+    fn is_available(&self, caps: &Capabilities) -> bool {
+        caps.wallets
+    }
+
+    async fn run(&self, ctx: &mut GlobalContext) -> Result<StepOutcome> {
+        let read = ctx.read_context()?;
+        let active = ctx.active_signer().map(|signer| signer.address());
+        let names: Vec<String> = ctx
+            .unlocked_wallets
+            .contains_key(DEFAULT_WALLET)
+            .then(|| DEFAULT_WALLET.to_string())
+            .into_iter()
+            .chain(ctx.wallets.iter().map(|wallet| wallet.name.clone()))
+            .collect();
+
+        let mut summaries = Vec::new();
+        for name in &names {
+            let address = match ctx.unlocked_wallets.get(name) {
+                Some(signer) => Some(signer.address()),
+                None => ctx
+                    .wallets
+                    .iter()
+                    .find(|wallet| &wallet.name == name)
+                    .and_then(|wallet| wallet.address(&ctx.data_dir)),
+            };
+            let (balance, nonce) = match address {
+                Some(address) => (
+                    read.get_balance(address).await.ok(),
+                    read.get_transaction_count(address).await.ok(),
+                ),
+                None => (None, None),
+            };
+            summaries.push(WalletSummary {
+                name: name.clone(),
+                address,
+                balance,
+                nonce,
+                active: address.is_some() && address == active,
+            });
+        }
+
+        let name = &names[prompt::select_wallet(&summaries)?];
+        let signer = match ctx.unlocked_wallets.get(name) {
+            Some(signer) => signer.clone(),
+            None => {
+                let chain_id = ctx
+                    .chain_id
+                    .parse::<u64>()
+                    .map_err(|_| Error::InvalidChainId(ctx.chain_id.clone()))?;
+                let config = ctx
+                    .wallets
+                    .iter()
+                    .find(|wallet| &wallet.name == name)
+                    .ok_or_else(|| Error::InvalidConfig(format!("Unknown wallet {}", name)))?;
+                let signer = config.unlock(&ctx.data_dir, chain_id).await?;
+                ctx.unlocked_wallets.insert(name.clone(), signer.clone());
+                signer
+            }
+        };
+        eprintln!(
+            "{} {} ({})",
+            "Sending from".green(),
+            name,
+            signer.address().to_checksum(None)
+        );
+        ctx.signer = Some(signer);
+        // Picking a wallet ends acting as an impersonated account.
+        ctx.impersonating = None;
+        Ok(StepOutcome::Continue)
+    }
+}
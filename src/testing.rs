@@ -0,0 +1,285 @@
+/// src/testing.rs
+use crate::config::Config;
+use crate::context::{GlobalContext, ReadContext};
+use crate::transport::RpcUrls;
+use alloy::json_abi::JsonAbi;
+use alloy::primitives::{address, Address, Bytes};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{
+    ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload, SerializedRequest,
+};
+use alloy::transports::{TransportError, TransportFut};
+use serde::Serialize;
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// Error code nodes use for `eth_call` and `eth_estimateGas` reverts.
+pub const REVERT_CODE: i64 = 3;
+
+/// Private key of the first default anvil account, used by [`fixtures::context`].
+pub const TEST_PRIVATE_KEY: &str =
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Address of the contract selected by [`fixtures::context`].
+pub const TEST_CONTRACT: Address = address!("5FbDB2315678afecb367f032d93F642f64180aa3");
+
+/// A JSON-RPC request received by a [`MockRpc`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCall {
+    /// The JSON-RPC method
+    pub method: String,
+    /// The request parameters, `Null` when none were sent
+    pub params: serde_json::Value,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    responses: HashMap<String, VecDeque<ResponsePayload>>,
+    calls: Vec<RecordedCall>,
+}
+
+/// In-memory JSON-RPC transport answering with scripted responses.
+///
+/// Responses are queued per method and consumed in order; the last response
+/// of a method is repeated once its queue is exhausted. Requests for methods
+/// without any scripted response fail with a "method not found" error. Every
+/// request is recorded and can be inspected with [`MockRpc::calls`].
+///
+/// Cloning the mock is cheap and every clone shares the same script and record.
+#[derive(Debug, Clone, Default)]
+pub struct MockRpc {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockRpc {
+    /// Creates a mock without any scripted response.
+    ///
+    /// # Returns
+    ///
+    /// * `MockRpc` - A new, empty mock
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a successful response for a method.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The JSON-RPC method, e.g. `eth_call`
+    /// * `result` - The value returned in the response's `result` field
+    ///
+    /// # Returns
+    ///
+    /// * `&MockRpc` - The mock, for chaining
+    pub fn respond<T: Serialize>(&self, method: &str, result: T) -> &Self {
+        let raw = serde_json::to_string(&result)
+            .and_then(RawValue::from_string)
+            .expect("mock responses must serialize to JSON");
+        self.push(method, ResponsePayload::Success(raw))
+    }
+
+    /// Queues an error response for a method.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The JSON-RPC method
+    /// * `code` - The JSON-RPC error code
+    /// * `message` - The error message
+    ///
+    /// # Returns
+    ///
+    /// * `&MockRpc` - The mock, for chaining
+    pub fn respond_error(&self, method: &str, code: i64, message: &str) -> &Self {
+        self.push(
+            method,
+            ResponsePayload::Failure(ErrorPayload {
+                code,
+                message: Cow::Owned(message.to_string()),
+                data: None,
+            }),
+        )
+    }
+
+    /// Queues a revert carrying the given revert data for a method.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The JSON-RPC method, usually `eth_call` or `eth_estimateGas`
+    /// * `data` - The raw revert data
+    ///
+    /// # Returns
+    ///
+    /// * `&MockRpc` - The mock, for chaining
+    pub fn revert(&self, method: &str, data: Bytes) -> &Self {
+        let raw = RawValue::from_string(format!("\"{}\"", data)).ok();
+        self.push(
+            method,
+            ResponsePayload::Failure(ErrorPayload {
+                code: REVERT_CODE,
+                message: Cow::Borrowed("execution reverted"),
+                data: raw,
+            }),
+        )
+    }
+
+    /// Returns every request received so far, in order.
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.lock().calls.clone()
+    }
+
+    /// Returns the requests received so far for one method.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The JSON-RPC method
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<RecordedCall>` - The matching requests, in order
+    pub fn calls_to(&self, method: &str) -> Vec<RecordedCall> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Creates an RPC client backed by this mock.
+    pub fn client(&self) -> RpcClient {
+        RpcClient::new(self.clone(), true)
+    }
+
+    /// Creates a read context backed by this mock.
+    pub fn read_context(&self) -> ReadContext {
+        ReadContext::from_client(self.client())
+    }
+
+    fn push(&self, method: &str, payload: ResponsePayload) -> &Self {
+        self.lock()
+            .responses
+            .entry(method.to_string())
+            .or_default()
+            .push_back(payload);
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn handle(&self, request: &SerializedRequest) -> Response {
+        let mut state = self.lock();
+        let params = request
+            .params()
+            .and_then(|raw| serde_json::from_str(raw.get()).ok())
+            .unwrap_or_default();
+        state.calls.push(RecordedCall {
+            method: request.method().to_string(),
+            params,
+        });
+
+        let payload = match state.responses.get_mut(request.method()) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+        Response {
+            id: request.id().clone(),
+            payload: payload.unwrap_or_else(|| {
+                ResponsePayload::Failure(ErrorPayload {
+                    code: -32601,
+                    message: Cow::Owned(format!("{} is not scripted", request.method())),
+                    data: None,
+                })
+            }),
+        }
+    }
+}
+
+impl tower::Service<RequestPacket> for MockRpc {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let response = match request {
+            RequestPacket::Single(request) => ResponsePacket::Single(self.handle(&request)),
+            RequestPacket::Batch(requests) => {
+                ResponsePacket::Batch(requests.iter().map(|r| self.handle(r)).collect())
+            }
+        };
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// Ready-made ABIs, configurations and contexts for tests.
+pub mod fixtures {
+    use super::*;
+    use alloy::signers::local::PrivateKeySigner;
+    use std::str::FromStr;
+
+    /// Minimal ERC-20 ABI.
+    pub const ERC20_ABI: &str = r#"[
+        {"type":"function","name":"name","inputs":[],"outputs":[{"name":"","type":"string"}],"stateMutability":"view"},
+        {"type":"function","name":"symbol","inputs":[],"outputs":[{"name":"","type":"string"}],"stateMutability":"view"},
+        {"type":"function","name":"decimals","inputs":[],"outputs":[{"name":"","type":"uint8"}],"stateMutability":"view"},
+        {"type":"function","name":"totalSupply","inputs":[],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+        {"type":"function","name":"balanceOf","inputs":[{"name":"owner","type":"address"}],"outputs":[{"name":"balance","type":"uint256"}],"stateMutability":"view"},
+        {"type":"function","name":"allowance","inputs":[{"name":"owner","type":"address"},{"name":"spender","type":"address"}],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+        {"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"},
+        {"type":"function","name":"approve","inputs":[{"name":"spender","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"},
+        {"type":"event","name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}],"anonymous":false},
+        {"type":"event","name":"Approval","inputs":[{"name":"owner","type":"address","indexed":true},{"name":"spender","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}],"anonymous":false},
+        {"type":"error","name":"InsufficientBalance","inputs":[{"name":"available","type":"uint256"},{"name":"required","type":"uint256"}]}
+    ]"#;
+
+    /// Returns the parsed [`ERC20_ABI`].
+    pub fn erc20_abi() -> JsonAbi {
+        serde_json::from_str(ERC20_ABI).expect("fixture ABI is valid")
+    }
+
+    /// Returns a valid configuration pointing at a local development node.
+    pub fn config() -> Config {
+        Config {
+            rpc_url: RpcUrls::from("http://127.0.0.1:8545"),
+            private_key: Some(TEST_PRIVATE_KEY.to_string()),
+            chain_id: 31337,
+            contract_name: Some("Token".to_string()),
+            contract_address: Some(TEST_CONTRACT.to_checksum(None)),
+            ..Config::default()
+        }
+    }
+
+    /// Returns a context wired to the mock, with the ERC-20 ABI loaded as
+    /// `Token` and selected at [`TEST_CONTRACT`].
+    ///
+    /// # Arguments
+    ///
+    /// * `mock` - The mock answering the context's RPC requests
+    ///
+    /// # Returns
+    ///
+    /// * `GlobalContext` - A context ready for calls and sends
+    pub fn context(mock: &MockRpc) -> GlobalContext {
+        let data_dir = std::env::temp_dir().join("inachus-fixtures");
+        let abis = HashMap::from([("Token".to_string(), erc20_abi())]);
+        GlobalContext::builder()
+            .abis(abis)
+            .rpc_url("http://127.0.0.1:8545")
+            .chain_id(31337)
+            .contract("Token", &TEST_CONTRACT.to_checksum(None))
+            .signer(PrivateKeySigner::from_str(TEST_PRIVATE_KEY).expect("fixture key is valid"))
+            .data_dir(data_dir)
+            .client(mock.client())
+            .build()
+            .expect("fixture context is valid")
+    }
+}
@@ -0,0 +1,246 @@
+/// src/trace.rs
+use crate::codec;
+use crate::context::GlobalContext;
+use crate::error::Error;
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::{Address, Bytes, Selector, U256};
+use colored::Colorize;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// A call frame returned by `debug_traceCall` with the `callTracer`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CallFrame {
+    /// Call type, e.g. `CALL`, `STATICCALL`, `DELEGATECALL` or `CREATE`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Caller address
+    pub from: Address,
+    /// Callee address, `None` for a failed creation
+    pub to: Option<Address>,
+    /// Native value sent with the call
+    pub value: Option<U256>,
+    /// Gas used by the call and its sub-calls
+    pub gas_used: U256,
+    /// Calldata
+    pub input: Bytes,
+    /// Return or revert data
+    pub output: Option<Bytes>,
+    /// Error the call failed with, e.g. `execution reverted`
+    pub error: Option<String>,
+    /// Revert reason decoded by the node, when it provides one
+    pub revert_reason: Option<String>,
+    /// Sub-calls in execution order
+    pub calls: Vec<CallFrame>,
+}
+
+/// Renders call trees, naming known contracts and decoding calls and reverts
+/// against every loaded ABI.
+#[derive(Debug)]
+pub struct TraceDecoder<'a> {
+    functions: HashMap<Selector, &'a Function>,
+    abis: Vec<&'a JsonAbi>,
+    names: HashMap<Address, String>,
+}
+
+impl<'a> TraceDecoder<'a> {
+    /// Creates a decoder from the ABIs and contracts known to a context.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context holding the loaded ABIs and contracts
+    ///
+    /// # Returns
+    ///
+    /// * `TraceDecoder` - A decoder recognising every loaded function and error
+    pub fn new(ctx: &'a GlobalContext) -> Self {
+        let chain_id = ctx.chain_id.parse::<u64>().unwrap_or_default();
+        let mut names: HashMap<Address, String> = ctx
+            .contracts
+            .iter()
+            .filter_map(|info| {
+                let address = info.address_on(chain_id)?;
+                Address::from_str(address)
+                    .ok()
+                    .map(|address| (address, info.name.clone()))
+            })
+            .collect();
+        names.extend(
+            ctx.address_book
+                .entries()
+                .map(|(alias, address)| (address, alias.to_string())),
+        );
+        names.insert(ctx.contract_address, ctx.contract_name.clone());
+        let abis: Vec<&JsonAbi> = ctx.abis.values().collect();
+        let functions = abis
+            .iter()
+            .flat_map(|abi| abi.functions())
+            .map(|function| (function.selector(), function))
+            .collect();
+        Self {
+            functions,
+            abis,
+            names,
+        }
+    }
+
+    /// Renders a call frame and its sub-calls as an indented tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The root call frame
+    ///
+    /// # Returns
+    ///
+    /// * `String` - One line per call, failed calls marked with their decoded revert
+    pub fn render(&self, frame: &CallFrame) -> String {
+        let mut lines = vec![self.describe(frame)];
+        self.render_calls(&frame.calls, "", &mut lines);
+        lines.join("\n")
+    }
+
+    fn render_calls(&self, calls: &[CallFrame], prefix: &str, lines: &mut Vec<String>) {
+        for (i, call) in calls.iter().enumerate() {
+            let last = i + 1 == calls.len();
+            let (branch, indent) = if last {
+                ("└─ ", "   ")
+            } else {
+                ("├─ ", "│  ")
+            };
+            lines.push(format!("{}{}{}", prefix, branch, self.describe(call)));
+            self.render_calls(&call.calls, &format!("{}{}", prefix, indent), lines);
+        }
+    }
+
+    /// Describes a single call, e.g. `CALL Token::transfer(to: 0x…, amount: 5) [21000 gas]`.
+    fn describe(&self, frame: &CallFrame) -> String {
+        let target = match frame.to {
+            Some(to) => self
+                .names
+                .get(&to)
+                .cloned()
+                .unwrap_or_else(|| to.to_checksum(None)),
+            None => "(creation failed)".to_string(),
+        };
+        let mut line = format!(
+            "{} {}::{}",
+            frame.kind,
+            target,
+            self.describe_input(&frame.input)
+        );
+        if let Some(value) = frame.value.filter(|value| !value.is_zero()) {
+            line.push_str(&format!(" {{value: {} wei}}", value));
+        }
+        line.push_str(&format!(" [{} gas]", frame.gas_used));
+        match &frame.error {
+            Some(error) => format!(
+                "{} {}",
+                line.red(),
+                format!("✗ {}", self.describe_failure(frame, error)).red()
+            ),
+            None => line,
+        }
+    }
+
+    fn describe_input(&self, input: &[u8]) -> String {
+        if input.is_empty() {
+            return "fallback()".to_string();
+        }
+        let function = input
+            .get(..4)
+            .and_then(|selector| self.functions.get(&Selector::from_slice(selector)));
+        match function.map(|f| (f, codec::decode_input(f, input))) {
+            Some((function, Ok(values))) => {
+                let args = function
+                    .inputs
+                    .iter()
+                    .zip(&values)
+                    .map(|(input, value)| {
+                        let rendered = match codec::to_json(value) {
+                            Value::String(s) => s,
+                            other => other.to_string(),
+                        };
+                        match input.name.as_str() {
+                            "" => rendered,
+                            name => format!("{}: {}", name, rendered),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                format!("{}({})", function.name, args.join(", "))
+            }
+            Some((function, Err(_))) => format!("{}(<undecodable>)", function.name),
+            None => format!("0x{}", hex::encode(&input[..input.len().min(4)])),
+        }
+    }
+
+    fn describe_failure(&self, frame: &CallFrame, error: &str) -> String {
+        let data = frame.output.clone().unwrap_or_default();
+        let decoded = self
+            .abis
+            .iter()
+            .map(|abi| Error::revert(data.clone(), Some(abi)))
+            .chain(std::iter::once(Error::revert(data.clone(), None)))
+            .find(|e| e.revert_error().is_some());
+        match (decoded, &frame.revert_reason) {
+            (Some(e), _) => e.to_string(),
+            (None, Some(reason)) => format!("{}: {}", error, reason),
+            (None, None) => error.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixtures, MockRpc, TEST_CONTRACT};
+    use alloy::dyn_abi::DynSolValue;
+
+    #[test]
+    fn test_render_decodes_calls_and_reverts() {
+        let ctx = fixtures::context(&MockRpc::new());
+        let abi = ctx.get_abi().unwrap();
+        let transfer = &abi.function("transfer").unwrap()[0];
+        let error = &abi.errors["InsufficientBalance"][0];
+        let revert = DynSolValue::Tuple(vec![
+            DynSolValue::Uint(U256::from(1), 256),
+            DynSolValue::Uint(U256::from(5), 256),
+        ]);
+        let frame: CallFrame = serde_json::from_value(serde_json::json!({
+            "type": "CALL",
+            "from": Address::ZERO,
+            "to": Address::repeat_byte(0x22),
+            "gasUsed": "0x5208",
+            "input": "0x",
+            "error": "execution reverted",
+            "calls": [{
+                "type": "CALL",
+                "from": Address::repeat_byte(0x22),
+                "to": TEST_CONTRACT,
+                "gasUsed": "0x100",
+                "input": codec::encode_calldata(
+                    transfer,
+                    &["0x0000000000000000000000000000000000000002", "5"],
+                )
+                .unwrap(),
+                "output": Bytes::from(
+                    [&error.selector()[..], &revert.abi_encode_params()].concat()
+                ),
+                "error": "execution reverted",
+            }],
+        }))
+        .unwrap();
+
+        let rendered = text::strip_ansi(&TraceDecoder::new(&ctx).render(&frame));
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(
+            "CALL 0x2222222222222222222222222222222222222222::fallback() [21000 gas]"
+        ));
+        assert!(lines[1].starts_with(&format!("└─ CALL {}::transfer(", ctx.contract_name)));
+        assert!(lines[1].contains("amount: 5"));
+        assert!(lines[1].ends_with("✗ Execution reverted: InsufficientBalance(1, 5)"));
+    }
+}
@@ -0,0 +1,635 @@
+/// src/transaction.rs
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::l1_fee::L1DataFee;
+use alloy::dyn_abi::DynSolValue;
+use alloy::network::TransactionBuilder;
+use alloy::primitives::utils::format_units;
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::rpc::types::{AccessList, TransactionRequest};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// Envelope type of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxType {
+    /// Pre-EIP-2718 transaction priced with `gasPrice`
+    Legacy,
+    /// EIP-2930 transaction carrying an access list
+    Eip2930,
+    /// EIP-1559 transaction priced with a base and a priority fee
+    Eip1559,
+}
+
+impl TxType {
+    /// Returns the EIP-2718 type byte.
+    pub fn as_u8(self) -> u8 {
+        match self {
+            TxType::Legacy => 0,
+            TxType::Eip2930 => 1,
+            TxType::Eip1559 => 2,
+        }
+    }
+}
+
+impl fmt::Display for TxType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxType::Legacy => write!(f, "legacy"),
+            TxType::Eip2930 => write!(f, "eip2930"),
+            TxType::Eip1559 => write!(f, "eip1559"),
+        }
+    }
+}
+
+impl FromStr for TxType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "legacy" | "0" => Ok(TxType::Legacy),
+            "eip2930" | "2930" | "1" => Ok(TxType::Eip2930),
+            "eip1559" | "1559" | "2" => Ok(TxType::Eip1559),
+            other => Err(Error::InvalidArguments(format!(
+                "Unknown transaction type: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Per-transaction overrides applied on top of the values filled by the provider.
+///
+/// Every field left unset is filled in by the provider (nonce, gas, fees) or
+/// takes its default (zero value, one confirmation).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransactionOptions {
+    gas_limit: Option<u64>,
+    gas_price: Option<u128>,
+    max_fee_per_gas: Option<u128>,
+    max_priority_fee_per_gas: Option<u128>,
+    nonce: Option<u64>,
+    value: U256,
+    access_list: Option<AccessList>,
+    generate_access_list: bool,
+    tx_type: Option<TxType>,
+    confirmations: u64,
+}
+
+impl Default for TransactionOptions {
+    fn default() -> Self {
+        Self {
+            gas_limit: None,
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            nonce: None,
+            value: U256::ZERO,
+            access_list: None,
+            generate_access_list: false,
+            tx_type: None,
+            confirmations: 1,
+        }
+    }
+}
+
+impl TransactionOptions {
+    /// Creates options without any override.
+    ///
+    /// # Returns
+    ///
+    /// * `TransactionOptions` - Options leaving every value to the provider
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the gas limit instead of estimating it.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets the legacy gas price, in wei.
+    pub fn with_gas_price(mut self, gas_price: u128) -> Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+
+    /// Sets the EIP-1559 maximum fee per gas, in wei.
+    pub fn with_max_fee_per_gas(mut self, max_fee_per_gas: u128) -> Self {
+        self.max_fee_per_gas = Some(max_fee_per_gas);
+        self
+    }
+
+    /// Sets the EIP-1559 maximum priority fee per gas, in wei.
+    pub fn with_max_priority_fee_per_gas(mut self, max_priority_fee_per_gas: u128) -> Self {
+        self.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+        self
+    }
+
+    /// Sets the nonce instead of using the next pending one.
+    pub fn with_nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the amount of native currency sent with the transaction, in wei.
+    pub fn with_value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Attaches an EIP-2930 access list.
+    pub fn with_access_list(mut self, access_list: AccessList) -> Self {
+        self.access_list = Some(access_list);
+        self
+    }
+
+    /// Asks for an access list to be generated with `eth_createAccessList`
+    /// before sending, and attached when it saves gas.
+    pub fn with_access_list_generation(mut self, generate: bool) -> Self {
+        self.generate_access_list = generate;
+        self
+    }
+
+    /// Forces the transaction envelope type.
+    pub fn with_tx_type(mut self, tx_type: TxType) -> Self {
+        self.tx_type = Some(tx_type);
+        self
+    }
+
+    /// Sets how many confirmations to wait for when watching the transaction.
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Returns the gas limit override.
+    pub fn gas_limit(&self) -> Option<u64> {
+        self.gas_limit
+    }
+
+    /// Returns the legacy gas price override.
+    pub fn gas_price(&self) -> Option<u128> {
+        self.gas_price
+    }
+
+    /// Returns the maximum fee per gas override.
+    pub fn max_fee_per_gas(&self) -> Option<u128> {
+        self.max_fee_per_gas
+    }
+
+    /// Returns the maximum priority fee per gas override.
+    pub fn max_priority_fee_per_gas(&self) -> Option<u128> {
+        self.max_priority_fee_per_gas
+    }
+
+    /// Returns the nonce override.
+    pub fn nonce(&self) -> Option<u64> {
+        self.nonce
+    }
+
+    /// Returns the value sent with the transaction.
+    pub fn value(&self) -> U256 {
+        self.value
+    }
+
+    /// Returns the access list, if one is attached.
+    pub fn access_list(&self) -> Option<&AccessList> {
+        self.access_list.as_ref()
+    }
+
+    /// Returns whether an access list should be generated before sending,
+    /// which is only the case when none is attached.
+    pub fn generates_access_list(&self) -> bool {
+        self.generate_access_list && self.access_list.is_none()
+    }
+
+    /// Returns the forced envelope type.
+    pub fn tx_type(&self) -> Option<TxType> {
+        self.tx_type
+    }
+
+    /// Returns the number of confirmations to wait for.
+    pub fn confirmations(&self) -> u64 {
+        self.confirmations
+    }
+
+    /// Checks that the overrides are consistent with each other.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Ok if the options can be applied, or an error
+    pub fn validate(&self) -> Result<()> {
+        let has_1559_fees =
+            self.max_fee_per_gas.is_some() || self.max_priority_fee_per_gas.is_some();
+        if self.gas_price.is_some() && has_1559_fees {
+            return Err(Error::InvalidArguments(
+                "gas price cannot be combined with EIP-1559 fees".to_string(),
+            ));
+        }
+        if let (Some(max_fee), Some(priority_fee)) =
+            (self.max_fee_per_gas, self.max_priority_fee_per_gas)
+        {
+            if priority_fee > max_fee {
+                return Err(Error::InvalidArguments(
+                    "max priority fee exceeds max fee per gas".to_string(),
+                ));
+            }
+        }
+        match self.tx_type {
+            Some(ty @ (TxType::Legacy | TxType::Eip2930)) if has_1559_fees => Err(
+                Error::InvalidArguments(format!("{} transactions cannot use EIP-1559 fees", ty)),
+            ),
+            Some(TxType::Legacy) if self.access_list.is_some() || self.generate_access_list => {
+                Err(Error::InvalidArguments(
+                    "legacy transactions cannot carry an access list".to_string(),
+                ))
+            }
+            Some(TxType::Eip1559) if self.gas_price.is_some() => Err(Error::InvalidArguments(
+                "eip1559 transactions cannot use a gas price".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the overrides to a transaction request.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The request to update
+    ///
+    /// # Returns
+    ///
+    /// * `TransactionRequest` - The request with every set override applied
+    pub fn apply(&self, mut tx: TransactionRequest) -> TransactionRequest {
+        if let Some(gas_limit) = self.gas_limit {
+            tx = tx.with_gas_limit(gas_limit);
+        }
+        if let Some(gas_price) = self.gas_price {
+            tx = tx.with_gas_price(gas_price);
+        }
+        if let Some(max_fee) = self.max_fee_per_gas {
+            tx = tx.with_max_fee_per_gas(max_fee);
+        }
+        if let Some(priority_fee) = self.max_priority_fee_per_gas {
+            tx = tx.with_max_priority_fee_per_gas(priority_fee);
+        }
+        if let Some(nonce) = self.nonce {
+            tx = tx.with_nonce(nonce);
+        }
+        if !self.value.is_zero() {
+            tx = tx.with_value(self.value);
+        }
+        if let Some(access_list) = &self.access_list {
+            tx = tx.with_access_list(access_list.clone());
+        }
+        if let Some(tx_type) = self.tx_type {
+            tx.transaction_type = Some(tx_type.as_u8());
+        }
+        tx
+    }
+}
+
+/// Default gas settings, the `[gas]` table of `config.toml`.
+///
+/// Anything left unset is estimated from the node when a transaction is sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GasConfig {
+    /// Transaction type to send
+    pub tx_type: Option<TxType>,
+    /// Gas limit, instead of `eth_estimateGas`
+    pub gas_limit: Option<u64>,
+    /// Legacy gas price in wei
+    pub gas_price: Option<u64>,
+    /// EIP-1559 max fee per gas in wei
+    pub max_fee_per_gas: Option<u64>,
+    /// EIP-1559 max priority fee per gas in wei
+    pub max_priority_fee_per_gas: Option<u64>,
+    /// Generate an access list for every transaction, attached when it saves gas
+    pub access_list: bool,
+}
+
+impl GasConfig {
+    /// Returns transaction options carrying these defaults.
+    ///
+    /// # Returns
+    ///
+    /// * `TransactionOptions` - Options with the configured gas settings and nothing else
+    pub fn options(&self) -> TransactionOptions {
+        TransactionOptions {
+            gas_limit: self.gas_limit,
+            gas_price: self.gas_price.map(u128::from),
+            max_fee_per_gas: self.max_fee_per_gas.map(u128::from),
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas.map(u128::from),
+            tx_type: self.tx_type,
+            // Legacy transactions cannot carry one.
+            generate_access_list: self.access_list && self.tx_type != Some(TxType::Legacy),
+            ..TransactionOptions::default()
+        }
+    }
+}
+
+/// An access list generated for a transaction, with the gas the transaction
+/// is estimated to use without and with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessListEstimate {
+    /// Accounts and storage slots the transaction touches
+    pub access_list: AccessList,
+    /// Gas estimated without the list
+    pub gas_without: u64,
+    /// Gas estimated with the list attached
+    pub gas_with: u64,
+}
+
+impl AccessListEstimate {
+    /// Returns whether attaching the list lowers the gas used: warm accesses
+    /// cost less, but each listed address and slot costs upfront.
+    pub fn saves_gas(&self) -> bool {
+        self.gas_with < self.gas_without
+    }
+
+    /// Attaches the list to the options when it saves gas.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options the transaction is sent with
+    ///
+    /// # Returns
+    ///
+    /// * `TransactionOptions` - The options, with the list attached if it saves gas
+    pub fn attach(&self, options: TransactionOptions) -> TransactionOptions {
+        match self.saves_gas() {
+            true => options.with_access_list(self.access_list.clone()),
+            false => options,
+        }
+    }
+}
+
+impl fmt::Display for AccessListEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", "Access list:".bold())?;
+        if self.access_list.0.is_empty() {
+            writeln!(f, "  (empty)")?;
+        }
+        for item in &self.access_list.0 {
+            writeln!(
+                f,
+                "  {} {}",
+                item.address.to_checksum(None),
+                format!("({} storage keys)", item.storage_keys.len()).dimmed()
+            )?;
+        }
+        let delta = self.gas_without.abs_diff(self.gas_with);
+        let verdict = match self.saves_gas() {
+            true => format!("saves {} gas, attached", delta).green(),
+            false => format!("saves nothing ({} more), not attached", delta).yellow(),
+        };
+        write!(
+            f,
+            "{} {} without, {} with: {}",
+            "Gas:".bold(),
+            self.gas_without,
+            self.gas_with,
+            verdict
+        )
+    }
+}
+
+/// Everything a transaction will do, shown to the user before it is signed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionPreview {
+    /// Chain the transaction is signed for
+    pub chain_id: u64,
+    /// Sender address
+    pub from: Address,
+    /// Target contract
+    pub to: Address,
+    /// Signature of the called function
+    pub function: String,
+    /// Argument names and values, unnamed inputs labelled by position
+    pub args: Vec<(String, DynSolValue)>,
+    /// Encoded calldata
+    pub calldata: Bytes,
+    /// Native value sent, in wei
+    pub value: U256,
+    /// Nonce the transaction will use
+    pub nonce: u64,
+    /// Gas limit, estimated unless overridden
+    pub gas_limit: u64,
+    /// Gas price for legacy transactions, max fee per gas otherwise
+    pub max_fee_per_gas: u128,
+    /// Max priority fee per gas, `None` for legacy pricing
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Fee for posting the transaction's data to L1, on OP-stack and Arbitrum chains
+    pub l1_data_fee: Option<L1DataFee>,
+}
+
+impl TransactionPreview {
+    /// Returns the most L2 execution can cost, in wei, leaving out the gas
+    /// Arbitrum charges for L1 data.
+    pub fn execution_cost(&self) -> U256 {
+        let l1_gas = self.l1_data_fee.and_then(|fee| fee.gas).unwrap_or(0);
+        U256::from(self.gas_limit.saturating_sub(l1_gas)) * U256::from(self.max_fee_per_gas)
+    }
+
+    /// Returns the most the transaction can cost, L1 data fee and value included, in wei.
+    pub fn max_cost(&self) -> U256 {
+        let l1_fee = match self.l1_data_fee {
+            Some(L1DataFee { gas: None, fee, .. }) => fee,
+            _ => U256::ZERO,
+        };
+        U256::from(self.gas_limit) * U256::from(self.max_fee_per_gas) + l1_fee + self.value
+    }
+
+    /// Describes the fees, e.g. `max 30 gwei (priority 2 gwei)` or `12 gwei` for legacy pricing.
+    pub fn fees(&self) -> String {
+        let gwei = |wei: u128| {
+            format_units(U256::from(wei), "gwei")
+                .map(|g| format!("{} gwei", g))
+                .unwrap_or_else(|_| format!("{} wei", wei))
+        };
+        match self.max_priority_fee_per_gas {
+            Some(priority) => format!(
+                "max {} (priority {})",
+                gwei(self.max_fee_per_gas),
+                gwei(priority)
+            ),
+            None => gwei(self.max_fee_per_gas),
+        }
+    }
+}
+
+impl fmt::Display for TransactionPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = self
+            .args
+            .iter()
+            .map(|(name, value)| {
+                let rendered = match codec::to_json(value) {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                match value.as_uint().and_then(|(amount, _)| ether_hint(amount)) {
+                    Some(hint) => format!("{}: {} ({})", name, rendered, hint),
+                    None => format!("{}: {}", name, rendered),
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut rows = vec![
+            ("Chain", self.chain_id.to_string()),
+            ("From", self.from.to_checksum(None)),
+            ("To", self.to.to_checksum(None)),
+            ("Function", self.function.clone()),
+            (
+                "Arguments",
+                if args.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    args.join("\n")
+                },
+            ),
+            ("Calldata", self.calldata.to_string()),
+            ("Value", with_ether(self.value)),
+            ("Nonce", self.nonce.to_string()),
+            ("Gas limit", self.gas_limit.to_string()),
+            ("Fees", self.fees()),
+        ];
+        if let Some(l1) = self.l1_data_fee {
+            let l1_data = match l1.gas {
+                Some(gas) => format!("{}, as {} gas of the limit", with_ether(l1.fee), gas),
+                None => with_ether(l1.fee),
+            };
+            rows.push(("L2 execution", with_ether(self.execution_cost())));
+            rows.push(("L1 data", format!("{} ({})", l1_data, l1.stack)));
+        }
+        rows.push(("Max cost", with_ether(self.max_cost())));
+        let rows = rows
+            .into_iter()
+            .map(|(key, value)| (key.bold().to_string(), value))
+            .collect::<Vec<_>>();
+        write!(f, "{}", text::table(&rows))
+    }
+}
+
+/// Shows an amount of wei in ether too, e.g. `1.5 ether (1500000000000000000 wei)`.
+fn with_ether(wei: U256) -> String {
+    match wei.is_zero() {
+        true => "0 wei".to_string(),
+        false => format!("{} ether ({} wei)", trimmed_ether(wei), wei),
+    }
+}
+
+/// Reads an integer argument as an amount in ether when it looks like one:
+/// at least 0.001 ether with at most 6 decimals, the scale of amounts typed
+/// as `1.5 ether` or `1e18`.
+fn ether_hint(amount: U256) -> Option<String> {
+    if amount < U256::from(10u64.pow(15)) {
+        return None;
+    }
+    let ether = trimmed_ether(amount);
+    match ether.split_once('.') {
+        Some((_, decimals)) if decimals.len() > 6 => None,
+        _ => Some(format!("{} ether", ether)),
+    }
+}
+
+/// Formats wei in ether without trailing zeros.
+fn trimmed_ether(wei: U256) -> String {
+    let ether = format_units(wei, "ether").unwrap_or_else(|_| wei.to_string());
+    match ether.contains('.') {
+        true => ether
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+        false => ether,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_and_validate() {
+        let options = TransactionOptions::new()
+            .with_gas_limit(100_000)
+            .with_max_fee_per_gas(30)
+            .with_max_priority_fee_per_gas(2)
+            .with_nonce(7)
+            .with_value(U256::from(1));
+        assert!(options.validate().is_ok());
+
+        let tx = options.apply(TransactionRequest::default());
+        assert_eq!(tx.gas, Some(100_000));
+        assert_eq!(tx.nonce, Some(7));
+        assert_eq!(tx.value, Some(U256::from(1)));
+
+        let conflicting = TransactionOptions::new()
+            .with_gas_price(10)
+            .with_max_fee_per_gas(30);
+        assert!(conflicting.validate().is_err());
+        assert!(TransactionOptions::new()
+            .with_tx_type(TxType::Legacy)
+            .with_max_fee_per_gas(1)
+            .validate()
+            .is_err());
+
+        let gas: GasConfig = toml::from_str("tx_type = \"legacy\"\ngas_price = 10").unwrap();
+        assert_eq!(gas.options().gas_price(), Some(10));
+        assert_eq!(gas.options().tx_type(), Some(TxType::Legacy));
+        assert!(gas.options().validate().is_ok());
+    }
+
+    #[test]
+    fn test_access_list_estimate() {
+        let access_list: AccessList = serde_json::from_str(
+            r#"[{"address":"0xdac17f958d2ee523a2206206994597c13d831ec7","storageKeys":["0x0000000000000000000000000000000000000000000000000000000000000002"]}]"#,
+        )
+        .unwrap();
+        let mut estimate = AccessListEstimate {
+            access_list: access_list.clone(),
+            gas_without: 52_000,
+            gas_with: 51_800,
+        };
+        assert!(estimate.saves_gas());
+        let options = estimate.attach(TransactionOptions::new().with_access_list_generation(true));
+        assert_eq!(options.access_list(), Some(&access_list));
+        assert!(!options.generates_access_list());
+        let rendered = text::strip_ansi(&estimate.to_string());
+        assert!(rendered.contains("(1 storage keys)"));
+        assert!(rendered.ends_with("52000 without, 51800 with: saves 200 gas, attached"));
+
+        estimate.gas_with = 53_000;
+        assert!(estimate
+            .attach(TransactionOptions::new())
+            .access_list()
+            .is_none());
+        assert!(TransactionOptions::new()
+            .with_tx_type(TxType::Legacy)
+            .with_access_list_generation(true)
+            .validate()
+            .is_err());
+        let gas: GasConfig = toml::from_str("tx_type = \"legacy\"\naccess_list = true").unwrap();
+        assert!(!gas.options().generates_access_list());
+    }
+
+    #[test]
+    fn test_amount_display() {
+        let ether = U256::from(10u64.pow(18));
+        assert_eq!(
+            with_ether(ether * U256::from(3) / U256::from(2)),
+            "1.5 ether (1500000000000000000 wei)"
+        );
+        assert_eq!(with_ether(U256::ZERO), "0 wei");
+        assert_eq!(ether_hint(ether).as_deref(), Some("1 ether"));
+        assert_eq!(ether_hint(U256::from(1_000_000)), None);
+        assert_eq!(ether_hint(ether + U256::from(1)), None);
+    }
+}
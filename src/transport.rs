@@ -0,0 +1,658 @@
+/// src/transport.rs
+use crate::cancel::Timeouts;
+use crate::error::{Error, Result};
+use crate::validation;
+use alloy::primitives::U64;
+use alloy::providers::{IpcConnect, WsConnect};
+use alloy::rpc::client::{ClientBuilder, RpcClient};
+use alloy::rpc::json_rpc::{
+    ErrorPayload, RequestPacket, ResponsePacket, ResponsePayload, SerializedRequest,
+};
+use alloy::transports::{BoxTransport, RpcError, TransportError, TransportErrorKind, TransportFut};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Reconnection attempts made when a WebSocket connection drops.
+pub const WS_MAX_RETRIES: u32 = 10;
+
+/// Delay between two WebSocket reconnection attempts.
+pub const WS_RETRY_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Time an endpoint has to connect and answer the startup health check.
+pub const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Retry settings of RPC requests, the `[retry]` table of `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Retries of a request after a rate limit or connection error, 0 to disable
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each following one (e.g. "500ms")
+    pub initial_backoff: String,
+    /// Longest delay between two retries (e.g. "10s")
+    pub max_backoff: String,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: "500ms".to_string(),
+            max_backoff: "10s".to_string(),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns the backoff described by the configuration.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Backoff>` - The parsed backoff or an error if a delay is malformed
+    pub fn backoff(&self) -> Result<Backoff> {
+        Ok(Backoff {
+            max_retries: self.max_retries,
+            initial: validation::parse_duration("retry.initial_backoff", &self.initial_backoff)?,
+            max: validation::parse_duration("retry.max_backoff", &self.max_backoff)?,
+        })
+    }
+}
+
+/// Exponential backoff between retries of an RPC request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial: Duration,
+    /// Longest delay between two retries
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns the delay before a retry, doubling from `initial` up to `max`.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry` - Index of the retry, starting at 0
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - The delay to wait
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.initial
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max)
+    }
+}
+
+/// One or several RPC URLs, the `rpc_url` of `config.toml`: a string, or a
+/// list of endpoints to fail over between.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RpcUrls {
+    /// A single endpoint
+    One(String),
+    /// Endpoints to fail over between, the fastest healthy one first
+    Many(Vec<String>),
+}
+
+impl RpcUrls {
+    /// Returns the URLs in the order they are configured.
+    pub fn urls(&self) -> Vec<&str> {
+        match self {
+            RpcUrls::One(url) => vec![url.as_str()],
+            RpcUrls::Many(urls) => urls.iter().map(String::as_str).collect(),
+        }
+    }
+
+    /// Returns the first configured URL, or `""` for an empty list.
+    pub fn primary(&self) -> &str {
+        self.urls().first().copied().unwrap_or_default()
+    }
+
+    /// Returns the URLs after the first one.
+    pub fn fallbacks(&self) -> Vec<String> {
+        self.urls()
+            .iter()
+            .skip(1)
+            .map(|url| url.to_string())
+            .collect()
+    }
+}
+
+impl Default for RpcUrls {
+    fn default() -> Self {
+        RpcUrls::One("http://localhost:8545".to_string())
+    }
+}
+
+impl From<&str> for RpcUrls {
+    fn from(url: &str) -> Self {
+        RpcUrls::One(url.to_string())
+    }
+}
+
+impl fmt::Display for RpcUrls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.urls().join(", "))
+    }
+}
+
+/// Where the RPC endpoint of `rpc_url` lives and how to reach it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcEndpoint {
+    /// `http://` or `https://` URL, one request per connection
+    Http(url::Url),
+    /// `ws://` or `wss://` URL, a persistent connection that reconnects when dropped
+    Ws(url::Url),
+    /// Filesystem path of a node's IPC socket, e.g. `/var/run/geth.ipc`
+    Ipc(PathBuf),
+}
+
+impl RpcEndpoint {
+    /// Detects the transport of an RPC URL from its scheme; anything without
+    /// a scheme is taken as an IPC path.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_url` - An `http(s)://` or `ws(s)://` URL, or an IPC socket path
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RpcEndpoint>` - The endpoint, or an error if the URL is malformed
+    ///   or uses another scheme
+    pub fn parse(rpc_url: &str) -> Result<Self> {
+        let rpc_url = rpc_url.trim();
+        if rpc_url.is_empty() {
+            return Err(Error::InvalidConfig("RPC URL is empty".to_string()));
+        }
+        if !rpc_url.contains("://") {
+            return Ok(RpcEndpoint::Ipc(PathBuf::from(rpc_url)));
+        }
+        let url: url::Url = rpc_url
+            .parse()
+            .map_err(|_| Error::InvalidConfig(format!("Invalid RPC URL: {}", rpc_url)))?;
+        match url.scheme() {
+            "http" | "https" => Ok(RpcEndpoint::Http(url)),
+            "ws" | "wss" => Ok(RpcEndpoint::Ws(url)),
+            scheme => Err(Error::InvalidConfig(format!(
+                "Unsupported RPC URL scheme {}: use http(s)://, ws(s):// or an IPC path",
+                scheme
+            ))),
+        }
+    }
+
+    /// Returns whether the endpoint keeps a persistent connection, which has
+    /// to be opened once with [`RpcEndpoint::connect`] and then shared.
+    pub fn is_persistent(&self) -> bool {
+        !matches!(self, RpcEndpoint::Http(_))
+    }
+
+    /// Asks the endpoint for its chain ID, giving up after [`ENDPOINT_TIMEOUT`].
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64>` - The chain ID, or an error if the endpoint did not answer
+    pub async fn chain_id(&self) -> Result<u64> {
+        let operation = format!("fetching the chain ID of {}", self);
+        let request = async {
+            let client = self.connect().await?;
+            client
+                .request_noparams::<U64>("eth_chainId")
+                .await
+                .map_err(|e| Error::rpc(&operation, e))
+        };
+        match tokio::time::timeout(ENDPOINT_TIMEOUT, request).await {
+            Ok(chain_id) => Ok(chain_id?.to::<u64>()),
+            Err(_) => Err(Error::Timeout {
+                operation,
+                after: ENDPOINT_TIMEOUT,
+            }),
+        }
+    }
+
+    /// Opens an RPC client on the endpoint.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RpcClient>` - The client, or an error if the WebSocket or IPC
+    ///   connection cannot be established
+    pub async fn connect(&self) -> Result<RpcClient> {
+        let operation = format!("connecting to {}", self);
+        match self {
+            RpcEndpoint::Http(url) => Ok(ClientBuilder::default().http(url.clone())),
+            RpcEndpoint::Ws(url) => ClientBuilder::default()
+                .ws(WsConnect::new(url.as_str())
+                    .with_max_retries(WS_MAX_RETRIES)
+                    .with_retry_interval(WS_RETRY_INTERVAL))
+                .await
+                .map_err(|e| Error::rpc(operation, e)),
+            RpcEndpoint::Ipc(path) => ClientBuilder::default()
+                .ipc(IpcConnect::new(path.clone()))
+                .await
+                .map_err(|e| Error::rpc(operation, e)),
+        }
+    }
+}
+
+impl fmt::Display for RpcEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcEndpoint::Http(url) | RpcEndpoint::Ws(url) => write!(f, "{}", url),
+            RpcEndpoint::Ipc(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Result of the startup health check of an endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// The endpoint
+    pub endpoint: RpcEndpoint,
+    /// Time it took to answer `eth_blockNumber`, `None` if it did not
+    pub latency: Option<Duration>,
+}
+
+impl EndpointHealth {
+    /// Returns whether the endpoint answered the health check.
+    pub fn is_healthy(&self) -> bool {
+        self.latency.is_some()
+    }
+}
+
+/// JSON-RPC transport spreading requests over several endpoints.
+///
+/// Requests go to the preferred endpoint, initially the fastest healthy one.
+/// When it fails at the transport level (connection error, HTTP error such as
+/// a 429, or no answer within the call timeout, the send timeout for
+/// broadcasts), the request is retried on the next endpoint, which becomes
+/// the preferred one if it succeeds. Error responses from the node, e.g.
+/// reverts, are returned as they are.
+#[derive(Clone)]
+pub struct FailoverTransport {
+    endpoints: Arc<Vec<(RpcEndpoint, BoxTransport)>>,
+    preferred: Arc<AtomicUsize>,
+    timeouts: Timeouts,
+}
+
+impl fmt::Debug for FailoverTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FailoverTransport")
+            .field(
+                "endpoints",
+                &self.endpoints.iter().map(|(e, _)| e).collect::<Vec<_>>(),
+            )
+            .field("preferred", &self.preferred.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl FailoverTransport {
+    /// Connects to every endpoint and checks its health, ordering the healthy
+    /// endpoints by latency before the unhealthy ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `urls` - The RPC URLs
+    /// * `timeouts` - Time each endpoint has to answer a forwarded request
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(FailoverTransport, Vec<EndpointHealth>)>` - The transport and the
+    ///   health of each endpoint in preference order, or an error if no endpoint
+    ///   answered
+    pub async fn connect(urls: &[&str], timeouts: Timeouts) -> Result<(Self, Vec<EndpointHealth>)> {
+        let endpoints = urls
+            .iter()
+            .map(|url| RpcEndpoint::parse(url))
+            .collect::<Result<Vec<_>>>()?;
+        let checks = endpoints.into_iter().map(|endpoint| async move {
+            let client = tokio::time::timeout(ENDPOINT_TIMEOUT, endpoint.connect()).await;
+            let client = match client {
+                Ok(Ok(client)) => client,
+                _ => return (endpoint, None),
+            };
+            let started = Instant::now();
+            let latency = tokio::time::timeout(
+                ENDPOINT_TIMEOUT,
+                client.request_noparams::<U64>("eth_blockNumber"),
+            )
+            .await
+            .ok()
+            .and_then(|response| response.ok())
+            .map(|_| started.elapsed());
+            (endpoint, Some((client.transport().clone(), latency)))
+        });
+        let mut checked = futures::future::join_all(checks).await;
+        checked.sort_by_key(|(_, check)| match check {
+            Some((_, Some(latency))) => (0, *latency),
+            Some((_, None)) => (1, Duration::ZERO),
+            None => (2, Duration::ZERO),
+        });
+
+        let health: Vec<EndpointHealth> = checked
+            .iter()
+            .map(|(endpoint, check)| EndpointHealth {
+                endpoint: endpoint.clone(),
+                latency: check.as_ref().and_then(|(_, latency)| *latency),
+            })
+            .collect();
+        if !health.iter().any(EndpointHealth::is_healthy) {
+            return Err(Error::Other(format!(
+                "No RPC endpoint answered among {}",
+                urls.join(", ")
+            )));
+        }
+        let endpoints = checked
+            .into_iter()
+            .filter_map(|(endpoint, check)| check.map(|(transport, _)| (endpoint, transport)))
+            .collect();
+        Ok((
+            Self {
+                endpoints: Arc::new(endpoints),
+                preferred: Arc::new(AtomicUsize::new(0)),
+                timeouts,
+            },
+            health,
+        ))
+    }
+
+    /// Returns the endpoint requests are currently sent to first.
+    pub fn preferred(&self) -> &RpcEndpoint {
+        &self.endpoints[self.preferred.load(Ordering::Relaxed)].0
+    }
+
+    /// Creates an RPC client sending its requests through this transport.
+    pub fn into_client(self) -> RpcClient {
+        RpcClient::new(self, false)
+    }
+}
+
+impl tower::Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let first = this.preferred.load(Ordering::Relaxed);
+            let count = this.endpoints.len();
+            let mut last_error = TransportErrorKind::custom_str("no RPC endpoint");
+            let timeout = request_timeout(&this.timeouts, &request);
+            for index in (0..count).map(|i| (first + i) % count) {
+                let (endpoint, transport) = &this.endpoints[index];
+                let mut transport = transport.clone();
+                let response = tokio::time::timeout(
+                    timeout,
+                    tower::Service::call(&mut transport, request.clone()),
+                )
+                .await;
+                match response {
+                    Ok(Ok(response)) => {
+                        if index != first {
+                            this.preferred.store(index, Ordering::Relaxed);
+                            tracing::warn!(%endpoint, "switched RPC endpoint");
+                        }
+                        return Ok(response);
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!(%endpoint, error = %e, "RPC endpoint failed");
+                        last_error = e;
+                    }
+                    Err(_) => {
+                        tracing::warn!(%endpoint, "RPC endpoint timed out");
+                        last_error = TransportErrorKind::custom_str(&format!(
+                            "{} did not answer within {}",
+                            endpoint,
+                            humantime::format_duration(timeout)
+                        ));
+                    }
+                }
+            }
+            Err(last_error)
+        })
+    }
+}
+
+/// Returns the time an endpoint has to answer a request: the send timeout for
+/// broadcasts, the call timeout for anything else.
+fn request_timeout(timeouts: &Timeouts, request: &RequestPacket) -> Duration {
+    let is_send = |request: &SerializedRequest| {
+        matches!(
+            request.method(),
+            "eth_sendRawTransaction" | "eth_sendTransaction"
+        )
+    };
+    let sends = match request {
+        RequestPacket::Single(request) => is_send(request),
+        RequestPacket::Batch(requests) => requests.iter().any(is_send),
+    };
+    match sends {
+        true => timeouts.send,
+        false => timeouts.call,
+    }
+}
+
+/// JSON-RPC transport retrying requests that failed for transient reasons.
+///
+/// Rate limits (HTTP 429, error codes `429`, `-32005` and `-32016`), HTTP 502
+/// to 504, dropped connections and connection errors are retried with
+/// exponential [`Backoff`], each retry being logged. Other failures are
+/// returned at once.
+#[derive(Clone)]
+pub struct RetryTransport {
+    inner: BoxTransport,
+    backoff: Backoff,
+}
+
+impl fmt::Debug for RetryTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryTransport")
+            .field("backoff", &self.backoff)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryTransport {
+    /// Wraps a transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The transport requests are sent with
+    /// * `backoff` - How many times and how long apart requests are retried
+    ///
+    /// # Returns
+    ///
+    /// * `RetryTransport` - The retrying transport
+    pub fn new(inner: BoxTransport, backoff: Backoff) -> Self {
+        Self { inner, backoff }
+    }
+
+    /// Creates an RPC client sending its requests through this transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_local` - Whether the node is local, which shortens polling intervals
+    pub fn into_client(self, is_local: bool) -> RpcClient {
+        RpcClient::new(self, is_local)
+    }
+}
+
+impl tower::Service<RequestPacket> for RetryTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let mut retry = 0;
+            loop {
+                let mut inner = this.inner.clone();
+                let response = tower::Service::call(&mut inner, request.clone()).await;
+                let reason = match &response {
+                    Ok(response) => retryable_response(response),
+                    Err(e) if is_retryable_error(e) => Some(e.to_string()),
+                    Err(_) => None,
+                };
+                match reason {
+                    Some(reason) if retry < this.backoff.max_retries => {
+                        let delay = this.backoff.delay(retry);
+                        retry += 1;
+                        tracing::warn!(retry, ?delay, %reason, "retrying RPC request");
+                        tokio::time::sleep(delay).await;
+                    }
+                    _ => return response,
+                }
+            }
+        })
+    }
+}
+
+/// Returns whether a transport error is transient: a rate limit, an
+/// unavailable gateway or a connection failure.
+fn is_retryable_error(error: &TransportError) -> bool {
+    match error {
+        RpcError::Transport(TransportErrorKind::HttpError(e)) => {
+            matches!(e.status, 429 | 502 | 503 | 504)
+        }
+        RpcError::Transport(TransportErrorKind::BackendGone)
+        | RpcError::Transport(TransportErrorKind::MissingBatchResponse(_)) => true,
+        RpcError::Transport(TransportErrorKind::Custom(e)) => {
+            let message = e.to_string().to_lowercase();
+            [
+                "429",
+                "error sending request",
+                "connection reset",
+                "connection closed",
+                "broken pipe",
+            ]
+            .iter()
+            .any(|pattern| message.contains(pattern))
+        }
+        _ => false,
+    }
+}
+
+/// Returns why a response should be retried, if one of its payloads is a
+/// rate limit error.
+fn retryable_response(response: &ResponsePacket) -> Option<String> {
+    let responses = match response {
+        ResponsePacket::Single(response) => std::slice::from_ref(response),
+        ResponsePacket::Batch(responses) => responses.as_slice(),
+    };
+    responses
+        .iter()
+        .find_map(|response| match &response.payload {
+            ResponsePayload::Failure(error) if is_rate_limit(error) => Some(error.to_string()),
+            _ => None,
+        })
+}
+
+/// Returns whether a JSON-RPC error reports a rate or request limit.
+fn is_rate_limit(error: &ErrorPayload) -> bool {
+    let message = error.message.to_lowercase();
+    matches!(error.code, 429 | -32005 | -32016)
+        || message.contains("rate limit")
+        || message.contains("too many requests")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockRpc;
+
+    #[tokio::test]
+    async fn test_retry_rate_limited_requests() {
+        let mock = MockRpc::new();
+        mock.respond_error("eth_blockNumber", -32005, "limit exceeded")
+            .respond("eth_blockNumber", "0x10")
+            .respond_error("eth_chainId", -32000, "header not found");
+        let backoff = Backoff {
+            max_retries: 2,
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(2),
+        };
+        assert_eq!(backoff.delay(5), Duration::from_millis(2));
+        let client =
+            RetryTransport::new(BoxTransport::new(mock.clone()), backoff).into_client(true);
+
+        let block: U64 = client.request_noparams("eth_blockNumber").await.unwrap();
+        assert_eq!(block, U64::from(16));
+        assert_eq!(mock.calls_to("eth_blockNumber").len(), 2);
+        assert!(client.request_noparams::<U64>("eth_chainId").await.is_err());
+        assert_eq!(mock.calls_to("eth_chainId").len(), 1);
+    }
+
+    #[test]
+    fn test_parse_endpoints() {
+        assert!(matches!(
+            RpcEndpoint::parse("https://eth.llamarpc.com").unwrap(),
+            RpcEndpoint::Http(_)
+        ));
+        let ws = RpcEndpoint::parse("wss://eth.example.com/ws").unwrap();
+        assert!(matches!(ws, RpcEndpoint::Ws(_)));
+        assert!(ws.is_persistent());
+        assert_eq!(
+            RpcEndpoint::parse(" /tmp/geth.ipc ").unwrap(),
+            RpcEndpoint::Ipc(PathBuf::from("/tmp/geth.ipc"))
+        );
+        assert!(RpcEndpoint::parse("ftp://example.com").is_err());
+        assert!(RpcEndpoint::parse("http://").is_err());
+        assert!(RpcEndpoint::parse("").is_err());
+
+        let urls: RpcUrls = serde_json::from_str(r#"["http://a:8545", "ws://b:8546"]"#).unwrap();
+        assert_eq!(urls.primary(), "http://a:8545");
+        assert_eq!(urls.fallbacks(), vec!["ws://b:8546"]);
+        assert_eq!(urls.to_string(), "http://a:8545, ws://b:8546");
+        let url: RpcUrls = serde_json::from_str(r#""http://a:8545""#).unwrap();
+        assert_eq!(url, RpcUrls::from("http://a:8545"));
+        assert!(url.fallbacks().is_empty());
+    }
+
+    #[test]
+    fn test_failover_request_timeouts() {
+        let timeouts = Timeouts {
+            call: Duration::from_secs(120),
+            send: Duration::from_secs(90),
+            receipt: Duration::from_secs(30),
+        };
+        let request = |method: &'static str| {
+            alloy::rpc::json_rpc::Request::new(method, alloy::rpc::json_rpc::Id::Number(1), ())
+                .serialize()
+                .unwrap()
+        };
+        let logs = RequestPacket::Single(request("eth_getLogs"));
+        assert_eq!(request_timeout(&timeouts, &logs), timeouts.call);
+        let batch = RequestPacket::Batch(vec![
+            request("eth_chainId"),
+            request("eth_sendRawTransaction"),
+        ]);
+        assert_eq!(request_timeout(&timeouts, &batch), timeouts.send);
+    }
+}
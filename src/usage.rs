@@ -0,0 +1,235 @@
+/// src/usage.rs
+use crate::error::Result;
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Methods of a contract listed as favorites at most.
+pub const MAX_FAVORITES: usize = 3;
+
+/// Calls a method needs before it is listed as a favorite.
+pub const FAVORITE_MIN_CALLS: u64 = 3;
+
+/// Recently executed methods listed at most, favorites aside.
+pub const MAX_RECENT: usize = 3;
+
+/// How often and how recently a method was executed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodStats {
+    /// Times the method was executed
+    pub count: u64,
+    /// When it was last executed, in seconds since the Unix epoch
+    pub last_used: u64,
+}
+
+/// A method execution, as replayed by the "Repeat last call" step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastCall {
+    /// Chain the method was executed on
+    pub chain_id: u64,
+    /// Name of the contract's ABI
+    pub contract: String,
+    /// Target contract address
+    pub address: Address,
+    /// Signature of the executed function
+    pub function: String,
+    /// Arguments as entered, address book aliases resolved
+    pub args: Vec<String>,
+    /// Value sent along, in wei
+    #[serde(default)]
+    pub value: U256,
+}
+
+impl LastCall {
+    /// Returns the call as `Contract.function(args)`, arguments included.
+    pub fn call(&self) -> String {
+        let name = self.function.split('(').next().unwrap_or(&self.function);
+        format!("{}.{}({})", self.contract, name, self.args.join(", "))
+    }
+}
+
+/// Why a method is listed first in the method selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickAccess {
+    /// One of the most executed methods of the contract
+    Favorite,
+    /// One of the last executed methods of the contract
+    Recent,
+}
+
+impl fmt::Display for QuickAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuickAccess::Favorite => write!(f, "★ favorite"),
+            QuickAccess::Recent => write!(f, "recent"),
+        }
+    }
+}
+
+/// Usage of the methods of every contract, persisted in `usage.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Statistics by contract name, then by function signature
+    #[serde(default)]
+    pub methods: BTreeMap<String, BTreeMap<String, MethodStats>>,
+    /// The last method executed, on any contract
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_call: Option<LastCall>,
+}
+
+impl Usage {
+    /// Reads the usage stored at a path. A missing file is empty usage, and
+    /// an unreadable one is ignored with a warning, usage being a convenience.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of `usage.json`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Usage>` - The usage, or an error if the file cannot be read
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!(path = %path.display(), error = %e, "ignoring unreadable usage file");
+            Self::default()
+        }))
+    }
+
+    /// Writes the usage to a path, creating its directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of `usage.json`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or an error during writing
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Counts an execution of a method and remembers it as the last call.
+    ///
+    /// # Arguments
+    ///
+    /// * `call` - The execution
+    pub fn record(&mut self, call: LastCall) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let stats = self
+            .methods
+            .entry(call.contract.clone())
+            .or_default()
+            .entry(call.function.clone())
+            .or_default();
+        stats.count += 1;
+        stats.last_used = now;
+        self.last_call = Some(call);
+    }
+
+    /// Returns the methods of a contract to list first: the most executed
+    /// ones as favorites, then the last executed others.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract` - Name of the contract's ABI
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(String, QuickAccess)>` - Function signatures with why they are listed
+    pub fn quick_access(&self, contract: &str) -> Vec<(String, QuickAccess)> {
+        let Some(methods) = self.methods.get(contract) else {
+            return Vec::new();
+        };
+        let mut by_count: Vec<(&String, &MethodStats)> = methods
+            .iter()
+            .filter(|(_, stats)| stats.count >= FAVORITE_MIN_CALLS)
+            .collect();
+        by_count.sort_by(|a, b| {
+            b.1.count
+                .cmp(&a.1.count)
+                .then(b.1.last_used.cmp(&a.1.last_used))
+        });
+        let favorites: Vec<&String> = by_count
+            .into_iter()
+            .take(MAX_FAVORITES)
+            .map(|(signature, _)| signature)
+            .collect();
+        let mut by_date: Vec<(&String, &MethodStats)> = methods
+            .iter()
+            .filter(|(signature, _)| !favorites.contains(signature))
+            .collect();
+        by_date.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.last_used));
+        favorites
+            .into_iter()
+            .map(|signature| (signature.clone(), QuickAccess::Favorite))
+            .chain(
+                by_date
+                    .into_iter()
+                    .take(MAX_RECENT)
+                    .map(|(signature, _)| (signature.clone(), QuickAccess::Recent)),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quick_access() {
+        let call = |function: &str| LastCall {
+            chain_id: 1,
+            contract: "Token".to_string(),
+            address: Address::ZERO,
+            function: function.to_string(),
+            args: vec!["1".to_string()],
+            value: U256::ZERO,
+        };
+        let mut usage = Usage::default();
+        for _ in 0..FAVORITE_MIN_CALLS {
+            usage.record(call("balanceOf(address)"));
+        }
+        usage.record(call("approve(address,uint256)"));
+        usage.record(call("transfer(address,uint256)"));
+        // Same-second executions: make the order of use explicit.
+        let stats = usage.methods.get_mut("Token").unwrap();
+        stats.get_mut("approve(address,uint256)").unwrap().last_used -= 1;
+
+        assert_eq!(
+            usage.quick_access("Token"),
+            vec![
+                ("balanceOf(address)".to_string(), QuickAccess::Favorite),
+                ("transfer(address,uint256)".to_string(), QuickAccess::Recent),
+                ("approve(address,uint256)".to_string(), QuickAccess::Recent),
+            ]
+        );
+        assert!(usage.quick_access("Other").is_empty());
+        assert_eq!(
+            usage.last_call.as_ref().map(LastCall::call).as_deref(),
+            Some("Token.transfer(1)")
+        );
+
+        let path = std::env::temp_dir().join(format!("inachus-usage-{}.json", std::process::id()));
+        usage.save(&path).unwrap();
+        let loaded = Usage::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded, usage);
+        assert_eq!(Usage::load(&path).unwrap(), Usage::default());
+    }
+}
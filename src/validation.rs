@@ -1,9 +1,14 @@
 /// src/validation.rs
+use crate::codec;
 use crate::error::{Error, Result};
-use alloy::primitives::U256;
+use alloy::dyn_abi::DynSolType;
+use alloy::eips::{BlockId, BlockNumberOrTag};
+use alloy::primitives::utils::Unit;
+use alloy::primitives::{Address, B256, U256};
 use std::str::FromStr;
+use std::time::Duration;
 
-/// Validates that the RPC URL is correctly formatted.
+/// Validates that the RPC URL is an `http(s)://` or `ws(s)://` URL, or an IPC path.
 ///
 /// # Arguments
 ///
@@ -14,10 +19,9 @@ use std::str::FromStr;
 /// * `Ok(())` if the URL is valid
 /// * `Err(Error)` if the URL is invalid
 pub fn validate_rpc_url(url: &str) -> Result<()> {
-    if !url.starts_with("http://") && !url.starts_with("https://") {
-        return Err(Error::InvalidAddress(format!("Invalid RPC URL: {}", url)));
-    }
-    Ok(())
+    crate::transport::RpcEndpoint::parse(url)
+        .map(|_| ())
+        .map_err(|_| Error::InvalidAddress(format!("Invalid RPC URL: {}", url)))
 }
 
 /// Validates that an Ethereum address is correctly formatted.
@@ -113,6 +117,76 @@ pub fn validate_wait_time(wait_time: &str) -> Result<()> {
     Ok(())
 }
 
+/// Parses a human-readable duration such as "30s" or "2m".
+///
+/// # Arguments
+///
+/// * `name` - Name of the setting, used in errors
+/// * `value` - The duration to parse
+///
+/// # Returns
+///
+/// * `Ok(Duration)` if the duration is valid
+/// * `Err(Error)` if the duration is invalid
+pub fn parse_duration(name: &str, value: &str) -> Result<Duration> {
+    humantime::parse_duration(value)
+        .map_err(|_| Error::InvalidConfig(format!("Invalid {}: {}", name, value)))
+}
+
+/// Parses a block number or tag.
+///
+/// # Arguments
+///
+/// * `value` - A decimal or 0x-prefixed block number, or a tag such as `latest`
+///
+/// # Returns
+///
+/// * `Ok(BlockNumberOrTag)` if the block is valid
+/// * `Err(Error)` if the block is invalid
+pub fn parse_block(value: &str) -> Result<BlockNumberOrTag> {
+    let value = value.trim();
+    if let Ok(number) = value.parse::<u64>() {
+        return Ok(BlockNumberOrTag::Number(number));
+    }
+    BlockNumberOrTag::from_str(value)
+        .map_err(|_| Error::InvalidArguments(format!("Invalid block: {}", value)))
+}
+
+/// Parses a block to read state at: a number, a tag or a block hash.
+///
+/// # Arguments
+///
+/// * `value` - A block number, a tag such as `finalized`, or a 32-byte block hash
+///
+/// # Returns
+///
+/// * `Ok(BlockId)` if the block is valid
+/// * `Err(Error)` if the block is invalid
+pub fn parse_block_id(value: &str) -> Result<BlockId> {
+    let value = value.trim();
+    if value.len() == 66 && value.starts_with("0x") {
+        return B256::from_str(value)
+            .map(BlockId::from)
+            .map_err(|_| Error::InvalidArguments(format!("Invalid block hash: {}", value)));
+    }
+    parse_block(value).map(BlockId::from)
+}
+
+/// Parses a native token amount with an optional unit.
+///
+/// # Arguments
+///
+/// * `value` - An amount such as `1.5 ether`, `2000 gwei`, `100 wei` or
+///   `1e18 wei`, digits optionally grouped with `_`; a bare number is in ether
+///
+/// # Returns
+///
+/// * `Ok(U256)` - The amount in wei
+/// * `Err(Error)` if the amount or the unit is invalid
+pub fn parse_value(value: &str) -> Result<U256> {
+    codec::parse_amount(value, Unit::ETHER)
+}
+
 /// Validates that a contract name is correctly formatted.
 ///
 /// # Arguments
@@ -155,3 +229,149 @@ pub fn validate_contract_name(contract_name: &str) -> Result<()> {
 pub fn validate_contract_address(address: &str) -> Result<()> {
     validate_address(address).map_err(|_| Error::InvalidContract(address.to_string()))
 }
+
+/// Validates the input of a method parameter against its type, so a mistake
+/// is reported while it is entered rather than when the call is encoded.
+///
+/// Mixed-case addresses must carry a valid EIP-55 checksum, unsigned integers
+/// must fit their bit width and fixed-size bytes must have exactly their
+/// length; other types must parse as the type.
+///
+/// # Arguments
+///
+/// * `ty` - The parameter type
+/// * `input` - The input, address book aliases already resolved
+///
+/// # Returns
+///
+/// * `Ok(())` if the input is a valid value of the type
+/// * `Err(Error)` describing why it is not
+pub fn validate_param(ty: &DynSolType, input: &str) -> Result<()> {
+    let input = input.trim();
+    match ty {
+        DynSolType::Address => {
+            validate_address(input)?;
+            let hex = &input[2..];
+            let mixed_case = hex.chars().any(|c| c.is_ascii_uppercase())
+                && hex.chars().any(|c| c.is_ascii_lowercase());
+            if mixed_case && Address::parse_checksummed(input, None).is_err() {
+                return Err(Error::InvalidAddress(
+                    "Address checksum is invalid; check for a typo, or enter it in lowercase"
+                        .to_string(),
+                ));
+            }
+            Ok(())
+        }
+        DynSolType::Uint(bits) if codec::coerce(ty, input).is_err() => {
+            let max = U256::MAX >> (256 - bits);
+            match codec::parse_amount(input, Unit::WEI) {
+                Ok(value) if value > max => Err(Error::InvalidArguments(format!(
+                    "uint{} must be at most {}",
+                    bits, max
+                ))),
+                _ => Err(Error::InvalidArguments(format!(
+                    "Expected an unsigned integer such as 1000, 1.5 ether or 1e18, got {}",
+                    input
+                ))),
+            }
+        }
+        DynSolType::FixedBytes(size) => {
+            let hex = input.strip_prefix("0x").ok_or_else(|| {
+                Error::InvalidArguments(format!("bytes{} must start with 0x", size))
+            })?;
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(Error::InvalidArguments(format!(
+                    "bytes{} must be hexadecimal",
+                    size
+                )));
+            }
+            if hex.len() != size * 2 {
+                return Err(Error::InvalidArguments(format!(
+                    "bytes{} takes exactly {} hex characters, got {}",
+                    size,
+                    size * 2,
+                    hex.len()
+                )));
+            }
+            Ok(())
+        }
+        _ => codec::coerce(ty, input).map(|_| ()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(
+            parse_value("1.5 ether").unwrap(),
+            U256::from(1_500_000_000_000_000_000u128)
+        );
+        assert_eq!(
+            parse_value("2000gwei").unwrap(),
+            U256::from(2_000_000_000_000u64)
+        );
+        assert_eq!(parse_value("100 wei").unwrap(), U256::from(100));
+        assert_eq!(
+            parse_value("0.01").unwrap(),
+            parse_value("0.01 ETH").unwrap()
+        );
+        assert!(parse_value("1 furlong").is_err());
+        assert!(parse_value("0.5 wei").is_err());
+        assert!(parse_value("-1 ether").is_err());
+        assert_eq!(
+            parse_value("1e18 wei").unwrap(),
+            parse_value("1_000_000_000 gwei").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_block_id() {
+        assert_eq!(
+            parse_block_id("19000000").unwrap(),
+            BlockId::number(19_000_000)
+        );
+        assert_eq!(
+            parse_block_id("finalized").unwrap(),
+            BlockId::from(BlockNumberOrTag::Finalized)
+        );
+        let hash = B256::repeat_byte(0xab);
+        assert_eq!(
+            parse_block_id(&hash.to_string()).unwrap(),
+            BlockId::from(hash)
+        );
+        assert!(parse_block_id("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_validate_param() {
+        let ty = |ty: &str| codec::parse_type(ty).unwrap();
+        assert!(
+            validate_param(&ty("address"), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok()
+        );
+        assert!(
+            validate_param(&ty("address"), "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_ok()
+        );
+        assert!(
+            validate_param(&ty("address"), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err()
+        );
+        assert!(
+            validate_param(&ty("address"), "0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea").is_err()
+        );
+
+        assert!(validate_param(&ty("uint8"), "255").is_ok());
+        let err = validate_param(&ty("uint8"), "256").unwrap_err();
+        assert!(err.to_string().contains("at most 255"));
+        assert!(validate_param(&ty("uint256"), "-1").is_err());
+
+        assert!(validate_param(&ty("bytes4"), "0xa9059cbb").is_ok());
+        let err = validate_param(&ty("bytes4"), "0xa9059c").unwrap_err();
+        assert!(err.to_string().contains("exactly 8 hex characters, got 6"));
+        assert!(validate_param(&ty("bytes4"), "a9059cbb").is_err());
+
+        assert!(validate_param(&ty("uint256[]"), "[1, 2]").is_ok());
+        assert!(validate_param(&ty("bool"), "maybe").is_err());
+    }
+}
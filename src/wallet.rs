@@ -0,0 +1,278 @@
+/// src/wallet.rs
+use crate::error::{Error, Result};
+#[cfg(feature = "interactive")]
+use crate::prompt;
+use crate::signer::{LedgerConfig, MnemonicConfig, Signer};
+use alloy::primitives::{Address, U256};
+use alloy::signers::local::PrivateKeySigner;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Directory name for keystores created by Inachus, inside the data directory.
+pub const KEYSTORE_DIR: &str = "keystores";
+
+/// Environment variable holding the keystore passphrase, for non-interactive runs.
+pub const PASSPHRASE_ENV: &str = "INACHUS_KEYSTORE_PASSPHRASE";
+
+/// Name under which the top-level signer of `config.toml` is listed among the wallets.
+pub const DEFAULT_WALLET: &str = "default";
+
+/// A named signer, one of the `[[wallets]]` tables of `config.toml`.
+///
+/// Exactly one of `private_key`, `keystore`, `ledger` and `mnemonic` must be set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalletConfig {
+    /// Name shown in the wallet selector
+    pub name: String,
+    /// Raw private key
+    pub private_key: Option<String>,
+    /// Encrypted keystore, relative to the data directory
+    pub keystore: Option<PathBuf>,
+    /// Ledger device
+    pub ledger: Option<LedgerConfig>,
+    /// BIP-39 mnemonic, signing with the account at `index` (0 when unset)
+    pub mnemonic: Option<MnemonicConfig>,
+}
+
+impl WalletConfig {
+    /// Validates the wallet name and that it has exactly one key source.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success if the wallet is valid, or an error
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || self.name == DEFAULT_WALLET {
+            return Err(Error::InvalidConfig(format!(
+                "Wallet name {:?} is empty or reserved",
+                self.name
+            )));
+        }
+        let sources = [
+            self.private_key.is_some(),
+            self.keystore.is_some(),
+            self.ledger.is_some(),
+            self.mnemonic.is_some(),
+        ];
+        if sources.iter().filter(|set| **set).count() != 1 {
+            return Err(Error::InvalidConfig(format!(
+                "Wallet {} needs exactly one of private_key, keystore, ledger and mnemonic",
+                self.name
+            )));
+        }
+        if let Some(ref pk) = self.private_key {
+            crate::validation::validate_private_key(pk)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the address of the wallet when it is known without unlocking it.
+    ///
+    /// Keystores record their address in clear; a Ledger has to be connected first.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - Directory keystore paths are relative to
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Address>` - The address, or `None` if it cannot be known yet
+    pub fn address(&self, data_dir: &Path) -> Option<Address> {
+        if let Some(ref pk) = self.private_key {
+            return PrivateKeySigner::from_str(pk).ok().map(|s| s.address());
+        }
+        if let Some(ref keystore) = self.keystore {
+            let content = std::fs::read_to_string(data_dir.join(keystore)).ok()?;
+            let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+            return Address::from_str(json.get("address")?.as_str()?).ok();
+        }
+        if let Some(ref mnemonic) = self.mnemonic {
+            let path = mnemonic.path(mnemonic.index.unwrap_or(0));
+            return Signer::mnemonic(&mnemonic.phrase()?, &path)
+                .ok()
+                .map(|s| s.address());
+        }
+        None
+    }
+
+    /// Unlocks the wallet, prompting for a keystore passphrase unless
+    /// `INACHUS_KEYSTORE_PASSPHRASE` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir` - Directory keystore paths are relative to
+    /// * `chain_id` - Chain ID transactions are signed for
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Signer>` - The wallet's signer, or an error if it cannot be unlocked
+    pub async fn unlock(&self, data_dir: &Path, chain_id: u64) -> Result<Signer> {
+        if let Some(ref pk) = self.private_key {
+            return PrivateKeySigner::from_str(pk)
+                .map(Signer::local)
+                .map_err(|e| Error::InvalidPrivateKey(e.to_string()));
+        }
+        if let Some(ref keystore) = self.keystore {
+            let path = data_dir.join(keystore);
+            let passphrase = match std::env::var(PASSPHRASE_ENV) {
+                Ok(passphrase) => passphrase,
+                #[cfg(feature = "interactive")]
+                Err(_) => prompt::input_passphrase(&path)?,
+                #[cfg(not(feature = "interactive"))]
+                Err(_) => {
+                    return Err(Error::InvalidConfig(format!(
+                        "{} is not set to unlock {}",
+                        PASSPHRASE_ENV,
+                        path.display()
+                    )))
+                }
+            };
+            return Signer::keystore(&path, &passphrase);
+        }
+        if let Some(ref ledger) = self.ledger {
+            return Signer::ledger(&ledger.derivation_path, chain_id).await;
+        }
+        if let Some(ref mnemonic) = self.mnemonic {
+            let phrase = mnemonic.phrase().ok_or_else(|| {
+                Error::InvalidConfig(format!("Wallet {} has no mnemonic phrase", self.name))
+            })?;
+            return Signer::mnemonic(&phrase, &mnemonic.path(mnemonic.index.unwrap_or(0)));
+        }
+        Err(Error::InvalidConfig(format!(
+            "Wallet {} has no key source",
+            self.name
+        )))
+    }
+}
+
+/// A wallet as listed in the wallet selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletSummary {
+    /// Name of the wallet
+    pub name: String,
+    /// Address, `None` until a Ledger wallet is connected
+    pub address: Option<Address>,
+    /// Native balance, when it could be fetched
+    pub balance: Option<U256>,
+    /// Nonce of the next transaction, when it could be fetched
+    pub nonce: Option<u64>,
+    /// Whether transactions are currently sent from this wallet
+    pub active: bool,
+}
+
+/// Decrypts a Web3 Secret Storage (geth keystore) file.
+///
+/// # Arguments
+///
+/// * `path` - Path to the keystore JSON file
+/// * `passphrase` - The passphrase the keystore was encrypted with
+///
+/// # Returns
+///
+/// * `Result<PrivateKeySigner>` - The decrypted signer, or an error if the file or passphrase is wrong
+pub fn decrypt_keystore(path: &Path, passphrase: &str) -> Result<PrivateKeySigner> {
+    PrivateKeySigner::decrypt_keystore(path, passphrase).map_err(|e| {
+        Error::InvalidPrivateKey(format!("Cannot decrypt keystore {}: {}", path.display(), e))
+    })
+}
+
+/// Encrypts a raw private key into a Web3 Secret Storage file.
+///
+/// # Arguments
+///
+/// * `private_key` - The hex-encoded private key, with or without `0x`
+/// * `dir` - Directory the keystore is written to, created if missing
+/// * `name` - File name of the keystore
+/// * `passphrase` - The passphrase to encrypt the key with
+///
+/// # Returns
+///
+/// * `Result<PathBuf>` - Path of the written keystore, or an error
+pub fn encrypt_private_key(
+    private_key: &str,
+    dir: &Path,
+    name: &str,
+    passphrase: &str,
+) -> Result<PathBuf> {
+    let signer = PrivateKeySigner::from_str(private_key)
+        .map_err(|e| Error::InvalidPrivateKey(e.to_string()))?;
+    std::fs::create_dir_all(dir)?;
+    PrivateKeySigner::encrypt_keystore(
+        dir,
+        &mut rand::thread_rng(),
+        signer.to_bytes(),
+        passphrase,
+        Some(name),
+    )
+    .map_err(|e| Error::signing("encrypting keystore", e))?;
+    Ok(dir.join(name))
+}
+
+/// Returns the hex-encoded private key of a signer.
+pub fn private_key_hex(signer: &PrivateKeySigner) -> String {
+    hex::encode(signer.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TEST_PRIVATE_KEY;
+
+    #[test]
+    fn test_wallet_config_address() {
+        let dir = std::env::temp_dir().join(format!("inachus-wallets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("cold"),
+            r#"{"address":"f39fd6e51aad88f6f4ce6ab8827279cfffb92266","crypto":{}}"#,
+        )
+        .unwrap();
+
+        let hot = WalletConfig {
+            name: "hot".to_string(),
+            private_key: Some(TEST_PRIVATE_KEY.to_string()),
+            ..WalletConfig::default()
+        };
+        hot.validate().unwrap();
+        assert_eq!(
+            hot.address(&dir),
+            Some(
+                PrivateKeySigner::from_str(TEST_PRIVATE_KEY)
+                    .unwrap()
+                    .address()
+            )
+        );
+
+        let cold = WalletConfig {
+            name: "cold".to_string(),
+            keystore: Some(PathBuf::from("cold")),
+            ..WalletConfig::default()
+        };
+        cold.validate().unwrap();
+        assert_eq!(
+            cold.address(&dir),
+            Some(Address::from_str("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap())
+        );
+
+        let ledger = WalletConfig {
+            name: "ledger".to_string(),
+            ledger: Some(LedgerConfig::default()),
+            ..WalletConfig::default()
+        };
+        assert_eq!(ledger.address(&dir), None);
+        assert!(WalletConfig {
+            private_key: hot.private_key.clone(),
+            ..ledger
+        }
+        .validate()
+        .is_err());
+        assert!(WalletConfig {
+            name: DEFAULT_WALLET.to_string(),
+            ..hot
+        }
+        .validate()
+        .is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,469 @@
+use alloy::consensus::{SignableTransaction, TxEnvelope, TypedTransaction};
+use alloy::dyn_abi::DynSolValue;
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::TxSignerSync;
+use alloy::primitives::{b256, hex, Address, Bytes, U256};
+use alloy::rpc::types::TransactionReceipt;
+use alloy::signers::local::PrivateKeySigner;
+use inachus::codec;
+use inachus::config::OperatingMode;
+use inachus::error::{Error, ErrorKind};
+use inachus::hooks::{CommandHook, HookStage};
+use inachus::offline::{SignedTransaction, UnsignedTransaction};
+use inachus::policy::{PolicyConfig, PolicyHook};
+use inachus::safe::{self, SafeConfig};
+use inachus::steps::execute_method;
+use inachus::testing::{fixtures, MockRpc, TEST_CONTRACT, TEST_PRIVATE_KEY};
+use inachus::{
+    CallResult, ContractClient, Event, ExecutionResult, GlobalContext, TransactionOptions,
+    TransactionPreview, TxType,
+};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn test_call_is_decoded_from_mocked_eth_call() {
+    let mock = MockRpc::new();
+    mock.respond(
+        "eth_call",
+        Bytes::from(U256::from(1_000_000u64).to_be_bytes_vec()),
+    );
+    let ctx = fixtures::context(&mock);
+    let function = ctx.get_abi().unwrap().function("balanceOf").unwrap()[0].clone();
+
+    let result = execute_method(
+        &ctx,
+        &function,
+        &["0x0000000000000000000000000000000000000001".to_string()],
+        &TransactionOptions::default(),
+    )
+    .await
+    .unwrap();
+
+    let ExecutionResult::Call(call) = result else {
+        panic!("expected a call result, got {:?}", result);
+    };
+    assert_eq!(
+        call.get("balance"),
+        Some(&DynSolValue::Uint(U256::from(1_000_000u64), 256))
+    );
+    let calls = mock.calls_to("eth_call");
+    assert_eq!(calls.len(), 1);
+    assert_eq!(
+        calls[0].params[0]["to"].as_str().map(str::to_lowercase),
+        Some(TEST_CONTRACT.to_string().to_lowercase())
+    );
+}
+
+#[tokio::test]
+async fn test_revert_data_is_preserved() {
+    let mock = MockRpc::new();
+    mock.revert("eth_call", Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]));
+    let ctx = fixtures::context(&mock);
+    let function = ctx.get_abi().unwrap().function("totalSupply").unwrap()[0].clone();
+
+    let err = ctx
+        .read_context()
+        .unwrap()
+        .call(TEST_CONTRACT, &function, &[])
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Revert { .. }));
+    assert_eq!(
+        err.revert_data(),
+        Some(&Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]))
+    );
+}
+
+#[tokio::test]
+async fn test_send_broadcasts_signed_transaction() {
+    let hash = b256!("0x1111111111111111111111111111111111111111111111111111111111111111");
+    let mock = MockRpc::new();
+    mock.respond("eth_sendRawTransaction", hash);
+    let ctx = fixtures::context(&mock);
+    let function = ctx.get_abi().unwrap().function("transfer").unwrap()[0].clone();
+    let args = inachus::codec::coerce_args(
+        &function,
+        &["0x0000000000000000000000000000000000000002", "5"],
+    )
+    .unwrap();
+    let options = TransactionOptions::new()
+        .with_nonce(0)
+        .with_gas_limit(60_000)
+        .with_max_fee_per_gas(2_000_000_000)
+        .with_max_priority_fee_per_gas(1_000_000_000);
+
+    let pending = ctx
+        .write_context()
+        .unwrap()
+        .send(TEST_CONTRACT, &function, &args, &options)
+        .await
+        .unwrap();
+
+    assert_eq!(*pending.tx_hash(), hash);
+    assert_eq!(mock.calls_to("eth_sendRawTransaction").len(), 1);
+}
+
+#[tokio::test]
+async fn test_sends_publish_events() {
+    let hash = b256!("0x1111111111111111111111111111111111111111111111111111111111111111");
+    let mock = MockRpc::new();
+    mock.respond("eth_sendRawTransaction", hash);
+    let mut ctx = fixtures::context(&mock);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = events.clone();
+    ctx.events
+        .subscribe(move |event| seen.lock().unwrap().push(event.clone()));
+    let function = ctx.get_abi().unwrap().function("transfer").unwrap()[0].clone();
+    let args = codec::coerce_args(
+        &function,
+        &["0x0000000000000000000000000000000000000002", "5"],
+    )
+    .unwrap();
+    let options = TransactionOptions::new()
+        .with_nonce(0)
+        .with_gas_limit(60_000)
+        .with_max_fee_per_gas(2_000_000_000)
+        .with_max_priority_fee_per_gas(1_000_000_000);
+
+    let pending = ctx
+        .write_context()
+        .unwrap()
+        .send(TEST_CONTRACT, &function, &args, &options)
+        .await
+        .unwrap();
+    assert_eq!(*pending.tx_hash(), hash);
+    assert_eq!(
+        events.lock().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![Event::TxSubmitted {
+            hash,
+            contract: TEST_CONTRACT,
+            function: "transfer(address,uint256)".to_string(),
+        }]
+    );
+
+    mock.revert("eth_call", Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]));
+    ctx.require_simulation = true;
+    let err = ctx
+        .write_context()
+        .unwrap()
+        .send(TEST_CONTRACT, &function, &args, &options)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Vetoed { .. }));
+    assert!(matches!(
+        events.lock().unwrap().as_slice(),
+        [Event::SimulationFailed { function, .. }] if function == "transfer(address,uint256)"
+    ));
+    assert_eq!(mock.calls_to("eth_sendRawTransaction").len(), 1);
+}
+
+#[tokio::test]
+async fn test_send_returns_the_options_hooks_left() {
+    let hash = b256!("0x1111111111111111111111111111111111111111111111111111111111111111");
+    let mock = MockRpc::new();
+    mock.respond("eth_sendRawTransaction", hash);
+    let mut ctx = fixtures::context(&mock);
+    ctx.hooks.register(CommandHook::new(
+        HookStage::PreSend,
+        r#"cat > /dev/null; echo '{"value": "0x10"}'"#,
+    ));
+    let function = ctx.get_abi().unwrap().function("transfer").unwrap()[0].clone();
+    let args = codec::coerce_args(
+        &function,
+        &["0x0000000000000000000000000000000000000002", "5"],
+    )
+    .unwrap();
+    let options = TransactionOptions::new()
+        .with_nonce(0)
+        .with_gas_limit(60_000)
+        .with_max_fee_per_gas(2_000_000_000)
+        .with_max_priority_fee_per_gas(1_000_000_000);
+
+    let (pending, sent) = ctx
+        .write_context()
+        .unwrap()
+        .send_with_final_options(TEST_CONTRACT, &function, &args, &options)
+        .await
+        .unwrap();
+
+    assert_eq!(*pending.tx_hash(), hash);
+    assert_eq!(sent.value(), U256::from(0x10));
+    assert_eq!(options.value(), U256::ZERO);
+}
+
+#[tokio::test]
+async fn test_preview_estimates_unset_values() {
+    let mock = MockRpc::new();
+    mock.respond("eth_getTransactionCount", "0x7")
+        .respond("eth_estimateGas", "0xea60")
+        .respond("eth_gasPrice", "0x3b9aca00");
+    let ctx = fixtures::context(&mock);
+    let function = ctx.get_abi().unwrap().function("transfer").unwrap()[0].clone();
+    let args = inachus::codec::coerce_args(
+        &function,
+        &["0x0000000000000000000000000000000000000002", "5"],
+    )
+    .unwrap();
+    let options = TransactionOptions::new().with_tx_type(TxType::Legacy);
+
+    let preview = ctx
+        .write_context()
+        .unwrap()
+        .preview(TEST_CONTRACT, &function, &args, &options)
+        .await
+        .unwrap();
+
+    assert_eq!(preview.nonce, 7);
+    assert_eq!(preview.gas_limit, 60_000);
+    assert_eq!(preview.max_fee_per_gas, 1_000_000_000);
+    assert_eq!(preview.max_priority_fee_per_gas, None);
+    assert_eq!(preview.max_cost(), U256::from(60_000_000_000_000u64));
+    assert_eq!(preview.args[1].0, "amount");
+    assert!(preview.to_string().contains("amount: 5"));
+}
+
+#[test]
+fn test_target_resolves_aliases_and_decodes_inputs() {
+    let mut ctx = fixtures::context(&MockRpc::new());
+    let treasury = Address::repeat_byte(0x11);
+    ctx.address_book.insert("treasury", treasury).unwrap();
+
+    let (_, function, address) = ctx
+        .target(Some("Token"), Some("treasury"), "transfer")
+        .unwrap();
+    assert_eq!(address, treasury);
+    let (_, _, address) = ctx.target(None, None, "transfer(address,uint256)").unwrap();
+    assert_eq!(address, TEST_CONTRACT);
+    assert!(ctx.target(Some("Missing"), None, "transfer").is_err());
+
+    let args = ctx
+        .address_book
+        .resolve_args(function, &["treasury".to_string(), "5".to_string()]);
+    let calldata = codec::encode_calldata(function, &args).unwrap();
+    let values = codec::decode_input(function, &calldata).unwrap();
+    let decoded = CallResult::from_inputs(function, values, calldata);
+    assert_eq!(decoded.get("to"), Some(&DynSolValue::Address(treasury)));
+    assert_eq!(
+        decoded.get("amount"),
+        Some(&DynSolValue::Uint(U256::from(5), 256))
+    );
+}
+
+#[tokio::test]
+async fn test_contract_client_reads_and_decodes_logs() {
+    let mock = MockRpc::new();
+    mock.respond("eth_call", Bytes::from(U256::from(42u64).to_be_bytes_vec()));
+    let provider = mock.read_context().provider().clone();
+    let client = ContractClient::new(provider, fixtures::erc20_abi(), TEST_CONTRACT);
+
+    let result = client
+        .read("balanceOf", &["0x0000000000000000000000000000000000000001"])
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get("balance"),
+        Some(&DynSolValue::Uint(U256::from(42u64), 256))
+    );
+    let unsigned = client
+        .write(
+            "transfer",
+            &["0x0000000000000000000000000000000000000002", "5"],
+            &TransactionOptions::default(),
+        )
+        .await;
+    assert!(matches!(unsigned, Err(Error::InvalidConfig(_))));
+
+    let word = |byte: u8| format!("0x{}", "00".repeat(31) + &format!("{:02x}", byte));
+    let transfer = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+    let log = |address: Address| {
+        serde_json::json!({
+            "address": address,
+            "topics": [transfer, word(1), word(2)],
+            "data": word(5),
+            "blockNumber": "0x1",
+            "blockHash": format!("0x{}", "22".repeat(32)),
+            "transactionHash": format!("0x{}", "11".repeat(32)),
+            "transactionIndex": "0x0",
+            "logIndex": "0x0",
+            "removed": false
+        })
+    };
+    let receipt: TransactionReceipt = serde_json::from_value(serde_json::json!({
+        "type": "0x2",
+        "status": "0x1",
+        "transactionHash": format!("0x{}", "11".repeat(32)),
+        "transactionIndex": "0x0",
+        "blockHash": format!("0x{}", "22".repeat(32)),
+        "blockNumber": "0x1",
+        "from": Address::repeat_byte(0x01),
+        "to": TEST_CONTRACT,
+        "contractAddress": null,
+        "gasUsed": "0xea60",
+        "cumulativeGasUsed": "0xea60",
+        "effectiveGasPrice": "0x1",
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "logs": [log(TEST_CONTRACT), log(Address::repeat_byte(0x33))]
+    }))
+    .unwrap();
+    let logs = client.decode_logs(&receipt);
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].event, "Transfer(address,address,uint256)");
+}
+
+#[tokio::test]
+async fn test_context_builder_builds_partial_contexts() {
+    let mock = MockRpc::new();
+    mock.respond("eth_blockNumber", "0x10");
+    let ctx = GlobalContext::builder()
+        .client(mock.client())
+        .build()
+        .unwrap();
+    assert!(ctx.contract_name.is_empty());
+    assert_eq!(ctx.contract_address, Address::ZERO);
+    assert_eq!(ctx.chain_id, "1");
+    assert!(ctx.active_signer().is_none());
+    assert!(!ctx.capabilities().signer);
+    assert!(mock.calls_to("eth_blockNumber").is_empty());
+    assert_eq!(
+        ctx.read_context()
+            .unwrap()
+            .get_block_number()
+            .await
+            .unwrap(),
+        16
+    );
+
+    let invalid = GlobalContext::builder()
+        .rpc_url("ftp://node.example")
+        .build();
+    assert!(matches!(invalid, Err(Error::InvalidConfig(_))));
+    let invalid = GlobalContext::builder()
+        .contract("Token", "0xnot-an-address")
+        .build();
+    assert!(matches!(invalid, Err(Error::InvalidAddress(_))));
+}
+
+#[tokio::test]
+async fn test_safe_proposals_go_through_the_policy() {
+    let mock = MockRpc::new();
+    let mut ctx = fixtures::context(&mock);
+    ctx.safe = Some(SafeConfig {
+        address: "0x5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a".to_string(),
+        service_url: None,
+        api_key: None,
+    });
+    let policy = PolicyConfig {
+        denied_methods: vec!["transfer".to_string()],
+        ..Default::default()
+    }
+    .policy(&ctx.address_book)
+    .unwrap();
+    ctx.hooks.register(PolicyHook::new(policy, false));
+    let function = ctx.get_abi().unwrap().function("transfer").unwrap()[0].clone();
+    let args = codec::coerce_args(
+        &function,
+        &["0x0000000000000000000000000000000000000002", "5"],
+    )
+    .unwrap();
+
+    let err = safe::propose(
+        &ctx,
+        TEST_CONTRACT,
+        &function,
+        &args,
+        &TransactionOptions::new(),
+        false,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(matches!(err, Error::Vetoed { hook, .. } if hook == "policy"));
+    assert!(mock.calls().is_empty());
+}
+
+#[tokio::test]
+async fn test_operating_modes_refuse_to_send() {
+    let mock = MockRpc::new();
+    let mut ctx = fixtures::context(&mock);
+    let function = ctx.get_abi().unwrap().function("transfer").unwrap()[0].clone();
+    let args = codec::coerce_args(
+        &function,
+        &["0x0000000000000000000000000000000000000002", "5"],
+    )
+    .unwrap();
+
+    ctx.mode = OperatingMode::DryRun;
+    let err = ctx
+        .write_context()
+        .unwrap()
+        .send(TEST_CONTRACT, &function, &args, &TransactionOptions::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::ModeForbids {
+            mode: OperatingMode::DryRun,
+            ..
+        }
+    ));
+    assert_eq!(err.kind(), ErrorKind::Mode);
+    assert!(mock.calls_to("eth_sendRawTransaction").is_empty());
+
+    ctx.mode = OperatingMode::ReadOnly;
+    let err = ctx.write_context().err().unwrap();
+    assert!(matches!(
+        err,
+        Error::ModeForbids {
+            mode: OperatingMode::ReadOnly,
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_imported_transactions_go_through_the_policy() {
+    let mock = MockRpc::new();
+    let mut ctx = fixtures::context(&mock);
+    let policy = PolicyConfig {
+        denied_methods: vec!["transfer(address,uint256)".to_string()],
+        ..Default::default()
+    }
+    .policy(&ctx.address_book)
+    .unwrap();
+    ctx.hooks.register(PolicyHook::new(policy, false));
+    let key = PrivateKeySigner::from_str(TEST_PRIVATE_KEY).unwrap();
+    let function = ctx.get_abi().unwrap().function("transfer").unwrap()[0].clone();
+    let args = codec::coerce_args(
+        &function,
+        &["0x0000000000000000000000000000000000000002", "5"],
+    )
+    .unwrap();
+    let unsigned = UnsignedTransaction::from_preview(&TransactionPreview {
+        chain_id: 31337,
+        from: key.address(),
+        to: TEST_CONTRACT,
+        function: function.signature(),
+        args: vec![],
+        calldata: codec::encode_values(&function, &args).unwrap(),
+        value: U256::ZERO,
+        nonce: 0,
+        gas_limit: 60_000,
+        max_fee_per_gas: 2_000_000_000,
+        max_priority_fee_per_gas: Some(1_000_000_000),
+        l1_data_fee: None,
+    });
+    let TypedTransaction::Eip1559(mut tx) = unsigned.tx else {
+        panic!("expected an EIP-1559 transaction");
+    };
+    let signature = key.sign_transaction_sync(&mut tx).unwrap();
+    let raw = TxEnvelope::from(tx.into_signed(signature)).encoded_2718();
+    let signed = SignedTransaction::decode(&hex::encode_prefixed(&raw)).unwrap();
+    assert_eq!(signed.options().nonce(), Some(0));
+
+    let err = ctx.send_raw_transaction(&signed).await.err().unwrap();
+
+    assert!(matches!(err, Error::Vetoed { hook, .. } if hook == "policy"));
+    assert!(mock.calls_to("eth_sendRawTransaction").is_empty());
+}